@@ -17,7 +17,6 @@ use std::{
     collections::{HashMap, HashSet},
     net::SocketAddr,
     sync::Arc,
-    time::Duration,
 };
 
 use anyhow::Context;
@@ -32,7 +31,7 @@ use axum::{
 };
 use chrono::Utc;
 use cowboy_common::{
-    GameInstanceResponse, GameStatus, PlayerId, PlayerName, StepEvent, StepEventType,
+    Backoff, GameInstanceResponse, GameStatus, PlayerId, PlayerName, StepEvent, StepEventType,
     expand_env_vars,
 };
 use rdkafka::{
@@ -41,7 +40,7 @@ use rdkafka::{
     consumer::{CommitMode, Consumer, StreamConsumer},
 };
 use serde::{Deserialize, Serialize};
-use tokio::sync::{Mutex, oneshot};
+use tokio::sync::{Mutex, RwLock, oneshot};
 use tower_http::{cors::CorsLayer, trace::TraceLayer};
 use tracing::{info, warn};
 
@@ -59,6 +58,9 @@ struct AppState {
     output_topic_prefix: String,
     consumer_group_id: String,
     default_game_guide_version: String,
+    game_guide: Arc<GameGuide>,
+    game_llm_overrides: Arc<Mutex<HashMap<String, HashMap<PlayerName, LlmProfile>>>>,
+    forwarding_gate: Arc<RwLock<()>>,
 }
 
 struct GameTopicWorker {
@@ -98,6 +100,8 @@ struct DefaultAssignmentRequest {
     game_guide_version: Option<String>,
     #[serde(default)]
     force_recreate: Option<bool>,
+    #[serde(default)]
+    llm_overrides: Option<HashMap<PlayerName, LlmProfile>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -108,6 +112,8 @@ struct BulkAssignmentRequest {
     game_guide_version: Option<String>,
     #[serde(default)]
     force_recreate: Option<bool>,
+    #[serde(default)]
+    llm_overrides: Option<HashMap<PlayerName, LlmProfile>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -166,6 +172,28 @@ struct StopBotsResponse {
     destroyed_bot_count: usize,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct BotMove {
+    game_id: String,
+    player_id: PlayerId,
+    bot_id: String,
+    from_base_url: String,
+    to_base_url: String,
+}
+
+#[derive(Debug, Serialize)]
+struct RebalanceFailure {
+    #[serde(flatten)]
+    bot_move: BotMove,
+    error: String,
+}
+
+#[derive(Debug, Serialize)]
+struct RebalanceResponse {
+    moved: Vec<BotMove>,
+    failed: Vec<RebalanceFailure>,
+}
+
 #[derive(Debug, Serialize)]
 struct BotCreateRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -183,6 +211,8 @@ struct BotCreateRequest {
     llm_api_key: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     llm_output_mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expose_bot_reasoning: Option<bool>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -211,6 +241,23 @@ struct TeachGameRequest {
     examples: Vec<serde_json::Value>,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+struct GameGuide {
+    rules_markdown: String,
+    command_schema: serde_json::Value,
+    examples: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct GameGuideFile {
+    #[serde(default)]
+    rules_markdown: Option<String>,
+    #[serde(default)]
+    command_schema: Option<serde_json::Value>,
+    #[serde(default)]
+    examples: Option<Vec<serde_json::Value>>,
+}
+
 #[derive(Debug, Clone, Default)]
 struct LlmProfilesConfig {
     default: Option<LlmProfile>,
@@ -227,6 +274,8 @@ struct LlmProfile {
     api_key: Option<String>,
     #[serde(default)]
     output_mode: Option<String>,
+    #[serde(default)]
+    expose_bot_reasoning: Option<bool>,
 }
 
 #[derive(Debug, Clone, Default, Deserialize)]
@@ -270,6 +319,9 @@ async fn main() -> anyhow::Result<()> {
         default_game_guide_version: std::env::var("BOT_GAME_GUIDE_VERSION")
             .ok()
             .unwrap_or_else(|| "v1".to_string()),
+        game_guide: Arc::new(load_game_guide()),
+        game_llm_overrides: Arc::new(Mutex::new(HashMap::new())),
+        forwarding_gate: Arc::new(RwLock::new(())),
     };
     info!(
         bot_service_base_urls = ?state.bot_service_base_urls,
@@ -280,6 +332,10 @@ async fn main() -> anyhow::Result<()> {
         "bot-manager loaded bot-service instance config"
     );
 
+    if let Err(error) = recover_assignments(&state).await {
+        warn!(error = %error, "bot-manager failed to recover assignments from bot state table");
+    }
+
     let kafka_state = state.clone();
     tokio::spawn(async move {
         if let Err(error) = run_output_consumer(kafka_state).await {
@@ -291,7 +347,10 @@ async fn main() -> anyhow::Result<()> {
     let bind_addr = parse_bind_addr("BOT_MANAGER_BIND", "0.0.0.0:8090")?;
     info!(%bind_addr, "bot-manager-service listening");
     let listener = tokio::net::TcpListener::bind(bind_addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(cowboy_common::shutdown_signal())
+        .await?;
+    info!("bot-manager-service shut down");
     Ok(())
 }
 
@@ -314,6 +373,7 @@ fn build_router(state: AppState) -> Router {
             "/internal/v3/games/{game_id}/bots/stop",
             post(stop_bots_handler),
         )
+        .route("/internal/v3/rebalance", post(rebalance_handler))
         .with_state(state)
         .layer(CorsLayer::permissive())
         .layer(TraceLayer::new_for_http())
@@ -424,6 +484,56 @@ fn load_llm_profiles_config() -> LlmProfilesConfig {
     config
 }
 
+fn load_game_guide() -> GameGuide {
+    let path = std::env::var("BOT_GAME_GUIDE_PATH")
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+    load_game_guide_from_path(path.as_deref())
+}
+
+/// Reads teach-game content from `path`. A `.md` file replaces only the rules
+/// text; a YAML file may override any of `rules_markdown`, `command_schema` and
+/// `examples`. Anything missing or unreadable keeps the built-in default.
+fn load_game_guide_from_path(path: Option<&str>) -> GameGuide {
+    let mut guide = GameGuide::default();
+    let Some(path) = path else {
+        return guide;
+    };
+
+    let raw = match std::fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(error) => {
+            warn!(path = %path, error = %error, "failed to read bot game guide path");
+            return guide;
+        }
+    };
+
+    if path.ends_with(".md") || path.ends_with(".markdown") {
+        guide.rules_markdown = raw;
+    } else {
+        let parsed = match serde_yaml::from_str::<GameGuideFile>(&raw) {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                warn!(path = %path, error = %error, "failed to parse bot game guide yaml");
+                return guide;
+            }
+        };
+        if let Some(rules_markdown) = normalize_optional_string(parsed.rules_markdown) {
+            guide.rules_markdown = rules_markdown;
+        }
+        if let Some(command_schema) = parsed.command_schema {
+            guide.command_schema = command_schema;
+        }
+        if let Some(examples) = parsed.examples {
+            guide.examples = examples;
+        }
+    }
+
+    info!(path = %path, "loaded bot game guide");
+    guide
+}
+
 fn parse_player_name(value: &str) -> Option<PlayerName> {
     match value.trim().to_ascii_uppercase().as_str() {
         "A" => Some(PlayerName::A),
@@ -434,14 +544,37 @@ fn parse_player_name(value: &str) -> Option<PlayerName> {
     }
 }
 
+impl Default for GameGuide {
+    fn default() -> Self {
+        Self {
+            rules_markdown: default_rules_markdown(),
+            command_schema: serde_json::json!({
+                "allowed": ["move", "shoot", "shield", "speak", "pass", "charge"],
+                "direction_required_for": ["move", "shoot", "shield"],
+                "speak_text_required_for": ["speak"]
+            }),
+            examples: vec![
+                serde_json::json!({"command_type":"move","direction":"up"}),
+                serde_json::json!({"command_type":"speak","speak_text":"Watch this turn."}),
+            ],
+        }
+    }
+}
+
 impl LlmProfile {
     fn normalized(self) -> Option<Self> {
         let base_url = normalize_optional_string(self.base_url);
         let model = normalize_optional_string(self.model);
         let api_key = normalize_optional_string(self.api_key);
         let output_mode = normalize_optional_string(self.output_mode);
+        let expose_bot_reasoning = self.expose_bot_reasoning;
 
-        if base_url.is_none() && model.is_none() && api_key.is_none() && output_mode.is_none() {
+        if base_url.is_none()
+            && model.is_none()
+            && api_key.is_none()
+            && output_mode.is_none()
+            && expose_bot_reasoning.is_none()
+        {
             None
         } else {
             Some(Self {
@@ -449,6 +582,7 @@ impl LlmProfile {
                 model,
                 api_key,
                 output_mode,
+                expose_bot_reasoning,
             })
         }
     }
@@ -460,32 +594,53 @@ fn normalize_optional_string(value: Option<String>) -> Option<String> {
         .filter(|entry| !entry.is_empty())
 }
 
-fn resolve_llm_profile(config: &LlmProfilesConfig, player_name: PlayerName) -> Option<LlmProfile> {
-    let default = config.default.clone();
-    let player = config.players.get(&player_name).cloned();
+/// Merges LLM settings field by field: the per-game override wins, then the
+/// per-player file entry, then the file default.
+fn resolve_llm_profile(
+    config: &LlmProfilesConfig,
+    game_override: Option<&LlmProfile>,
+    player_name: PlayerName,
+) -> Option<LlmProfile> {
+    let layers: Vec<&LlmProfile> = [
+        game_override,
+        config.players.get(&player_name),
+        config.default.as_ref(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    let pick = |field: fn(&LlmProfile) -> &Option<String>| {
+        layers.iter().find_map(|layer| field(layer).clone())
+    };
 
     let merged = LlmProfile {
-        base_url: player
-            .as_ref()
-            .and_then(|value| value.base_url.clone())
-            .or_else(|| default.as_ref().and_then(|value| value.base_url.clone())),
-        model: player
-            .as_ref()
-            .and_then(|value| value.model.clone())
-            .or_else(|| default.as_ref().and_then(|value| value.model.clone())),
-        api_key: player
-            .as_ref()
-            .and_then(|value| value.api_key.clone())
-            .or_else(|| default.as_ref().and_then(|value| value.api_key.clone())),
-        output_mode: player
-            .as_ref()
-            .and_then(|value| value.output_mode.clone())
-            .or_else(|| default.as_ref().and_then(|value| value.output_mode.clone())),
+        base_url: pick(|value| &value.base_url),
+        model: pick(|value| &value.model),
+        api_key: pick(|value| &value.api_key),
+        output_mode: pick(|value| &value.output_mode),
+        expose_bot_reasoning: layers.iter().find_map(|layer| layer.expose_bot_reasoning),
     };
 
     merged.normalized()
 }
 
+async fn set_game_llm_overrides(
+    state: &AppState,
+    game_id: &str,
+    overrides: HashMap<PlayerName, LlmProfile>,
+) {
+    let overrides: HashMap<PlayerName, LlmProfile> = overrides
+        .into_iter()
+        .filter_map(|(player_name, profile)| profile.normalized().map(|value| (player_name, value)))
+        .collect();
+    let mut game_llm_overrides = state.game_llm_overrides.lock().await;
+    if overrides.is_empty() {
+        game_llm_overrides.remove(game_id);
+    } else {
+        game_llm_overrides.insert(game_id.to_string(), overrides);
+    }
+}
+
 fn player_name_value(player_name: PlayerName) -> &'static str {
     match player_name {
         PlayerName::A => "A",
@@ -499,10 +654,12 @@ fn game_status_value(status: GameStatus) -> &'static str {
     match status {
         GameStatus::Created => "CREATED",
         GameStatus::Running => "RUNNING",
+        GameStatus::Paused => "PAUSED",
         GameStatus::Finished => "FINISHED",
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn upsert_bot_state_record(
     state: &AppState,
     payload: &BotCreateRequest,
@@ -680,6 +837,134 @@ async fn update_assignment_game_state(
     }
 }
 
+/// Rebuilds in-memory assignments for running games from the bot state table so
+/// a restarted bot-manager keeps forwarding step updates to existing bots.
+async fn recover_assignments(state: &AppState) -> anyhow::Result<usize> {
+    let Some(store) = state.bot_state_store.as_ref() else {
+        return Ok(0);
+    };
+
+    let mut rows = Vec::new();
+    let mut start_key = None;
+    loop {
+        let output = store
+            .client
+            .scan()
+            .table_name(&store.table_name)
+            .set_exclusive_start_key(start_key)
+            .send()
+            .await
+            .context("failed to scan bot state table")?;
+        rows.extend(output.items().iter().cloned());
+        start_key = output.last_evaluated_key().cloned();
+        if start_key.is_none() {
+            break;
+        }
+    }
+
+    let mut recovered = 0usize;
+    for (game_id, mut assignment) in assignments_from_rows(&rows) {
+        let game = match fetch_game(state, &game_id).await {
+            Ok(game) => game,
+            Err(error) => {
+                warn!(
+                    game_id = %game_id,
+                    error = %error.message,
+                    "skipping assignment recovery for game"
+                );
+                continue;
+            }
+        };
+        if !matches!(game.status, GameStatus::Running | GameStatus::Paused) {
+            continue;
+        }
+
+        assignment.humans = game
+            .state
+            .players
+            .iter()
+            .filter(|player| !assignment.bindings.contains_key(&player.player_id))
+            .map(|player| (player.player_id.clone(), player.player_name))
+            .collect();
+        {
+            let mut assignments = state.assignments.lock().await;
+            if assignments.contains_key(&game_id) {
+                continue;
+            }
+            assignments.insert(game_id.clone(), assignment);
+        }
+        recovered += 1;
+
+        if let Some(output_topic) = game.output_topic.as_deref()
+            && let Err(error) = ensure_game_topic_worker(state, &game_id, output_topic).await
+        {
+            warn!(
+                game_id = %game_id,
+                output_topic = %output_topic,
+                error = %error,
+                "failed to ensure per-game output consumer after recovery"
+            );
+        }
+    }
+
+    info!(
+        scanned_rows = rows.len(),
+        recovered_games = recovered,
+        "bot-manager recovered assignments from bot state table"
+    );
+    Ok(recovered)
+}
+
+fn assignments_from_rows(
+    rows: &[HashMap<String, AttributeValue>],
+) -> HashMap<String, GameAssignment> {
+    let mut assignments: HashMap<String, GameAssignment> = HashMap::new();
+    for row in rows {
+        if !matches!(row_string(row, "game_state"), Some("RUNNING" | "PAUSED"))
+            || row_string(row, "bot_status") != Some("READY")
+        {
+            continue;
+        }
+        let Some((game_id, binding)) = binding_from_row(row) else {
+            warn!(
+                game_id = ?row_string(row, "game_id"),
+                player_id = ?row_string(row, "player_id"),
+                "ignoring malformed bot state row during recovery"
+            );
+            continue;
+        };
+
+        assignments
+            .entry(game_id.clone())
+            .or_insert_with(|| GameAssignment {
+                game_id,
+                humans: HashMap::new(),
+                bindings: HashMap::new(),
+            })
+            .bindings
+            .insert(binding.player_id.clone(), binding);
+    }
+    assignments
+}
+
+fn binding_from_row(row: &HashMap<String, AttributeValue>) -> Option<(String, BotBinding)> {
+    let binding = BotBinding {
+        player_name: parse_player_name(row_string(row, "player_name")?)?,
+        player_id: row_string(row, "player_id")?.to_string(),
+        bot_id: row_string(row, "bot_id")?.to_string(),
+        bot_service_base_url: row_string(row, "bot_service_base_url")?.to_string(),
+        status: "READY".to_string(),
+        game_guide_version: row_string(row, "game_guide_version")?.to_string(),
+    };
+    Some((row_string(row, "game_id")?.to_string(), binding))
+}
+
+fn row_string<'a>(row: &'a HashMap<String, AttributeValue>, key: &str) -> Option<&'a str> {
+    row.get(key)
+        .and_then(|value| value.as_s().ok())
+        .map(String::as_str)
+}
+
 async fn health() -> Json<serde_json::Value> {
     Json(serde_json::json!({"ok": true, "service": "bot-manager-service"}))
 }
@@ -694,6 +979,9 @@ async fn default_assignment_handler(
         .game_guide_version
         .unwrap_or_else(|| state.default_game_guide_version.clone());
     let force_recreate = request.force_recreate.unwrap_or(false);
+    if let Some(overrides) = request.llm_overrides {
+        set_game_llm_overrides(&state, &game_id, overrides).await;
+    }
 
     let assignment = assign_default_for_game(
         &state,
@@ -723,6 +1011,9 @@ async fn assignments_handler(
         .game_guide_version
         .unwrap_or_else(|| state.default_game_guide_version.clone());
     let force_recreate = request.force_recreate.unwrap_or(false);
+    if let Some(overrides) = request.llm_overrides {
+        set_game_llm_overrides(&state, &game_id, overrides).await;
+    }
 
     let humans: HashSet<String> = request.human_player_ids.into_iter().collect();
     let bots: HashSet<String> = request.bot_player_ids.into_iter().collect();
@@ -855,7 +1146,7 @@ async fn bind_bot_handler(
         .await;
     }
 
-    if game.status == GameStatus::Running
+    if matches!(game.status, GameStatus::Running | GameStatus::Paused)
         && let Some(output_topic) = game.output_topic.as_deref()
         && let Err(error) = ensure_game_topic_worker(&state, &game.game_id, output_topic).await
     {
@@ -906,6 +1197,288 @@ async fn stop_bots_handler(
     }))
 }
 
+async fn rebalance_handler(
+    State(state): State<AppState>,
+) -> Result<Json<RebalanceResponse>, ApiError> {
+    if state.bot_service_base_urls.is_empty() {
+        return Err(ApiError::bad_gateway(
+            "no bot-service instance configured (BOT_SERVICE_BASE_URLS)",
+        ));
+    }
+
+    let bindings: Vec<(String, BotBinding)> = {
+        let assignments = state.assignments.lock().await;
+        assignments
+            .values()
+            .flat_map(|assignment| {
+                assignment
+                    .bindings
+                    .values()
+                    .map(|binding| (assignment.game_id.clone(), binding.clone()))
+            })
+            .collect()
+    };
+
+    let mut moved = Vec::new();
+    let mut failed = Vec::new();
+    for bot_move in plan_rebalance(&state.bot_service_base_urls, &bindings) {
+        match migrate_bot(&state, &bot_move).await {
+            Ok(()) => {
+                info!(
+                    game_id = %bot_move.game_id,
+                    player_id = %bot_move.player_id,
+                    bot_id = %bot_move.bot_id,
+                    from = %bot_move.from_base_url,
+                    to = %bot_move.to_base_url,
+                    "bot-manager moved bot to another bot-service instance"
+                );
+                moved.push(bot_move);
+            }
+            Err(error) => {
+                warn!(
+                    game_id = %bot_move.game_id,
+                    player_id = %bot_move.player_id,
+                    bot_id = %bot_move.bot_id,
+                    error = %error.message,
+                    "failed to move bot during rebalance"
+                );
+                failed.push(RebalanceFailure {
+                    bot_move,
+                    error: error.message,
+                });
+            }
+        }
+    }
+
+    Ok(Json(RebalanceResponse { moved, failed }))
+}
+
+/// Plans the moves needed so no instance holds more than its even share
+/// (`ceil(total / instances)`) of bots. Bots bound to an instance that is no
+/// longer configured are always moved.
+fn plan_rebalance(instance_urls: &[String], bindings: &[(String, BotBinding)]) -> Vec<BotMove> {
+    if instance_urls.is_empty() {
+        return Vec::new();
+    }
+
+    let mut ordered: Vec<&(String, BotBinding)> = bindings.iter().collect();
+    ordered.sort_by(|left, right| {
+        left.0
+            .cmp(&right.0)
+            .then_with(|| left.1.player_id.cmp(&right.1.player_id))
+    });
+
+    let share = ordered.len().div_ceil(instance_urls.len());
+    let mut loads: Vec<usize> = vec![0; instance_urls.len()];
+    let mut displaced: Vec<&(String, BotBinding)> = Vec::new();
+    for entry in ordered {
+        match instance_urls
+            .iter()
+            .position(|url| *url == entry.1.bot_service_base_url)
+        {
+            Some(index) if loads[index] < share => loads[index] += 1,
+            _ => displaced.push(entry),
+        }
+    }
+
+    let mut moves = Vec::with_capacity(displaced.len());
+    for (game_id, binding) in displaced {
+        let Some(target) = (0..instance_urls.len()).min_by_key(|index| loads[*index]) else {
+            break;
+        };
+        loads[target] += 1;
+        moves.push(BotMove {
+            game_id: game_id.clone(),
+            player_id: binding.player_id.clone(),
+            bot_id: binding.bot_id.clone(),
+            from_base_url: binding.bot_service_base_url.clone(),
+            to_base_url: instance_urls[target].clone(),
+        });
+    }
+    moves
+}
+
+/// Moves one bot without a gap in play: the copy on the target is created and
+/// taught while the source keeps playing, the binding is swapped over, and
+/// only then is the source copy deleted. A failure before the swap deletes
+/// the target copy and leaves the bot where it was.
+async fn migrate_bot(state: &AppState, bot_move: &BotMove) -> Result<(), ApiError> {
+    let game = fetch_game(state, &bot_move.game_id).await?;
+    let binding = {
+        let assignments = state.assignments.lock().await;
+        assignments
+            .get(&bot_move.game_id)
+            .and_then(|assignment| assignment.bindings.get(&bot_move.player_id).cloned())
+    }
+    .ok_or_else(|| {
+        ApiError::not_found(format!(
+            "player {} is no longer bound in game {}",
+            bot_move.player_id, bot_move.game_id
+        ))
+    })?;
+
+    let create_payload = bot_create_request(
+        state,
+        &game,
+        binding.player_name,
+        &binding.player_id,
+        Some(binding.bot_id.clone()),
+    )
+    .await?;
+    let bot_id = create_bot(state, &bot_move.to_base_url, &create_payload)
+        .await?
+        .bot_id;
+
+    let prepared = prepare_moved_bot(
+        state,
+        &game,
+        &binding,
+        &create_payload,
+        &bot_id,
+        &bot_move.to_base_url,
+    )
+    .await;
+    if let Err(error) = prepared {
+        discard_moved_bot(
+            state,
+            bot_move,
+            &binding,
+            &create_payload,
+            &bot_id,
+            game.status,
+        )
+        .await;
+        return Err(error);
+    }
+
+    let swapped = {
+        // Step updates wait here so none is mid-flight to the old instance.
+        let _paused = state.forwarding_gate.write().await;
+        let mut assignments = state.assignments.lock().await;
+        repoint_binding(&mut assignments, bot_move, &bot_id)
+    };
+    if !swapped {
+        discard_moved_bot(
+            state,
+            bot_move,
+            &binding,
+            &create_payload,
+            &bot_id,
+            game.status,
+        )
+        .await;
+        return Err(ApiError::not_found(format!(
+            "player {} was rebound in game {} while its bot was moving",
+            bot_move.player_id, bot_move.game_id
+        )));
+    }
+
+    if let Err(error) = delete_bot(state, &bot_move.from_base_url, &binding.bot_id).await {
+        warn!(
+            game_id = %bot_move.game_id,
+            bot_id = %binding.bot_id,
+            from = %bot_move.from_base_url,
+            error = %error.message,
+            "moved bot but failed to delete its copy on the source instance"
+        );
+    }
+    Ok(())
+}
+
+/// Records and teaches the freshly created target copy.
+async fn prepare_moved_bot(
+    state: &AppState,
+    game: &GameInstanceResponse,
+    binding: &BotBinding,
+    create_payload: &BotCreateRequest,
+    bot_id: &str,
+    to_base_url: &str,
+) -> Result<(), ApiError> {
+    upsert_bot_state_record(
+        state,
+        create_payload,
+        bot_id,
+        to_base_url,
+        &binding.game_guide_version,
+        "CREATED",
+        "BOT_REBALANCED",
+        game.status,
+    )
+    .await?;
+    teach_game(state, to_base_url, bot_id, &binding.game_guide_version).await?;
+    update_bot_state_record(
+        state,
+        &game.game_id,
+        &binding.player_id,
+        "READY",
+        "BOT_READY",
+        game.status,
+    )
+    .await
+}
+
+/// Rolls back a move that failed before the swap: deletes the target copy
+/// and points the bot's record back at the source instance.
+async fn discard_moved_bot(
+    state: &AppState,
+    bot_move: &BotMove,
+    binding: &BotBinding,
+    create_payload: &BotCreateRequest,
+    bot_id: &str,
+    game_status: GameStatus,
+) {
+    if let Err(error) = delete_bot(state, &bot_move.to_base_url, bot_id).await {
+        warn!(
+            game_id = %bot_move.game_id,
+            bot_id = %bot_id,
+            to = %bot_move.to_base_url,
+            error = %error.message,
+            "failed to delete target copy of a bot whose move was rolled back"
+        );
+    }
+    if let Err(error) = upsert_bot_state_record(
+        state,
+        create_payload,
+        &binding.bot_id,
+        &bot_move.from_base_url,
+        &binding.game_guide_version,
+        &binding.status,
+        "BOT_READY",
+        game_status,
+    )
+    .await
+    {
+        warn!(
+            game_id = %bot_move.game_id,
+            bot_id = %binding.bot_id,
+            error = %error.message,
+            "failed to restore bot state record after a rolled back move"
+        );
+    }
+}
+
+/// Points the moving binding at its new instance. False when the binding is
+/// gone or no longer the one the move started from.
+fn repoint_binding(
+    assignments: &mut HashMap<String, GameAssignment>,
+    bot_move: &BotMove,
+    bot_id: &str,
+) -> bool {
+    let Some(current) = assignments
+        .get_mut(&bot_move.game_id)
+        .and_then(|assignment| assignment.bindings.get_mut(&bot_move.player_id))
+    else {
+        return false;
+    };
+    if current.bot_id != bot_move.bot_id || current.bot_service_base_url != bot_move.from_base_url {
+        return false;
+    }
+    current.bot_id = bot_id.to_string();
+    current.bot_service_base_url = bot_move.to_base_url.clone();
+    current.status = "READY".to_string();
+    true
+}
+
 async fn assign_default_for_game(
     state: &AppState,
     game_id: &str,
@@ -1048,7 +1621,7 @@ async fn assign_players_for_game(
         assignments.insert(game.game_id.clone(), assignment.clone());
     }
 
-    if game.status == GameStatus::Running
+    if matches!(game.status, GameStatus::Running | GameStatus::Paused)
         && let Some(output_topic) = game.output_topic.as_deref()
         && let Err(error) = ensure_game_topic_worker(state, &game.game_id, output_topic).await
     {
@@ -1063,6 +1636,7 @@ async fn assign_players_for_game(
     Ok(assignment)
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn ensure_binding(
     state: &AppState,
     game: &GameInstanceResponse,
@@ -1073,14 +1647,8 @@ async fn ensure_binding(
     force_recreate: bool,
     pending_bindings: &HashMap<PlayerId, BotBinding>,
 ) -> Result<BotBinding, ApiError> {
-    let input_topic = game
-        .input_topic
-        .clone()
-        .ok_or_else(|| ApiError::bad_gateway("game has no input_topic"))?;
-    let output_topic = game
-        .output_topic
-        .clone()
-        .ok_or_else(|| ApiError::bad_gateway("game has no output_topic"))?;
+    let create_payload =
+        bot_create_request(state, game, player_name, player_id, desired_bot_id).await?;
 
     let maybe_existing = {
         let assignments = state.assignments.lock().await;
@@ -1116,43 +1684,13 @@ async fn ensure_binding(
     let preferred_instance_url = maybe_existing
         .as_ref()
         .map(|binding| binding.bot_service_base_url.as_str());
-    let bot_service_base_url =
-        select_bot_service_base_url(state, preferred_instance_url, pending_bindings).await?;
-
-    let llm_profile = resolve_llm_profile(&state.llm_profiles, player_name);
-    let create_payload = BotCreateRequest {
-        bot_id: desired_bot_id.clone(),
-        game_id: game.game_id.clone(),
-        player_name,
-        player_id: player_id.to_string(),
-        input_topic,
-        output_topic,
-        llm_base_url: llm_profile
-            .as_ref()
-            .and_then(|profile| profile.base_url.clone()),
-        llm_model: llm_profile
-            .as_ref()
-            .and_then(|profile| profile.model.clone()),
-        llm_api_key: llm_profile
-            .as_ref()
-            .and_then(|profile| profile.api_key.clone()),
-        llm_output_mode: llm_profile
-            .as_ref()
-            .and_then(|profile| profile.output_mode.clone()),
-    };
-
-    let bot_id = match create_bot(state, &bot_service_base_url, &create_payload).await {
-        Ok(response) => response.bot_id,
-        Err(error) => {
-            if let Some(id) = desired_bot_id.clone() {
-                // Existing bot id may already exist; treat that as attach and continue to teach.
-                warn!(bot_id = %id, error = %error.message, "bot create failed; attempting to continue with existing bot id");
-                id
-            } else {
-                return Err(error);
-            }
-        }
-    };
+    let (bot_service_base_url, bot_id) = create_bot_on_open_instance(
+        state,
+        preferred_instance_url,
+        pending_bindings,
+        &create_payload,
+    )
+    .await?;
 
     upsert_bot_state_record(
         state,
@@ -1188,6 +1726,98 @@ async fn ensure_binding(
     })
 }
 
+async fn bot_create_request(
+    state: &AppState,
+    game: &GameInstanceResponse,
+    player_name: PlayerName,
+    player_id: &str,
+    bot_id: Option<String>,
+) -> Result<BotCreateRequest, ApiError> {
+    let input_topic = game
+        .input_topic
+        .clone()
+        .ok_or_else(|| ApiError::bad_gateway("game has no input_topic"))?;
+    let output_topic = game
+        .output_topic
+        .clone()
+        .ok_or_else(|| ApiError::bad_gateway("game has no output_topic"))?;
+
+    let game_override = {
+        let game_llm_overrides = state.game_llm_overrides.lock().await;
+        game_llm_overrides
+            .get(&game.game_id)
+            .and_then(|overrides| overrides.get(&player_name))
+            .cloned()
+    };
+    let llm_profile = resolve_llm_profile(&state.llm_profiles, game_override.as_ref(), player_name);
+    Ok(BotCreateRequest {
+        bot_id,
+        game_id: game.game_id.clone(),
+        player_name,
+        player_id: player_id.to_string(),
+        input_topic,
+        output_topic,
+        llm_base_url: llm_profile
+            .as_ref()
+            .and_then(|profile| profile.base_url.clone()),
+        llm_model: llm_profile
+            .as_ref()
+            .and_then(|profile| profile.model.clone()),
+        llm_api_key: llm_profile
+            .as_ref()
+            .and_then(|profile| profile.api_key.clone()),
+        llm_output_mode: llm_profile
+            .as_ref()
+            .and_then(|profile| profile.output_mode.clone()),
+        expose_bot_reasoning: llm_profile
+            .as_ref()
+            .and_then(|profile| profile.expose_bot_reasoning),
+    })
+}
+
+/// Creates the bot on the instance `select_bot_service_base_url` picks,
+/// moving on to the next one while instances answer AT_CAPACITY.
+async fn create_bot_on_open_instance(
+    state: &AppState,
+    preferred_base_url: Option<&str>,
+    pending_bindings: &HashMap<PlayerId, BotBinding>,
+    payload: &BotCreateRequest,
+) -> Result<(String, String), ApiError> {
+    let mut full_instances = HashSet::new();
+    loop {
+        let bot_service_base_url = match select_bot_service_base_url(
+            state,
+            preferred_base_url,
+            pending_bindings,
+            &full_instances,
+        )
+        .await
+        {
+            Ok(url) => url,
+            Err(_) if !full_instances.is_empty() => {
+                return Err(ApiError::at_capacity(
+                    "every bot-service instance is at capacity",
+                ));
+            }
+            Err(error) => return Err(error),
+        };
+        match create_bot(state, &bot_service_base_url, payload).await {
+            Ok(response) => return Ok((bot_service_base_url, response.bot_id)),
+            Err(error) if error.code == "AT_CAPACITY" => {
+                warn!(
+                    bot_service_base_url = %bot_service_base_url,
+                    error = %error.message,
+                    "bot-service instance is at capacity; trying the next one"
+                );
+                full_instances.insert(bot_service_base_url);
+            }
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+/// A 409 means the requested bot id already exists there, which counts as
+/// attaching to it.
 async fn create_bot(
     state: &AppState,
     bot_service_base_url: &str,
@@ -1213,6 +1843,14 @@ async fn create_bot(
     if !response.status().is_success() {
         let status = response.status();
         let body = response.text().await.unwrap_or_else(|_| "".to_string());
+        let at_capacity = status == StatusCode::SERVICE_UNAVAILABLE
+            && serde_json::from_str::<serde_json::Value>(&body)
+                .is_ok_and(|error| error["error"]["code"] == "AT_CAPACITY");
+        if at_capacity {
+            return Err(ApiError::at_capacity(format!(
+                "bot-service {bot_service_base_url} is at capacity: {body}"
+            )));
+        }
         return Err(ApiError::bad_gateway(format!(
             "bot create returned {}: {}",
             status, body
@@ -1237,16 +1875,9 @@ async fn teach_game(
     );
     let payload = TeachGameRequest {
         game_guide_version: guide_version.to_string(),
-        rules_markdown: default_rules_markdown(),
-        command_schema: serde_json::json!({
-            "allowed": ["move", "shoot", "shield", "speak"],
-            "direction_required_for": ["move", "shoot", "shield"],
-            "speak_text_required_for": ["speak"]
-        }),
-        examples: vec![
-            serde_json::json!({"command_type":"move","direction":"up"}),
-            serde_json::json!({"command_type":"speak","speak_text":"Watch this turn."}),
-        ],
+        rules_markdown: state.game_guide.rules_markdown.clone(),
+        command_schema: state.game_guide.command_schema.clone(),
+        examples: state.game_guide.examples.clone(),
     };
 
     let response = state
@@ -1384,13 +2015,14 @@ fn player_sort_key(player_name: PlayerName) -> u8 {
 }
 
 fn default_rules_markdown() -> String {
-    "Cowboy game rules: one command per turn; valid commands are move, shoot, shield, speak. Timeouts advance turn. Late commands are ignored by game service but recorded.".to_string()
+    "Cowboy game rules: one command per turn; valid commands are move, shoot, shield, speak, pass (end the turn without acting), charge (spend the turn so your next shot deals 2 damage and pierces one wall; lost if you move or are hit). Timeouts advance turn. Late commands are ignored by game service but recorded.".to_string()
 }
 
 async fn select_bot_service_base_url(
     state: &AppState,
     preferred_base_url: Option<&str>,
     pending_bindings: &HashMap<PlayerId, BotBinding>,
+    full_instances: &HashSet<String>,
 ) -> Result<String, ApiError> {
     if state.bot_service_base_urls.is_empty() {
         return Err(ApiError::bad_gateway(
@@ -1422,12 +2054,14 @@ async fn select_bot_service_base_url(
             .or_insert(0usize) += 1;
     }
 
-    if let Some(preferred) = preferred_base_url {
-        if !preferred.trim().is_empty() {
-            loads.entry(preferred.to_string()).or_insert(0usize);
-            return Ok(preferred.to_string());
-        }
+    if let Some(preferred) = preferred_base_url
+        && !preferred.trim().is_empty()
+        && !full_instances.contains(preferred)
+    {
+        loads.entry(preferred.to_string()).or_insert(0usize);
+        return Ok(preferred.to_string());
     }
+    loads.retain(|url, _| !full_instances.contains(url));
 
     let mut ranked: Vec<(String, usize)> = loads.into_iter().collect();
     ranked.sort_by_key(|entry| entry.1);
@@ -1479,12 +2113,17 @@ async fn run_output_consumer(state: AppState) -> anyhow::Result<()> {
         "bot-manager control consumer subscribed to output topics"
     );
 
+    let mut backoff = Backoff::default();
+
     loop {
         let message = match consumer.recv().await {
-            Ok(message) => message,
+            Ok(message) => {
+                backoff.reset();
+                message
+            }
             Err(error) => {
                 warn!(?error, "bot-manager kafka recv error");
-                tokio::time::sleep(Duration::from_millis(300)).await;
+                tokio::time::sleep(backoff.next_delay()).await;
                 continue;
             }
         };
@@ -1511,8 +2150,7 @@ async fn run_output_consumer(state: AppState) -> anyhow::Result<()> {
             if let Err(error) = on_game_started(&state, &step.game_id).await {
                 warn!(game_id = %step.game_id, error = %error.message, "bot-manager failed to reconcile GAME_STARTED");
             }
-            if let Err(error) =
-                ensure_game_topic_worker(&state, &step.game_id, &output_topic).await
+            if let Err(error) = ensure_game_topic_worker(&state, &step.game_id, &output_topic).await
             {
                 warn!(
                     game_id = %step.game_id,
@@ -1524,13 +2162,8 @@ async fn run_output_consumer(state: AppState) -> anyhow::Result<()> {
         }
 
         if step.event_type == StepEventType::GameFinished {
-            let destroyed = stop_bots_for_game(
-                &state,
-                &step.game_id,
-                Some(GameStatus::Finished),
-                true,
-            )
-            .await;
+            let destroyed =
+                stop_bots_for_game(&state, &step.game_id, Some(GameStatus::Finished), true).await;
             info!(
                 game_id = %step.game_id,
                 destroyed_bot_count = destroyed,
@@ -1644,6 +2277,8 @@ async fn run_game_topic_worker(
         "bot-manager per-game consumer subscribed"
     );
 
+    let mut backoff = Backoff::default();
+
     loop {
         tokio::select! {
             _ = &mut stop_rx => {
@@ -1651,10 +2286,13 @@ async fn run_game_topic_worker(
             }
             message = consumer.recv() => {
                 let message = match message {
-                    Ok(message) => message,
+                    Ok(message) => {
+                        backoff.reset();
+                        message
+                    }
                     Err(error) => {
                         warn!(game_id = %game_id, output_topic = %output_topic, ?error, "bot-manager per-game kafka recv error");
-                        tokio::time::sleep(Duration::from_millis(250)).await;
+                        tokio::time::sleep(backoff.next_delay()).await;
                         continue;
                     }
                 };
@@ -1721,6 +2359,16 @@ async fn run_game_topic_worker(
 }
 
 async fn forward_step_updates_for_game(state: &AppState, game_id: &str, step: &StepEvent) {
+    // Bots publish thinking and reasoning markers themselves; they're for
+    // viewers only.
+    if matches!(
+        step.event_type,
+        StepEventType::BotThinking | StepEventType::BotReasoning
+    ) {
+        return;
+    }
+    // Held for the whole fan-out so a rebalance never moves a bot mid-update.
+    let _forwarding = state.forwarding_gate.read().await;
     let assignment = {
         let assignments = state.assignments.lock().await;
         assignments.get(game_id).cloned()
@@ -1783,6 +2431,7 @@ async fn stop_bots_for_game(
     if stop_topic_worker {
         stop_game_topic_worker(state, game_id).await;
     }
+    state.game_llm_overrides.lock().await.remove(game_id);
 
     let assignment = {
         let mut assignments = state.assignments.lock().await;
@@ -1828,6 +2477,8 @@ async fn stop_bots_for_game(
 #[derive(Debug)]
 struct ApiError {
     status: StatusCode,
+    /// Stable machine-readable error code, e.g. `NOT_FOUND`.
+    code: &'static str,
     message: String,
 }
 
@@ -1835,6 +2486,7 @@ impl ApiError {
     fn bad_request(message: impl Into<String>) -> Self {
         Self {
             status: StatusCode::BAD_REQUEST,
+            code: "BAD_REQUEST",
             message: message.into(),
         }
     }
@@ -1842,6 +2494,7 @@ impl ApiError {
     fn not_found(message: impl Into<String>) -> Self {
         Self {
             status: StatusCode::NOT_FOUND,
+            code: "NOT_FOUND",
             message: message.into(),
         }
     }
@@ -1849,6 +2502,15 @@ impl ApiError {
     fn bad_gateway(message: impl Into<String>) -> Self {
         Self {
             status: StatusCode::BAD_GATEWAY,
+            code: "BAD_GATEWAY",
+            message: message.into(),
+        }
+    }
+
+    fn at_capacity(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            code: "AT_CAPACITY",
             message: message.into(),
         }
     }
@@ -1856,11 +2518,434 @@ impl ApiError {
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
-        warn!(status = %self.status, message = %self.message, "bot-manager request failed");
+        warn!(status = %self.status, code = self.code, message = %self.message, "bot-manager request failed");
         (
             self.status,
-            Json(serde_json::json!({"error": self.message})),
+            Json(serde_json::json!({
+                "error": {"code": self.code, "message": self.message}
+            })),
         )
             .into_response()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binding(player_name: PlayerName, player_id: &str, base_url: &str) -> BotBinding {
+        BotBinding {
+            player_name,
+            player_id: player_id.to_string(),
+            bot_id: format!("bot-{player_id}"),
+            bot_service_base_url: base_url.to_string(),
+            status: "READY".to_string(),
+            game_guide_version: "v1".to_string(),
+        }
+    }
+
+    fn fleet() -> Vec<String> {
+        vec![
+            "http://bot-service-0:8091".to_string(),
+            "http://bot-service-1:8091".to_string(),
+        ]
+    }
+
+    fn test_state(bot_service_base_urls: Vec<String>) -> AppState {
+        AppState {
+            assignments: Arc::new(Mutex::new(HashMap::new())),
+            game_topic_workers: Arc::new(Mutex::new(HashMap::new())),
+            client: reqwest::Client::new(),
+            manager_base_url: "http://game-manager-service:8081".to_string(),
+            bot_service_base_urls,
+            bots_per_instance_capacity: 8,
+            llm_profiles: LlmProfilesConfig::default(),
+            bot_state_store: None,
+            bootstrap_servers: "127.0.0.1:9".to_string(),
+            output_topic_prefix: "game.output".to_string(),
+            consumer_group_id: "bot-manager-test".to_string(),
+            default_game_guide_version: "v1".to_string(),
+            game_guide: Arc::new(GameGuide::default()),
+            game_llm_overrides: Arc::new(Mutex::new(HashMap::new())),
+            forwarding_gate: Arc::new(RwLock::new(())),
+        }
+    }
+
+    /// A bot-service that answers every create with `status` and `body`,
+    /// counting the calls.
+    async fn spawn_bot_service(
+        status: StatusCode,
+        body: serde_json::Value,
+        creates: Arc<std::sync::atomic::AtomicUsize>,
+    ) -> String {
+        let app = Router::new().route(
+            "/internal/v3/bots",
+            post(move || async move {
+                creates.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                (status, Json(body))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{addr}")
+    }
+
+    fn create_request(bot_id: Option<&str>) -> BotCreateRequest {
+        BotCreateRequest {
+            bot_id: bot_id.map(str::to_string),
+            game_id: "g-1".to_string(),
+            player_name: PlayerName::B,
+            player_id: "p-b".to_string(),
+            input_topic: "game.commands.g-1.v1".to_string(),
+            output_topic: "game.output.g-1.v1".to_string(),
+            llm_base_url: None,
+            llm_model: None,
+            llm_api_key: None,
+            llm_output_mode: None,
+            expose_bot_reasoning: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn bot_create_moves_past_a_full_instance_but_not_other_failures() {
+        let at_capacity = serde_json::json!({"error": {"code": "AT_CAPACITY", "message": "full"}});
+        let full_creates = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let full = spawn_bot_service(
+            StatusCode::SERVICE_UNAVAILABLE,
+            at_capacity.clone(),
+            full_creates.clone(),
+        )
+        .await;
+        let open_creates = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let open = spawn_bot_service(
+            StatusCode::OK,
+            serde_json::json!({"bot_id": "bot-new"}),
+            open_creates.clone(),
+        )
+        .await;
+        let state = test_state(vec![full.clone(), open.clone()]);
+
+        let (base_url, bot_id) = create_bot_on_open_instance(
+            &state,
+            Some(&full),
+            &HashMap::new(),
+            &create_request(None),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            (base_url.as_str(), bot_id.as_str()),
+            (open.as_str(), "bot-new")
+        );
+        assert_eq!(full_creates.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(open_creates.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        let all_full = test_state(vec![full.clone()]);
+        let error = create_bot_on_open_instance(
+            &all_full,
+            None,
+            &HashMap::new(),
+            &create_request(Some("bot-1")),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error.code, "AT_CAPACITY");
+
+        // A failure other than a conflict is not an attach, even for a
+        // requested bot id.
+        let broken = spawn_bot_service(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            serde_json::json!({"error": {"code": "INTERNAL", "message": "boom"}}),
+            Arc::default(),
+        )
+        .await;
+        let error = create_bot_on_open_instance(
+            &test_state(vec![broken]),
+            None,
+            &HashMap::new(),
+            &create_request(Some("bot-1")),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error.status, StatusCode::BAD_GATEWAY);
+
+        let conflicted = spawn_bot_service(
+            StatusCode::CONFLICT,
+            serde_json::json!({"error": {"code": "CONFLICT", "message": "exists"}}),
+            Arc::default(),
+        )
+        .await;
+        let (_, bot_id) = create_bot_on_open_instance(
+            &test_state(vec![conflicted]),
+            None,
+            &HashMap::new(),
+            &create_request(Some("bot-1")),
+        )
+        .await
+        .unwrap();
+        assert_eq!(bot_id, "bot-1");
+    }
+
+    fn row(
+        game_id: &str,
+        player_id: &str,
+        bot_status: &str,
+        game_state: &str,
+    ) -> HashMap<String, AttributeValue> {
+        [
+            ("game_id", game_id.to_string()),
+            ("player_id", player_id.to_string()),
+            ("bot_id", format!("bot-{player_id}")),
+            ("player_name", "B".to_string()),
+            (
+                "bot_service_base_url",
+                "http://bot-service:8091".to_string(),
+            ),
+            ("game_guide_version", "v1".to_string()),
+            ("bot_status", bot_status.to_string()),
+            ("game_state", game_state.to_string()),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), AttributeValue::S(value)))
+        .collect()
+    }
+
+    #[test]
+    fn assignments_from_rows_rebuilds_live_ready_bindings() {
+        let mut malformed = row("game-1", "p-x", "READY", "RUNNING");
+        malformed.remove("bot_id");
+        let rows = vec![
+            row("game-1", "p-b", "READY", "RUNNING"),
+            row("game-1", "p-c", "READY", "RUNNING"),
+            row("game-1", "p-d", "STOPPED", "RUNNING"),
+            row("game-2", "p-b", "READY", "FINISHED"),
+            row("game-3", "p-b", "CREATED", "RUNNING"),
+            row("game-4", "p-c", "READY", "PAUSED"),
+            malformed,
+        ];
+
+        let assignments = assignments_from_rows(&rows);
+
+        assert_eq!(assignments.len(), 2);
+        assert!(assignments["game-4"].bindings.contains_key("p-c"));
+        let assignment = &assignments["game-1"];
+        assert_eq!(assignment.game_id, "game-1");
+        assert!(assignment.humans.is_empty());
+        let mut player_ids: Vec<&str> = assignment.bindings.keys().map(String::as_str).collect();
+        player_ids.sort();
+        assert_eq!(player_ids, vec!["p-b", "p-c"]);
+        let binding = &assignment.bindings["p-b"];
+        assert_eq!(binding.player_name, PlayerName::B);
+        assert_eq!(binding.bot_id, "bot-p-b");
+        assert_eq!(binding.bot_service_base_url, "http://bot-service:8091");
+        assert_eq!(binding.status, "READY");
+        assert_eq!(binding.game_guide_version, "v1");
+    }
+
+    fn write_temp_file(name: &str, contents: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("bot-manager-{}-{name}", std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn game_guide_file_overrides_defaults() {
+        let path = write_temp_file(
+            "guide.yaml",
+            "rules_markdown: Custom rules.\nexamples:\n  - command_type: pass\n",
+        );
+
+        let guide = load_game_guide_from_path(path.to_str());
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(guide.rules_markdown, "Custom rules.");
+        assert_eq!(
+            guide.examples,
+            vec![serde_json::json!({"command_type": "pass"})]
+        );
+        assert_eq!(guide.command_schema, GameGuide::default().command_schema);
+    }
+
+    #[test]
+    fn markdown_game_guide_replaces_rules_only() {
+        let path = write_temp_file("guide.md", "# Rules\n\nShoot first.\n");
+
+        let guide = load_game_guide_from_path(path.to_str());
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(guide.rules_markdown, "# Rules\n\nShoot first.\n");
+        assert_eq!(guide.examples, GameGuide::default().examples);
+    }
+
+    #[test]
+    fn missing_game_guide_file_falls_back_to_defaults() {
+        assert_eq!(
+            load_game_guide_from_path(Some("/nonexistent/bot-game-guide.yaml")),
+            GameGuide::default()
+        );
+        assert_eq!(load_game_guide_from_path(None), GameGuide::default());
+    }
+
+    fn profile(model: &str, base_url: Option<&str>) -> LlmProfile {
+        LlmProfile {
+            base_url: base_url.map(str::to_string),
+            model: Some(model.to_string()),
+            api_key: None,
+            output_mode: None,
+            expose_bot_reasoning: None,
+        }
+    }
+
+    #[test]
+    fn game_llm_override_beats_player_and_default_config() {
+        let config = LlmProfilesConfig {
+            default: Some(profile("default-model", Some("http://default-llm"))),
+            players: HashMap::from([(PlayerName::B, profile("player-model", None))]),
+        };
+        let game_override = profile("override-model", None);
+
+        let resolved = resolve_llm_profile(&config, Some(&game_override), PlayerName::B).unwrap();
+        assert_eq!(resolved.model.as_deref(), Some("override-model"));
+        assert_eq!(resolved.base_url.as_deref(), Some("http://default-llm"));
+
+        let resolved = resolve_llm_profile(&config, None, PlayerName::B).unwrap();
+        assert_eq!(resolved.model.as_deref(), Some("player-model"));
+        let resolved = resolve_llm_profile(&config, None, PlayerName::C).unwrap();
+        assert_eq!(resolved.model.as_deref(), Some("default-model"));
+    }
+
+    #[test]
+    fn plan_rebalance_moves_bots_off_over_capacity_instance() {
+        let urls = fleet();
+        let bindings = vec![
+            (
+                "game-1".to_string(),
+                binding(PlayerName::A, "p-a", &urls[0]),
+            ),
+            (
+                "game-1".to_string(),
+                binding(PlayerName::B, "p-b", &urls[0]),
+            ),
+            (
+                "game-2".to_string(),
+                binding(PlayerName::A, "p-c", &urls[0]),
+            ),
+            (
+                "game-2".to_string(),
+                binding(PlayerName::B, "p-d", &urls[0]),
+            ),
+        ];
+
+        let moves = plan_rebalance(&urls, &bindings);
+
+        assert_eq!(moves.len(), 2);
+        for bot_move in &moves {
+            assert_eq!(bot_move.from_base_url, urls[0]);
+            assert_eq!(bot_move.to_base_url, urls[1]);
+            assert_eq!(bot_move.bot_id, format!("bot-{}", bot_move.player_id));
+        }
+        let moved: Vec<&str> = moves.iter().map(|m| m.player_id.as_str()).collect();
+        assert_eq!(moved, vec!["p-c", "p-d"]);
+    }
+
+    #[test]
+    fn repoint_binding_swaps_only_the_binding_the_move_started_from() {
+        let fleet = fleet();
+        let bot_move = BotMove {
+            game_id: "g1".to_string(),
+            player_id: "p1".to_string(),
+            bot_id: "bot-p1".to_string(),
+            from_base_url: fleet[0].clone(),
+            to_base_url: fleet[1].clone(),
+        };
+        let mut assignments = HashMap::from([(
+            "g1".to_string(),
+            GameAssignment {
+                game_id: "g1".to_string(),
+                humans: HashMap::new(),
+                bindings: HashMap::from([(
+                    "p1".to_string(),
+                    binding(PlayerName::B, "p1", &fleet[0]),
+                )]),
+            },
+        )]);
+
+        assert!(repoint_binding(&mut assignments, &bot_move, "bot-p1-moved"));
+        let moved = &assignments["g1"].bindings["p1"];
+        assert_eq!(moved.bot_id, "bot-p1-moved");
+        assert_eq!(moved.bot_service_base_url, fleet[1]);
+
+        // The binding no longer matches the move, so a retry leaves it alone.
+        assert!(!repoint_binding(
+            &mut assignments,
+            &bot_move,
+            "bot-p1-again"
+        ));
+        assert_eq!(assignments["g1"].bindings["p1"].bot_id, "bot-p1-moved");
+
+        assignments.clear();
+        assert!(!repoint_binding(
+            &mut assignments,
+            &bot_move,
+            "bot-p1-moved"
+        ));
+    }
+
+    #[test]
+    fn plan_rebalance_is_a_no_op_for_balanced_fleet() {
+        let urls = fleet();
+        let bindings = vec![
+            (
+                "game-1".to_string(),
+                binding(PlayerName::A, "p-a", &urls[0]),
+            ),
+            (
+                "game-1".to_string(),
+                binding(PlayerName::B, "p-b", &urls[1]),
+            ),
+            (
+                "game-1".to_string(),
+                binding(PlayerName::C, "p-c", &urls[0]),
+            ),
+        ];
+
+        assert!(plan_rebalance(&urls, &bindings).is_empty());
+    }
+
+    #[test]
+    fn plan_rebalance_moves_bots_from_unconfigured_instances() {
+        let urls = fleet();
+        let bindings = vec![
+            (
+                "game-1".to_string(),
+                binding(PlayerName::A, "p-a", &urls[0]),
+            ),
+            (
+                "game-1".to_string(),
+                binding(PlayerName::B, "p-b", "http://retired:8091"),
+            ),
+        ];
+
+        let moves = plan_rebalance(&urls, &bindings);
+
+        assert_eq!(moves.len(), 1);
+        assert_eq!(moves[0].player_id, "p-b");
+        assert_eq!(moves[0].to_base_url, urls[1]);
+    }
+
+    #[tokio::test]
+    async fn api_error_body_carries_code_and_message() {
+        let response = ApiError::bad_gateway("bot-service unreachable").into_response();
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({"error": {"code": "BAD_GATEWAY", "message": "bot-service unreachable"}})
+        );
+    }
+}
@@ -33,7 +33,7 @@ from fastapi import FastAPI
 from pydantic import BaseModel
 
 
-ALLOWED_COMMANDS = {"move", "shoot", "shield", "speak"}
+ALLOWED_COMMANDS = {"move", "shoot", "shield", "speak", "pass", "charge"}
 ALLOWED_DIRECTIONS = {"up", "left", "down", "right"}
 DIRECTION_ALIASES = {
     "1": "up",
@@ -233,6 +233,9 @@ def normalize_decision(
             return decision
         return {"command_type": "speak", "direction": None, "speak_text": speak_text[:140]}
 
+    if command_type in ("pass", "charge"):
+        return {"command_type": command_type, "direction": None, "speak_text": None}
+
     direction = normalize_direction(raw.get("direction"))
     if not direction:
         decision = fallback_decision(game, player_id, force_speak)
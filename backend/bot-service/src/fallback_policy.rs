@@ -0,0 +1,389 @@
+// Copyright (C) 2026 StarHuntingGames
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Rust-side policies used when the Python player agent cannot produce a
+//! usable decision. They mirror the game-manager rules closely enough to only
+//! ever pick commands the manager would accept.
+
+use cowboy_common::{Direction, GameStateSnapshot, PlayerCommandType, PlayerState};
+use rand::{Rng, seq::IndexedRandom};
+
+const ALL_DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Left,
+    Direction::Down,
+    Direction::Right,
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum FallbackPolicy {
+    /// Speak the failure reason and otherwise do nothing (the original behavior).
+    #[default]
+    Speak,
+    /// Pick any legal move or shot.
+    Random,
+    /// Shoot the nearest opponent when a shot reaches them, else close the distance.
+    Aggressive,
+}
+
+impl FallbackPolicy {
+    pub(crate) fn parse(value: Option<&str>) -> Result<Self, String> {
+        let Some(value) = value.map(str::trim).filter(|value| !value.is_empty()) else {
+            return Ok(Self::Speak);
+        };
+        match value.to_ascii_lowercase().as_str() {
+            "speak" => Ok(Self::Speak),
+            "random" => Ok(Self::Random),
+            "aggressive" => Ok(Self::Aggressive),
+            other => Err(format!(
+                "unknown fallback_policy '{other}' (expected speak, random, or aggressive)"
+            )),
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Speak => "speak",
+            Self::Random => "random",
+            Self::Aggressive => "aggressive",
+        }
+    }
+
+    /// Chooses a command for `player_id`, or `None` when the policy speaks
+    /// instead or no legal action exists.
+    pub(crate) fn choose<R: Rng + ?Sized>(
+        self,
+        state: &GameStateSnapshot,
+        player_id: &str,
+        rng: &mut R,
+    ) -> Option<(PlayerCommandType, Direction)> {
+        let me = state
+            .players
+            .iter()
+            .find(|player| player.alive && player.player_id == player_id)?;
+
+        match self {
+            Self::Speak => None,
+            Self::Random => random_action(state, me, rng),
+            Self::Aggressive => {
+                aggressive_action(state, me).or_else(|| random_action(state, me, rng))
+            }
+        }
+    }
+}
+
+fn random_action<R: Rng + ?Sized>(
+    state: &GameStateSnapshot,
+    me: &PlayerState,
+    rng: &mut R,
+) -> Option<(PlayerCommandType, Direction)> {
+    let mut actions: Vec<(PlayerCommandType, Direction)> = legal_moves(state, me)
+        .into_iter()
+        .map(|direction| (PlayerCommandType::Move, direction))
+        .collect();
+    actions.extend(
+        legal_shots(state, me)
+            .into_iter()
+            .map(|direction| (PlayerCommandType::Shoot, direction)),
+    );
+    actions.choose(rng).copied()
+}
+
+fn aggressive_action(
+    state: &GameStateSnapshot,
+    me: &PlayerState,
+) -> Option<(PlayerCommandType, Direction)> {
+    let mut opponents: Vec<&PlayerState> = state
+        .players
+        .iter()
+        .filter(|player| player.alive && player.player_id != me.player_id)
+        .collect();
+    opponents.sort_by_key(|player| manhattan(me, player.row, player.col));
+    let nearest = *opponents.first()?;
+
+    // Prefer a shot the target's shield won't absorb, but any hit beats moving.
+    let shots: Vec<(Direction, &PlayerState, Direction)> = legal_shots(state, me)
+        .into_iter()
+        .flat_map(|direction| {
+            let (sweep_a, sweep_b) = perpendicular_directions(direction);
+            [sweep_a, sweep_b].into_iter().filter_map(move |sweep| {
+                laser_target(state, me, direction, sweep).map(|target| (direction, target, sweep))
+            })
+        })
+        .collect();
+    let nearest_shot = |unshielded_only: bool| {
+        shots
+            .iter()
+            .filter(|(_, target, sweep)| !unshielded_only || target.shield != opposite(*sweep))
+            .min_by_key(|(_, target, _)| manhattan(me, target.row, target.col))
+            .map(|(direction, _, _)| *direction)
+    };
+    let best_shot = nearest_shot(true).or_else(|| nearest_shot(false));
+    if let Some(direction) = best_shot {
+        return Some((PlayerCommandType::Shoot, direction));
+    }
+
+    let current = manhattan(me, nearest.row, nearest.col);
+    legal_moves(state, me)
+        .into_iter()
+        .filter_map(|direction| {
+            let (row, col) = step(state, me.row, me.col, direction)?;
+            let distance = manhattan_cells(row, col, nearest.row, nearest.col);
+            (distance < current).then_some((distance, direction))
+        })
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, direction)| (PlayerCommandType::Move, direction))
+}
+
+/// Directions the player can move into: in bounds, no block, no living player.
+fn legal_moves(state: &GameStateSnapshot, me: &PlayerState) -> Vec<Direction> {
+    ALL_DIRECTIONS
+        .into_iter()
+        .filter(|direction| {
+            step(state, me.row, me.col, *direction)
+                .is_some_and(|(row, col)| is_open(state, row, col))
+        })
+        .collect()
+}
+
+/// Directions the player can shoot: not through their own shield, and the
+/// adjacent entry cell must be an open, unoccupied cell.
+fn legal_shots(state: &GameStateSnapshot, me: &PlayerState) -> Vec<Direction> {
+    ALL_DIRECTIONS
+        .into_iter()
+        .filter(|direction| *direction != me.shield)
+        .filter(|direction| {
+            step(state, me.row, me.col, *direction)
+                .is_some_and(|(row, col)| is_open(state, row, col))
+        })
+        .collect()
+}
+
+/// First living player hit by the sweep leaving the entry cell, if the sweep
+/// is not stopped by a block first.
+fn laser_target<'a>(
+    state: &'a GameStateSnapshot,
+    me: &PlayerState,
+    direction: Direction,
+    sweep: Direction,
+) -> Option<&'a PlayerState> {
+    let (mut row, mut col) = step(state, me.row, me.col, direction)?;
+    while let Some((next_row, next_col)) = step(state, row, col, sweep) {
+        if state.map.cells[next_row][next_col] != 0 {
+            return None;
+        }
+        if let Some(player) = living_player_at(state, next_row, next_col) {
+            return Some(player);
+        }
+        row = next_row;
+        col = next_col;
+    }
+    None
+}
+
+fn is_open(state: &GameStateSnapshot, row: usize, col: usize) -> bool {
+    state.map.cells[row][col] == 0 && living_player_at(state, row, col).is_none()
+}
+
+fn living_player_at(state: &GameStateSnapshot, row: usize, col: usize) -> Option<&PlayerState> {
+    state
+        .players
+        .iter()
+        .find(|player| player.alive && player.row == row && player.col == col)
+}
+
+fn step(
+    state: &GameStateSnapshot,
+    row: usize,
+    col: usize,
+    direction: Direction,
+) -> Option<(usize, usize)> {
+    let (row, col) = match direction {
+        Direction::Up => (row.checked_sub(1)?, col),
+        Direction::Down => (row + 1, col),
+        Direction::Left => (row, col.checked_sub(1)?),
+        Direction::Right => (row, col + 1),
+    };
+    (row < state.map.rows && col < state.map.cols).then_some((row, col))
+}
+
+fn perpendicular_directions(direction: Direction) -> (Direction, Direction) {
+    match direction {
+        Direction::Up | Direction::Down => (Direction::Left, Direction::Right),
+        Direction::Left | Direction::Right => (Direction::Up, Direction::Down),
+    }
+}
+
+fn opposite(direction: Direction) -> Direction {
+    match direction {
+        Direction::Up => Direction::Down,
+        Direction::Down => Direction::Up,
+        Direction::Left => Direction::Right,
+        Direction::Right => Direction::Left,
+    }
+}
+
+fn manhattan(me: &PlayerState, row: usize, col: usize) -> usize {
+    manhattan_cells(me.row, me.col, row, col)
+}
+
+fn manhattan_cells(row_a: usize, col_a: usize, row_b: usize, col_b: usize) -> usize {
+    row_a.abs_diff(row_b) + col_a.abs_diff(col_b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cowboy_common::{MapData, PlayerName};
+    use rand::{SeedableRng, rngs::StdRng};
+
+    fn player(
+        name: PlayerName,
+        id: &str,
+        row: usize,
+        col: usize,
+        shield: Direction,
+    ) -> PlayerState {
+        PlayerState {
+            player_name: name,
+            player_id: id.to_string(),
+            hp: 10,
+            row,
+            col,
+            shield,
+            alive: true,
+            charge_level: 0,
+            shield_charges: 0,
+            last_words: None,
+            color: None,
+            display_name: None,
+        }
+    }
+
+    fn open_state(rows: usize, cols: usize, players: Vec<PlayerState>) -> GameStateSnapshot {
+        GameStateSnapshot {
+            map: MapData {
+                rows,
+                cols,
+                cells: vec![vec![0; cols]; rows],
+            },
+            players,
+            stats: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn parse_defaults_to_speak_and_rejects_unknown() {
+        assert_eq!(FallbackPolicy::parse(None), Ok(FallbackPolicy::Speak));
+        assert_eq!(FallbackPolicy::parse(Some(" ")), Ok(FallbackPolicy::Speak));
+        assert_eq!(
+            FallbackPolicy::parse(Some("Aggressive")),
+            Ok(FallbackPolicy::Aggressive)
+        );
+        assert_eq!(
+            FallbackPolicy::parse(Some("random")),
+            Ok(FallbackPolicy::Random)
+        );
+        assert!(FallbackPolicy::parse(Some("berserk")).is_err());
+    }
+
+    #[test]
+    fn aggressive_shoots_reachable_opponent() {
+        // Shooting Up from (2,2) enters (1,2) and sweeps along row 1, hitting B at (1,4).
+        let state = open_state(
+            5,
+            5,
+            vec![
+                player(PlayerName::A, "a", 2, 2, Direction::Down),
+                player(PlayerName::B, "b", 1, 4, Direction::Up),
+            ],
+        );
+        let mut rng = StdRng::seed_from_u64(7);
+
+        assert_eq!(
+            FallbackPolicy::Aggressive.choose(&state, "a", &mut rng),
+            Some((PlayerCommandType::Shoot, Direction::Up))
+        );
+    }
+
+    #[test]
+    fn aggressive_moves_toward_opponent_when_no_shot_lands() {
+        let mut state = open_state(
+            7,
+            7,
+            vec![
+                player(PlayerName::A, "a", 0, 0, Direction::Up),
+                player(PlayerName::B, "b", 6, 6, Direction::Up),
+            ],
+        );
+        // A wall at (1,1) stops the only sweeps that could head toward B.
+        state.map.cells[1][1] = -1;
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let (command_type, direction) = FallbackPolicy::Aggressive
+            .choose(&state, "a", &mut rng)
+            .expect("an action");
+        assert_eq!(command_type, PlayerCommandType::Move);
+        assert!(matches!(direction, Direction::Down | Direction::Right));
+    }
+
+    #[test]
+    fn policies_never_return_illegal_directions() {
+        // A is boxed into a corner by a wall to the right, B below, and its own
+        // shield facing Up, so there is no legal move or shot at all.
+        let mut state = open_state(
+            4,
+            4,
+            vec![
+                player(PlayerName::A, "a", 0, 0, Direction::Up),
+                player(PlayerName::B, "b", 1, 0, Direction::Up),
+            ],
+        );
+        state.map.cells[0][1] = 5;
+        let mut rng = StdRng::seed_from_u64(1);
+        assert_eq!(
+            FallbackPolicy::Aggressive.choose(&state, "a", &mut rng),
+            None
+        );
+        assert_eq!(FallbackPolicy::Random.choose(&state, "a", &mut rng), None);
+
+        // Open the wall: only Right is legal, for moving and shooting alike.
+        state.map.cells[0][1] = 0;
+        for seed in 0..32 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            for policy in [FallbackPolicy::Random, FallbackPolicy::Aggressive] {
+                let (_, direction) = policy.choose(&state, "a", &mut rng).expect("an action");
+                assert_eq!(direction, Direction::Right);
+            }
+        }
+    }
+
+    #[test]
+    fn speak_policy_and_dead_players_choose_nothing() {
+        let mut state = open_state(
+            3,
+            3,
+            vec![
+                player(PlayerName::A, "a", 0, 0, Direction::Up),
+                player(PlayerName::B, "b", 2, 2, Direction::Up),
+            ],
+        );
+        let mut rng = StdRng::seed_from_u64(3);
+        assert_eq!(FallbackPolicy::Speak.choose(&state, "a", &mut rng), None);
+
+        state.players[0].alive = false;
+        assert_eq!(FallbackPolicy::Random.choose(&state, "a", &mut rng), None);
+    }
+}
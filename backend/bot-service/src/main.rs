@@ -13,27 +13,34 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+mod fallback_policy;
+
 use std::{
     collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
     net::{SocketAddr, TcpListener as StdTcpListener},
     path::Path as FsPath,
     process::Stdio,
-    sync::Arc,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
     time::Duration,
 };
 
 use anyhow::Context;
 use axum::{
     Json, Router,
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{StatusCode, header},
     response::{IntoResponse, Response},
     routing::{get, post},
 };
 use chrono::Utc;
 use cowboy_common::{
-    CommandEnvelope, CommandSource, CommandType, Direction, GameInstanceResponse, GameStatus,
-    PlayerId, PlayerName, ResultStatus, StepEvent, StepEventType, expand_env_vars,
+    Backoff, CommandEnvelope, CommandSource, Direction, GameInstanceResponse, GameStatus,
+    PlayerCommandType, PlayerId, PlayerName, ResultStatus, StepEvent, StepEventType,
+    bot_reasoning_step, bot_thinking_step, expand_env_vars,
 };
 use rdkafka::{
     Message,
@@ -48,6 +55,26 @@ use tower_http::{cors::CorsLayer, trace::TraceLayer};
 use tracing::{info, warn};
 use uuid::Uuid;
 
+use fallback_policy::FallbackPolicy;
+
+/// Retries allowed after the bot's own command is rejected within one turn.
+const DEFAULT_MAX_RETRIES_PER_TURN: u32 = 2;
+/// Headroom left between a decide call and the server's turn timeout.
+const DEFAULT_DECIDE_SAFETY_MARGIN_MS: u64 = 1500;
+/// Never give the agent less than this, even on very short turns.
+const MIN_DECIDE_TIMEOUT_MS: u64 = 500;
+/// Longest reasoning summary shown to viewers.
+const REASONING_SUMMARY_MAX_CHARS: usize = 400;
+/// Weight of the newest sample in the decide latency average.
+const DEFAULT_DECIDE_EMA_ALPHA: f64 = 0.3;
+/// How many times its average decide latency an agent is given.
+const DEFAULT_ADAPTIVE_TIMEOUT_FACTOR: f64 = 3.0;
+/// Floor for an adaptive decide timeout, so one quick answer can't starve
+/// the next.
+const DEFAULT_ADAPTIVE_TIMEOUT_MIN_MS: u64 = 2000;
+/// Retry hint sent with a create rejected because the instance is full.
+const CAPACITY_RETRY_AFTER_SECS: u64 = 5;
+
 #[derive(Clone)]
 struct AppState {
     bots: Arc<Mutex<HashMap<String, BotRecord>>>,
@@ -61,6 +88,11 @@ struct AppState {
     auto_install_python_requirements: bool,
     agent_timeout_ms: u64,
     agent_update_timeout_ms: u64,
+    decide_safety_margin_ms: u64,
+    adaptive_timeout: AdaptiveTimeout,
+    /// Most active bots this instance takes (`BOT_SERVICE_MAX_BOTS`); `None`
+    /// is unlimited.
+    max_bots: Option<usize>,
     mock_kafka: bool,
     deepagents_enabled: bool,
     python_requirements_status: Arc<Mutex<Option<Result<(), String>>>>,
@@ -113,8 +145,107 @@ struct AgentPromptConfigFile {
 struct BotRecord {
     config: BotConfig,
     status: BotLifecycleStatus,
-    game_guide_version: Option<String>,
+    /// The guide from the last teach-game call.
+    game_guide: Option<TaughtGuide>,
+    /// Operator label for the guide or prompt variant being tried, e.g. for
+    /// A/B tests. Changing only the label keeps the worker running.
+    variant_label: Option<String>,
     worker: Option<BotWorkerHandle>,
+    /// Shared with the worker, and kept across worker restarts.
+    metrics: Arc<BotLlmMetrics>,
+}
+
+/// Everything a teach-game call hands over, kept so a repeat teach can tell
+/// whether the guide actually changed.
+#[derive(Debug, Clone, PartialEq)]
+struct TaughtGuide {
+    version: String,
+    rules_markdown: Option<String>,
+    command_schema: Option<serde_json::Value>,
+    examples: Option<Vec<serde_json::Value>>,
+}
+
+/// What a teach-game call does to the bot's worker.
+enum TeachOutcome {
+    /// Same guide and a live worker: only the label changed.
+    Relabelled,
+    /// A new worker is needed; the old one, if any, must be stopped.
+    Restart(Option<BotWorkerHandle>),
+}
+
+/// Player-agent call counters for one bot, to compare models across games.
+/// Latencies are wall-clock milliseconds, including failed calls.
+#[derive(Default)]
+struct BotLlmMetrics {
+    decide_calls: AtomicU64,
+    decide_failures: AtomicU64,
+    decide_latency_ms: AtomicU64,
+    update_calls: AtomicU64,
+    update_failures: AtomicU64,
+    update_latency_ms: AtomicU64,
+}
+
+impl BotLlmMetrics {
+    fn record_decide(&self, elapsed: Duration, ok: bool) {
+        Self::record(
+            &self.decide_calls,
+            &self.decide_failures,
+            &self.decide_latency_ms,
+            elapsed,
+            ok,
+        );
+    }
+
+    fn record_update(&self, elapsed: Duration, ok: bool) {
+        Self::record(
+            &self.update_calls,
+            &self.update_failures,
+            &self.update_latency_ms,
+            elapsed,
+            ok,
+        );
+    }
+
+    fn record(
+        calls: &AtomicU64,
+        failures: &AtomicU64,
+        latency_ms: &AtomicU64,
+        elapsed: Duration,
+        ok: bool,
+    ) {
+        calls.fetch_add(1, Ordering::Relaxed);
+        if !ok {
+            failures.fetch_add(1, Ordering::Relaxed);
+        }
+        let elapsed_ms = u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX);
+        latency_ms.fetch_add(elapsed_ms, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> BotLlmMetricsSnapshot {
+        let decide_calls = self.decide_calls.load(Ordering::Relaxed);
+        let decide_latency_ms = self.decide_latency_ms.load(Ordering::Relaxed);
+        BotLlmMetricsSnapshot {
+            decide_calls,
+            decide_failures: self.decide_failures.load(Ordering::Relaxed),
+            decide_latency_ms_total: decide_latency_ms,
+            decide_latency_ms_avg: decide_latency_ms.checked_div(decide_calls),
+            update_calls: self.update_calls.load(Ordering::Relaxed),
+            update_failures: self.update_failures.load(Ordering::Relaxed),
+            update_latency_ms_total: self.update_latency_ms.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+struct BotLlmMetricsSnapshot {
+    decide_calls: u64,
+    decide_failures: u64,
+    decide_latency_ms_total: u64,
+    /// `None` until the first decide call.
+    decide_latency_ms_avg: Option<u64>,
+    update_calls: u64,
+    update_failures: u64,
+    update_latency_ms_total: u64,
 }
 
 struct BotWorkerHandle {
@@ -135,6 +266,9 @@ struct BotConfig {
     llm_model: Option<String>,
     llm_api_key: Option<String>,
     llm_output_mode: Option<String>,
+    fallback_policy: FallbackPolicy,
+    max_retries_per_turn: u32,
+    expose_bot_reasoning: bool,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -161,6 +295,15 @@ struct CreateBotRequest {
     llm_api_key: Option<String>,
     #[serde(default)]
     llm_output_mode: Option<String>,
+    /// What to do when the player agent fails: `speak` (default), `random`, or `aggressive`.
+    #[serde(default)]
+    fallback_policy: Option<String>,
+    /// Rejected-command retries per turn before waiting for the timeout (default 2).
+    #[serde(default)]
+    max_retries_per_turn: Option<u32>,
+    /// Publish a redacted summary of each LLM decision for viewers (default false).
+    #[serde(default)]
+    expose_bot_reasoning: Option<bool>,
 }
 
 #[derive(Debug, Serialize)]
@@ -178,6 +321,9 @@ struct TeachGameRequest {
     command_schema: Option<serde_json::Value>,
     #[serde(default)]
     examples: Option<Vec<serde_json::Value>>,
+    /// Which guide or prompt variant this is, for correlating behavior.
+    #[serde(default)]
+    variant_label: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -185,6 +331,9 @@ struct TeachGameResponse {
     bot_id: String,
     status: BotLifecycleStatus,
     game_guide_version: String,
+    variant_label: Option<String>,
+    /// False when only the label changed and the worker kept running.
+    worker_restarted: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -201,9 +350,32 @@ struct BotInfoResponse {
     player_id: PlayerId,
     status: BotLifecycleStatus,
     game_guide_version: Option<String>,
+    variant_label: Option<String>,
     llm_base_url: Option<String>,
     llm_model: Option<String>,
     llm_output_mode: Option<String>,
+    fallback_policy: &'static str,
+    max_retries_per_turn: u32,
+    expose_bot_reasoning: bool,
+    /// Whether the bot's worker task is currently running.
+    worker_running: bool,
+    llm_metrics: BotLlmMetricsSnapshot,
+}
+
+#[derive(Debug, Serialize)]
+struct BotMetricsResponse {
+    bot_id: String,
+    game_id: String,
+    player_id: PlayerId,
+    llm_model: Option<String>,
+    #[serde(flatten)]
+    metrics: BotLlmMetricsSnapshot,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ListBotsQuery {
+    #[serde(default)]
+    game_id: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -217,9 +389,9 @@ struct BotUpdateResponse {
     bot_id: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 struct AgentDecisionResponse {
-    command_type: CommandType,
+    command_type: PlayerCommandType,
     #[serde(default)]
     direction: Option<Direction>,
     #[serde(default)]
@@ -338,9 +510,85 @@ struct PythonPlayerAgent {
     base_url: String,
     client: reqwest::Client,
     timeout_ms: u64,
+    decide_latency: DecideLatency,
     child: Child,
 }
 
+/// How decide timeouts follow an agent's speed: the moving average of its
+/// successful decide latencies times `factor`, clamped to `[min_ms, max_ms]`.
+#[derive(Debug, Clone, Copy)]
+struct AdaptiveTimeout {
+    alpha: f64,
+    factor: f64,
+    min_ms: u64,
+    max_ms: u64,
+}
+
+impl AdaptiveTimeout {
+    /// `max_ms` defaults to the fixed agent timeout, which is also what an
+    /// agent gets before its first successful decide.
+    fn from_env(agent_timeout_ms: u64) -> Self {
+        let alpha = std::env::var("BOT_DECIDE_EMA_ALPHA")
+            .ok()
+            .and_then(|value| value.parse::<f64>().ok())
+            .filter(|alpha| *alpha > 0.0 && *alpha <= 1.0)
+            .unwrap_or(DEFAULT_DECIDE_EMA_ALPHA);
+        let factor = std::env::var("BOT_ADAPTIVE_TIMEOUT_FACTOR")
+            .ok()
+            .and_then(|value| value.parse::<f64>().ok())
+            .filter(|factor| factor.is_finite() && *factor > 0.0)
+            .unwrap_or(DEFAULT_ADAPTIVE_TIMEOUT_FACTOR);
+        let max_ms = std::env::var("BOT_ADAPTIVE_TIMEOUT_MAX_MS")
+            .ok()
+            .and_then(|value| value.parse::<u64>().ok())
+            .unwrap_or(agent_timeout_ms);
+        let min_ms = std::env::var("BOT_ADAPTIVE_TIMEOUT_MIN_MS")
+            .ok()
+            .and_then(|value| value.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_ADAPTIVE_TIMEOUT_MIN_MS)
+            .min(max_ms);
+        Self {
+            alpha,
+            factor,
+            min_ms,
+            max_ms,
+        }
+    }
+}
+
+/// One agent's running decide latency.
+#[derive(Debug, Clone, Copy)]
+struct DecideLatency {
+    config: AdaptiveTimeout,
+    ema_ms: Option<f64>,
+}
+
+impl DecideLatency {
+    fn new(config: AdaptiveTimeout) -> Self {
+        Self {
+            config,
+            ema_ms: None,
+        }
+    }
+
+    /// Folds in a successful decide; the first one seeds the average.
+    fn observe(&mut self, elapsed: Duration) {
+        let sample = elapsed.as_secs_f64() * 1000.0;
+        self.ema_ms = Some(match self.ema_ms {
+            Some(ema) => ema + self.config.alpha * (sample - ema),
+            None => sample,
+        });
+    }
+
+    fn timeout_ms(&self) -> u64 {
+        match self.ema_ms {
+            Some(ema) => ((ema * self.config.factor).ceil() as u64)
+                .clamp(self.config.min_ms, self.config.max_ms),
+            None => self.config.max_ms,
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     tracing_subscriber::fmt()
@@ -396,6 +644,10 @@ async fn main() -> anyhow::Result<()> {
         }
     }
 
+    let agent_timeout_ms = std::env::var("BOT_AGENT_TIMEOUT_MS")
+        .ok()
+        .and_then(|value| value.parse::<u64>().ok())
+        .unwrap_or(120000);
     let state = AppState {
         bots: Arc::new(Mutex::new(HashMap::new())),
         producer,
@@ -410,14 +662,20 @@ async fn main() -> anyhow::Result<()> {
         agent_script_path,
         python_requirements_path,
         auto_install_python_requirements,
-        agent_timeout_ms: std::env::var("BOT_AGENT_TIMEOUT_MS")
+        agent_timeout_ms,
+        agent_update_timeout_ms: std::env::var("BOT_AGENT_UPDATE_TIMEOUT_MS")
             .ok()
             .and_then(|value| value.parse::<u64>().ok())
             .unwrap_or(120000),
-        agent_update_timeout_ms: std::env::var("BOT_AGENT_UPDATE_TIMEOUT_MS")
+        decide_safety_margin_ms: std::env::var("BOT_DECIDE_SAFETY_MARGIN_MS")
             .ok()
             .and_then(|value| value.parse::<u64>().ok())
-            .unwrap_or(120000),
+            .unwrap_or(DEFAULT_DECIDE_SAFETY_MARGIN_MS),
+        adaptive_timeout: AdaptiveTimeout::from_env(agent_timeout_ms),
+        max_bots: std::env::var("BOT_SERVICE_MAX_BOTS")
+            .ok()
+            .and_then(|value| value.trim().parse::<usize>().ok())
+            .filter(|value| *value > 0),
         mock_kafka: parse_env_bool("BOT_SERVICE_MOCK_KAFKA", false),
         deepagents_enabled,
         python_requirements_status: Arc::new(Mutex::new(None)),
@@ -431,18 +689,64 @@ async fn main() -> anyhow::Result<()> {
             .context("failed to prepare bot-service python runtime")?;
     }
 
-    let app = build_router(state);
+    let app = build_router(state.clone());
     let bind_addr = parse_bind_addr("BOT_SERVICE_BIND", "0.0.0.0:8091")?;
     info!(%bind_addr, "bot-service listening");
     let listener = tokio::net::TcpListener::bind(bind_addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(cowboy_common::shutdown_signal())
+        .await?;
+    shutdown_bots(&state).await;
+    info!("bot-service shut down");
     Ok(())
 }
 
+/// How long a stopping worker gets to shut its Python agent down before it
+/// is aborted.
+const WORKER_SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+
+/// Stops every bot worker and waits for them, so no Python agent outlives
+/// the service.
+async fn shutdown_bots(state: &AppState) {
+    let workers = {
+        let mut bots = state.bots.lock().await;
+        stop_all_workers(&mut bots)
+    };
+    info!(workers = workers.len(), "stopping bot workers");
+    for worker in workers {
+        let mut join = worker.join;
+        if tokio::time::timeout(WORKER_SHUTDOWN_GRACE, &mut join)
+            .await
+            .is_err()
+        {
+            warn!("bot worker did not stop in time; aborting it");
+            join.abort();
+        }
+    }
+}
+
+/// Takes every running worker and sends it the stop signal; a stopped
+/// worker shuts its Python agent down on the way out.
+fn stop_all_workers(bots: &mut HashMap<String, BotRecord>) -> Vec<BotWorkerHandle> {
+    bots.values_mut()
+        .filter_map(|record| {
+            let mut worker = record.worker.take()?;
+            if let Some(stop_tx) = worker.stop_tx.take() {
+                let _ = stop_tx.send(());
+            }
+            Some(worker)
+        })
+        .collect()
+}
+
 fn build_router(state: AppState) -> Router {
     Router::new()
         .route("/health", get(health))
-        .route("/internal/v3/bots", post(create_bot_handler))
+        .route("/ready", get(ready_handler))
+        .route(
+            "/internal/v3/bots",
+            get(list_bots_handler).post(create_bot_handler),
+        )
         .route(
             "/internal/v3/bots/{bot_id}",
             get(get_bot_handler).delete(delete_bot_handler),
@@ -455,6 +759,10 @@ fn build_router(state: AppState) -> Router {
             "/internal/v3/bots/{bot_id}/update",
             post(update_bot_handler),
         )
+        .route(
+            "/internal/v3/bots/{bot_id}/metrics",
+            get(bot_metrics_handler),
+        )
         .with_state(state)
         .layer(CorsLayer::permissive())
         .layer(TraceLayer::new_for_http())
@@ -800,6 +1108,27 @@ async fn ensure_python_requirements_ready(state: &AppState) -> anyhow::Result<()
     resolved.map_err(anyhow::Error::msg)
 }
 
+/// One line of the LLM's output that is safe to show viewers: whitespace is
+/// collapsed, the bot's API key and anything shaped like a `sk-` key are
+/// replaced with `[redacted]`, and the result is cut to
+/// `REASONING_SUMMARY_MAX_CHARS`.
+fn reasoning_summary(llm_output: Option<&str>, api_key: Option<&str>) -> Option<String> {
+    let redacted = llm_output?
+        .split_whitespace()
+        .map(|word| {
+            let bare = word.trim_start_matches(|ch: char| !ch.is_alphanumeric());
+            if bare.starts_with("sk-") || api_key.is_some_and(|key| word.contains(key)) {
+                "[redacted]"
+            } else {
+                word
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    let summary = truncate_log_field(Some(&redacted), REASONING_SUMMARY_MAX_CHARS);
+    (!summary.is_empty()).then_some(summary)
+}
+
 fn truncate_log_field(value: Option<&str>, max_chars: usize) -> String {
     let Some(text) = value.map(str::trim).filter(|entry| !entry.is_empty()) else {
         return String::new();
@@ -816,8 +1145,82 @@ fn truncate_log_field(value: Option<&str>, max_chars: usize) -> String {
     out
 }
 
-async fn health() -> Json<serde_json::Value> {
-    Json(serde_json::json!({"ok": true, "service": "bot-service"}))
+async fn health(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let active_bots = active_bot_count(&*state.bots.lock().await);
+    Json(serde_json::json!({
+        "ok": true,
+        "service": "bot-service",
+        "active_bots": active_bots,
+        "max_bots": state.max_bots,
+    }))
+}
+
+/// Bots holding a slot: those whose worker is running or not yet started.
+/// A bot whose worker has exited no longer counts.
+fn active_bot_count(bots: &HashMap<String, BotRecord>) -> usize {
+    bots.values()
+        .filter(|record| {
+            record
+                .worker
+                .as_ref()
+                .is_none_or(|worker| !worker.join.is_finished())
+        })
+        .count()
+}
+
+/// Turns a create away once `max_bots` bots are active, so a bot-manager
+/// that lost track of its assignments can't overload this instance.
+fn check_bot_capacity(
+    bots: &HashMap<String, BotRecord>,
+    max_bots: Option<usize>,
+) -> Result<(), ApiError> {
+    let Some(max_bots) = max_bots else {
+        return Ok(());
+    };
+    let active = active_bot_count(bots);
+    if active >= max_bots {
+        return Err(ApiError::at_capacity(
+            format!("bot-service is full ({active}/{max_bots} bots)"),
+            CAPACITY_RETRY_AFTER_SECS,
+        ));
+    }
+    Ok(())
+}
+
+async fn ready_handler(State(state): State<AppState>) -> (StatusCode, Json<serde_json::Value>) {
+    let status = state.python_requirements_status.lock().await.clone();
+    let (code, body) = readiness(
+        state.deepagents_enabled,
+        state.auto_install_python_requirements,
+        status.as_ref(),
+    );
+    (code, Json(body))
+}
+
+/// Ready only once the cached python preflight succeeded, unless the python
+/// runtime is not in use (deepagents off) or its preflight is disabled.
+fn readiness(
+    deepagents_enabled: bool,
+    auto_install_python_requirements: bool,
+    python_requirements_status: Option<&Result<(), String>>,
+) -> (StatusCode, serde_json::Value) {
+    let not_ready = |error: &str| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            serde_json::json!({"ok": false, "service": "bot-service", "error": error}),
+        )
+    };
+    if deepagents_enabled && auto_install_python_requirements {
+        match python_requirements_status {
+            Some(Ok(())) => {}
+            Some(Err(error)) => return not_ready(error.as_str()),
+            None => return not_ready("python requirements preflight has not completed"),
+        }
+    }
+    (
+        StatusCode::OK,
+        serde_json::json!({"ok": true, "service": "bot-service"}),
+    )
 }
 
 async fn create_bot_handler(
@@ -834,6 +1237,9 @@ async fn create_bot_handler(
         ));
     }
 
+    let fallback_policy =
+        FallbackPolicy::parse(request.fallback_policy.as_deref()).map_err(ApiError::bad_request)?;
+
     let bot_id = request
         .bot_id
         .clone()
@@ -844,6 +1250,7 @@ async fn create_bot_handler(
     if bots.contains_key(&bot_id) {
         return Err(ApiError::conflict(format!("bot {} already exists", bot_id)));
     }
+    check_bot_capacity(&bots, state.max_bots)?;
 
     let config = BotConfig {
         bot_id: bot_id.clone(),
@@ -856,6 +1263,11 @@ async fn create_bot_handler(
         llm_model: normalize_optional_string(request.llm_model),
         llm_api_key: normalize_optional_string(request.llm_api_key),
         llm_output_mode: normalize_optional_string(request.llm_output_mode),
+        fallback_policy,
+        max_retries_per_turn: request
+            .max_retries_per_turn
+            .unwrap_or(DEFAULT_MAX_RETRIES_PER_TURN),
+        expose_bot_reasoning: request.expose_bot_reasoning.unwrap_or(false),
     };
 
     bots.insert(
@@ -863,8 +1275,10 @@ async fn create_bot_handler(
         BotRecord {
             config,
             status: BotLifecycleStatus::Created,
-            game_guide_version: None,
+            game_guide: None,
+            variant_label: None,
             worker: None,
+            metrics: Arc::default(),
         },
     );
 
@@ -883,17 +1297,73 @@ async fn get_bot_handler(
         .get(&bot_id)
         .ok_or_else(|| ApiError::not_found(format!("bot {} not found", bot_id)))?;
 
-    Ok(Json(BotInfoResponse {
-        bot_id: bot_id.clone(),
+    Ok(Json(bot_info(&bot_id, record)))
+}
+
+async fn bot_metrics_handler(
+    State(state): State<AppState>,
+    Path(bot_id): Path<String>,
+) -> Result<Json<BotMetricsResponse>, ApiError> {
+    let bots = state.bots.lock().await;
+    let record = bots
+        .get(&bot_id)
+        .ok_or_else(|| ApiError::not_found(format!("bot {} not found", bot_id)))?;
+
+    Ok(Json(BotMetricsResponse {
+        bot_id,
+        game_id: record.config.game_id.clone(),
+        player_id: record.config.player_id.clone(),
+        llm_model: record.config.llm_model.clone(),
+        metrics: record.metrics.snapshot(),
+    }))
+}
+
+async fn list_bots_handler(
+    State(state): State<AppState>,
+    Query(query): Query<ListBotsQuery>,
+) -> Json<Vec<BotInfoResponse>> {
+    let bots = state.bots.lock().await;
+    Json(list_bot_infos(&bots, query.game_id.as_deref()))
+}
+
+/// Bots sorted by id, optionally restricted to one game.
+fn list_bot_infos(
+    bots: &HashMap<String, BotRecord>,
+    game_id: Option<&str>,
+) -> Vec<BotInfoResponse> {
+    let mut infos: Vec<BotInfoResponse> = bots
+        .iter()
+        .filter(|(_, record)| game_id.is_none_or(|game_id| record.config.game_id == game_id))
+        .map(|(bot_id, record)| bot_info(bot_id, record))
+        .collect();
+    infos.sort_by(|a, b| a.bot_id.cmp(&b.bot_id));
+    infos
+}
+
+fn bot_info(bot_id: &str, record: &BotRecord) -> BotInfoResponse {
+    BotInfoResponse {
+        bot_id: bot_id.to_string(),
         game_id: record.config.game_id.clone(),
         player_name: record.config.player_name,
         player_id: record.config.player_id.clone(),
         status: record.status,
-        game_guide_version: record.game_guide_version.clone(),
+        game_guide_version: record
+            .game_guide
+            .as_ref()
+            .map(|guide| guide.version.clone()),
+        variant_label: record.variant_label.clone(),
         llm_base_url: record.config.llm_base_url.clone(),
         llm_model: record.config.llm_model.clone(),
         llm_output_mode: record.config.llm_output_mode.clone(),
-    }))
+        fallback_policy: record.config.fallback_policy.as_str(),
+        max_retries_per_turn: record.config.max_retries_per_turn,
+        expose_bot_reasoning: record.config.expose_bot_reasoning,
+        worker_running: record
+            .worker
+            .as_ref()
+            .is_some_and(|worker| !worker.join.is_finished()),
+        llm_metrics: record.metrics.snapshot(),
+    }
 }
 
 async fn teach_game_handler(
@@ -905,21 +1375,49 @@ async fn teach_game_handler(
         return Err(ApiError::bad_request("game_guide_version is required"));
     }
 
-    let (config, previous_worker) = {
+    let TeachGameRequest {
+        game_guide_version,
+        rules_markdown,
+        command_schema,
+        examples,
+        variant_label,
+    } = request;
+    let guide = TaughtGuide {
+        version: game_guide_version.clone(),
+        rules_markdown,
+        command_schema,
+        examples,
+    };
+
+    let (config, metrics, previous_worker) = {
         let mut bots = state.bots.lock().await;
         let record = bots
             .get_mut(&bot_id)
             .ok_or_else(|| ApiError::not_found(format!("bot {} not found", bot_id)))?;
 
-        // Hold onto optional payload for future prompt templates.
-        let _ = request.rules_markdown.as_deref();
-        let _ = request.command_schema.as_ref();
-        let _ = request.examples.as_ref();
-
-        let previous_worker = record.worker.take();
-        record.game_guide_version = Some(request.game_guide_version.clone());
-        record.status = BotLifecycleStatus::Ready;
-        (record.config.clone(), previous_worker)
+        let outcome = apply_teach(record, guide, variant_label.clone());
+        info!(
+            bot_id = %bot_id,
+            game_id = %record.config.game_id,
+            game_guide_version = %game_guide_version,
+            variant_label = ?variant_label,
+            worker_restarted = matches!(outcome, TeachOutcome::Restart(_)),
+            "bot taught game guide"
+        );
+        let TeachOutcome::Restart(previous_worker) = outcome else {
+            return Ok(Json(TeachGameResponse {
+                bot_id,
+                status: BotLifecycleStatus::Ready,
+                game_guide_version,
+                variant_label,
+                worker_restarted: false,
+            }));
+        };
+        (
+            record.config.clone(),
+            record.metrics.clone(),
+            previous_worker,
+        )
     };
 
     if let Some(mut worker) = previous_worker {
@@ -929,7 +1427,7 @@ async fn teach_game_handler(
         worker.join.abort();
     }
 
-    let worker = spawn_bot_worker(state.clone(), config.clone());
+    let worker = spawn_bot_worker(state.clone(), config.clone(), metrics);
 
     {
         let mut bots = state.bots.lock().await;
@@ -942,10 +1440,35 @@ async fn teach_game_handler(
     Ok(Json(TeachGameResponse {
         bot_id,
         status: BotLifecycleStatus::Ready,
-        game_guide_version: request.game_guide_version,
+        game_guide_version,
+        variant_label,
+        worker_restarted: true,
     }))
 }
 
+/// Records a teach on `record`. Re-teaching the guide a live worker already
+/// has only updates the label; anything else hands back the old worker to
+/// stop so a new one can start.
+fn apply_teach(
+    record: &mut BotRecord,
+    guide: TaughtGuide,
+    variant_label: Option<String>,
+) -> TeachOutcome {
+    let worker_running = record
+        .worker
+        .as_ref()
+        .is_some_and(|worker| !worker.join.is_finished());
+    let unchanged = record.game_guide.as_ref() == Some(&guide);
+    record.game_guide = Some(guide);
+    record.variant_label = variant_label;
+    record.status = BotLifecycleStatus::Ready;
+    if unchanged && worker_running {
+        TeachOutcome::Relabelled
+    } else {
+        TeachOutcome::Restart(record.worker.take())
+    }
+}
+
 async fn update_bot_handler(
     State(state): State<AppState>,
     Path(bot_id): Path<String>,
@@ -1055,6 +1578,7 @@ impl PythonPlayerAgent {
             base_url,
             client: state.client.clone(),
             timeout_ms: state.agent_timeout_ms,
+            decide_latency: DecideLatency::new(state.adaptive_timeout),
             child,
         };
         agent.wait_until_ready().await?;
@@ -1086,10 +1610,11 @@ impl PythonPlayerAgent {
         &mut self,
         game: &GameInstanceResponse,
         force_speak: bool,
+        timeout_ms: u64,
     ) -> anyhow::Result<AgentDecisionResponse> {
         let request = PlayerAgentDecideRequest { force_speak, game };
         let response = self
-            .post_json("/decide", &request)
+            .post_json_with_timeout("/decide", &request, timeout_ms)
             .await
             .context("player-agent decide request failed")?;
         if !response.ok {
@@ -1188,12 +1713,22 @@ impl PythonPlayerAgent {
         &self,
         path: &str,
         payload: &T,
+    ) -> anyhow::Result<PlayerAgentEnvelopeResponse> {
+        self.post_json_with_timeout(path, payload, self.timeout_ms)
+            .await
+    }
+
+    async fn post_json_with_timeout<T: Serialize>(
+        &self,
+        path: &str,
+        payload: &T,
+        timeout_ms: u64,
     ) -> anyhow::Result<PlayerAgentEnvelopeResponse> {
         let url = format!("{}/{}", self.base_url, path.trim_start_matches('/'));
         let response = self
             .client
             .post(&url)
-            .timeout(Duration::from_millis(self.timeout_ms))
+            .timeout(Duration::from_millis(timeout_ms))
             .json(payload)
             .send()
             .await
@@ -1218,11 +1753,16 @@ impl PythonPlayerAgent {
     }
 }
 
-fn spawn_bot_worker(state: AppState, config: BotConfig) -> BotWorkerHandle {
+fn spawn_bot_worker(
+    state: AppState,
+    config: BotConfig,
+    metrics: Arc<BotLlmMetrics>,
+) -> BotWorkerHandle {
     let (stop_tx, stop_rx) = oneshot::channel::<()>();
     let (update_tx, update_rx) = mpsc::unbounded_channel::<StepEvent>();
     let join = tokio::spawn(async move {
-        if let Err(error) = run_bot_worker(state, config.clone(), stop_rx, update_rx).await {
+        if let Err(error) = run_bot_worker(state, config.clone(), metrics, stop_rx, update_rx).await
+        {
             warn!(
                 bot_id = %config.bot_id,
                 game_id = %config.game_id,
@@ -1242,6 +1782,7 @@ fn spawn_bot_worker(state: AppState, config: BotConfig) -> BotWorkerHandle {
 async fn run_bot_worker(
     state: AppState,
     config: BotConfig,
+    metrics: Arc<BotLlmMetrics>,
     mut stop_rx: oneshot::Receiver<()>,
     mut update_rx: mpsc::UnboundedReceiver<StepEvent>,
 ) -> anyhow::Result<()> {
@@ -1272,10 +1813,11 @@ async fn run_bot_worker(
         "bot worker started"
     );
 
-    let mut last_acted_turn_no: u64 = 0;
+    let mut turn_retries = TurnRetries::new(config.max_retries_per_turn);
     let mut has_spoken_once = false;
-    let mut retry_count: u32 = 0;
-    const MAX_RETRIES_PER_TURN: u32 = 2;
+    // Last python decision and the state it was made for; reused when asked
+    // to decide again for an identical board.
+    let mut cached_decision: Option<(u64, AgentDecisionResponse)> = None;
     let mut python_agent = if state.deepagents_enabled {
         match PythonPlayerAgent::start(&state, &config).await {
             Ok(agent) => Some(agent),
@@ -1294,6 +1836,8 @@ async fn run_bot_worker(
         None
     };
 
+    let mut backoff = Backoff::default();
+
     loop {
         tokio::select! {
             _ = &mut stop_rx => {
@@ -1328,7 +1872,7 @@ async fn run_bot_worker(
                 };
 
                 if let Err(error) =
-                    process_python_update_for_step(&state, &config, &game, &step, &mut python_agent)
+                    process_python_update_for_step(&state, &config, &metrics, &game, &step, &mut python_agent)
                         .await
                 {
                     warn!(
@@ -1349,10 +1893,13 @@ async fn run_bot_worker(
                 }
             } => {
                 let message = match message {
-                    Ok(message) => message,
+                    Ok(message) => {
+                        backoff.reset();
+                        message
+                    }
                     Err(error) => {
                         warn!(bot_id = %config.bot_id, ?error, "bot worker kafka recv error");
-                        tokio::time::sleep(Duration::from_millis(250)).await;
+                        tokio::time::sleep(backoff.next_delay()).await;
                         continue;
                     }
                 };
@@ -1407,56 +1954,53 @@ async fn run_bot_worker(
                 let is_bot_turn = game.current_player_id == config.player_id;
 
                 // If the step event shows our own command was rejected (InvalidCommand)
-                // and the turn has NOT advanced, reset last_acted_turn_no so we retry
-                // (up to MAX_RETRIES_PER_TURN times with fallback policy).
+                // and the turn has NOT advanced, decide again (up to
+                // max_retries_per_turn times with fallback policy).
                 let mut force_fallback_retry = false;
-                if is_bot_turn
-                    && game.turn_no == last_acted_turn_no
-                    && step.result_status == ResultStatus::InvalidCommand
+                if let Some((cmd, rejected)) =
+                    turn_retries.rejected_command(&step, is_bot_turn, game.turn_no, &config.player_id)
                 {
-                    if let Some(ref cmd) = step.command {
-                        if cmd.player_id.as_deref() == Some(config.player_id.as_str()) {
-                            if retry_count < MAX_RETRIES_PER_TURN {
-                                retry_count += 1;
-                                warn!(
-                                    bot_id = %config.bot_id,
-                                    game_id = %config.game_id,
-                                    player_id = %config.player_id,
-                                    turn_no = game.turn_no,
-                                    retry_count = retry_count,
-                                    max_retries = MAX_RETRIES_PER_TURN,
-                                    rejected_command_type = ?cmd.command_type,
-                                    rejected_direction = ?cmd.direction,
-                                    "bot command rejected; retrying with fallback policy"
-                                );
-                                last_acted_turn_no = game.turn_no.saturating_sub(1);
-                                force_fallback_retry = true;
-                            } else {
-                                warn!(
-                                    bot_id = %config.bot_id,
-                                    game_id = %config.game_id,
-                                    player_id = %config.player_id,
-                                    turn_no = game.turn_no,
-                                    retry_count = retry_count,
-                                    "bot command rejected; max retries reached, waiting for timeout"
-                                );
-                            }
+                    cached_decision = None;
+                    match rejected {
+                        RejectedCommand::Retry => {
+                            warn!(
+                                bot_id = %config.bot_id,
+                                game_id = %config.game_id,
+                                player_id = %config.player_id,
+                                turn_no = game.turn_no,
+                                retry_count = turn_retries.retry_count,
+                                max_retries = config.max_retries_per_turn,
+                                rejected_command_type = ?cmd.command_type,
+                                rejected_direction = ?cmd.direction,
+                                "bot command rejected; retrying with fallback policy"
+                            );
+                            force_fallback_retry = true;
+                        }
+                        RejectedCommand::GiveUp => {
+                            warn!(
+                                bot_id = %config.bot_id,
+                                game_id = %config.game_id,
+                                player_id = %config.player_id,
+                                turn_no = game.turn_no,
+                                retry_count = turn_retries.retry_count,
+                                "bot command rejected; max retries reached, waiting for timeout"
+                            );
                         }
                     }
                 }
 
-                // Reset retry counter when the turn advances.
-                if game.turn_no > last_acted_turn_no && !force_fallback_retry {
-                    retry_count = 0;
-                }
-
-                let should_decide = is_bot_turn && game.turn_no > last_acted_turn_no;
+                let should_decide =
+                    turn_retries.should_decide(is_bot_turn, game.turn_no, force_fallback_retry);
 
                 if !should_decide {
                     if let Some(consumer) = &consumer { let _ = consumer.commit_message(&message, CommitMode::Async); }
                     continue;
                 }
 
+                if let Err(error) = publish_thinking(&state, &config, &game).await {
+                    warn!(bot_id = %config.bot_id, game_id = %config.game_id, error = %error, "bot worker failed to publish thinking marker");
+                }
+
                 if python_agent.is_none() && state.deepagents_enabled {
                     python_agent = match PythonPlayerAgent::start(&state, &config).await {
                         Ok(agent) => Some(agent),
@@ -1486,9 +2030,34 @@ async fn run_bot_worker(
                         "using fallback policy for retry after rejected command"
                     );
                     None
+                } else if let Some(decision) =
+                    reusable_decision(cached_decision.as_ref(), decision_state_hash(&game))
+                {
+                    info!(
+                        bot_id = %config.bot_id,
+                        game_id = %config.game_id,
+                        turn_no = game.turn_no,
+                        "game state unchanged since last decision; reusing it"
+                    );
+                    Some(decision)
                 } else if let Some(agent) = python_agent.as_mut() {
-                    match agent.decide(&game, force_speak).await {
-                        Ok(decision) => Some(decision),
+                    let timeout_ms = decide_timeout_ms(
+                        agent.decide_latency.timeout_ms(),
+                        game.turn_timeout_seconds
+                            .saturating_add(game.turn_grace_seconds),
+                        state.decide_safety_margin_ms,
+                    );
+                    let decide_start = std::time::Instant::now();
+                    let decided = agent.decide(&game, force_speak, timeout_ms).await;
+                    metrics.record_decide(decide_start.elapsed(), decided.is_ok());
+                    match decided {
+                        Ok(decision) => {
+                            agent.decide_latency.observe(decide_start.elapsed());
+                            cached_decision = llm_failure_message_from_decision(&decision)
+                                .is_none()
+                                .then(|| (decision_state_hash(&game), decision.clone()));
+                            Some(decision)
+                        }
                         Err(error) => {
                             let error_detail = format!("{:#}", error);
                             let mut agent_exited = false;
@@ -1587,10 +2156,18 @@ async fn run_bot_worker(
                         command_type = ?command.command_type,
                         "bot command published"
                     );
-                    last_acted_turn_no = game.turn_no;
-                    if command.command_type == CommandType::Speak {
+                    turn_retries.acted(game.turn_no);
+                    if command.command_type == PlayerCommandType::Speak {
                         has_spoken_once = true;
                     }
+                    if config.expose_bot_reasoning
+                        && let Some(summary) = decision.as_ref().and_then(|decision| {
+                            reasoning_summary(decision.llm_output.as_deref(), config.llm_api_key.as_deref())
+                        })
+                        && let Err(error) = publish_reasoning(&state, &config, &game, summary).await
+                    {
+                        warn!(bot_id = %config.bot_id, game_id = %config.game_id, error = %error, "bot worker failed to publish reasoning");
+                    }
                 }
 
                 if let Some(consumer) = &consumer { let _ = consumer.commit_message(&message, CommitMode::Async); }
@@ -1609,6 +2186,7 @@ async fn run_bot_worker(
 async fn process_python_update_for_step(
     state: &AppState,
     config: &BotConfig,
+    metrics: &BotLlmMetrics,
     game: &GameInstanceResponse,
     step: &StepEvent,
     python_agent: &mut Option<PythonPlayerAgent>,
@@ -1637,7 +2215,9 @@ async fn process_python_update_for_step(
     if let Some(agent) = python_agent.as_mut() {
         let is_bot_turn = game.current_player_id == config.player_id;
         let update_start = std::time::Instant::now();
-        match agent.update(game, step, is_bot_turn).await {
+        let updated = agent.update(game, step, is_bot_turn).await;
+        metrics.record_update(update_start.elapsed(), updated.is_ok());
+        match updated {
             Ok(update) => {
                 let llm_system_log = truncate_log_field(update.llm_system.as_deref(), 1200);
                 let llm_input_log = truncate_log_field(update.llm_input.as_deref(), 2400);
@@ -1785,6 +2365,97 @@ fn build_bot_command(
     )
 }
 
+/// Fingerprint of the parts of a game a decision depends on: the turn and
+/// every player's position, HP, shield and liveness.
+fn decision_state_hash(game: &GameInstanceResponse) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    game.turn_no.hash(&mut hasher);
+    for player in &game.state.players {
+        player.player_id.hash(&mut hasher);
+        (player.row, player.col, player.hp).hash(&mut hasher);
+        (player.shield as u8).hash(&mut hasher);
+        player.alive.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+fn reusable_decision(
+    cached: Option<&(u64, AgentDecisionResponse)>,
+    state_hash: u64,
+) -> Option<AgentDecisionResponse> {
+    cached
+        .filter(|(hash, _)| *hash == state_hash)
+        .map(|(_, decision)| decision.clone())
+}
+
+/// The turn a bot worker last published a command for, and how many times
+/// its command has been rejected on that turn.
+struct TurnRetries {
+    last_acted_turn_no: u64,
+    retry_count: u32,
+    max_retries_per_turn: u32,
+}
+
+/// What a worker does after its own command is rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RejectedCommand {
+    /// Decide again for the same turn.
+    Retry,
+    /// Out of retries; leave the turn to the timeout.
+    GiveUp,
+}
+
+impl TurnRetries {
+    fn new(max_retries_per_turn: u32) -> Self {
+        Self {
+            last_acted_turn_no: 0,
+            retry_count: 0,
+            max_retries_per_turn,
+        }
+    }
+
+    /// Returns the rejected command and whether to retry it when `step`
+    /// rejects `player_id`'s command for the turn it last acted on.
+    fn rejected_command<'a>(
+        &mut self,
+        step: &'a StepEvent,
+        is_bot_turn: bool,
+        turn_no: u64,
+        player_id: &str,
+    ) -> Option<(&'a CommandEnvelope, RejectedCommand)> {
+        if !is_bot_turn
+            || turn_no != self.last_acted_turn_no
+            || step.result_status != ResultStatus::InvalidCommand
+        {
+            return None;
+        }
+        let command = step
+            .command
+            .as_ref()
+            .filter(|command| command.player_id.as_deref() == Some(player_id))?;
+        if self.retry_count < self.max_retries_per_turn {
+            self.retry_count += 1;
+            self.last_acted_turn_no = turn_no.saturating_sub(1);
+            Some((command, RejectedCommand::Retry))
+        } else {
+            Some((command, RejectedCommand::GiveUp))
+        }
+    }
+
+    /// Whether to decide for `turn_no`; a new turn starts with no retries
+    /// used.
+    fn should_decide(&mut self, is_bot_turn: bool, turn_no: u64, retrying: bool) -> bool {
+        if turn_no > self.last_acted_turn_no && !retrying {
+            self.retry_count = 0;
+        }
+        is_bot_turn && turn_no > self.last_acted_turn_no
+    }
+
+    fn acted(&mut self, turn_no: u64) {
+        self.last_acted_turn_no = turn_no;
+    }
+}
+
 fn llm_failure_message_from_decision(decision: &AgentDecisionResponse) -> Option<&str> {
     let source = decision
         .decision_source
@@ -1858,11 +2529,12 @@ fn build_llm_failure_speak_command(
         source: CommandSource::Bot,
         game_id: config.game_id.clone(),
         player_id: Some(config.player_id.clone()),
-        command_type: CommandType::Speak,
+        command_type: PlayerCommandType::Speak,
         direction: None,
         speak_text: Some(build_llm_failure_speak_text(message)),
         turn_no: game.turn_no,
         sent_at: Utc::now(),
+        player_token: None,
     }
 }
 
@@ -1875,7 +2547,7 @@ fn command_from_decision(
         return Err(DecisionValidationError::UnsupportedCommandType);
     }
 
-    let (direction, speak_text) = if decision.command_type == CommandType::Speak {
+    let (direction, speak_text) = if decision.command_type == PlayerCommandType::Speak {
         let speak_text = decision
             .speak_text
             .as_deref()
@@ -1884,6 +2556,11 @@ fn command_from_decision(
             .ok_or(DecisionValidationError::MissingSpeakText)?
             .to_string();
         (None, Some(speak_text))
+    } else if matches!(
+        decision.command_type,
+        PlayerCommandType::Pass | PlayerCommandType::Charge
+    ) {
+        (None, None)
     } else {
         (
             Some(
@@ -1910,13 +2587,19 @@ fn command_from_decision(
         speak_text,
         turn_no: game.turn_no,
         sent_at: Utc::now(),
+        player_token: None,
     })
 }
 
-fn is_supported_bot_command(command_type: CommandType) -> bool {
+fn is_supported_bot_command(command_type: PlayerCommandType) -> bool {
     matches!(
         command_type,
-        CommandType::Move | CommandType::Shoot | CommandType::Shield | CommandType::Speak
+        PlayerCommandType::Move
+            | PlayerCommandType::Shoot
+            | PlayerCommandType::Shield
+            | PlayerCommandType::Speak
+            | PlayerCommandType::Pass
+            | PlayerCommandType::Charge
     )
 }
 
@@ -1925,7 +2608,40 @@ fn build_fallback_bot_command(
     game: &GameInstanceResponse,
     message: &str,
 ) -> CommandEnvelope {
-    build_llm_failure_speak_command(config, game, message)
+    let Some((command_type, direction)) =
+        config
+            .fallback_policy
+            .choose(&game.state, &config.player_id, &mut rand::rng())
+    else {
+        return build_llm_failure_speak_command(config, game, message);
+    };
+
+    info!(
+        bot_id = %config.bot_id,
+        game_id = %config.game_id,
+        fallback_policy = config.fallback_policy.as_str(),
+        command_type = ?command_type,
+        direction = ?direction,
+        fallback_reason = %message,
+        "fallback policy chose a command"
+    );
+    CommandEnvelope {
+        command_id: format!(
+            "bot-{}-{}-{}",
+            config.bot_id,
+            game.turn_no,
+            Utc::now().timestamp_millis()
+        ),
+        source: CommandSource::Bot,
+        game_id: config.game_id.clone(),
+        player_id: Some(config.player_id.clone()),
+        command_type,
+        direction: Some(direction),
+        speak_text: None,
+        turn_no: game.turn_no,
+        sent_at: Utc::now(),
+        player_token: None,
+    }
 }
 
 async fn publish_command(
@@ -1957,6 +2673,59 @@ async fn publish_command(
     Ok(())
 }
 
+/// Tells watchers this bot has started deciding, so a slow agent doesn't look
+/// like a hung game.
+async fn publish_thinking(
+    state: &AppState,
+    config: &BotConfig,
+    game: &GameInstanceResponse,
+) -> anyhow::Result<()> {
+    if state.mock_kafka {
+        return Ok(());
+    }
+
+    publish_marker(state, config, &bot_thinking_step(game, &config.player_id)).await
+}
+
+/// Shares why the bot chose its move, for bots with `expose_bot_reasoning`.
+async fn publish_reasoning(
+    state: &AppState,
+    config: &BotConfig,
+    game: &GameInstanceResponse,
+    summary: String,
+) -> anyhow::Result<()> {
+    if state.mock_kafka {
+        return Ok(());
+    }
+
+    publish_marker(
+        state,
+        config,
+        &bot_reasoning_step(game, &config.player_id, summary),
+    )
+    .await
+}
+
+/// Sends a viewer-only marker step to the game's output topic.
+async fn publish_marker(
+    state: &AppState,
+    config: &BotConfig,
+    marker: &StepEvent,
+) -> anyhow::Result<()> {
+    let payload = serde_json::to_string(marker).context("failed to encode bot marker")?;
+    state
+        .producer
+        .send(
+            FutureRecord::to(&config.output_topic)
+                .key(&config.game_id)
+                .payload(&payload),
+            Duration::from_secs(5),
+        )
+        .await
+        .map_err(|(error, _)| anyhow::anyhow!("kafka publish failed: {error:?}"))?;
+    Ok(())
+}
+
 async fn fetch_game(state: &AppState, game_id: &str) -> anyhow::Result<GameInstanceResponse> {
     let url = format!("{}/v2/games/{}", state.manager_base_url, game_id);
     let response = state
@@ -1980,39 +2749,567 @@ async fn fetch_game(state: &AppState, game_id: &str) -> anyhow::Result<GameInsta
 #[derive(Debug)]
 struct ApiError {
     status: StatusCode,
+    /// Stable machine-readable error code, e.g. `NOT_FOUND`.
+    code: &'static str,
     message: String,
+    /// Seconds the client should wait before retrying, sent as `Retry-After`.
+    retry_after_secs: Option<u64>,
 }
 
 impl ApiError {
     fn bad_request(message: impl Into<String>) -> Self {
         Self {
             status: StatusCode::BAD_REQUEST,
+            code: "BAD_REQUEST",
             message: message.into(),
+            retry_after_secs: None,
         }
     }
 
     fn not_found(message: impl Into<String>) -> Self {
         Self {
             status: StatusCode::NOT_FOUND,
+            code: "NOT_FOUND",
             message: message.into(),
+            retry_after_secs: None,
         }
     }
 
     fn conflict(message: impl Into<String>) -> Self {
         Self {
             status: StatusCode::CONFLICT,
+            code: "CONFLICT",
             message: message.into(),
+            retry_after_secs: None,
+        }
+    }
+
+    fn at_capacity(message: impl Into<String>, retry_after_secs: u64) -> Self {
+        Self {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            code: "AT_CAPACITY",
+            message: message.into(),
+            retry_after_secs: Some(retry_after_secs),
         }
     }
 }
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
-        warn!(status = %self.status, message = %self.message, "bot-service request failed");
+        warn!(status = %self.status, code = self.code, message = %self.message, "bot-service request failed");
+        let mut error = serde_json::json!({"code": self.code, "message": self.message});
+        let Some(retry_after_secs) = self.retry_after_secs else {
+            return (self.status, Json(serde_json::json!({"error": error}))).into_response();
+        };
+        error["retry_after"] = retry_after_secs.into();
         (
             self.status,
-            Json(serde_json::json!({"error": self.message})),
+            [(header::RETRY_AFTER, retry_after_secs.to_string())],
+            Json(serde_json::json!({"error": error})),
         )
             .into_response()
     }
 }
+
+/// Per-turn decide budget: the agent's (adaptive) timeout, cut down so the
+/// agent answers `safety_margin_ms` before the game's own turn timeout fires.
+fn decide_timeout_ms(
+    agent_timeout_ms: u64,
+    turn_timeout_seconds: u64,
+    safety_margin_ms: u64,
+) -> u64 {
+    let turn_budget_ms = turn_timeout_seconds
+        .saturating_mul(1000)
+        .saturating_sub(safety_margin_ms);
+    agent_timeout_ms
+        .min(turn_budget_ms)
+        .max(MIN_DECIDE_TIMEOUT_MS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cowboy_common::{GameStateSnapshot, MapSource, default_map, initial_players};
+
+    fn running_game() -> GameInstanceResponse {
+        let map = default_map();
+        GameInstanceResponse {
+            game_id: "game-1".to_string(),
+            status: GameStatus::Running,
+            map_source: MapSource::Default,
+            turn_timeout_seconds: 10,
+            turn_no: 3,
+            round_no: 2,
+            current_player_id: "p".to_string(),
+            created_at: Utc::now(),
+            started_at: None,
+            turn_started_at: None,
+            input_topic: None,
+            output_topic: None,
+            state: GameStateSnapshot {
+                players: initial_players(map.rows, map.cols, 10, 2),
+                map,
+                stats: Vec::new(),
+            },
+            last_step_seq: 0,
+            turn_seconds_remaining: None,
+            turn_grace_seconds: 0,
+        }
+    }
+
+    fn pass_decision() -> AgentDecisionResponse {
+        serde_json::from_value(serde_json::json!({"command_type": "pass"})).unwrap()
+    }
+
+    #[test]
+    fn decision_state_hash_is_stable_and_tracks_opponent_moves() {
+        let game = running_game();
+        assert_eq!(
+            decision_state_hash(&game),
+            decision_state_hash(&game.clone())
+        );
+
+        let mut moved = game.clone();
+        moved.state.players[1].col += 1;
+        assert_ne!(decision_state_hash(&game), decision_state_hash(&moved));
+
+        let mut next_turn = game.clone();
+        next_turn.turn_no += 1;
+        assert_ne!(decision_state_hash(&game), decision_state_hash(&next_turn));
+    }
+
+    #[test]
+    fn cached_decision_is_only_reused_for_the_same_state() {
+        let game = running_game();
+        let cached = (decision_state_hash(&game), pass_decision());
+        assert!(reusable_decision(Some(&cached), decision_state_hash(&game)).is_some());
+
+        let mut moved = game.clone();
+        moved.state.players[1].row += 1;
+        assert!(reusable_decision(Some(&cached), decision_state_hash(&moved)).is_none());
+        assert!(reusable_decision(None, decision_state_hash(&game)).is_none());
+    }
+
+    fn rejected_step(game: &GameInstanceResponse, player_id: &str) -> StepEvent {
+        serde_json::from_value(serde_json::json!({
+            "game_id": game.game_id,
+            "step_seq": 7,
+            "turn_no": game.turn_no,
+            "round_no": game.round_no,
+            "event_type": "STEP_APPLIED",
+            "result_status": "INVALID_COMMAND",
+            "command": CommandEnvelope {
+                command_id: "c-1".to_string(),
+                source: CommandSource::Bot,
+                game_id: game.game_id.clone(),
+                player_id: Some(player_id.to_string()),
+                command_type: PlayerCommandType::Move,
+                direction: Some(Direction::Up),
+                speak_text: None,
+                turn_no: game.turn_no,
+                sent_at: Utc::now(),
+                player_token: None,
+            },
+            "state_after": game.state,
+            "created_at": Utc::now(),
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn rejected_commands_are_retried_up_to_the_limit_then_left_to_the_timeout() {
+        let game = running_game();
+        let mut turn_retries = TurnRetries::new(DEFAULT_MAX_RETRIES_PER_TURN);
+        assert!(turn_retries.should_decide(true, game.turn_no, false));
+        turn_retries.acted(game.turn_no);
+
+        let rejected = rejected_step(&game, "p");
+        for _ in 0..DEFAULT_MAX_RETRIES_PER_TURN {
+            let (_, outcome) = turn_retries
+                .rejected_command(&rejected, true, game.turn_no, "p")
+                .unwrap();
+            assert_eq!(outcome, RejectedCommand::Retry);
+            assert!(turn_retries.should_decide(true, game.turn_no, true));
+            turn_retries.acted(game.turn_no);
+        }
+
+        let (_, outcome) = turn_retries
+            .rejected_command(&rejected, true, game.turn_no, "p")
+            .unwrap();
+        assert_eq!(outcome, RejectedCommand::GiveUp);
+        assert!(!turn_retries.should_decide(true, game.turn_no, false));
+
+        // The next turn gets the full retry budget again.
+        let next_turn = game.turn_no + 3;
+        assert!(turn_retries.should_decide(true, next_turn, false));
+        turn_retries.acted(next_turn);
+        let mut next_game = game.clone();
+        next_game.turn_no = next_turn;
+        let (_, outcome) = turn_retries
+            .rejected_command(&rejected_step(&next_game, "p"), true, next_turn, "p")
+            .unwrap();
+        assert_eq!(outcome, RejectedCommand::Retry);
+    }
+
+    #[test]
+    fn zero_retries_waits_for_timeout_after_rejection() {
+        let game = running_game();
+        let mut turn_retries = TurnRetries::new(0);
+        turn_retries.acted(game.turn_no);
+
+        let (_, outcome) = turn_retries
+            .rejected_command(&rejected_step(&game, "p"), true, game.turn_no, "p")
+            .unwrap();
+        assert_eq!(outcome, RejectedCommand::GiveUp);
+        assert!(!turn_retries.should_decide(true, game.turn_no, false));
+    }
+
+    #[test]
+    fn other_players_rejections_are_not_retried() {
+        let game = running_game();
+        let mut turn_retries = TurnRetries::new(DEFAULT_MAX_RETRIES_PER_TURN);
+        turn_retries.acted(game.turn_no);
+
+        let rejected = rejected_step(&game, "someone-else");
+        assert!(
+            turn_retries
+                .rejected_command(&rejected, true, game.turn_no, "p")
+                .is_none()
+        );
+        assert!(!turn_retries.should_decide(true, game.turn_no, false));
+    }
+
+    #[test]
+    fn decide_timeout_is_clamped_to_turn_budget() {
+        let cases = [
+            // (agent_timeout_ms, turn_timeout_seconds, margin_ms, expected)
+            (120_000, 10, 1_500, 8_500),
+            (5_000, 10, 1_500, 5_000),
+            (120_000, 120, 1_500, 118_500),
+            (120_000, 2, 1_500, 500),
+            (120_000, 1, 1_500, MIN_DECIDE_TIMEOUT_MS),
+            (300, 30, 1_500, MIN_DECIDE_TIMEOUT_MS),
+            (120_000, 10, 0, 10_000),
+            (120_000, u64::MAX, 1_500, 120_000),
+        ];
+        for (agent, turn, margin, expected) in cases {
+            assert_eq!(
+                decide_timeout_ms(agent, turn, margin),
+                expected,
+                "agent={agent} turn={turn}s margin={margin}"
+            );
+        }
+    }
+
+    #[test]
+    fn decide_latency_average_drives_a_clamped_timeout() {
+        let mut latency = DecideLatency::new(AdaptiveTimeout {
+            alpha: 0.5,
+            factor: 3.0,
+            min_ms: 1_000,
+            max_ms: 60_000,
+        });
+        // No samples yet: the full configured timeout.
+        assert_eq!(latency.timeout_ms(), 60_000);
+
+        latency.observe(Duration::from_millis(2_000));
+        assert_eq!(latency.ema_ms, Some(2_000.0));
+        assert_eq!(latency.timeout_ms(), 6_000);
+
+        latency.observe(Duration::from_millis(4_000));
+        assert_eq!(latency.ema_ms, Some(3_000.0));
+        assert_eq!(latency.timeout_ms(), 9_000);
+
+        // A fast local model is held at the floor...
+        for _ in 0..20 {
+            latency.observe(Duration::from_millis(10));
+        }
+        assert_eq!(latency.timeout_ms(), 1_000);
+        // ...and a very slow one at the ceiling.
+        for _ in 0..20 {
+            latency.observe(Duration::from_secs(50));
+        }
+        assert_eq!(latency.timeout_ms(), 60_000);
+    }
+
+    #[test]
+    fn readiness_follows_cached_python_requirements_status() {
+        let failed = Err("pip install failed".to_string());
+        let (code, body) = readiness(true, true, Some(&failed));
+        assert_eq!(code, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["error"], "pip install failed");
+
+        let (code, _) = readiness(true, true, None);
+        assert_eq!(code, StatusCode::SERVICE_UNAVAILABLE);
+
+        let (code, body) = readiness(true, true, Some(&Ok(())));
+        assert_eq!(code, StatusCode::OK);
+        assert_eq!(body["ok"], true);
+    }
+
+    #[test]
+    fn readiness_ignores_python_status_when_runtime_unused() {
+        let failed = Err("pip install failed".to_string());
+        assert_eq!(readiness(false, true, Some(&failed)).0, StatusCode::OK);
+        assert_eq!(readiness(true, false, None).0, StatusCode::OK);
+    }
+
+    fn bot_record(bot_id: &str, game_id: &str, player_name: PlayerName) -> BotRecord {
+        BotRecord {
+            config: BotConfig {
+                bot_id: bot_id.to_string(),
+                game_id: game_id.to_string(),
+                player_name,
+                player_id: format!("{bot_id}-player"),
+                input_topic: format!("game.commands.{game_id}.v1"),
+                output_topic: format!("game.output.{game_id}.v1"),
+                llm_base_url: None,
+                llm_model: None,
+                llm_api_key: None,
+                llm_output_mode: None,
+                fallback_policy: FallbackPolicy::default(),
+                max_retries_per_turn: DEFAULT_MAX_RETRIES_PER_TURN,
+                expose_bot_reasoning: false,
+            },
+            status: BotLifecycleStatus::Created,
+            game_guide: None,
+            variant_label: None,
+            worker: None,
+            metrics: Arc::default(),
+        }
+    }
+
+    fn guide(version: &str, rules: &str) -> TaughtGuide {
+        TaughtGuide {
+            version: version.to_string(),
+            rules_markdown: Some(rules.to_string()),
+            command_schema: None,
+            examples: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn list_bots_reports_each_bot_and_worker_state() {
+        let (stop_tx, _stop_rx) = oneshot::channel();
+        let (update_tx, _update_rx) = mpsc::unbounded_channel();
+        let mut running = bot_record("bot-b", "game-1", PlayerName::C);
+        running.status = BotLifecycleStatus::Ready;
+        running.game_guide = Some(guide("v1", "rules"));
+        running.worker = Some(BotWorkerHandle {
+            stop_tx: Some(stop_tx),
+            update_tx,
+            join: tokio::spawn(std::future::pending()),
+        });
+
+        let mut bots = HashMap::new();
+        bots.insert(
+            "bot-a".to_string(),
+            bot_record("bot-a", "game-1", PlayerName::B),
+        );
+        bots.insert("bot-b".to_string(), running);
+        bots.insert(
+            "bot-c".to_string(),
+            bot_record("bot-c", "game-2", PlayerName::B),
+        );
+
+        let all = list_bot_infos(&bots, None);
+        let ids: Vec<&str> = all.iter().map(|info| info.bot_id.as_str()).collect();
+        assert_eq!(ids, vec!["bot-a", "bot-b", "bot-c"]);
+
+        let game_1 = list_bot_infos(&bots, Some("game-1"));
+        assert_eq!(game_1.len(), 2);
+        assert_eq!(game_1[0].status, BotLifecycleStatus::Created);
+        assert!(!game_1[0].worker_running);
+        assert_eq!(game_1[1].status, BotLifecycleStatus::Ready);
+        assert!(game_1[1].worker_running);
+        assert_eq!(game_1[1].game_guide_version.as_deref(), Some("v1"));
+
+        if let Some(worker) = bots
+            .get_mut("bot-b")
+            .and_then(|record| record.worker.take())
+        {
+            worker.join.abort();
+        }
+    }
+
+    #[tokio::test]
+    async fn api_error_body_carries_code_and_message() {
+        let response = ApiError::conflict("bot b-1 already exists").into_response();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({"error": {"code": "CONFLICT", "message": "bot b-1 already exists"}})
+        );
+    }
+
+    #[test]
+    fn reasoning_summary_redacts_keys_and_truncates() {
+        let output = "Moving up:\n the key is sk-live-123 and  secret-abc.   B is two tiles away.";
+        let summary = reasoning_summary(Some(output), Some("secret-abc")).unwrap();
+        assert_eq!(
+            summary,
+            "Moving up: the key is [redacted] and [redacted] B is two tiles away."
+        );
+
+        let long = "word ".repeat(200);
+        let summary = reasoning_summary(Some(&long), None).unwrap();
+        assert!(summary.ends_with("...[truncated]"));
+        assert_eq!(
+            summary.chars().count(),
+            REASONING_SUMMARY_MAX_CHARS + "...[truncated]".len()
+        );
+
+        assert_eq!(reasoning_summary(Some("   "), None), None);
+        assert_eq!(reasoning_summary(None, None), None);
+    }
+
+    #[test]
+    fn decide_calls_are_counted_and_their_latency_accumulates() {
+        let metrics = BotLlmMetrics::default();
+        assert_eq!(metrics.snapshot().decide_latency_ms_avg, None);
+
+        metrics.record_decide(Duration::from_millis(120), true);
+        metrics.record_decide(Duration::from_millis(80), false);
+        metrics.record_update(Duration::from_millis(30), true);
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.decide_calls, 2);
+        assert_eq!(snapshot.decide_failures, 1);
+        assert_eq!(snapshot.decide_latency_ms_total, 200);
+        assert_eq!(snapshot.decide_latency_ms_avg, Some(100));
+        assert_eq!(snapshot.update_calls, 1);
+        assert_eq!(snapshot.update_failures, 0);
+        assert_eq!(snapshot.update_latency_ms_total, 30);
+    }
+
+    #[test]
+    fn bot_info_reports_the_shared_worker_metrics() {
+        let record = bot_record("bot-a", "game-1", PlayerName::B);
+        let worker_metrics = record.metrics.clone();
+        worker_metrics.record_decide(Duration::from_millis(50), true);
+
+        let info = bot_info("bot-a", &record);
+        assert_eq!(info.llm_metrics.decide_calls, 1);
+        assert_eq!(info.llm_metrics.decide_latency_ms_total, 50);
+    }
+
+    #[tokio::test]
+    async fn reteaching_the_same_guide_only_updates_the_label() {
+        let (stop_tx, _stop_rx) = oneshot::channel();
+        let (update_tx, _update_rx) = mpsc::unbounded_channel();
+        let mut record = bot_record("bot-a", "game-1", PlayerName::B);
+        record.game_guide = Some(guide("v1", "rules"));
+        record.variant_label = Some("control".to_string());
+        record.worker = Some(BotWorkerHandle {
+            stop_tx: Some(stop_tx),
+            update_tx,
+            join: tokio::spawn(std::future::pending()),
+        });
+
+        let outcome = apply_teach(
+            &mut record,
+            guide("v1", "rules"),
+            Some("terse-prompt".to_string()),
+        );
+        assert!(matches!(outcome, TeachOutcome::Relabelled));
+        assert!(record.worker.is_some());
+        let info = bot_info("bot-a", &record);
+        assert_eq!(info.variant_label.as_deref(), Some("terse-prompt"));
+        assert_eq!(info.game_guide_version.as_deref(), Some("v1"));
+        assert!(info.worker_running);
+
+        let outcome = apply_teach(
+            &mut record,
+            guide("v1", "new rules"),
+            Some("terse-prompt".to_string()),
+        );
+        let TeachOutcome::Restart(Some(previous)) = outcome else {
+            panic!("a changed guide should hand back the running worker");
+        };
+        previous.join.abort();
+        assert!(record.worker.is_none());
+        assert_eq!(
+            record
+                .game_guide
+                .as_ref()
+                .and_then(|guide| guide.rules_markdown.as_deref()),
+            Some("new rules")
+        );
+    }
+
+    #[tokio::test]
+    async fn shutdown_sends_stop_to_every_worker() {
+        let mut bots = HashMap::new();
+        let mut stop_rxs = Vec::new();
+        for (bot_id, player_name) in [("bot-a", PlayerName::B), ("bot-b", PlayerName::C)] {
+            let (stop_tx, stop_rx) = oneshot::channel();
+            let (update_tx, _update_rx) = mpsc::unbounded_channel();
+            let mut record = bot_record(bot_id, "game-1", player_name);
+            record.worker = Some(BotWorkerHandle {
+                stop_tx: Some(stop_tx),
+                update_tx,
+                join: tokio::spawn(async {}),
+            });
+            bots.insert(bot_id.to_string(), record);
+            stop_rxs.push(stop_rx);
+        }
+        bots.insert(
+            "idle".to_string(),
+            bot_record("idle", "game-1", PlayerName::D),
+        );
+
+        let workers = stop_all_workers(&mut bots);
+        assert_eq!(workers.len(), 2);
+        for stop_rx in stop_rxs {
+            assert_eq!(stop_rx.await, Ok(()));
+        }
+        assert!(bots.values().all(|record| record.worker.is_none()));
+    }
+
+    #[tokio::test]
+    async fn create_past_max_bots_is_rejected_with_a_retry_hint() {
+        let mut bots = HashMap::new();
+        for id in ["bot-a", "bot-b"] {
+            bots.insert(id.to_string(), bot_record(id, "game-1", PlayerName::B));
+        }
+        assert!(check_bot_capacity(&bots, None).is_ok());
+        assert!(check_bot_capacity(&bots, Some(3)).is_ok());
+
+        bots.insert(
+            "bot-c".to_string(),
+            bot_record("bot-c", "game-1", PlayerName::C),
+        );
+        let error = check_bot_capacity(&bots, Some(3)).unwrap_err();
+        assert_eq!(error.status, StatusCode::SERVICE_UNAVAILABLE);
+        let response = error.into_response();
+        assert_eq!(
+            response.headers().get(header::RETRY_AFTER).unwrap(),
+            &CAPACITY_RETRY_AFTER_SECS.to_string()
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"]["code"], "AT_CAPACITY");
+        assert_eq!(body["error"]["retry_after"], CAPACITY_RETRY_AFTER_SECS);
+
+        // A bot whose worker has exited frees its slot.
+        let (stop_tx, _stop_rx) = oneshot::channel();
+        let (update_tx, _update_rx) = mpsc::unbounded_channel();
+        let finished = tokio::spawn(async {});
+        while !finished.is_finished() {
+            tokio::task::yield_now().await;
+        }
+        bots.get_mut("bot-c").unwrap().worker = Some(BotWorkerHandle {
+            stop_tx: Some(stop_tx),
+            update_tx,
+            join: finished,
+        });
+        assert_eq!(active_bot_count(&bots), 2);
+        assert!(check_bot_capacity(&bots, Some(3)).is_ok());
+    }
+}
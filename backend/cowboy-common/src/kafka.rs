@@ -0,0 +1,166 @@
+// Copyright (C) 2026 StarHuntingGames
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! rdkafka helpers shared by the services that publish to game topics. Built
+//! with the `kafka` feature so crates without a broker client skip rdkafka.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use rdkafka::{
+    message::{Header, OwnedHeaders},
+    producer::{FutureProducer, FutureRecord, Producer},
+};
+
+use crate::{
+    DEAD_LETTER_ERROR_HEADER, DEAD_LETTER_SOURCE_TOPIC_HEADER, DeadLetter, PartitionStrategy,
+    StepEvent, step_record_routing,
+};
+
+#[async_trait]
+pub trait DeadLetterPublisher: Send + Sync {
+    async fn publish_dead_letter(&self, letter: &DeadLetter) -> anyhow::Result<()>;
+}
+
+/// Republishes the raw payload to `<source topic>.dlq`, with the decode error
+/// and source topic in headers.
+pub struct KafkaDeadLetterPublisher {
+    pub producer: FutureProducer,
+}
+
+#[async_trait]
+impl DeadLetterPublisher for KafkaDeadLetterPublisher {
+    async fn publish_dead_letter(&self, letter: &DeadLetter) -> anyhow::Result<()> {
+        let topic = crate::dead_letter_topic(&letter.source_topic);
+        let headers = OwnedHeaders::new()
+            .insert(Header {
+                key: DEAD_LETTER_ERROR_HEADER,
+                value: Some(letter.error.as_str()),
+            })
+            .insert(Header {
+                key: DEAD_LETTER_SOURCE_TOPIC_HEADER,
+                value: Some(letter.source_topic.as_str()),
+            });
+        self.producer
+            .send(
+                FutureRecord::to(&topic)
+                    .key(&letter.source_topic)
+                    .payload(&letter.payload)
+                    .headers(headers),
+                Duration::from_secs(5),
+            )
+            .await
+            .map_err(|(error, _)| anyhow::anyhow!("Kafka dead-letter publish failed: {error:?}"))?;
+        Ok(())
+    }
+}
+
+/// Publishes step events to per-game output topics following a
+/// `PartitionStrategy`. Every service that writes steps goes through one of
+/// these so a game's steps are routed the same way whoever publishes them.
+///
+/// Round-robin needs the topic's real partition count, which is fixed when
+/// the topic is created and may differ from the current
+/// `GAME_TOPIC_PARTITIONS`, so it is read from the broker's metadata once per
+/// topic and cached.
+pub struct StepRecordRouter {
+    strategy: PartitionStrategy,
+    published: AtomicU64,
+    partitions: Mutex<HashMap<String, i32>>,
+}
+
+impl StepRecordRouter {
+    pub fn new(strategy: PartitionStrategy) -> Self {
+        Self {
+            strategy,
+            published: AtomicU64::new(0),
+            partitions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn publish_step_event(
+        &self,
+        producer: &FutureProducer,
+        topic: &str,
+        event: &StepEvent,
+    ) -> anyhow::Result<()> {
+        let payload = serde_json::to_string(event).context("failed to encode step event")?;
+        let partitions = match self.strategy {
+            PartitionStrategy::GameId => 1,
+            PartitionStrategy::RoundRobin => self.topic_partitions(producer, topic).await?,
+        };
+        let seq = self.published.fetch_add(1, Ordering::Relaxed);
+        let (key, partition) = step_record_routing(self.strategy, &event.game_id, partitions, seq);
+        let mut record = FutureRecord::<str, str>::to(topic).payload(&payload);
+        if let Some(key) = key {
+            record = record.key(key);
+        }
+        if let Some(partition) = partition {
+            record = record.partition(partition);
+        }
+        producer
+            .send(record, Duration::from_secs(5))
+            .await
+            .map_err(|(error, _)| anyhow::anyhow!("Kafka publish failed: {error:?}"))?;
+        Ok(())
+    }
+
+    /// Drops the cached partition count once a game's topic is done with.
+    pub fn forget_topic(&self, topic: &str) {
+        self.partitions.lock().unwrap().remove(topic);
+    }
+
+    async fn topic_partitions(
+        &self,
+        producer: &FutureProducer,
+        topic: &str,
+    ) -> anyhow::Result<i32> {
+        if let Some(partitions) = self.partitions.lock().unwrap().get(topic) {
+            return Ok(*partitions);
+        }
+        let producer = producer.clone();
+        let name = topic.to_string();
+        // `fetch_metadata` blocks on the broker round trip.
+        let metadata = tokio::task::spawn_blocking(move || {
+            producer
+                .client()
+                .fetch_metadata(Some(&name), Duration::from_secs(5))
+        })
+        .await
+        .context("metadata lookup task failed")?
+        .with_context(|| format!("failed to fetch Kafka metadata for {topic}"))?;
+        let partitions = metadata
+            .topics()
+            .iter()
+            .find(|entry| entry.name() == topic)
+            .map(|entry| entry.partitions().len())
+            .filter(|count| *count > 0)
+            .ok_or_else(|| anyhow::anyhow!("Kafka topic {topic} has no partitions"))?;
+        let partitions = i32::try_from(partitions).unwrap_or(i32::MAX);
+        self.partitions
+            .lock()
+            .unwrap()
+            .insert(topic.to_string(), partitions);
+        Ok(partitions)
+    }
+}
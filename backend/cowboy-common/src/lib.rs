@@ -13,17 +13,48 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+use std::{collections::HashMap, time::Duration};
+
 use chrono::{DateTime, Utc};
-use rand::Rng;
+use rand::{Rng, SeedableRng, rngs::StdRng};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+#[cfg(feature = "kafka")]
+pub mod kafka;
+pub mod schema;
+#[cfg(feature = "testkit")]
+pub mod testkit;
+
 pub const DEFAULT_TURN_TIMEOUT_SECONDS: u64 = 120;
 pub const DEFAULT_PLAYER_HP: i32 = 10;
 pub const DEFAULT_NUM_PLAYERS: u8 = 2;
+/// Stands in for a map cell outside a player's vision in a fogged
+/// `PlayerView`; never a real cell value.
+pub const FOG_CELL: i32 = -2;
 pub const MAX_NUM_PLAYERS: u8 = 4;
 pub const MIN_NUM_PLAYERS: u8 = 1;
+/// Longest speak, in characters after whitespace is collapsed. Speak text
+/// lands in game state and every watcher frame, so it has to stay small.
+pub const MAX_SPEAK_CHARS: usize = 280;
+/// Longest first-turn grace a game may ask for; agents that need longer
+/// than this to start have a bigger problem than the turn clock.
+pub const MAX_FIRST_TURN_GRACE_SECONDS: u64 = 600;
+/// Largest accepted map side length (rows or cols).
+pub const MAX_MAP_DIM: usize = 100;
+/// Largest accepted map area (rows * cols), so long thin maps stay bounded too.
+pub const MAX_MAP_CELLS: usize = 4096;
+/// How many random layouts the generators try before giving up on connectivity.
+pub const MAP_GENERATION_MAX_ATTEMPTS: usize = 32;
+/// Header carrying a player's secret token on command submission.
+pub const PLAYER_TOKEN_HEADER: &str = "x-player-token";
+/// Header game-service uses to tell game-manager where a command came from.
+pub const COMMAND_SOURCE_HEADER: &str = "x-command-source";
+/// Kafka header on a dead-lettered record with the decode error.
+pub const DEAD_LETTER_ERROR_HEADER: &str = "x-dlq-error";
+/// Kafka header on a dead-lettered record naming the topic it was read from.
+pub const DEAD_LETTER_SOURCE_TOPIC_HEADER: &str = "x-dlq-source-topic";
 
 /// All possible player names in turn order.
 pub const ALL_PLAYER_NAMES: [PlayerName; 4] = [
@@ -33,6 +64,20 @@ pub const ALL_PLAYER_NAMES: [PlayerName; 4] = [
     PlayerName::D,
 ];
 
+/// Stable per-seat color, so every screen draws a player the same way.
+pub fn default_color(player_name: PlayerName) -> &'static str {
+    match player_name {
+        PlayerName::A => "#ff5f8f",
+        PlayerName::B => "#62d2ff",
+        PlayerName::C => "#78f47f",
+        PlayerName::D => "#ffd27a",
+    }
+}
+
+pub fn default_display_name(player_name: PlayerName) -> String {
+    format!("Player {player_name:?}")
+}
+
 pub type PlayerId = String;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -52,6 +97,8 @@ pub enum Direction {
     Right,
 }
 
+/// Everything that can show up in a game's timeline, including system events
+/// that no player can submit.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum CommandType {
@@ -59,10 +106,65 @@ pub enum CommandType {
     Shield,
     Shoot,
     Speak,
+    /// Ends the turn without acting.
+    Pass,
+    /// Spends the turn charging so the next shot hits harder.
+    Charge,
     Timeout,
     GameStarted,
 }
 
+/// The commands that act on a player's turn, as carried by
+/// [`SubmitCommandRequest`] and [`CommandEnvelope`]. Serializes exactly like
+/// the matching [`CommandType`]; event-only types fail to deserialize.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PlayerCommandType {
+    Move,
+    Shield,
+    Shoot,
+    Speak,
+    /// Ends the turn without acting.
+    Pass,
+    /// Spends the turn charging so the next shot hits harder.
+    Charge,
+    /// Sent by the timer on behalf of a player who ran out of time; clients
+    /// can't submit it.
+    Timeout,
+}
+
+impl From<PlayerCommandType> for CommandType {
+    fn from(command_type: PlayerCommandType) -> Self {
+        match command_type {
+            PlayerCommandType::Move => Self::Move,
+            PlayerCommandType::Shield => Self::Shield,
+            PlayerCommandType::Shoot => Self::Shoot,
+            PlayerCommandType::Speak => Self::Speak,
+            PlayerCommandType::Pass => Self::Pass,
+            PlayerCommandType::Charge => Self::Charge,
+            PlayerCommandType::Timeout => Self::Timeout,
+        }
+    }
+}
+
+impl TryFrom<CommandType> for PlayerCommandType {
+    /// The event-only type that has no command equivalent.
+    type Error = CommandType;
+
+    fn try_from(command_type: CommandType) -> Result<Self, Self::Error> {
+        match command_type {
+            CommandType::Move => Ok(Self::Move),
+            CommandType::Shield => Ok(Self::Shield),
+            CommandType::Shoot => Ok(Self::Shoot),
+            CommandType::Speak => Ok(Self::Speak),
+            CommandType::Pass => Ok(Self::Pass),
+            CommandType::Charge => Ok(Self::Charge),
+            CommandType::Timeout => Ok(Self::Timeout),
+            CommandType::GameStarted => Err(command_type),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum CommandSource {
@@ -77,6 +179,8 @@ pub enum CommandSource {
 pub enum GameStatus {
     Created,
     Running,
+    /// Temporarily halted; commands are rejected and the turn clock is frozen.
+    Paused,
     Finished,
 }
 
@@ -96,6 +200,71 @@ pub enum ResultStatus {
     InvalidCommand,
     InvalidTurn,
     DuplicateCommand,
+    /// Marker events that leave the game untouched.
+    NoChange,
+}
+
+/// Why a command was not applied. Serialized names are the wire reason codes.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RejectReason {
+    GameNotRunning,
+    GamePaused,
+    InvalidTurnPlayer,
+    StaleTurnNo,
+    PlayerDead,
+    PlayerNotInGame,
+    MissingDirection,
+    MissingSpeakText,
+    ReservedCommandType,
+    MoveOutOfBounds,
+    MoveBlockedByBlock,
+    MoveBlockedByPlayer,
+    CannotShootThroughOwnShield,
+    ShootBlockedByEdge,
+    ShootBlockedByBlock,
+    ShootBlockedByPlayer,
+    DuplicateCommand,
+    LateCommandIgnored,
+    LateTimeoutIgnored,
+    /// Longer than `MAX_SPEAK_CHARS`.
+    SpeakTooLong,
+    /// Contains a banned word.
+    SpeakRejected,
+}
+
+impl RejectReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::GameNotRunning => "GAME_NOT_RUNNING",
+            Self::GamePaused => "GAME_PAUSED",
+            Self::InvalidTurnPlayer => "INVALID_TURN_PLAYER",
+            Self::StaleTurnNo => "STALE_TURN_NO",
+            Self::PlayerDead => "PLAYER_DEAD",
+            Self::PlayerNotInGame => "PLAYER_NOT_IN_GAME",
+            Self::MissingDirection => "MISSING_DIRECTION",
+            Self::MissingSpeakText => "MISSING_SPEAK_TEXT",
+            Self::ReservedCommandType => "RESERVED_COMMAND_TYPE",
+            Self::MoveOutOfBounds => "MOVE_OUT_OF_BOUNDS",
+            Self::MoveBlockedByBlock => "MOVE_BLOCKED_BY_BLOCK",
+            Self::MoveBlockedByPlayer => "MOVE_BLOCKED_BY_PLAYER",
+            Self::CannotShootThroughOwnShield => "CANNOT_SHOOT_THROUGH_OWN_SHIELD",
+            Self::ShootBlockedByEdge => "SHOOT_BLOCKED_BY_EDGE",
+            Self::ShootBlockedByBlock => "SHOOT_BLOCKED_BY_BLOCK",
+            Self::ShootBlockedByPlayer => "SHOOT_BLOCKED_BY_PLAYER",
+            Self::DuplicateCommand => "DUPLICATE_COMMAND",
+            Self::LateCommandIgnored => "LATE_COMMAND_IGNORED",
+            Self::LateTimeoutIgnored => "LATE_TIMEOUT_IGNORED",
+            Self::SpeakTooLong => "SPEAK_TOO_LONG",
+            Self::SpeakRejected => "SPEAK_REJECTED",
+        }
+    }
+}
+
+impl std::fmt::Display for RejectReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -105,16 +274,22 @@ pub enum StepEventType {
     StepApplied,
     TimeoutApplied,
     GameFinished,
+    /// A bot has started deciding its move. A marker for viewers only: it is
+    /// not stored in step history and changes nothing.
+    BotThinking,
+    /// A short, redacted summary of why a bot chose its move, for bots
+    /// configured with `expose_bot_reasoning`. Viewer-only like `BotThinking`.
+    BotReasoning,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MapData {
     pub rows: usize,
     pub cols: usize,
     pub cells: Vec<Vec<i32>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PlayerState {
     pub player_name: PlayerName,
     pub player_id: PlayerId,
@@ -123,23 +298,248 @@ pub struct PlayerState {
     pub col: usize,
     pub shield: Direction,
     pub alive: bool,
+    /// Set by `Charge`; the next shot deals extra damage and pierces a wall.
+    /// Lost when the player moves, fires or takes damage.
+    #[serde(default)]
+    pub charge_level: u8,
+    /// The player's most recent speak text, kept after they are eliminated.
+    #[serde(default)]
+    pub last_words: Option<String>,
+    /// Hits the shield can still absorb under a limited `ShieldMode`; at 0
+    /// the shield is down until raised again. Unused for permanent shields.
+    #[serde(default)]
+    pub shield_charges: u8,
+    /// UI color as `#rrggbb`; see `default_color`.
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub display_name: Option<String>,
+}
+
+/// Running tallies for one player, kept for post-game analysis.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerStats {
+    pub player_id: PlayerId,
+    pub shots_fired: u32,
+    /// Players this player's beams damaged.
+    pub hits_landed: u32,
+    pub moves: u32,
+    pub shields: u32,
+    pub speaks: u32,
+    pub damage_dealt: u32,
+    pub damage_taken: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameStateSnapshot {
     pub map: MapData,
     pub players: Vec<PlayerState>,
+    #[serde(default)]
+    pub stats: Vec<PlayerStats>,
+}
+
+/// What a player view reveals about the other players.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ViewPolicy {
+    /// Other players' shield facing and remaining shield charges are hidden.
+    #[default]
+    HideOtherShields,
+}
+
+/// Another player as seen from a `PlayerView`; whatever the policy hides is
+/// `None`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VisiblePlayer {
+    pub player_name: PlayerName,
+    pub player_id: PlayerId,
+    pub hp: i32,
+    pub row: usize,
+    pub col: usize,
+    pub alive: bool,
+    pub shield: Option<Direction>,
+    pub shield_charges: Option<u8>,
+    pub charge_level: u8,
+    pub last_words: Option<String>,
+    pub color: Option<String>,
+    pub display_name: Option<String>,
+}
+
+/// A snapshot as one player may see it: their own full state, the others
+/// redacted by a `ViewPolicy`. Under fog of war, cells out of sight read
+/// `FOG_CELL` and players out of sight are left out. See
+/// `redact_snapshot_for`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerView {
+    pub map: MapData,
+    pub player: PlayerState,
+    pub others: Vec<VisiblePlayer>,
+    #[serde(default)]
+    pub stats: Vec<PlayerStats>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerViewResponse {
+    pub game_id: String,
+    pub status: GameStatus,
+    pub turn_no: u64,
+    pub round_no: u64,
+    pub current_player_id: PlayerId,
+    pub policy: ViewPolicy,
+    #[serde(default)]
+    pub vision_radius: Option<usize>,
+    #[serde(flatten)]
+    pub view: PlayerView,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CreateGameRequest {
     pub turn_timeout_seconds: Option<u64>,
     pub map: Option<MapData>,
     #[serde(default)]
     pub bot_players: Option<Vec<PlayerName>>,
+    /// How many seats go to bots when `bot_players` isn't given; bots take
+    /// the last seats, so 3 of 4 players makes B, C and D bots.
+    #[serde(default)]
+    pub num_bots: Option<u8>,
     /// Number of players in this game (1-4, default 2).
     #[serde(default)]
     pub num_players: Option<u8>,
+    /// When true, lasers keep travelling through walls they destroy and
+    /// through players they hit (default false).
+    #[serde(default)]
+    pub laser_pierces: Option<bool>,
+    /// Furthest each laser arm travels from the entry cell, in cells.
+    /// Absent means the beam crosses the whole board.
+    #[serde(default)]
+    pub laser_range: Option<usize>,
+    /// Seed for a reproducible generated map (ignored when `map` is given).
+    #[serde(default)]
+    pub map_seed: Option<u64>,
+    /// Generate a mirrored map so every spawn has identical surroundings.
+    #[serde(default)]
+    pub symmetric_map: Option<bool>,
+    /// Damaged destructible walls regain 1 strength every this many rounds,
+    /// up to their starting value (0 or absent disables regeneration).
+    #[serde(default)]
+    pub wall_regen_rounds: Option<u64>,
+    /// Tile mix for a generated map (ignored when `map` is given).
+    #[serde(default)]
+    pub map_density: Option<MapDensity>,
+    /// Walls are kept this many tiles (Chebyshev) away from each spawn on a
+    /// generated map, so nobody starts boxed in. Default 0 clears only the
+    /// spawn tile.
+    #[serde(default)]
+    pub spawn_clear_radius: Option<usize>,
+    /// Storm mode: every this many rounds the outermost open ring of the map
+    /// turns into indestructible walls (0 or absent disables it).
+    #[serde(default)]
+    pub arena_shrink_rounds: Option<u64>,
+    /// Repeating a create with the same key returns the original game
+    /// (the `Idempotency-Key` header is used when this is absent).
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+    /// Per-player LLM settings for this game's bots; these win over the
+    /// bot-manager's file config.
+    #[serde(default)]
+    pub llm_overrides: Option<HashMap<PlayerName, LlmProfile>>,
+    /// Issue a secret token per player and reject user commands that don't
+    /// carry it (default false).
+    #[serde(default)]
+    pub require_player_token: Option<bool>,
+    /// `edge_centers` (default), `corners` or `random`.
+    #[serde(default)]
+    pub spawn_layout: Option<String>,
+    /// Once the round number passes this with several players still alive,
+    /// sudden death leaves only the player `winner_policy` ranks first, by
+    /// most HP under `first_alive`. Absent means no limit.
+    #[serde(default)]
+    pub max_rounds: Option<u64>,
+    /// `permanent` (default), `one_shot` or `degrading`.
+    #[serde(default)]
+    pub shield_mode: Option<String>,
+    /// Who wins among the survivors: `first_alive` (default), `most_hp` or
+    /// `fewest_damage_taken`.
+    #[serde(default)]
+    pub winner_policy: Option<String>,
+    /// When false, players may move onto tiles other players stand on; a
+    /// laser entering a shared tile hits the first player seated there
+    /// (default true).
+    #[serde(default)]
+    pub body_block: Option<bool>,
+    /// A shot that damages without killing pushes the target one tile along
+    /// the beam when that tile is open (default false).
+    #[serde(default)]
+    pub knockback: Option<bool>,
+    /// Start the game as soon as the last human seat is joined (default
+    /// false); bot seats count as filled from the start.
+    #[serde(default)]
+    pub auto_start_when_full: Option<bool>,
+    /// Fog of war: player views only show cells and players within this
+    /// Chebyshev distance of the player. Absent means full vision.
+    #[serde(default)]
+    pub vision_radius: Option<usize>,
+    /// Announce each elimination with a system SPEAK step naming the fallen
+    /// player (default false).
+    #[serde(default)]
+    pub narrate_eliminations: Option<bool>,
+    /// Let players step onto rubble, walls shot down to strength 1, which
+    /// clears it (default false: only empty cells are passable).
+    #[serde(default)]
+    pub allow_rubble_move: Option<bool>,
+    /// Seconds added to the turn timeout on turn 1 and on the first turn of
+    /// a player rebound to a bot, so agents still starting up aren't timed
+    /// out (default 0, at most `MAX_FIRST_TURN_GRACE_SECONDS`).
+    #[serde(default)]
+    pub first_turn_grace_seconds: Option<u64>,
+}
+
+/// LLM settings for a bot player. Unset fields fall back to the next
+/// configured layer.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LlmProfile {
+    #[serde(default)]
+    pub base_url: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub api_key: Option<String>,
+    #[serde(default)]
+    pub output_mode: Option<String>,
+    /// Publish a short, redacted summary of each decision for viewers.
+    #[serde(default)]
+    pub expose_bot_reasoning: Option<bool>,
+}
+
+/// A human claiming the next open seat in a game's lobby.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JoinGameRequest {
+    pub display_name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JoinGameResponse {
+    pub game_id: String,
+    pub display_name: String,
+    /// The claimed seat; carries its token when the game requires tokens.
+    pub player: PlayerIdentity,
+    /// Whether this join filled the last seat and auto-started the game.
+    #[serde(default)]
+    pub started: bool,
+}
+
+/// Swaps the map of a game that hasn't started: a custom `map`, or a
+/// freshly generated one (a random seed is used when none is given).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReplaceMapRequest {
+    #[serde(default)]
+    pub map: Option<MapData>,
+    #[serde(default)]
+    pub map_seed: Option<u64>,
+    #[serde(default)]
+    pub map_density: Option<MapDensity>,
+    #[serde(default)]
+    pub symmetric_map: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -165,6 +565,21 @@ pub struct StartGameResponse {
     pub round_no: u64,
     pub current_player_id: PlayerId,
     pub started_at: Option<DateTime<Utc>>,
+    /// Players with their tokens, only for games that require tokens.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub players: Vec<PlayerIdentity>,
+}
+
+/// Result of pausing or resuming a game.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PauseGameResponse {
+    pub game_id: String,
+    pub status: GameStatus,
+    /// False when the game was not in a state the request could change.
+    pub changed: bool,
+    pub reason: Option<String>,
+    pub turn_no: u64,
+    pub turn_started_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -186,6 +601,66 @@ pub struct GameInstanceResponse {
     #[serde(default)]
     pub output_topic: Option<String>,
     pub state: GameStateSnapshot,
+    /// Highest step sequence number the manager has issued for this game.
+    #[serde(default)]
+    pub last_step_seq: u64,
+    /// Whole seconds left in the current turn when the response was built.
+    #[serde(default)]
+    pub turn_seconds_remaining: Option<i64>,
+    /// Extra seconds the current turn gets on top of `turn_timeout_seconds`.
+    #[serde(default)]
+    pub turn_grace_seconds: u64,
+}
+
+/// Every rule setting that affects play in one game, so clients don't have
+/// to piece the rules together from several responses.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameConfig {
+    pub game_id: String,
+    pub map_source: MapSource,
+    pub map_rows: usize,
+    pub map_cols: usize,
+    pub num_players: u8,
+    pub player_hp: i32,
+    pub turn_timeout_seconds: u64,
+    pub laser_pierces: bool,
+    /// Cells each laser arm may travel; absent means unlimited.
+    #[serde(default)]
+    pub laser_range: Option<usize>,
+    /// 0 means walls never regenerate.
+    pub wall_regen_rounds: u64,
+    /// 0 means storm mode is off.
+    pub arena_shrink_rounds: u64,
+    pub spawn_layout: SpawnLayout,
+    /// Rounds before sudden death; absent means games run until one survivor.
+    #[serde(default)]
+    pub max_rounds: Option<u64>,
+    #[serde(default)]
+    pub shield_mode: ShieldMode,
+    #[serde(default)]
+    pub winner_policy: WinnerPolicy,
+    /// Whether living players block each other's movement.
+    #[serde(default = "default_body_block")]
+    pub body_block: bool,
+    #[serde(default)]
+    pub knockback: bool,
+    #[serde(default)]
+    pub auto_start_when_full: bool,
+    #[serde(default)]
+    pub vision_radius: Option<usize>,
+    #[serde(default)]
+    pub spawn_clear_radius: usize,
+    #[serde(default)]
+    pub narrate_eliminations: bool,
+    #[serde(default)]
+    pub allow_rubble_move: bool,
+    #[serde(default)]
+    pub first_turn_grace_seconds: u64,
+}
+
+/// Players have always blocked each other's movement.
+pub fn default_body_block() -> bool {
+    true
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -200,19 +675,30 @@ pub struct SnapshotResponse {
     /// When the current turn began (used by frontend for accurate countdown).
     #[serde(default)]
     pub turn_started_at: Option<DateTime<Utc>>,
+    /// Whole seconds left in the current turn when the snapshot was built.
+    #[serde(default)]
+    pub turn_seconds_remaining: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlayerIdentity {
     pub player_name: PlayerName,
     pub player_id: PlayerId,
+    /// Secret the player must send as `x-player-token`; only present for
+    /// games created with `require_player_token`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player_token: Option<String>,
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub display_name: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubmitCommandRequest {
     pub command_id: String,
     pub player_id: PlayerId,
-    pub command_type: CommandType,
+    pub command_type: PlayerCommandType,
     pub direction: Option<Direction>,
     #[serde(default)]
     pub speak_text: Option<String>,
@@ -233,12 +719,74 @@ pub struct CommandEnvelope {
     pub source: CommandSource,
     pub game_id: String,
     pub player_id: Option<PlayerId>,
-    pub command_type: CommandType,
+    pub command_type: PlayerCommandType,
     pub direction: Option<Direction>,
     #[serde(default)]
     pub speak_text: Option<String>,
     pub turn_no: u64,
     pub sent_at: DateTime<Utc>,
+    /// Token the submitting client presented, forwarded to game-manager.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player_token: Option<String>,
+}
+
+/// A player eliminated during a step, and who fired the fatal shot.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Elimination {
+    pub victim_player_id: PlayerId,
+    pub killer_player_id: PlayerId,
+    pub turn_no: u64,
+}
+
+/// Why one arm of a laser sweep stopped travelling.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LaserStop {
+    /// Ran off the map.
+    Edge,
+    /// Hit a wall it did not destroy (or any wall without piercing).
+    Wall,
+    /// Hit a player without piercing.
+    Player,
+    /// Travelled the game's full laser range.
+    Range,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LaserCell {
+    pub row: usize,
+    pub col: usize,
+}
+
+/// One perpendicular arm of a shot. `cells` are in travel order and include
+/// the wall or player cell that stopped the beam.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LaserArm {
+    pub direction: Direction,
+    pub cells: Vec<LaserCell>,
+    pub stopped_by: LaserStop,
+}
+
+/// Cells a shot's laser travelled through: it enters the cell next to the
+/// shooter, then sweeps both perpendicular arms from there.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LaserPath {
+    pub entry: LaserCell,
+    pub arms: Vec<LaserArm>,
+}
+
+/// One ring of the arena closing in "storm" mode.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ArenaShrink {
+    pub round_no: u64,
+    /// 0 is the outermost ring.
+    pub ring: usize,
+    /// Players caught on the ring, who each lost 1 HP.
+    #[serde(default)]
+    pub damaged_player_ids: Vec<PlayerId>,
+    /// Players the storm damage finished off.
+    #[serde(default)]
+    pub eliminated_player_ids: Vec<PlayerId>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -252,132 +800,1177 @@ pub struct StepEvent {
     pub command: Option<CommandEnvelope>,
     pub state_after: GameStateSnapshot,
     pub created_at: DateTime<Utc>,
+    /// Players eliminated by this step (empty for most steps).
+    #[serde(default)]
+    pub eliminations: Vec<Elimination>,
+    /// Set when this step crossed a round boundary that closed an arena ring.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arena_shrink: Option<ArenaShrink>,
+    /// Where the laser went, for applied shoot steps.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub laser_path: Option<LaserPath>,
+    /// Who the event is about when it carries no command (bot markers).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player_id: Option<PlayerId>,
+    /// Why the command was not applied, on events game-manager publishes
+    /// for queued commands.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reject_reason: Option<RejectReason>,
+    /// The bot's reasoning summary, on `BotReasoning` events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<String>,
+    /// Why the game ended, on `GameFinished` events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finish_reason: Option<FinishReason>,
 }
 
-/// Create the initial set of players for a game.
-///
-/// `num_players` must be 1–4.  Players are assigned in order A, B, C, D and
-/// placed on the edges of the grid (top, left, bottom, right respectively).
-pub fn initial_players(rows: usize, cols: usize, hp: i32, num_players: u8) -> Vec<PlayerState> {
-    let mid_row = rows / 2;
-    let mid_col = cols / 2;
-    let n = (num_players.max(MIN_NUM_PLAYERS).min(MAX_NUM_PLAYERS)) as usize;
+/// Why a game ended.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FinishReason {
+    /// Every other player was eliminated in play.
+    LastPlayerStanding,
+    /// No player survived.
+    Draw,
+    /// The round limit passed and sudden death picked the winner.
+    MaxRounds,
+    /// An operator ended the game.
+    Aborted,
+    /// The last opponent forfeited.
+    Forfeit,
+}
 
-    let all = vec![
-        PlayerState {
-            player_name: PlayerName::A,
-            player_id: Uuid::new_v4().to_string(),
-            hp,
-            row: 0,
-            col: mid_col,
-            shield: Direction::Up,
-            alive: true,
-        },
-        PlayerState {
-            player_name: PlayerName::B,
-            player_id: Uuid::new_v4().to_string(),
-            hp,
-            row: mid_row,
-            col: 0,
-            shield: Direction::Left,
-            alive: true,
-        },
-        PlayerState {
-            player_name: PlayerName::C,
-            player_id: Uuid::new_v4().to_string(),
-            hp,
-            row: rows.saturating_sub(1),
-            col: mid_col,
-            shield: Direction::Down,
-            alive: true,
-        },
-        PlayerState {
-            player_name: PlayerName::D,
-            player_id: Uuid::new_v4().to_string(),
-            hp,
-            row: mid_row,
-            col: cols.saturating_sub(1),
-            shield: Direction::Right,
-            alive: true,
-        },
-    ];
+/// The marker a bot publishes on its game's output topic while it decides
+/// its move for `game`'s current turn.
+pub fn bot_thinking_step(game: &GameInstanceResponse, player_id: &str) -> StepEvent {
+    StepEvent {
+        game_id: game.game_id.clone(),
+        step_seq: game.last_step_seq,
+        turn_no: game.turn_no,
+        round_no: game.round_no,
+        event_type: StepEventType::BotThinking,
+        result_status: ResultStatus::NoChange,
+        command: None,
+        state_after: game.state.clone(),
+        created_at: Utc::now(),
+        eliminations: Vec::new(),
+        arena_shrink: None,
+        laser_path: None,
+        player_id: Some(player_id.to_string()),
+        reject_reason: None,
+        reasoning: None,
+        finish_reason: None,
+    }
+}
 
-    all.into_iter().take(n).collect()
+/// Why a bot chose its move for `game`'s current turn, published after the
+/// command. `reasoning` should already be redacted and truncated.
+pub fn bot_reasoning_step(
+    game: &GameInstanceResponse,
+    player_id: &str,
+    reasoning: String,
+) -> StepEvent {
+    StepEvent {
+        event_type: StepEventType::BotReasoning,
+        reasoning: Some(reasoning),
+        finish_reason: None,
+        ..bot_thinking_step(game, player_id)
+    }
 }
 
-pub fn generate_default_map(rows: usize, cols: usize, num_players: u8) -> MapData {
-    let mut rng = rand::rng();
-    let mut cells = vec![vec![0_i32; cols]; rows];
+/// Seconds left in a turn that began at `turn_started_at`, as of `now`.
+/// Never negative; `None` when no turn is in progress.
+pub fn turn_seconds_remaining(
+    turn_started_at: Option<DateTime<Utc>>,
+    turn_timeout_seconds: u64,
+    now: DateTime<Utc>,
+) -> Option<i64> {
+    let elapsed = (now - turn_started_at?).num_seconds();
+    let timeout = i64::try_from(turn_timeout_seconds).unwrap_or(i64::MAX);
+    Some(timeout.saturating_sub(elapsed).max(0))
+}
 
-    for row in &mut cells {
-        for cell in row {
-            let roll: u8 = rng.random_range(0..100);
-            *cell = if roll < 70 {
-                0
-            } else if roll < 86 {
-                1
-            } else if roll < 96 {
-                2
-            } else {
-                -1
-            };
-        }
-    }
+/// A fresh secret for a player in a game that requires player tokens.
+pub fn generate_player_token() -> String {
+    Uuid::new_v4().simple().to_string()
+}
 
-    let mid_row = rows / 2;
-    let mid_col = cols / 2;
-    let n = (num_players.max(MIN_NUM_PLAYERS).min(MAX_NUM_PLAYERS)) as usize;
-    let all_safe_positions = [
-        (0, mid_col),
-        (mid_row, 0),
-        (rows.saturating_sub(1), mid_col),
-        (mid_row, cols.saturating_sub(1)),
-    ];
+/// First wait after a Kafka consumer error.
+pub const CONSUMER_BACKOFF_BASE: Duration = Duration::from_millis(250);
+/// Longest wait between Kafka consumer retries.
+pub const CONSUMER_BACKOFF_CAP: Duration = Duration::from_secs(10);
+
+/// Exponential backoff with jitter for consecutive failures. Each delay is
+/// drawn from the upper half of `base * 2^failures` (capped), so services
+/// retrying the same broker spread out instead of reconnecting in lockstep.
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    base: Duration,
+    cap: Duration,
+    failures: u32,
+}
+
+impl Default for Backoff {
+    /// The policy every Kafka consumer uses.
+    fn default() -> Self {
+        Self::new(CONSUMER_BACKOFF_BASE, CONSUMER_BACKOFF_CAP)
+    }
+}
 
-    for &(r, c) in all_safe_positions.iter().take(n) {
-        if r < rows && c < cols {
-            cells[r][c] = 0;
+impl Backoff {
+    pub fn new(base: Duration, cap: Duration) -> Self {
+        Self {
+            base,
+            cap,
+            failures: 0,
         }
     }
 
-    MapData { rows, cols, cells }
-}
+    /// The most the next delay can be.
+    pub fn ceiling(&self) -> Duration {
+        self.base
+            .saturating_mul(2_u32.saturating_pow(self.failures))
+            .min(self.cap)
+    }
 
-pub fn default_map() -> MapData {
-    MapData {
-        rows: 11,
-        cols: 11,
-        cells: vec![
-            vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-            vec![0, 0, 0, 2, 0, 0, 0, 2, 0, 0, 0],
-            vec![0, -1, 0, 0, 0, 1, 0, 0, 0, -1, 0],
-            vec![2, 0, 1, 0, -1, 0, -1, 0, 1, 0, 2],
-            vec![0, 0, 0, 0, 2, 0, 2, 0, 0, 0, 0],
-            vec![0, 1, -1, 2, 0, 0, 0, 2, -1, 1, 0],
-            vec![0, 0, 0, 0, 2, 0, 2, 0, 0, 0, 0],
-            vec![2, 0, 1, 0, -1, 0, -1, 0, 1, 0, 2],
-            vec![0, -1, 0, 0, 0, 1, 0, 0, 0, -1, 0],
-            vec![0, 0, 0, 2, 0, 0, 0, 2, 0, 0, 0],
-            vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-        ],
+    /// Records a failure and returns how long to wait before retrying.
+    pub fn next_delay(&mut self) -> Duration {
+        let ceiling = self.ceiling();
+        self.failures = self.failures.saturating_add(1);
+        let ceiling_ms = ceiling.as_millis() as u64;
+        Duration::from_millis(rand::rng().random_range(ceiling_ms / 2..=ceiling_ms))
+    }
+
+    /// Starts over from `base` after a success.
+    pub fn reset(&mut self) {
+        self.failures = 0;
     }
 }
 
-/// Replace `${VAR_NAME}` patterns in a string with values from environment variables.
-/// Unknown or unset variables are replaced with an empty string.
-pub fn expand_env_vars(input: &str) -> String {
-    let re = Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}").unwrap();
-    re.replace_all(input, |caps: &regex::Captures| {
-        let var_name = &caps[1];
-        std::env::var(var_name).unwrap_or_default()
-    })
-    .into_owned()
+/// A consumed Kafka payload that could not be decoded, kept byte-for-byte so
+/// it can be inspected or replayed from the dead-letter topic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeadLetter {
+    pub source_topic: String,
+    pub partition: i32,
+    pub offset: i64,
+    pub error: String,
+    pub payload: Vec<u8>,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::collections::HashSet;
+/// `game.commands.<id>.v1` dead-letters to `game.commands.<id>.v1.dlq`, which
+/// the `.v1$` subscription patterns never pick up again.
+pub fn dead_letter_topic(source_topic: &str) -> String {
+    format!("{source_topic}.dlq")
+}
+
+/// Resolves on Ctrl-C or, on Unix, SIGTERM, which container runtimes send
+/// before killing a service. Meant for `axum::serve(..).with_graceful_shutdown`.
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if tokio::signal::ctrl_c().await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        () = ctrl_c => {}
+        () = terminate => {}
+    }
+}
+
+/// Reads a boolean switch such as `KAFKA_DLQ_ENABLED`; off unless the
+/// variable is set to `1`, `true`, `yes` or `on` (case-insensitive).
+pub fn env_flag(name: &str) -> bool {
+    std::env::var(name)
+        .ok()
+        .map(|value| {
+            matches!(
+                value.trim().to_ascii_lowercase().as_str(),
+                "1" | "true" | "yes" | "on"
+            )
+        })
+        .unwrap_or(false)
+}
+
+/// How step events are spread over the partitions of a game's output topic.
+///
+/// Consumers rely on seeing a game's steps in `step_seq` order, which Kafka
+/// only guarantees within one partition. `GameId` keys every record by game
+/// so a game stays on one partition; `RoundRobin` spreads records over all
+/// partitions for throughput and gives up that ordering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PartitionStrategy {
+    #[default]
+    GameId,
+    RoundRobin,
+}
+
+impl std::str::FromStr for PartitionStrategy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "game_id" => Ok(Self::GameId),
+            "round_robin" => Ok(Self::RoundRobin),
+            other => Err(format!(
+                "unknown partition strategy {other:?}; expected game_id or round_robin"
+            )),
+        }
+    }
+}
+
+/// Reads `GAME_PARTITION_STRATEGY`. Unset means the default; an unknown name
+/// is an error so the caller can log it before falling back.
+pub fn partition_strategy_from_env() -> Result<PartitionStrategy, String> {
+    match std::env::var("GAME_PARTITION_STRATEGY") {
+        Ok(value) => value.parse(),
+        Err(_) => Ok(PartitionStrategy::default()),
+    }
+}
+
+/// Picks the record key and explicit partition for the `seq`-th step event
+/// published to a topic with `partitions` partitions. With a single partition
+/// round-robin is a no-op, so the game key is kept and ordering is preserved.
+pub fn step_record_routing(
+    strategy: PartitionStrategy,
+    game_id: &str,
+    partitions: i32,
+    seq: u64,
+) -> (Option<&str>, Option<i32>) {
+    match strategy {
+        PartitionStrategy::RoundRobin if partitions > 1 => {
+            (None, Some((seq % partitions as u64) as i32))
+        }
+        _ => (Some(game_id), None),
+    }
+}
+
+/// Where players start when a game is created.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpawnLayout {
+    /// A/B/C/D on the top, left, bottom and right edge centers.
+    #[default]
+    EdgeCenters,
+    /// A/B/C/D in the top-left, bottom-left, bottom-right and top-right corners.
+    Corners,
+    /// Distinct empty cells picked at random.
+    Random,
+}
+
+impl std::str::FromStr for SpawnLayout {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "edge_centers" => Ok(Self::EdgeCenters),
+            "corners" => Ok(Self::Corners),
+            "random" => Ok(Self::Random),
+            other => Err(format!(
+                "unknown spawn layout {other:?} (expected edge_centers, corners or random)"
+            )),
+        }
+    }
+}
+
+/// Hits a degrading shield absorbs before it drops.
+pub const DEGRADING_SHIELD_CHARGES: u8 = 2;
+
+/// How well shields hold up against hits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShieldMode {
+    /// A shield blocks every hit from the direction it faces.
+    #[default]
+    Permanent,
+    /// A shield blocks one hit, then is down until the player raises it again.
+    OneShot,
+    /// A shield blocks `DEGRADING_SHIELD_CHARGES` hits, then is down until
+    /// the player raises it again.
+    Degrading,
+}
+
+impl ShieldMode {
+    /// Hits a freshly raised shield can absorb; `None` means unlimited.
+    pub fn raised_charges(self) -> Option<u8> {
+        match self {
+            Self::Permanent => None,
+            Self::OneShot => Some(1),
+            Self::Degrading => Some(DEGRADING_SHIELD_CHARGES),
+        }
+    }
+}
+
+impl std::str::FromStr for ShieldMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "permanent" => Ok(Self::Permanent),
+            "one_shot" => Ok(Self::OneShot),
+            "degrading" => Ok(Self::Degrading),
+            other => Err(format!(
+                "unknown shield mode {other:?} (expected permanent, one_shot or degrading)"
+            )),
+        }
+    }
+}
+
+/// How the winner is picked from the players still alive when the game
+/// ends. Ties always go to the earliest seat.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WinnerPolicy {
+    /// The earliest seated survivor.
+    #[default]
+    FirstAlive,
+    /// The survivor with the most HP.
+    MostHp,
+    /// The survivor who took the least damage over the game.
+    FewestDamageTaken,
+}
+
+impl std::str::FromStr for WinnerPolicy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "first_alive" => Ok(Self::FirstAlive),
+            "most_hp" => Ok(Self::MostHp),
+            "fewest_damage_taken" => Ok(Self::FewestDamageTaken),
+            other => Err(format!(
+                "unknown winner policy {other:?} (expected first_alive, most_hp or fewest_damage_taken)"
+            )),
+        }
+    }
+}
+
+/// `snapshot` as `player_id` may see it, or `None` when they aren't in it.
+/// With a `vision_radius`, only cells and players within that Chebyshev
+/// distance of the player are shown.
+pub fn redact_snapshot_for(
+    player_id: &str,
+    snapshot: &GameStateSnapshot,
+    policy: ViewPolicy,
+    vision_radius: Option<usize>,
+) -> Option<PlayerView> {
+    let player = snapshot
+        .players
+        .iter()
+        .find(|player| player.player_id == player_id)?
+        .clone();
+    let in_sight = |row: usize, col: usize| {
+        vision_radius
+            .is_none_or(|radius| row.abs_diff(player.row).max(col.abs_diff(player.col)) <= radius)
+    };
+    let mut map = snapshot.map.clone();
+    for (r, row) in map.cells.iter_mut().enumerate() {
+        for (c, cell) in row.iter_mut().enumerate() {
+            if !in_sight(r, c) {
+                *cell = FOG_CELL;
+            }
+        }
+    }
+    let others = snapshot
+        .players
+        .iter()
+        .filter(|other| other.player_id != player_id && in_sight(other.row, other.col))
+        .map(|other| {
+            let hide_shield = match policy {
+                ViewPolicy::HideOtherShields => true,
+            };
+            VisiblePlayer {
+                player_name: other.player_name,
+                player_id: other.player_id.clone(),
+                hp: other.hp,
+                row: other.row,
+                col: other.col,
+                alive: other.alive,
+                shield: (!hide_shield).then_some(other.shield),
+                shield_charges: (!hide_shield).then_some(other.shield_charges),
+                charge_level: other.charge_level,
+                last_words: other.last_words.clone(),
+                color: other.color.clone(),
+                display_name: other.display_name.clone(),
+            }
+        })
+        .collect();
+
+    Some(PlayerView {
+        map,
+        player,
+        others,
+        stats: snapshot.stats.clone(),
+    })
+}
+
+/// Create the initial set of players for a game.
+///
+/// `num_players` must be 1–4.  Players are assigned in order A, B, C, D and
+/// placed on the edges of the grid (top, left, bottom, right respectively).
+/// On maps too small for distinct edge centers, later players are moved to
+/// the nearest free tile (see `distinct_slots`).
+pub fn initial_players(rows: usize, cols: usize, hp: i32, num_players: u8) -> Vec<PlayerState> {
+    players_at(
+        &distinct_slots(edge_center_slots(rows, cols), rows, cols),
+        hp,
+        num_players,
+    )
+}
+
+/// `initial_players` for any `SpawnLayout`. `Random` only uses empty cells
+/// and falls back to edge centers when the map has too few of them.
+pub fn initial_players_with_layout(
+    map: &MapData,
+    hp: i32,
+    num_players: u8,
+    layout: SpawnLayout,
+) -> Vec<PlayerState> {
+    let slots = match layout {
+        SpawnLayout::EdgeCenters => edge_center_slots(map.rows, map.cols),
+        SpawnLayout::Corners => corner_slots(map.rows, map.cols),
+        SpawnLayout::Random => random_spawn_slots(map, num_players, &mut rand::rng())
+            .unwrap_or_else(|| edge_center_slots(map.rows, map.cols)),
+    };
+    players_at(&distinct_slots(slots, map.rows, map.cols), hp, num_players)
+}
+
+/// Moves every slot that lands on a tile an earlier slot already holds to the
+/// nearest free tile (ties go to the lowest row, then column). On tiny maps
+/// edge centers and corners coincide, e.g. C and D on a 2x2 map. A slot with
+/// no free tile left keeps its place; `check_map_fits_players` rejects maps
+/// that small.
+fn distinct_slots(
+    mut slots: Vec<(usize, usize, Direction)>,
+    rows: usize,
+    cols: usize,
+) -> Vec<(usize, usize, Direction)> {
+    for idx in 1..slots.len() {
+        let (row, col, _) = slots[idx];
+        let taken = |r: usize, c: usize| slots[..idx].iter().any(|&(sr, sc, _)| (sr, sc) == (r, c));
+        if !taken(row, col) {
+            continue;
+        }
+        let nearest = (0..rows)
+            .flat_map(|r| (0..cols).map(move |c| (r, c)))
+            .filter(|&(r, c)| !taken(r, c))
+            .min_by_key(|&(r, c)| r.abs_diff(row) + c.abs_diff(col));
+        if let Some((r, c)) = nearest {
+            slots[idx].0 = r;
+            slots[idx].1 = c;
+        }
+    }
+    slots
+}
+
+/// `num_players` clamped to the supported range, as a count.
+fn player_count(num_players: u8) -> usize {
+    usize::from(num_players.clamp(MIN_NUM_PLAYERS, MAX_NUM_PLAYERS))
+}
+
+/// A map needs at least one tile per player so no two players share a spawn.
+pub fn check_map_fits_players(map: &MapData, num_players: u8) -> Result<(), String> {
+    let needed = player_count(num_players);
+    let tiles = map.rows * map.cols;
+    if tiles < needed {
+        return Err(format!(
+            "a {}x{} map has {tiles} tiles, too few for {needed} players",
+            map.rows, map.cols
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that every spawn `layout` would use for `num_players` on a custom
+/// map is an empty cell, naming the first player whose spawn is not. A
+/// `Random` layout only falls back to edge centers on maps with too few
+/// empty cells, so only then are those checked.
+pub fn validate_map_for_players(
+    map: &MapData,
+    num_players: u8,
+    layout: SpawnLayout,
+) -> Result<(), String> {
+    let n = player_count(num_players);
+    let slots = match layout {
+        SpawnLayout::EdgeCenters => edge_center_slots(map.rows, map.cols),
+        SpawnLayout::Corners => corner_slots(map.rows, map.cols),
+        SpawnLayout::Random => {
+            let empty = map
+                .cells
+                .iter()
+                .flatten()
+                .filter(|cell| **cell == 0)
+                .count();
+            if empty >= n {
+                return Ok(());
+            }
+            edge_center_slots(map.rows, map.cols)
+        }
+    };
+    let slots = distinct_slots(slots, map.rows, map.cols);
+    for (player_name, &(row, col, _)) in ALL_PLAYER_NAMES.iter().zip(&slots).take(n) {
+        let cell = map.cells[row][col];
+        if cell != 0 {
+            return Err(format!(
+                "spawn for player {player_name:?} at ({row},{col}) is not empty (cell value {cell})"
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn players_at(slots: &[(usize, usize, Direction)], hp: i32, num_players: u8) -> Vec<PlayerState> {
+    let n = player_count(num_players);
+    ALL_PLAYER_NAMES
+        .iter()
+        .zip(slots)
+        .take(n)
+        .map(|(&player_name, &(row, col, shield))| PlayerState {
+            player_name,
+            player_id: Uuid::new_v4().to_string(),
+            hp,
+            row,
+            col,
+            shield,
+            alive: true,
+            charge_level: 0,
+            shield_charges: 0,
+            last_words: None,
+            color: Some(default_color(player_name).to_string()),
+            display_name: Some(default_display_name(player_name)),
+        })
+        .collect()
+}
+
+fn edge_center_slots(rows: usize, cols: usize) -> Vec<(usize, usize, Direction)> {
+    let mid_row = rows / 2;
+    let mid_col = cols / 2;
+    vec![
+        (0, mid_col, Direction::Up),
+        (mid_row, 0, Direction::Left),
+        (rows.saturating_sub(1), mid_col, Direction::Down),
+        (mid_row, cols.saturating_sub(1), Direction::Right),
+    ]
+}
+
+fn corner_slots(rows: usize, cols: usize) -> Vec<(usize, usize, Direction)> {
+    let last_row = rows.saturating_sub(1);
+    let last_col = cols.saturating_sub(1);
+    vec![
+        (0, 0, Direction::Up),
+        (last_row, 0, Direction::Left),
+        (last_row, last_col, Direction::Down),
+        (0, last_col, Direction::Right),
+    ]
+}
+
+/// Distinct empty cells for `num_players` players, or `None` if the map
+/// doesn't have enough of them.
+fn random_spawn_slots<R: Rng>(
+    map: &MapData,
+    num_players: u8,
+    rng: &mut R,
+) -> Option<Vec<(usize, usize, Direction)>> {
+    let n = player_count(num_players);
+    let mut empty: Vec<(usize, usize)> = map
+        .cells
+        .iter()
+        .enumerate()
+        .flat_map(|(r, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, cell)| **cell == 0)
+                .map(move |(c, _)| (r, c))
+        })
+        .collect();
+    if empty.len() < n {
+        return None;
+    }
+
+    let shields = [
+        Direction::Up,
+        Direction::Left,
+        Direction::Down,
+        Direction::Right,
+    ];
+    let mut slots = Vec::with_capacity(n);
+    for shield in shields.into_iter().take(n) {
+        let (row, col) = empty.swap_remove(rng.random_range(0..empty.len()));
+        slots.push((row, col, shield));
+    }
+    Some(slots)
+}
+
+/// Percent chance of each tile kind when generating a map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MapDensity {
+    pub empty_pct: u8,
+    pub wall1_pct: u8,
+    pub wall2_pct: u8,
+    pub indestructible_pct: u8,
+}
+
+impl Default for MapDensity {
+    /// 70% empty, 16% strength-1, 10% strength-2, 4% indestructible.
+    fn default() -> Self {
+        Self {
+            empty_pct: 70,
+            wall1_pct: 16,
+            wall2_pct: 10,
+            indestructible_pct: 4,
+        }
+    }
+}
+
+impl MapDensity {
+    /// The four percentages must add up to exactly 100.
+    pub fn validate(&self) -> Result<(), String> {
+        let total = u32::from(self.empty_pct)
+            + u32::from(self.wall1_pct)
+            + u32::from(self.wall2_pct)
+            + u32::from(self.indestructible_pct);
+        if total != 100 {
+            return Err(format!("map density must sum to 100, got {total}"));
+        }
+        Ok(())
+    }
+}
+
+/// A random map that only clears the spawn tiles themselves.
+pub fn generate_default_map(rows: usize, cols: usize, num_players: u8) -> MapData {
+    generate_map_with_density(
+        rows,
+        cols,
+        num_players,
+        &MapDensity::default(),
+        random_map_seed(),
+        0,
+    )
+}
+
+/// Like `generate_default_map`, but the same seed always yields the same map.
+pub fn generate_map_seeded(rows: usize, cols: usize, num_players: u8, seed: u64) -> MapData {
+    generate_map_with_density(rows, cols, num_players, &MapDensity::default(), seed, 0)
+}
+
+/// Seeded generation with a caller-chosen tile mix. `density` is expected to
+/// have passed `MapDensity::validate`. Sizes past the map ceilings are cut
+/// down to fit (see `clamp_map_size`). Every tile within
+/// `spawn_clear_radius` of a spawn is left empty; 1 guarantees an open
+/// first move.
+pub fn generate_map_with_density(
+    rows: usize,
+    cols: usize,
+    num_players: u8,
+    density: &MapDensity,
+    seed: u64,
+    spawn_clear_radius: usize,
+) -> MapData {
+    let (rows, cols) = clamp_map_size(rows, cols);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let spawns = spawn_positions(rows, cols, num_players);
+    let mut map = random_map(&mut rng, rows, cols, &spawns, density, spawn_clear_radius);
+    for _ in 1..MAP_GENERATION_MAX_ATTEMPTS {
+        if positions_connected(&map, &spawns) {
+            break;
+        }
+        map = random_map(&mut rng, rows, cols, &spawns, density, spawn_clear_radius);
+    }
+    map
+}
+
+fn random_map<R: Rng>(
+    rng: &mut R,
+    rows: usize,
+    cols: usize,
+    spawns: &[(usize, usize)],
+    density: &MapDensity,
+    spawn_clear_radius: usize,
+) -> MapData {
+    let mut cells = vec![vec![0_i32; cols]; rows];
+
+    for row in &mut cells {
+        for cell in row {
+            *cell = random_cell(rng, density);
+        }
+    }
+
+    for (r, c) in spawn_clearing(rows, cols, spawns, spawn_clear_radius) {
+        cells[r][c] = 0;
+    }
+
+    MapData { rows, cols, cells }
+}
+
+/// The tiles within `radius` of any spawn, clipped to the board.
+fn spawn_clearing(
+    rows: usize,
+    cols: usize,
+    spawns: &[(usize, usize)],
+    radius: usize,
+) -> Vec<(usize, usize)> {
+    let mut tiles = Vec::new();
+    for &(r, c) in spawns {
+        if r >= rows || c >= cols {
+            continue;
+        }
+        for row in r.saturating_sub(radius)..=(r + radius).min(rows - 1) {
+            for col in c.saturating_sub(radius)..=(c + radius).min(cols - 1) {
+                tiles.push((row, col));
+            }
+        }
+    }
+    tiles
+}
+
+/// True when every player's tile can reach every other player's tile by
+/// walking over empty (`0`) cells.
+pub fn spawn_reachability(map: &MapData, players: &[PlayerState]) -> bool {
+    let positions: Vec<(usize, usize)> = players.iter().map(|p| (p.row, p.col)).collect();
+    positions_connected(map, &positions)
+}
+
+fn positions_connected(map: &MapData, positions: &[(usize, usize)]) -> bool {
+    let Some(&(start_row, start_col)) = positions.first() else {
+        return true;
+    };
+    let is_open = |r: usize, c: usize| {
+        map.cells
+            .get(r)
+            .and_then(|row| row.get(c))
+            .is_some_and(|cell| *cell == 0)
+    };
+    if !is_open(start_row, start_col) {
+        return positions.len() == 1;
+    }
+
+    let mut visited: Vec<Vec<bool>> = map.cells.iter().map(|row| vec![false; row.len()]).collect();
+    let mut stack = vec![(start_row, start_col)];
+    visited[start_row][start_col] = true;
+    while let Some((r, c)) = stack.pop() {
+        let neighbors = [
+            (r.wrapping_sub(1), c),
+            (r + 1, c),
+            (r, c.wrapping_sub(1)),
+            (r, c + 1),
+        ];
+        for (nr, nc) in neighbors {
+            if is_open(nr, nc) && !visited[nr][nc] {
+                visited[nr][nc] = true;
+                stack.push((nr, nc));
+            }
+        }
+    }
+
+    positions
+        .iter()
+        .all(|&(r, c)| visited.get(r).and_then(|row| row.get(c)) == Some(&true))
+}
+
+/// Generate a map from one seeded quadrant mirrored across both axes, so every
+/// spawn sees the same surroundings. Square maps are also mirrored across the
+/// diagonal so side spawns match top/bottom spawns. With odd dimensions the
+/// center row/column is the mirror axis.
+pub fn generate_symmetric_map(rows: usize, cols: usize, num_players: u8, seed: u64) -> MapData {
+    generate_symmetric_map_with_density(rows, cols, num_players, &MapDensity::default(), seed, 0)
+}
+
+/// `generate_symmetric_map` with a caller-chosen tile mix for the quadrant.
+/// Oversized requests are clamped and spawns cleared like
+/// `generate_map_with_density`.
+pub fn generate_symmetric_map_with_density(
+    rows: usize,
+    cols: usize,
+    num_players: u8,
+    density: &MapDensity,
+    seed: u64,
+    spawn_clear_radius: usize,
+) -> MapData {
+    let (rows, cols) = clamp_map_size(rows, cols);
+    if rows == 0 || cols == 0 {
+        return MapData {
+            rows,
+            cols,
+            cells: vec![Vec::new(); rows],
+        };
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let spawns = spawn_positions(rows, cols, num_players);
+    let mut map = random_symmetric_map(&mut rng, rows, cols, &spawns, density, spawn_clear_radius);
+    for _ in 1..MAP_GENERATION_MAX_ATTEMPTS {
+        if positions_connected(&map, &spawns) {
+            break;
+        }
+        map = random_symmetric_map(&mut rng, rows, cols, &spawns, density, spawn_clear_radius);
+    }
+    map
+}
+
+fn random_symmetric_map<R: Rng>(
+    rng: &mut R,
+    rows: usize,
+    cols: usize,
+    spawns: &[(usize, usize)],
+    density: &MapDensity,
+    spawn_clear_radius: usize,
+) -> MapData {
+    let quad_rows = rows.div_ceil(2);
+    let quad_cols = cols.div_ceil(2);
+    let mut quadrant = vec![vec![0_i32; quad_cols]; quad_rows];
+
+    for row in &mut quadrant {
+        for cell in row {
+            *cell = random_cell(rng, density);
+        }
+    }
+
+    if rows == cols {
+        let upper = quadrant.clone();
+        for (r, row) in quadrant.iter_mut().enumerate() {
+            for (c, cell) in row.iter_mut().enumerate().take(r) {
+                *cell = upper[c][r];
+            }
+        }
+    }
+
+    // Spawns fold into the quadrant's top-right (A/C) and bottom-left (B/D)
+    // corners. They are cleared after the diagonal mirror, which would
+    // otherwise refill the part of a spawn's square below the diagonal;
+    // clearing the transposed tile too keeps square maps symmetric.
+    let fold = |r: usize, c: usize| (r.min(rows - 1 - r), c.min(cols - 1 - c));
+    for (r, c) in spawn_clearing(rows, cols, spawns, spawn_clear_radius) {
+        let (qr, qc) = fold(r, c);
+        quadrant[qr][qc] = 0;
+        if rows == cols {
+            quadrant[qc][qr] = 0;
+        }
+    }
+
+    let cells = (0..rows)
+        .map(|r| {
+            (0..cols)
+                .map(|c| {
+                    let (qr, qc) = fold(r, c);
+                    quadrant[qr][qc]
+                })
+                .collect()
+        })
+        .collect();
+
+    MapData { rows, cols, cells }
+}
+
+/// A fresh random seed for callers that want a seeded generator without
+/// choosing the seed themselves.
+pub fn random_map_seed() -> u64 {
+    rand::rng().random()
+}
+
+/// Roll a single cell according to `density`.
+fn random_cell<R: Rng>(rng: &mut R, density: &MapDensity) -> i32 {
+    let roll = u32::from(rng.random_range(0..100_u8));
+    let empty = u32::from(density.empty_pct);
+    let wall1 = empty + u32::from(density.wall1_pct);
+    let wall2 = wall1 + u32::from(density.wall2_pct);
+    if roll < empty {
+        0
+    } else if roll < wall1 {
+        1
+    } else if roll < wall2 {
+        2
+    } else {
+        -1
+    }
+}
+
+/// Spawn tiles for the first `num_players` players, matching `initial_players`.
+fn spawn_positions(rows: usize, cols: usize, num_players: u8) -> Vec<(usize, usize)> {
+    let n = player_count(num_players);
+    distinct_slots(edge_center_slots(rows, cols), rows, cols)
+        .into_iter()
+        .take(n)
+        .map(|(row, col, _)| (row, col))
+        .collect()
+}
+
+/// Caps each side at `MAX_MAP_DIM`, then trims columns until the area fits
+/// `MAX_MAP_CELLS`.
+fn clamp_map_size(rows: usize, cols: usize) -> (usize, usize) {
+    let rows = rows.min(MAX_MAP_DIM);
+    let cols = cols.min(MAX_MAP_DIM).min(MAX_MAP_CELLS / rows.max(1));
+    (rows, cols)
+}
+
+/// Check that a client-supplied map is well formed: non-zero size within
+/// `MAX_MAP_DIM` and `MAX_MAP_CELLS`, `cells` matching `rows`/`cols`, and only
+/// supported values.
+pub fn validate_map(map: &MapData) -> Result<(), String> {
+    if map.rows == 0 || map.cols == 0 {
+        return Err(format!(
+            "map must not be empty (rows={}, cols={})",
+            map.rows, map.cols
+        ));
+    }
+    if map.rows > MAX_MAP_DIM || map.cols > MAX_MAP_DIM {
+        return Err(format!(
+            "map size {}x{} exceeds maximum of {MAX_MAP_DIM}x{MAX_MAP_DIM}",
+            map.rows, map.cols
+        ));
+    }
+    if map.rows * map.cols > MAX_MAP_CELLS {
+        return Err(format!(
+            "map size {}x{} has {} cells, more than the maximum of {MAX_MAP_CELLS}",
+            map.rows,
+            map.cols,
+            map.rows * map.cols
+        ));
+    }
+    if map.cells.len() != map.rows {
+        return Err(format!(
+            "map has {} rows of cells but rows={}",
+            map.cells.len(),
+            map.rows
+        ));
+    }
+    for (r, row) in map.cells.iter().enumerate() {
+        if row.len() != map.cols {
+            return Err(format!(
+                "map row {r} has {} cells but cols={}",
+                row.len(),
+                map.cols
+            ));
+        }
+        if let Some((c, value)) = row
+            .iter()
+            .enumerate()
+            .find(|(_, value)| ![-1, 0, 1, 2].contains(*value))
+        {
+            return Err(format!("map cell ({r},{c}) has unsupported value {value}"));
+        }
+    }
+    Ok(())
+}
+
+/// Rotate a map a quarter turn clockwise: row `r` becomes column
+/// `rows - 1 - r`, so a `rows x cols` map comes back as `cols x rows`.
+pub fn rotate_map_90(map: &MapData) -> MapData {
+    let cells = (0..map.cols)
+        .map(|c| (0..map.rows).rev().map(|r| map.cells[r][c]).collect())
+        .collect();
+    MapData {
+        rows: map.cols,
+        cols: map.rows,
+        cells,
+    }
+}
+
+/// Mirror a map left-to-right; dimensions are unchanged.
+pub fn reflect_map_horizontal(map: &MapData) -> MapData {
+    let cells = map
+        .cells
+        .iter()
+        .map(|row| row.iter().rev().copied().collect())
+        .collect();
+    MapData {
+        rows: map.rows,
+        cols: map.cols,
+        cells,
+    }
+}
+
+/// Mirror a map top-to-bottom; dimensions are unchanged.
+pub fn reflect_map_vertical(map: &MapData) -> MapData {
+    MapData {
+        rows: map.rows,
+        cols: map.cols,
+        cells: map.cells.iter().rev().cloned().collect(),
+    }
+}
+
+/// Symbols of the compact map format, by cell value.
+const MAP_SYMBOLS: [(i32, char); 4] = [(0, '.'), (1, 'w'), (2, 'W'), (-1, '#')];
+
+/// Encodes a map in the compact text format: `{rows}x{cols}:` then the rows
+/// separated by `/`. Within a row, `.` is empty, `w` and `W` are walls of
+/// strength 1 and 2 and `#` is indestructible; a run of two or more equal
+/// cells is written as its length followed by the symbol, e.g. `3.W`.
+/// Values the format has no symbol for are written as `?`, which
+/// `decode_map` rejects.
+pub fn encode_map(map: &MapData) -> String {
+    let symbol = |value: i32| {
+        MAP_SYMBOLS
+            .iter()
+            .find(|(cell, _)| *cell == value)
+            .map_or('?', |(_, symbol)| *symbol)
+    };
+    let rows: Vec<String> = map
+        .cells
+        .iter()
+        .map(|row| {
+            let mut out = String::new();
+            let mut cells = row.iter().peekable();
+            while let Some(&value) = cells.next() {
+                let mut run = 1;
+                while cells.next_if(|next| **next == value).is_some() {
+                    run += 1;
+                }
+                if run > 1 {
+                    out.push_str(&run.to_string());
+                }
+                out.push(symbol(value));
+            }
+            out
+        })
+        .collect();
+    format!("{}x{}:{}", map.rows, map.cols, rows.join("/"))
+}
+
+/// Parses a map written by `encode_map`. Whitespace is ignored, so long maps
+/// can be wrapped; the result must pass `validate_map`.
+pub fn decode_map(encoded: &str) -> Result<MapData, String> {
+    let compact: String = encoded.chars().filter(|c| !c.is_whitespace()).collect();
+    let (header, body) = compact
+        .split_once(':')
+        .ok_or("encoded map must start with `{rows}x{cols}:`")?;
+    let (rows, cols) = header
+        .split_once('x')
+        .and_then(|(rows, cols)| Some((rows.parse::<usize>().ok()?, cols.parse::<usize>().ok()?)))
+        .ok_or_else(|| format!("invalid map size {header:?}, expected e.g. 11x11"))?;
+    if rows > MAX_MAP_DIM || cols > MAX_MAP_DIM {
+        return Err(format!(
+            "map size {rows}x{cols} exceeds maximum of {MAX_MAP_DIM}x{MAX_MAP_DIM}"
+        ));
+    }
+
+    let mut cells = Vec::with_capacity(rows);
+    for (r, line) in body.split('/').enumerate() {
+        let mut row = Vec::with_capacity(cols);
+        let mut run = String::new();
+        for c in line.chars() {
+            if c.is_ascii_digit() {
+                run.push(c);
+                continue;
+            }
+            let value = MAP_SYMBOLS
+                .iter()
+                .find(|(_, symbol)| *symbol == c)
+                .map(|(value, _)| *value)
+                .ok_or_else(|| format!("map row {r} has unknown symbol {c:?}"))?;
+            let count = if run.is_empty() {
+                1
+            } else {
+                run.parse::<usize>()
+                    .ok()
+                    .filter(|count| *count > 0)
+                    .ok_or_else(|| format!("map row {r} has invalid run length {run:?}"))?
+            };
+            run.clear();
+            let len = row
+                .len()
+                .checked_add(count)
+                .filter(|len| *len <= cols)
+                .ok_or_else(|| format!("map row {r} has more than cols={cols} cells"))?;
+            row.resize(len, value);
+        }
+        if !run.is_empty() {
+            return Err(format!("map row {r} ends with a run length and no symbol"));
+        }
+        cells.push(row);
+    }
+
+    let map = MapData { rows, cols, cells };
+    validate_map(&map)?;
+    Ok(map)
+}
+
+pub fn default_map() -> MapData {
+    MapData {
+        rows: 11,
+        cols: 11,
+        cells: vec![
+            vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            vec![0, 0, 0, 2, 0, 0, 0, 2, 0, 0, 0],
+            vec![0, -1, 0, 0, 0, 1, 0, 0, 0, -1, 0],
+            vec![2, 0, 1, 0, -1, 0, -1, 0, 1, 0, 2],
+            vec![0, 0, 0, 0, 2, 0, 2, 0, 0, 0, 0],
+            vec![0, 1, -1, 2, 0, 0, 0, 2, -1, 1, 0],
+            vec![0, 0, 0, 0, 2, 0, 2, 0, 0, 0, 0],
+            vec![2, 0, 1, 0, -1, 0, -1, 0, 1, 0, 2],
+            vec![0, -1, 0, 0, 0, 1, 0, 0, 0, -1, 0],
+            vec![0, 0, 0, 2, 0, 0, 0, 2, 0, 0, 0],
+            vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+        ],
+    }
+}
+
+/// Replace `${VAR_NAME}` patterns in a string with values from environment variables.
+/// Unknown or unset variables are replaced with an empty string.
+pub fn expand_env_vars(input: &str) -> String {
+    let re = Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}").unwrap();
+    re.replace_all(input, |caps: &regex::Captures| {
+        let var_name = &caps[1];
+        std::env::var(var_name).unwrap_or_default()
+    })
+    .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn asymmetric_map() -> MapData {
+        MapData {
+            rows: 2,
+            cols: 3,
+            cells: vec![vec![1, 2, -1], vec![0, 2, 1]],
+        }
+    }
+
+    #[test]
+    fn rotate_map_90_turns_clockwise_and_swaps_dimensions() {
+        let rotated = rotate_map_90(&asymmetric_map());
+        assert_eq!((rotated.rows, rotated.cols), (3, 2));
+        assert_eq!(rotated.cells, vec![vec![0, 1], vec![2, 2], vec![1, -1]]);
+        assert!(validate_map(&rotated).is_ok());
+    }
+
+    #[test]
+    fn rotating_four_times_returns_the_original() {
+        let map = asymmetric_map();
+        let mut rotated = map.clone();
+        for _ in 0..4 {
+            rotated = rotate_map_90(&rotated);
+        }
+        assert_eq!((rotated.rows, rotated.cols), (map.rows, map.cols));
+        assert_eq!(rotated.cells, map.cells);
+    }
+
+    #[test]
+    fn reflections_are_their_own_inverse() {
+        let map = asymmetric_map();
+
+        let horizontal = reflect_map_horizontal(&map);
+        assert_eq!(horizontal.cells, vec![vec![-1, 2, 1], vec![1, 2, 0]]);
+        assert_eq!(reflect_map_horizontal(&horizontal).cells, map.cells);
+
+        let vertical = reflect_map_vertical(&map);
+        assert_eq!(vertical.cells, vec![vec![0, 2, 1], vec![1, 2, -1]]);
+        assert_eq!(reflect_map_vertical(&vertical).cells, map.cells);
+    }
 
     #[test]
     fn initial_players_start_on_side_centers_4_players() {
@@ -417,6 +2010,63 @@ mod tests {
         assert_eq!(unique_ids.len(), 4);
     }
 
+    #[test]
+    fn corner_layout_places_4_players_in_the_corners() {
+        let map = MapData {
+            rows: 7,
+            cols: 9,
+            cells: vec![vec![0; 9]; 7],
+        };
+        let players = initial_players_with_layout(&map, DEFAULT_PLAYER_HP, 4, SpawnLayout::Corners);
+        let tiles: Vec<(PlayerName, usize, usize)> = players
+            .iter()
+            .map(|p| (p.player_name, p.row, p.col))
+            .collect();
+        assert_eq!(
+            tiles,
+            vec![
+                (PlayerName::A, 0, 0),
+                (PlayerName::B, 6, 0),
+                (PlayerName::C, 6, 8),
+                (PlayerName::D, 0, 8),
+            ]
+        );
+    }
+
+    #[test]
+    fn random_layout_uses_distinct_empty_cells() {
+        let mut map = MapData {
+            rows: 3,
+            cols: 3,
+            cells: vec![vec![1, 0, -1], vec![2, 0, 1], vec![0, 0, 2]],
+        };
+        for seed in 0..50 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let slots = random_spawn_slots(&map, 4, &mut rng).unwrap();
+            let tiles: HashSet<(usize, usize)> = slots.iter().map(|&(r, c, _)| (r, c)).collect();
+            assert_eq!(tiles.len(), 4);
+            assert!(tiles.iter().all(|&(r, c)| map.cells[r][c] == 0));
+        }
+
+        let players = initial_players_with_layout(&map, DEFAULT_PLAYER_HP, 4, SpawnLayout::Random);
+        assert!(players.iter().all(|p| map.cells[p.row][p.col] == 0));
+
+        // Too few empty cells: fall back to the edge centers.
+        map.cells[2][0] = 1;
+        assert!(random_spawn_slots(&map, 4, &mut StdRng::seed_from_u64(1)).is_none());
+    }
+
+    #[test]
+    fn spawn_layout_parses_from_request_strings() {
+        assert_eq!("corners".parse::<SpawnLayout>(), Ok(SpawnLayout::Corners));
+        assert_eq!(" Random ".parse::<SpawnLayout>(), Ok(SpawnLayout::Random));
+        assert_eq!(
+            "edge_centers".parse::<SpawnLayout>(),
+            Ok(SpawnLayout::EdgeCenters)
+        );
+        assert!("diagonal".parse::<SpawnLayout>().is_err());
+    }
+
     #[test]
     fn initial_players_default_2_players() {
         let players = initial_players(11, 11, DEFAULT_PLAYER_HP, DEFAULT_NUM_PLAYERS);
@@ -467,6 +2117,282 @@ mod tests {
         }
     }
 
+    #[test]
+    fn generate_map_seeded_is_reproducible() {
+        let first = generate_map_seeded(11, 11, 4, 42);
+        let second = generate_map_seeded(11, 11, 4, 42);
+        assert_eq!(first.cells, second.cells);
+
+        let other = generate_map_seeded(11, 11, 4, 43);
+        assert_ne!(first.cells, other.cells);
+    }
+
+    #[test]
+    fn generate_map_seeded_keeps_spawn_positions_empty() {
+        let map = generate_map_seeded(11, 11, 4, 7);
+        assert_eq!(map.cells[0][5], 0);
+        assert_eq!(map.cells[5][0], 0);
+        assert_eq!(map.cells[10][5], 0);
+        assert_eq!(map.cells[5][10], 0);
+    }
+
+    #[test]
+    fn all_empty_density_generates_an_empty_board() {
+        let density = MapDensity {
+            empty_pct: 100,
+            wall1_pct: 0,
+            wall2_pct: 0,
+            indestructible_pct: 0,
+        };
+        let map = generate_map_with_density(11, 11, 4, &density, 3, 0);
+        assert!(map.cells.iter().flatten().all(|cell| *cell == 0));
+    }
+
+    #[test]
+    fn all_wall_density_still_clears_spawns() {
+        let density = MapDensity {
+            empty_pct: 0,
+            wall1_pct: 100,
+            wall2_pct: 0,
+            indestructible_pct: 0,
+        };
+        let map = generate_map_with_density(11, 11, 4, &density, 3, 0);
+        for (r, c) in spawn_positions(11, 11, 4) {
+            assert_eq!(map.cells[r][c], 0);
+        }
+        assert_eq!(map.cells[5][5], 1);
+    }
+
+    #[test]
+    fn spawn_clear_radius_opens_the_tiles_around_each_spawn() {
+        let density = MapDensity {
+            empty_pct: 0,
+            wall1_pct: 100,
+            wall2_pct: 0,
+            indestructible_pct: 0,
+        };
+        let spawns = spawn_positions(11, 11, 4);
+        let maps = [
+            generate_map_with_density(11, 11, 4, &density, 3, 1),
+            generate_symmetric_map_with_density(11, 11, 4, &density, 3, 1),
+        ];
+        for map in &maps {
+            for &(r, c) in &spawns {
+                let neighbors = [
+                    (r.wrapping_sub(1), c),
+                    (r + 1, c),
+                    (r, c.wrapping_sub(1)),
+                    (r, c + 1),
+                ];
+                for (nr, nc) in neighbors {
+                    if nr < 11 && nc < 11 {
+                        assert_eq!(map.cells[nr][nc], 0, "({nr},{nc}) next to ({r},{c})");
+                    }
+                }
+            }
+            assert_eq!(map.cells[5][5], 1);
+        }
+
+        let plain = generate_map_with_density(11, 11, 4, &density, 3, 0);
+        let (r, c) = spawns[0];
+        assert_eq!(plain.cells[r + 1][c], 1);
+    }
+
+    #[test]
+    fn map_density_validate_requires_sum_of_100() {
+        assert!(MapDensity::default().validate().is_ok());
+        let bad = MapDensity {
+            empty_pct: 80,
+            wall1_pct: 16,
+            wall2_pct: 10,
+            indestructible_pct: 4,
+        };
+        let err = bad.validate().unwrap_err();
+        assert!(err.contains("110"), "{err}");
+    }
+
+    #[test]
+    fn generate_symmetric_map_is_rotationally_symmetric_for_4_players() {
+        let map = generate_symmetric_map(11, 11, 4, 99);
+        let n = map.rows;
+        for r in 0..n {
+            for c in 0..n {
+                assert_eq!(map.cells[r][c], map.cells[c][n - 1 - r], "cell ({r},{c})");
+            }
+        }
+
+        // Each corner's 3x3 neighborhood, read from its own corner inward, matches.
+        let corner = |rotate: fn(usize, usize, usize) -> (usize, usize)| -> Vec<i32> {
+            let mut cells = Vec::new();
+            for dr in 0..3 {
+                for dc in 0..3 {
+                    let (r, c) = rotate(dr, dc, n);
+                    cells.push(map.cells[r][c]);
+                }
+            }
+            cells
+        };
+        let top_left = corner(|r, c, _| (r, c));
+        assert_eq!(top_left, corner(|r, c, n| (c, n - 1 - r)));
+        assert_eq!(top_left, corner(|r, c, n| (n - 1 - r, n - 1 - c)));
+        assert_eq!(top_left, corner(|r, c, n| (n - 1 - c, r)));
+
+        for (r, c) in [(0, 5), (5, 0), (10, 5), (5, 10)] {
+            assert_eq!(map.cells[r][c], 0);
+        }
+    }
+
+    #[test]
+    fn generate_symmetric_map_mirrors_odd_and_even_dimensions() {
+        let map = generate_symmetric_map(10, 7, 2, 5);
+        assert_eq!(map.cells.len(), 10);
+        for r in 0..map.rows {
+            assert_eq!(map.cells[r].len(), 7);
+            for c in 0..map.cols {
+                assert_eq!(map.cells[r][c], map.cells[map.rows - 1 - r][c]);
+                assert_eq!(map.cells[r][c], map.cells[r][map.cols - 1 - c]);
+            }
+        }
+        assert_eq!(map.cells[0][3], 0);
+        assert_eq!(map.cells[5][0], 0);
+    }
+
+    #[test]
+    fn spawn_reachability_detects_sealed_spawn() {
+        let mut map = MapData {
+            rows: 5,
+            cols: 5,
+            cells: vec![vec![0; 5]; 5],
+        };
+        let players = initial_players(5, 5, DEFAULT_PLAYER_HP, 2);
+        assert!(spawn_reachability(&map, &players));
+
+        // Wall A in at (0,2).
+        map.cells[0][1] = -1;
+        map.cells[0][3] = -1;
+        map.cells[1][2] = -1;
+        assert!(!spawn_reachability(&map, &players));
+
+        // A lone player is trivially reachable.
+        assert!(spawn_reachability(&map, &players[..1]));
+    }
+
+    #[test]
+    fn generated_maps_connect_all_spawns() {
+        for seed in 0..20 {
+            let map = generate_map_seeded(11, 11, 4, seed);
+            let players = initial_players(11, 11, DEFAULT_PLAYER_HP, 4);
+            assert!(spawn_reachability(&map, &players), "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn generated_symmetric_maps_connect_all_spawns() {
+        for seed in 0..20 {
+            let map = generate_symmetric_map(11, 11, 4, seed);
+            let players = initial_players(11, 11, DEFAULT_PLAYER_HP, 4);
+            assert!(spawn_reachability(&map, &players), "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn validate_map_accepts_well_formed_map() {
+        assert_eq!(validate_map(&default_map()), Ok(()));
+    }
+
+    #[test]
+    fn validate_map_rejects_ragged_rows() {
+        let map = MapData {
+            rows: 2,
+            cols: 3,
+            cells: vec![vec![0, 0, 0], vec![0, 0]],
+        };
+        let error = validate_map(&map).unwrap_err();
+        assert!(error.contains("row 1"), "{error}");
+    }
+
+    #[test]
+    fn validate_map_rejects_mismatched_row_count() {
+        let map = MapData {
+            rows: 3,
+            cols: 1,
+            cells: vec![vec![0], vec![0]],
+        };
+        assert!(validate_map(&map).is_err());
+    }
+
+    #[test]
+    fn validate_map_rejects_unsupported_values() {
+        let map = MapData {
+            rows: 2,
+            cols: 2,
+            cells: vec![vec![0, 0], vec![0, 7]],
+        };
+        let error = validate_map(&map).unwrap_err();
+        assert!(error.contains("(1,1)"), "{error}");
+    }
+
+    #[test]
+    fn validate_map_rejects_empty_and_oversized_maps() {
+        let empty = MapData {
+            rows: 0,
+            cols: 0,
+            cells: vec![],
+        };
+        assert!(validate_map(&empty).is_err());
+
+        let huge = MapData {
+            rows: MAX_MAP_DIM + 1,
+            cols: 1,
+            cells: vec![vec![0]; MAX_MAP_DIM + 1],
+        };
+        assert!(validate_map(&huge).is_err());
+    }
+
+    #[test]
+    fn validate_map_enforces_the_cell_ceiling() {
+        let empty_map = |rows: usize, cols: usize| MapData {
+            rows,
+            cols,
+            cells: vec![vec![0; cols]; rows],
+        };
+
+        assert!(validate_map(&empty_map(64, 64)).is_ok());
+        assert!(validate_map(&empty_map(64, 65)).is_err());
+        assert!(validate_map(&empty_map(MAX_MAP_DIM, MAX_MAP_CELLS / MAX_MAP_DIM)).is_ok());
+        assert!(validate_map(&empty_map(MAX_MAP_DIM, MAX_MAP_CELLS / MAX_MAP_DIM + 1)).is_err());
+    }
+
+    #[test]
+    fn generators_clamp_oversized_requests() {
+        let at_ceiling = generate_map_seeded(64, 64, 4, 7);
+        assert_eq!((at_ceiling.rows, at_ceiling.cols), (64, 64));
+
+        for map in [
+            generate_map_seeded(10_000, 10_000, 4, 7),
+            generate_symmetric_map(10_000, 10_000, 4, 7),
+        ] {
+            assert_eq!(
+                (map.rows, map.cols),
+                (MAX_MAP_DIM, MAX_MAP_CELLS / MAX_MAP_DIM)
+            );
+            assert_eq!(map.cells.len(), map.rows);
+            assert!(map.cells.iter().all(|row| row.len() == map.cols));
+            assert!(validate_map(&map).is_ok());
+        }
+    }
+
+    #[test]
+    fn edge_spawns_stay_distinct_on_thin_maps() {
+        for (rows, cols) in [(2, 50), (50, 2)] {
+            let players = initial_players(rows, cols, DEFAULT_PLAYER_HP, 4);
+            let cells: HashSet<(usize, usize)> = players.iter().map(|p| (p.row, p.col)).collect();
+
+            assert_eq!(cells.len(), 4, "{rows}x{cols}: {cells:?}");
+            assert!(players.iter().all(|p| p.row < rows && p.col < cols));
+        }
+    }
+
     #[test]
     fn built_in_default_map_has_valid_size_and_safe_spawns() {
         let map = default_map();
@@ -479,4 +2405,364 @@ mod tests {
         assert_eq!(map.cells[10][5], 0);
         assert_eq!(map.cells[5][10], 0);
     }
+
+    #[test]
+    fn reject_reason_round_trips_with_legacy_wire_names() {
+        let cases = [
+            (RejectReason::GameNotRunning, "GAME_NOT_RUNNING"),
+            (RejectReason::GamePaused, "GAME_PAUSED"),
+            (RejectReason::InvalidTurnPlayer, "INVALID_TURN_PLAYER"),
+            (RejectReason::StaleTurnNo, "STALE_TURN_NO"),
+            (RejectReason::PlayerDead, "PLAYER_DEAD"),
+            (RejectReason::PlayerNotInGame, "PLAYER_NOT_IN_GAME"),
+            (RejectReason::MissingDirection, "MISSING_DIRECTION"),
+            (RejectReason::MissingSpeakText, "MISSING_SPEAK_TEXT"),
+            (RejectReason::ReservedCommandType, "RESERVED_COMMAND_TYPE"),
+            (RejectReason::MoveOutOfBounds, "MOVE_OUT_OF_BOUNDS"),
+            (RejectReason::MoveBlockedByBlock, "MOVE_BLOCKED_BY_BLOCK"),
+            (RejectReason::MoveBlockedByPlayer, "MOVE_BLOCKED_BY_PLAYER"),
+            (
+                RejectReason::CannotShootThroughOwnShield,
+                "CANNOT_SHOOT_THROUGH_OWN_SHIELD",
+            ),
+            (RejectReason::ShootBlockedByEdge, "SHOOT_BLOCKED_BY_EDGE"),
+            (RejectReason::ShootBlockedByBlock, "SHOOT_BLOCKED_BY_BLOCK"),
+            (
+                RejectReason::ShootBlockedByPlayer,
+                "SHOOT_BLOCKED_BY_PLAYER",
+            ),
+            (RejectReason::DuplicateCommand, "DUPLICATE_COMMAND"),
+            (RejectReason::LateCommandIgnored, "LATE_COMMAND_IGNORED"),
+            (RejectReason::LateTimeoutIgnored, "LATE_TIMEOUT_IGNORED"),
+            (RejectReason::SpeakTooLong, "SPEAK_TOO_LONG"),
+            (RejectReason::SpeakRejected, "SPEAK_REJECTED"),
+        ];
+        for (reason, wire) in cases {
+            let encoded = serde_json::to_string(&reason).unwrap();
+            assert_eq!(encoded, format!("\"{wire}\""));
+            assert_eq!(reason.as_str(), wire);
+            let decoded: RejectReason = serde_json::from_str(&encoded).unwrap();
+            assert_eq!(decoded, reason);
+        }
+    }
+
+    #[test]
+    fn turn_seconds_remaining_counts_down_and_clamps_at_zero() {
+        let started = Utc::now();
+        let at = |secs| started + chrono::Duration::seconds(secs);
+        assert_eq!(turn_seconds_remaining(Some(started), 10, at(0)), Some(10));
+        assert_eq!(turn_seconds_remaining(Some(started), 10, at(4)), Some(6));
+        assert_eq!(turn_seconds_remaining(Some(started), 10, at(10)), Some(0));
+        assert_eq!(turn_seconds_remaining(Some(started), 10, at(25)), Some(0));
+        assert_eq!(turn_seconds_remaining(None, 10, at(4)), None);
+        assert_eq!(
+            turn_seconds_remaining(Some(started), u64::MAX, at(4)),
+            Some(i64::MAX - 4)
+        );
+    }
+
+    #[test]
+    fn player_command_types_keep_the_command_wire_names() {
+        for command_type in [
+            PlayerCommandType::Move,
+            PlayerCommandType::Shield,
+            PlayerCommandType::Shoot,
+            PlayerCommandType::Speak,
+            PlayerCommandType::Pass,
+            PlayerCommandType::Charge,
+            PlayerCommandType::Timeout,
+        ] {
+            let as_event = CommandType::from(command_type);
+            assert_eq!(
+                serde_json::to_value(command_type).unwrap(),
+                serde_json::to_value(as_event).unwrap()
+            );
+            assert_eq!(PlayerCommandType::try_from(as_event), Ok(command_type));
+        }
+        assert_eq!(
+            PlayerCommandType::try_from(CommandType::GameStarted),
+            Err(CommandType::GameStarted)
+        );
+    }
+
+    #[test]
+    fn game_started_is_not_a_submittable_command() {
+        let submit = serde_json::json!({
+            "command_id": "cmd-1",
+            "player_id": "p-1",
+            "command_type": "game_started",
+            "direction": null,
+            "turn_no": 1,
+            "client_sent_at": "2026-01-01T00:00:00Z",
+        });
+        assert!(serde_json::from_value::<SubmitCommandRequest>(submit).is_err());
+
+        let envelope = serde_json::json!({
+            "command_id": "cmd-1",
+            "source": "system",
+            "game_id": "g-1",
+            "player_id": null,
+            "command_type": "game_started",
+            "direction": null,
+            "turn_no": 0,
+            "sent_at": "2026-01-01T00:00:00Z",
+        });
+        assert!(serde_json::from_value::<CommandEnvelope>(envelope).is_err());
+    }
+
+    #[test]
+    fn backoff_grows_caps_and_resets() {
+        let mut backoff = Backoff::new(Duration::from_millis(100), Duration::from_millis(1_000));
+        let mut ceilings = Vec::new();
+        for _ in 0..6 {
+            let ceiling = backoff.ceiling();
+            let delay = backoff.next_delay();
+            assert!(
+                delay <= ceiling && delay >= ceiling / 2,
+                "{delay:?} vs {ceiling:?}"
+            );
+            ceilings.push(ceiling.as_millis());
+        }
+        assert_eq!(ceilings, vec![100, 200, 400, 800, 1_000, 1_000]);
+
+        for _ in 0..100 {
+            backoff.next_delay();
+        }
+        assert_eq!(backoff.ceiling(), Duration::from_millis(1_000));
+
+        backoff.reset();
+        assert_eq!(backoff.ceiling(), Duration::from_millis(100));
+    }
+
+    fn distinct_positions(players: &[PlayerState]) -> bool {
+        let positions: HashSet<(usize, usize)> = players.iter().map(|p| (p.row, p.col)).collect();
+        positions.len() == players.len()
+    }
+
+    #[test]
+    fn four_players_get_distinct_spawns_on_tiny_maps() {
+        for (rows, cols) in [(2, 2), (1, 4), (4, 1), (2, 3), (3, 2)] {
+            let players = initial_players(rows, cols, 10, 4);
+            assert_eq!(players.len(), 4);
+            assert!(distinct_positions(&players), "{rows}x{cols}: {players:?}");
+            assert!(players.iter().all(|p| p.row < rows && p.col < cols));
+
+            let map = generate_map_seeded(rows, cols, 4, 5);
+            for layout in [SpawnLayout::EdgeCenters, SpawnLayout::Corners] {
+                let players = initial_players_with_layout(&map, 10, 4, layout);
+                assert!(distinct_positions(&players), "{rows}x{cols} {layout:?}");
+            }
+            for player in initial_players(rows, cols, 10, 4) {
+                assert_eq!(map.cells[player.row][player.col], 0);
+            }
+        }
+    }
+
+    #[test]
+    fn colliding_spawn_moves_to_the_nearest_free_tile() {
+        // C and D share (1, 1) on a 2x2 map; D takes the only free tile.
+        let players = initial_players(2, 2, 10, 4);
+        let spots: Vec<(usize, usize)> = players.iter().map(|p| (p.row, p.col)).collect();
+        assert_eq!(spots, vec![(0, 1), (1, 0), (1, 1), (0, 0)]);
+    }
+
+    #[test]
+    fn maps_with_fewer_tiles_than_players_are_rejected() {
+        let tiny = MapData {
+            rows: 1,
+            cols: 3,
+            cells: vec![vec![0; 3]],
+        };
+        assert!(check_map_fits_players(&tiny, 4).is_err());
+        assert!(check_map_fits_players(&tiny, 3).is_ok());
+    }
+
+    #[test]
+    fn walled_spawns_are_rejected_by_name() {
+        let mut map = MapData {
+            rows: 5,
+            cols: 5,
+            cells: vec![vec![0; 5]; 5],
+        };
+        assert!(validate_map_for_players(&map, 4, SpawnLayout::EdgeCenters).is_ok());
+
+        // C spawns at the bottom edge center.
+        map.cells[4][2] = 1;
+        let reason = validate_map_for_players(&map, 4, SpawnLayout::EdgeCenters).unwrap_err();
+        assert!(reason.contains("player C at (4,2)"), "{reason}");
+        // A two-player game never uses C's spawn, and corners avoid it.
+        assert!(validate_map_for_players(&map, 2, SpawnLayout::EdgeCenters).is_ok());
+        assert!(validate_map_for_players(&map, 4, SpawnLayout::Corners).is_ok());
+        assert!(validate_map_for_players(&map, 4, SpawnLayout::Random).is_ok());
+
+        map.cells[0][0] = -1;
+        let reason = validate_map_for_players(&map, 4, SpawnLayout::Corners).unwrap_err();
+        assert!(reason.contains("player A at (0,0)"), "{reason}");
+    }
+
+    #[test]
+    fn each_seat_gets_a_distinct_default_color() {
+        let colors: HashSet<&str> = ALL_PLAYER_NAMES.into_iter().map(default_color).collect();
+        assert_eq!(colors.len(), ALL_PLAYER_NAMES.len());
+
+        let players = initial_players(11, 11, 10, 4);
+        for player in &players {
+            assert_eq!(
+                player.color.as_deref(),
+                Some(default_color(player.player_name))
+            );
+        }
+        assert_eq!(players[3].display_name.as_deref(), Some("Player D"));
+    }
+
+    #[test]
+    fn player_view_shows_own_shield_and_hides_others() {
+        let mut players = initial_players(5, 5, 10, 3);
+        players[0].shield = Direction::Left;
+        players[1].shield = Direction::Down;
+        players[1].shield_charges = 2;
+        let snapshot = GameStateSnapshot {
+            map: default_map(),
+            players: players.clone(),
+            stats: Vec::new(),
+        };
+
+        let view = redact_snapshot_for(
+            &players[0].player_id,
+            &snapshot,
+            ViewPolicy::HideOtherShields,
+            None,
+        )
+        .unwrap();
+        assert_eq!(view.player, players[0]);
+        assert_eq!(view.others.len(), 2);
+        for other in &view.others {
+            assert_ne!(other.player_id, players[0].player_id);
+            assert_eq!(other.shield, None);
+            assert_eq!(other.shield_charges, None);
+        }
+        assert_eq!(view.others[0].row, players[1].row);
+        assert_eq!(view.others[0].hp, players[1].hp);
+
+        let json = serde_json::to_value(&view.others[0]).unwrap();
+        assert!(json["shield"].is_null());
+        assert_eq!(view.map, snapshot.map);
+        assert!(redact_snapshot_for("missing", &snapshot, ViewPolicy::default(), None).is_none());
+    }
+
+    #[test]
+    fn fog_of_war_hides_cells_and_players_out_of_sight() {
+        let mut players = initial_players(7, 7, 10, 3);
+        players[0].row = 3;
+        players[0].col = 3;
+        players[1].row = 1;
+        players[1].col = 5;
+        players[2].row = 6;
+        players[2].col = 0;
+        let snapshot = GameStateSnapshot {
+            map: MapData {
+                rows: 7,
+                cols: 7,
+                cells: vec![vec![1; 7]; 7],
+            },
+            players: players.clone(),
+            stats: Vec::new(),
+        };
+
+        let view = redact_snapshot_for(
+            &players[0].player_id,
+            &snapshot,
+            ViewPolicy::default(),
+            Some(2),
+        )
+        .unwrap();
+        let seen: Vec<&str> = view.others.iter().map(|p| p.player_id.as_str()).collect();
+        assert_eq!(seen, vec![players[1].player_id.as_str()]);
+        assert_eq!(view.map.cells[1][5], 1);
+        assert_eq!(view.map.cells[5][5], 1);
+        assert_eq!(view.map.cells[0][3], FOG_CELL);
+        assert_eq!(view.map.cells[6][0], FOG_CELL);
+        assert_eq!(view.map.cells[3][6], FOG_CELL);
+        assert_eq!(
+            view.map
+                .cells
+                .iter()
+                .flatten()
+                .filter(|cell| **cell != FOG_CELL)
+                .count(),
+            25
+        );
+    }
+
+    #[test]
+    fn step_records_are_keyed_by_game_unless_round_robin_has_partitions() {
+        assert_eq!(
+            step_record_routing(PartitionStrategy::GameId, "game-1", 4, 7),
+            (Some("game-1"), None)
+        );
+        assert_eq!(
+            step_record_routing(PartitionStrategy::RoundRobin, "game-1", 1, 7),
+            (Some("game-1"), None)
+        );
+        let partitions: Vec<Option<i32>> = (0..5)
+            .map(|seq| step_record_routing(PartitionStrategy::RoundRobin, "game-1", 3, seq).1)
+            .collect();
+        assert_eq!(
+            partitions,
+            vec![Some(0), Some(1), Some(2), Some(0), Some(1)]
+        );
+        assert_eq!(
+            step_record_routing(PartitionStrategy::RoundRobin, "game-1", 3, 0).0,
+            None
+        );
+    }
+
+    #[test]
+    fn partition_strategy_parses_known_names() {
+        assert_eq!(
+            "game_id".parse::<PartitionStrategy>(),
+            Ok(PartitionStrategy::GameId)
+        );
+        assert_eq!(
+            " Round_Robin ".parse::<PartitionStrategy>(),
+            Ok(PartitionStrategy::RoundRobin)
+        );
+        assert!("random".parse::<PartitionStrategy>().is_err());
+    }
+
+    #[test]
+    fn encoded_maps_round_trip() {
+        let map = default_map();
+        let encoded = encode_map(&map);
+        assert!(encoded.starts_with("11x11:11./3.W3.W3./.#3.w3.#./"));
+        assert_eq!(decode_map(&encoded).unwrap(), map);
+
+        for seed in 0..8 {
+            let map = generate_map_seeded(9, 13, 4, seed);
+            assert_eq!(decode_map(&encode_map(&map)).unwrap(), map);
+        }
+    }
+
+    #[test]
+    fn decode_map_ignores_whitespace_and_rejects_malformed_input() {
+        let map = decode_map("2x3:\n  .w# /\n  3W\n").unwrap();
+        assert_eq!(map.cells, vec![vec![0, 1, -1], vec![2, 2, 2]]);
+
+        for (encoded, error) in [
+            ("3.", "must start with"),
+            ("2by3:3./3.", "invalid map size"),
+            ("1x3:2.x", "unknown symbol"),
+            ("1x3:0.3.", "invalid run length"),
+            ("1x3:4.", "more than cols=3"),
+            ("1x3:.18446744073709551615.", "more than cols=3"),
+            ("1x3:99999999999999999999.", "invalid run length"),
+            ("1x3:3.2", "ends with a run length"),
+            ("2x3:3.", "rows of cells"),
+            ("1x3:2.", "has 2 cells"),
+            ("1x500:500.", "exceeds maximum"),
+        ] {
+            let message = decode_map(encoded).unwrap_err();
+            assert!(message.contains(error), "{encoded}: {message}");
+        }
+    }
 }
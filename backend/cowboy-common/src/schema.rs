@@ -0,0 +1,469 @@
+// Copyright (C) 2026 StarHuntingGames
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Hand-maintained JSON Schema for the public command API, served by
+//! game-manager at `GET /v2/schema`. Enum values are serialized from the
+//! Rust types themselves so they can't drift from the wire format; object
+//! shapes must be kept in step with the structs by hand.
+
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+
+use crate::{
+    ALL_PLAYER_NAMES, CommandSource, Direction, GameStatus, MAX_FIRST_TURN_GRACE_SECONDS,
+    MAX_SPEAK_CHARS, PlayerCommandType, RejectReason, ShieldMode, SpawnLayout, WinnerPolicy,
+};
+
+/// Every command type a player's command can carry, in declaration order.
+pub const ALL_PLAYER_COMMAND_TYPES: [PlayerCommandType; 7] = [
+    PlayerCommandType::Move,
+    PlayerCommandType::Shield,
+    PlayerCommandType::Shoot,
+    PlayerCommandType::Speak,
+    PlayerCommandType::Pass,
+    PlayerCommandType::Charge,
+    PlayerCommandType::Timeout,
+];
+
+const ALL_DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Left,
+    Direction::Down,
+    Direction::Right,
+];
+
+const ALL_COMMAND_SOURCES: [CommandSource; 4] = [
+    CommandSource::User,
+    CommandSource::Bot,
+    CommandSource::Timer,
+    CommandSource::System,
+];
+
+const ALL_GAME_STATUSES: [GameStatus; 4] = [
+    GameStatus::Created,
+    GameStatus::Running,
+    GameStatus::Paused,
+    GameStatus::Finished,
+];
+
+const ALL_REJECT_REASONS: [RejectReason; 21] = [
+    RejectReason::GameNotRunning,
+    RejectReason::GamePaused,
+    RejectReason::InvalidTurnPlayer,
+    RejectReason::StaleTurnNo,
+    RejectReason::PlayerDead,
+    RejectReason::PlayerNotInGame,
+    RejectReason::MissingDirection,
+    RejectReason::MissingSpeakText,
+    RejectReason::ReservedCommandType,
+    RejectReason::MoveOutOfBounds,
+    RejectReason::MoveBlockedByBlock,
+    RejectReason::MoveBlockedByPlayer,
+    RejectReason::CannotShootThroughOwnShield,
+    RejectReason::ShootBlockedByEdge,
+    RejectReason::ShootBlockedByBlock,
+    RejectReason::ShootBlockedByPlayer,
+    RejectReason::DuplicateCommand,
+    RejectReason::LateCommandIgnored,
+    RejectReason::LateTimeoutIgnored,
+    RejectReason::SpeakTooLong,
+    RejectReason::SpeakRejected,
+];
+
+const ALL_SPAWN_LAYOUTS: [SpawnLayout; 3] = [
+    SpawnLayout::EdgeCenters,
+    SpawnLayout::Corners,
+    SpawnLayout::Random,
+];
+
+const ALL_SHIELD_MODES: [ShieldMode; 3] = [
+    ShieldMode::Permanent,
+    ShieldMode::OneShot,
+    ShieldMode::Degrading,
+];
+
+const ALL_WINNER_POLICIES: [WinnerPolicy; 3] = [
+    WinnerPolicy::FirstAlive,
+    WinnerPolicy::MostHp,
+    WinnerPolicy::FewestDamageTaken,
+];
+
+fn enum_schema<T: Serialize>(description: &str, values: &[T]) -> Value {
+    let values: Vec<Value> = values
+        .iter()
+        .map(|value| serde_json::to_value(value).expect("enum serializes"))
+        .collect();
+    json!({"type": "string", "description": description, "enum": values})
+}
+
+fn optional(schema: Value) -> Value {
+    json!({"anyOf": [schema, {"type": "null"}]})
+}
+
+fn reference(name: &str) -> Value {
+    json!({"$ref": format!("#/$defs/{name}")})
+}
+
+fn object(description: Option<&str>, required: &[&str], properties: Vec<(&str, Value)>) -> Value {
+    let properties: Map<String, Value> = properties
+        .into_iter()
+        .map(|(name, schema)| (name.to_string(), schema))
+        .collect();
+    let mut schema = json!({"type": "object", "properties": properties});
+    if !required.is_empty() {
+        schema["required"] = json!(required);
+    }
+    if let Some(description) = description {
+        schema["description"] = json!(description);
+    }
+    schema
+}
+
+fn count() -> Value {
+    json!({"type": "integer", "minimum": 0})
+}
+
+fn string() -> Value {
+    json!({"type": "string"})
+}
+
+fn boolean() -> Value {
+    json!({"type": "boolean"})
+}
+
+fn timestamp() -> Value {
+    json!({"type": "string", "format": "date-time"})
+}
+
+fn create_game_request() -> Value {
+    object(
+        Some("Body of POST /v2/games. Every field is optional."),
+        &[],
+        vec![
+            ("turn_timeout_seconds", optional(count())),
+            ("map", optional(reference("MapData"))),
+            (
+                "bot_players",
+                optional(json!({"type": "array", "items": reference("PlayerName")})),
+            ),
+            ("num_bots", optional(count())),
+            (
+                "num_players",
+                optional(json!({"type": "integer", "minimum": 1, "maximum": 4})),
+            ),
+            ("laser_pierces", optional(boolean())),
+            (
+                "laser_range",
+                optional(json!({"type": "integer", "minimum": 1})),
+            ),
+            ("map_seed", optional(count())),
+            ("symmetric_map", optional(boolean())),
+            ("wall_regen_rounds", optional(count())),
+            ("map_density", optional(reference("MapDensity"))),
+            ("spawn_clear_radius", optional(count())),
+            ("arena_shrink_rounds", optional(count())),
+            ("idempotency_key", optional(string())),
+            (
+                "llm_overrides",
+                optional(json!({
+                    "type": "object",
+                    "propertyNames": reference("PlayerName"),
+                    "additionalProperties": reference("LlmProfile"),
+                })),
+            ),
+            ("require_player_token", optional(boolean())),
+            (
+                "spawn_layout",
+                optional(enum_schema("Where players start.", &ALL_SPAWN_LAYOUTS)),
+            ),
+            (
+                "max_rounds",
+                optional(json!({"type": "integer", "minimum": 1})),
+            ),
+            (
+                "shield_mode",
+                optional(enum_schema(
+                    "How many hits a raised shield absorbs.",
+                    &ALL_SHIELD_MODES,
+                )),
+            ),
+            (
+                "winner_policy",
+                optional(enum_schema(
+                    "How the winner is picked among survivors.",
+                    &ALL_WINNER_POLICIES,
+                )),
+            ),
+            ("body_block", optional(boolean())),
+            ("knockback", optional(boolean())),
+            ("auto_start_when_full", optional(boolean())),
+            ("vision_radius", optional(count())),
+            ("narrate_eliminations", optional(boolean())),
+            ("allow_rubble_move", optional(boolean())),
+            (
+                "first_turn_grace_seconds",
+                optional(json!({
+                    "type": "integer",
+                    "minimum": 0,
+                    "maximum": MAX_FIRST_TURN_GRACE_SECONDS,
+                })),
+            ),
+        ],
+    )
+}
+
+/// The JSON Schema (draft 2020-12) of the command API's public request and
+/// response types.
+pub fn command_api_schema() -> Value {
+    let defs: Map<String, Value> = [
+        (
+            "Direction",
+            enum_schema("A facing or travel direction.", &ALL_DIRECTIONS),
+        ),
+        (
+            "PlayerName",
+            enum_schema("A seat at the table, in turn order.", &ALL_PLAYER_NAMES),
+        ),
+        (
+            "CommandType",
+            enum_schema(
+                "What a command does. `timeout` is sent by the timer and can't be submitted.",
+                &ALL_PLAYER_COMMAND_TYPES,
+            ),
+        ),
+        (
+            "CommandSource",
+            enum_schema("Who sent a command.", &ALL_COMMAND_SOURCES),
+        ),
+        (
+            "GameStatus",
+            enum_schema("Lifecycle of a game.", &ALL_GAME_STATUSES),
+        ),
+        (
+            "RejectReason",
+            enum_schema("Why a command was not applied.", &ALL_REJECT_REASONS),
+        ),
+        (
+            "MapData",
+            object(
+                Some(
+                    "Cells hold 0 (empty), 1 or 2 (destructible wall strength) or -1 (indestructible wall).",
+                ),
+                &["rows", "cols", "cells"],
+                vec![
+                    ("rows", count()),
+                    ("cols", count()),
+                    (
+                        "cells",
+                        json!({
+                            "type": "array",
+                            "items": {"type": "array", "items": {"enum": [-1, 0, 1, 2]}},
+                        }),
+                    ),
+                ],
+            ),
+        ),
+        (
+            "MapDensity",
+            object(
+                Some("Percent chance of each tile kind; the four must add up to 100."),
+                &["empty_pct", "wall1_pct", "wall2_pct", "indestructible_pct"],
+                vec![
+                    ("empty_pct", count()),
+                    ("wall1_pct", count()),
+                    ("wall2_pct", count()),
+                    ("indestructible_pct", count()),
+                ],
+            ),
+        ),
+        (
+            "LlmProfile",
+            object(
+                None,
+                &[],
+                vec![
+                    ("base_url", optional(string())),
+                    ("model", optional(string())),
+                    ("api_key", optional(string())),
+                    ("output_mode", optional(string())),
+                    ("expose_bot_reasoning", optional(boolean())),
+                ],
+            ),
+        ),
+        ("CreateGameRequest", create_game_request()),
+        (
+            "SubmitCommandRequest",
+            object(
+                Some("One command for the player whose turn it is."),
+                &[
+                    "command_id",
+                    "player_id",
+                    "command_type",
+                    "turn_no",
+                    "client_sent_at",
+                ],
+                vec![
+                    (
+                        "command_id",
+                        json!({
+                            "type": "string",
+                            "description": "Unique per game; repeats are rejected as duplicates.",
+                        }),
+                    ),
+                    ("player_id", string()),
+                    ("command_type", reference("CommandType")),
+                    ("direction", optional(reference("Direction"))),
+                    (
+                        "speak_text",
+                        optional(json!({"type": "string", "maxLength": MAX_SPEAK_CHARS})),
+                    ),
+                    ("turn_no", count()),
+                    ("client_sent_at", timestamp()),
+                ],
+            ),
+        ),
+        (
+            "SubmitCommandResponse",
+            object(
+                None,
+                &["accepted", "command_id", "queued_at"],
+                vec![
+                    ("accepted", boolean()),
+                    ("command_id", string()),
+                    ("queued_at", timestamp()),
+                ],
+            ),
+        ),
+        (
+            "Elimination",
+            object(
+                None,
+                &["victim_player_id", "killer_player_id", "turn_no"],
+                vec![
+                    ("victim_player_id", string()),
+                    ("killer_player_id", string()),
+                    ("turn_no", count()),
+                ],
+            ),
+        ),
+        (
+            "CommandEnvelope",
+            object(
+                Some("A command as recorded on a step, attributed to its source."),
+                &[
+                    "command_id",
+                    "source",
+                    "game_id",
+                    "command_type",
+                    "turn_no",
+                    "sent_at",
+                ],
+                vec![
+                    ("command_id", string()),
+                    ("source", reference("CommandSource")),
+                    ("game_id", string()),
+                    ("player_id", optional(string())),
+                    ("command_type", reference("CommandType")),
+                    ("direction", optional(reference("Direction"))),
+                    ("speak_text", optional(string())),
+                    ("turn_no", count()),
+                    ("sent_at", timestamp()),
+                ],
+            ),
+        ),
+        (
+            "ApplyCommandResponse",
+            object(
+                Some("Outcome of applying or validating a command."),
+                &[
+                    "accepted",
+                    "applied",
+                    "reason",
+                    "turn_no",
+                    "round_no",
+                    "current_player_id",
+                    "status",
+                ],
+                vec![
+                    ("accepted", boolean()),
+                    ("applied", boolean()),
+                    ("reason", optional(reference("RejectReason"))),
+                    ("turn_no", count()),
+                    ("round_no", count()),
+                    ("current_player_id", string()),
+                    ("status", reference("GameStatus")),
+                    (
+                        "eliminations",
+                        json!({"type": "array", "items": reference("Elimination")}),
+                    ),
+                    ("arena_shrink", json!({"type": "object"})),
+                    ("laser_path", json!({"type": "object"})),
+                    ("command", reference("CommandEnvelope")),
+                ],
+            ),
+        ),
+    ]
+    .into_iter()
+    .map(|(name, schema)| (name.to_string(), schema))
+    .collect();
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Cowboy command API",
+        "$defs": defs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_enums_match_the_wire_names() {
+        let schema = command_api_schema();
+        let command_types = &schema["$defs"]["CommandType"]["enum"];
+        for value in command_types.as_array().unwrap() {
+            serde_json::from_value::<PlayerCommandType>(value.clone()).unwrap();
+        }
+        assert_eq!(command_types[0], "move");
+
+        let reasons = schema["$defs"]["RejectReason"]["enum"].as_array().unwrap();
+        assert!(reasons.contains(&json!("SPEAK_TOO_LONG")));
+        for value in reasons {
+            let reason = serde_json::from_value::<RejectReason>(value.clone()).unwrap();
+            assert_eq!(value, reason.as_str());
+        }
+
+        let create = &schema["$defs"]["CreateGameRequest"]["properties"];
+        assert_eq!(
+            create["spawn_layout"]["anyOf"][0]["enum"],
+            json!(["edge_centers", "corners", "random"])
+        );
+        for value in create["shield_mode"]["anyOf"][0]["enum"]
+            .as_array()
+            .unwrap()
+        {
+            let mode = serde_json::from_value::<ShieldMode>(value.clone()).unwrap();
+            assert_eq!(value.as_str().unwrap().parse::<ShieldMode>(), Ok(mode));
+        }
+        for value in create["winner_policy"]["anyOf"][0]["enum"]
+            .as_array()
+            .unwrap()
+        {
+            let policy = serde_json::from_value::<WinnerPolicy>(value.clone()).unwrap();
+            assert_eq!(value.as_str().unwrap().parse::<WinnerPolicy>(), Ok(policy));
+        }
+    }
+}
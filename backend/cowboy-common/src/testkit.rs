@@ -0,0 +1,227 @@
+// Copyright (C) 2026 StarHuntingGames
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Building blocks for tests that drive a whole game without a broker: an
+//! in-memory topic bus, a recorder and a scripted player. Built with the
+//! `testkit` feature.
+//!
+//! The kit does not implement any service trait. The topic provisioner, step
+//! publisher and bot assigner traits are private to game-manager's `main.rs`
+//! and take its own types, so the adapters onto these types are written in
+//! that service's test module and cannot be reused from here.
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::Mutex,
+};
+
+use chrono::Utc;
+use serde::{Serialize, de::DeserializeOwned};
+use uuid::Uuid;
+
+use crate::{Direction, PlayerCommandType, PlayerId, SubmitCommandRequest};
+
+/// Topics held in memory as JSON messages, in publish order.
+///
+/// Like Kafka, publishing needs the topic to exist, and deleting a topic
+/// refuses later publishes. Unlike Kafka, a deleted topic's messages stay
+/// readable so a test can inspect what was sent before the delete.
+#[derive(Default)]
+pub struct LocalBus {
+    topics: Mutex<BTreeMap<String, LocalTopic>>,
+}
+
+#[derive(Default)]
+struct LocalTopic {
+    messages: Vec<String>,
+    deleted: bool,
+}
+
+impl LocalBus {
+    /// Creates `topic`; creating an existing live topic is a no-op.
+    pub fn create_topic(&self, topic: &str) {
+        let mut topics = self.topics.lock().unwrap();
+        let entry = topics.entry(topic.to_string()).or_default();
+        entry.deleted = false;
+    }
+
+    pub fn delete_topic(&self, topic: &str) -> Result<(), String> {
+        let mut topics = self.topics.lock().unwrap();
+        match topics.get_mut(topic) {
+            Some(entry) if !entry.deleted => {
+                entry.deleted = true;
+                Ok(())
+            }
+            _ => Err(format!("topic {topic} does not exist")),
+        }
+    }
+
+    pub fn topic_exists(&self, topic: &str) -> bool {
+        let topics = self.topics.lock().unwrap();
+        topics.get(topic).is_some_and(|entry| !entry.deleted)
+    }
+
+    /// Appends `message` to `topic` and returns its offset.
+    pub fn publish<T: Serialize>(&self, topic: &str, message: &T) -> Result<usize, String> {
+        let payload = serde_json::to_string(message).map_err(|error| error.to_string())?;
+        let mut topics = self.topics.lock().unwrap();
+        match topics.get_mut(topic) {
+            Some(entry) if !entry.deleted => {
+                entry.messages.push(payload);
+                Ok(entry.messages.len() - 1)
+            }
+            _ => Err(format!("topic {topic} does not exist")),
+        }
+    }
+
+    /// Decodes the messages on `topic` from `offset` on. Panics on a message
+    /// that doesn't decode as `T`, which in a test is a wiring mistake.
+    pub fn read<T: DeserializeOwned>(&self, topic: &str, offset: usize) -> Vec<T> {
+        let topics = self.topics.lock().unwrap();
+        let Some(entry) = topics.get(topic) else {
+            return Vec::new();
+        };
+        entry
+            .messages
+            .iter()
+            .skip(offset)
+            .map(|payload| {
+                serde_json::from_str(payload)
+                    .unwrap_or_else(|error| panic!("message on {topic} does not decode: {error}"))
+            })
+            .collect()
+    }
+}
+
+/// Collects whatever a fake hands it, for assertions after the fact.
+pub struct Recorder<T> {
+    items: Mutex<Vec<T>>,
+}
+
+impl<T> Default for Recorder<T> {
+    fn default() -> Self {
+        Self {
+            items: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl<T: Clone> Recorder<T> {
+    pub fn record(&self, item: T) {
+        self.items.lock().unwrap().push(item);
+    }
+
+    pub fn items(&self) -> Vec<T> {
+        self.items.lock().unwrap().clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A player that plays a fixed list of commands, one per turn, the way a
+/// stubbed bot loop would.
+pub struct ScriptedPlayer {
+    pub player_id: PlayerId,
+    moves: Mutex<VecDeque<(PlayerCommandType, Option<Direction>)>>,
+}
+
+impl ScriptedPlayer {
+    pub fn new(
+        player_id: impl Into<PlayerId>,
+        moves: impl IntoIterator<Item = (PlayerCommandType, Option<Direction>)>,
+    ) -> Self {
+        Self {
+            player_id: player_id.into(),
+            moves: Mutex::new(moves.into_iter().collect()),
+        }
+    }
+
+    /// The same command every turn, `turns` times.
+    pub fn repeating(
+        player_id: impl Into<PlayerId>,
+        command_type: PlayerCommandType,
+        direction: Option<Direction>,
+        turns: usize,
+    ) -> Self {
+        Self::new(
+            player_id,
+            std::iter::repeat_n((command_type, direction), turns),
+        )
+    }
+
+    /// The next scripted command for `turn_no`, or `None` once the script
+    /// has run out.
+    pub fn next_command(&self, turn_no: u64) -> Option<SubmitCommandRequest> {
+        let (command_type, direction) = self.moves.lock().unwrap().pop_front()?;
+        Some(SubmitCommandRequest {
+            command_id: Uuid::new_v4().to_string(),
+            player_id: self.player_id.clone(),
+            command_type,
+            direction,
+            speak_text: None,
+            turn_no,
+            client_sent_at: Utc::now(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_bus_keeps_order_and_refuses_missing_topics() {
+        let bus = LocalBus::default();
+        assert!(bus.publish("game.output.g1.v1", &1).is_err());
+
+        bus.create_topic("game.output.g1.v1");
+        assert_eq!(bus.publish("game.output.g1.v1", &1), Ok(0));
+        assert_eq!(bus.publish("game.output.g1.v1", &2), Ok(1));
+        assert_eq!(bus.read::<i32>("game.output.g1.v1", 0), vec![1, 2]);
+        assert_eq!(bus.read::<i32>("game.output.g1.v1", 1), vec![2]);
+
+        bus.delete_topic("game.output.g1.v1").unwrap();
+        assert!(!bus.topic_exists("game.output.g1.v1"));
+        assert!(bus.publish("game.output.g1.v1", &3).is_err());
+        assert!(bus.delete_topic("game.output.g1.v1").is_err());
+        assert_eq!(bus.read::<i32>("game.output.g1.v1", 0), vec![1, 2]);
+    }
+
+    #[test]
+    fn scripted_player_plays_its_moves_in_order_then_stops() {
+        let player = ScriptedPlayer::new(
+            "p1",
+            [
+                (PlayerCommandType::Move, Some(Direction::Left)),
+                (PlayerCommandType::Shoot, Some(Direction::Up)),
+            ],
+        );
+
+        let first = player.next_command(1).unwrap();
+        assert_eq!(first.player_id, "p1");
+        assert_eq!(first.command_type, PlayerCommandType::Move);
+        assert_eq!(first.turn_no, 1);
+        let second = player.next_command(3).unwrap();
+        assert_eq!(second.command_type, PlayerCommandType::Shoot);
+        assert_ne!(first.command_id, second.command_id);
+        assert!(player.next_command(5).is_none());
+    }
+}
@@ -15,56 +15,242 @@
 
 use std::{
     collections::{HashMap, HashSet},
+    fmt::Write as _,
     net::SocketAddr,
-    sync::Arc,
+    path::{Path as FsPath, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
 };
 
 use anyhow::Context;
 use async_trait::async_trait;
+use aws_config::BehaviorVersion;
+use aws_sdk_dynamodb::{
+    Client as DynamoClient, error::SdkError, operation::put_item::PutItemError,
+    types::AttributeValue,
+};
 use axum::{
     Json, Router,
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode, header},
     response::{IntoResponse, Response},
     routing::{get, post},
 };
 use chrono::{DateTime, Utc};
 use cowboy_common::{
-    CommandType, CreateGameRequest, CreateGameResponse, DEFAULT_NUM_PLAYERS, DEFAULT_PLAYER_HP,
-    DEFAULT_TURN_TIMEOUT_SECONDS, Direction, GameInstanceResponse, GameStateSnapshot, GameStatus,
-    MAX_NUM_PLAYERS, MIN_NUM_PLAYERS, MapData, MapSource, PlayerId, PlayerIdentity, PlayerName,
-    ResultStatus, StartGameResponse, StepEvent, StepEventType, SubmitCommandRequest, default_map,
-    generate_default_map, initial_players,
+    ALL_PLAYER_NAMES, ArenaShrink, COMMAND_SOURCE_HEADER, CommandEnvelope, CommandSource,
+    CreateGameRequest, CreateGameResponse, DEFAULT_NUM_PLAYERS, DEFAULT_PLAYER_HP,
+    DEFAULT_TURN_TIMEOUT_SECONDS, Direction, Elimination, FinishReason, GameConfig,
+    GameInstanceResponse, GameStateSnapshot, GameStatus, JoinGameRequest, JoinGameResponse,
+    LaserArm, LaserCell, LaserPath, LaserStop, LlmProfile, MAX_FIRST_TURN_GRACE_SECONDS,
+    MAX_NUM_PLAYERS, MAX_SPEAK_CHARS, MIN_NUM_PLAYERS, MapData, MapDensity, MapSource,
+    PLAYER_TOKEN_HEADER, PartitionStrategy, PauseGameResponse, PlayerCommandType, PlayerId,
+    PlayerIdentity, PlayerName, PlayerState, PlayerStats, PlayerViewResponse, RejectReason,
+    ReplaceMapRequest, ResultStatus, ShieldMode, SpawnLayout, StartGameResponse, StepEvent,
+    StepEventType, SubmitCommandRequest, ViewPolicy, WinnerPolicy, check_map_fits_players,
+    dead_letter_topic, decode_map, default_body_block, default_display_name, default_map,
+    encode_map, generate_default_map, generate_map_with_density, generate_player_token,
+    generate_symmetric_map_with_density, initial_players_with_layout, kafka::StepRecordRouter,
+    random_map_seed, redact_snapshot_for, spawn_reachability, turn_seconds_remaining, validate_map,
+    validate_map_for_players,
 };
 use lambda_http::run as lambda_run;
+use rand::{SeedableRng, rngs::StdRng, seq::SliceRandom};
 use rdkafka::{
     admin::{AdminClient, AdminOptions, NewTopic, TopicReplication},
     client::DefaultClientContext,
     config::ClientConfig,
-    producer::{FutureProducer, FutureRecord},
+    producer::FutureProducer,
     types::RDKafkaErrorCode,
 };
 use serde::{Deserialize, Serialize};
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 use tower_http::{cors::CorsLayer, trace::TraceLayer};
 use tracing::{info, warn};
 use uuid::Uuid;
 
 #[derive(Clone)]
 struct AppState {
-    store: Arc<RwLock<InMemoryStore>>,
+    store: Arc<dyn GameStore>,
+    /// Per-process cache of the shared generated map.
+    default_map: Arc<RwLock<Option<MapData>>>,
+    /// Serializes read-modify-write cycles against `store` within this process.
+    mutation_lock: Arc<Mutex<()>>,
     topic_provisioner: Arc<dyn TopicProvisioner>,
     step_event_publisher: Arc<dyn StepEventPublisher>,
     bot_assigner: Arc<dyn BotAssigner>,
+    metrics: Arc<CommandMetrics>,
+    /// Directory the games are snapshotted to, one file each, when
+    /// `GAME_STATE_SNAPSHOT_PATH` is set.
+    snapshot_path: Option<PathBuf>,
+    /// Orders snapshot writes so an older copy of a game never lands last.
+    snapshot_writes: Arc<Mutex<()>>,
+    idempotency: Arc<IdempotencyCache>,
+    speak_filter: Arc<SpeakFilter>,
+    /// How long a finished game's topics outlive it; zero deletes them at once.
+    finished_topic_retention: Duration,
+    /// Serves the raw `GameInstance` dump; set by `DEBUG_ENDPOINTS`.
+    debug_endpoints: bool,
+}
+
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+const DEFAULT_IDEMPOTENCY_TTL_SECS: u64 = 600;
+const DEFAULT_FINISHED_TOPIC_RETENTION_SECS: u64 = 300;
+
+/// Remembers the `CreateGameResponse` for each idempotency key so a retried
+/// create returns the original game instead of provisioning a new one.
+struct IdempotencyCache {
+    ttl: Duration,
+    slots: std::sync::Mutex<HashMap<String, IdempotencySlot>>,
+}
+
+type IdempotencySlot = Arc<Mutex<Option<(Instant, CreateGameResponse)>>>;
+
+impl Default for IdempotencyCache {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(DEFAULT_IDEMPOTENCY_TTL_SECS))
+    }
+}
+
+impl IdempotencyCache {
+    fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            slots: std::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the slot for `key`, dropping settled entries whose window has
+    /// passed. Callers lock the slot for the whole create so concurrent
+    /// retries wait for the first attempt instead of racing it.
+    fn slot(&self, key: &str) -> IdempotencySlot {
+        let mut slots = self.slots.lock().expect("idempotency cache lock poisoned");
+        slots.retain(|_, slot| {
+            slot.try_lock()
+                .map(|entry| {
+                    entry
+                        .as_ref()
+                        .is_none_or(|(created_at, _)| created_at.elapsed() < self.ttl)
+                })
+                .unwrap_or(true)
+        });
+        slots.entry(key.to_string()).or_default().clone()
+    }
 }
 
+/// Banned substrings for speak text, read from the file named by
+/// `SPEAK_BANNED_WORDS_FILE`: one per line, blank lines and `#` comments
+/// skipped. Matching ignores case.
 #[derive(Default)]
-struct InMemoryStore {
-    default_map: Option<MapData>,
-    games: HashMap<String, GameInstance>,
+struct SpeakFilter {
+    banned: Vec<String>,
 }
 
-#[derive(Clone)]
+impl SpeakFilter {
+    fn new<S: AsRef<str>>(banned: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            banned: banned
+                .into_iter()
+                .map(|word| word.as_ref().trim().to_lowercase())
+                .filter(|word| !word.is_empty() && !word.starts_with('#'))
+                .collect(),
+        }
+    }
+
+    fn from_env() -> Self {
+        let Some(path) = std::env::var("SPEAK_BANNED_WORDS_FILE")
+            .ok()
+            .filter(|path| !path.trim().is_empty())
+        else {
+            return Self::default();
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(raw) => {
+                let filter = Self::new(raw.lines());
+                info!(path = %path, words = filter.banned.len(), "loaded banned speak words");
+                filter
+            }
+            Err(error) => {
+                warn!(path = %path, error = %error, "failed to read banned speak words; speak is unfiltered");
+                Self::default()
+            }
+        }
+    }
+
+    fn allows(&self, text: &str) -> bool {
+        let text = text.to_lowercase();
+        !self.banned.iter().any(|word| text.contains(word.as_str()))
+    }
+}
+
+/// Process-lifetime command counters exposed on `/metrics`.
+#[derive(Default)]
+struct CommandMetrics {
+    applied: AtomicU64,
+    rejected: AtomicU64,
+    shoots: AtomicU64,
+    timeouts: AtomicU64,
+}
+
+impl CommandMetrics {
+    fn record(&self, command_type: PlayerCommandType, applied: bool) {
+        if !applied {
+            self.rejected.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        self.applied.fetch_add(1, Ordering::Relaxed);
+        match command_type {
+            PlayerCommandType::Shoot => {
+                self.shoots.fetch_add(1, Ordering::Relaxed);
+            }
+            PlayerCommandType::Timeout => {
+                self.timeouts.fetch_add(1, Ordering::Relaxed);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[async_trait]
+trait GameStore: Send + Sync {
+    async fn get(&self, game_id: &str) -> anyhow::Result<Option<GameInstance>>;
+    /// Writes `game` and bumps its `version`. Fails with [`StoreConflict`]
+    /// when the stored game moved on since `game` was loaded.
+    async fn put(&self, game: &mut GameInstance) -> anyhow::Result<()>;
+    async fn remove(&self, game_id: &str) -> anyhow::Result<()>;
+    async fn list(&self) -> anyhow::Result<Vec<GameInstance>>;
+}
+
+/// Another writer changed the game after the copy being written was loaded.
+#[derive(Debug)]
+struct StoreConflict {
+    game_id: String,
+}
+
+impl std::fmt::Display for StoreConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "game {} was changed by another writer", self.game_id)
+    }
+}
+
+impl std::error::Error for StoreConflict {}
+
+#[derive(Default)]
+struct InMemoryGameStore {
+    games: RwLock<HashMap<String, GameInstance>>,
+}
+
+/// Stores each game as a JSON document keyed by `game_id`, so stateless
+/// (Lambda) deployments share games across invocations.
+struct DynamoGameStore {
+    client: DynamoClient,
+    table_name: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 struct GameInstance {
     game_id: String,
     status: GameStatus,
@@ -81,6 +267,93 @@ struct GameInstance {
     last_step_seq: u64,
     input_topic: String,
     output_topic: String,
+    /// Lasers continue through destroyed walls and hit players.
+    #[serde(default)]
+    laser_pierces: bool,
+    /// Cells each laser arm may travel; `None` is unlimited.
+    #[serde(default)]
+    laser_range: Option<usize>,
+    /// Wall strengths at creation, used as the ceiling for regeneration.
+    #[serde(default)]
+    original_cells: Vec<Vec<i32>>,
+    /// Rounds between wall regeneration ticks; 0 disables regeneration.
+    #[serde(default)]
+    wall_regen_rounds: u64,
+    /// Rounds between arena shrinks in storm mode; 0 disables it.
+    #[serde(default)]
+    arena_shrink_rounds: u64,
+    /// How many outer rings the storm has already walled off.
+    #[serde(default)]
+    arena_rings_closed: usize,
+    /// User commands must present the acting player's token.
+    #[serde(default)]
+    require_player_token: bool,
+    /// Secret per-player tokens, only issued when `require_player_token` is set.
+    #[serde(default)]
+    player_tokens: HashMap<PlayerId, String>,
+    #[serde(default)]
+    spawn_layout: SpawnLayout,
+    /// When the game was paused, so resume can give the turn its time back.
+    #[serde(default)]
+    paused_at: Option<DateTime<Utc>>,
+    /// Sudden death triggers once `round_no` passes this.
+    #[serde(default)]
+    max_rounds: Option<u64>,
+    #[serde(default)]
+    shield_mode: ShieldMode,
+    #[serde(default)]
+    winner_policy: WinnerPolicy,
+    /// Seats handed to bots at creation; humans can't join them.
+    #[serde(default)]
+    bot_slots: Vec<PlayerName>,
+    /// Seats humans have claimed through the lobby, with their display names.
+    #[serde(default)]
+    claimed_slots: HashMap<PlayerId, String>,
+    /// One pre-committed command per player, applied on their first turn at
+    /// or after the command's `turn_no`.
+    #[serde(default)]
+    queued_commands: HashMap<PlayerId, SubmitCommandRequest>,
+    /// Living players can't move onto each other's tiles.
+    #[serde(default = "default_body_block")]
+    body_block: bool,
+    /// Damaging, non-lethal hits push the target one tile along the beam.
+    #[serde(default)]
+    knockback: bool,
+    /// Start once the last human seat is joined.
+    #[serde(default)]
+    auto_start_when_full: bool,
+    /// Fog of war radius for player views; `None` is full vision.
+    #[serde(default)]
+    vision_radius: Option<usize>,
+    /// Generated maps keep walls this far from each spawn; also used when
+    /// the map is rerolled.
+    #[serde(default)]
+    spawn_clear_radius: usize,
+    /// Each elimination gets a system SPEAK step saying who fell.
+    #[serde(default)]
+    narrate_eliminations: bool,
+    /// Players may step onto rubble; see `MovableCellPredicate`.
+    #[serde(default)]
+    allow_rubble_move: bool,
+    /// Extra seconds on turn 1 and on a rebound player's first turn, while
+    /// a bot's agent may still be starting.
+    #[serde(default)]
+    first_turn_grace_seconds: u64,
+    /// Players rebound to a bot whose turn since then hasn't ended yet.
+    #[serde(default)]
+    rebound_bot_players: HashSet<PlayerId>,
+    /// Set when the game finishes. A forfeit that leaves one survivor sets
+    /// `Forfeit` early so the finish that follows keeps it.
+    #[serde(default)]
+    finish_reason: Option<FinishReason>,
+    /// When a finished game's topics are due for deletion; cleared once
+    /// they are gone.
+    #[serde(default)]
+    topics_retire_at: Option<DateTime<Utc>>,
+    /// Bumped by every store write; a write from a copy loaded before
+    /// someone else's write is refused.
+    #[serde(default)]
+    version: u64,
 }
 
 #[derive(Debug, Clone)]
@@ -98,6 +371,9 @@ trait TopicProvisioner: Send + Sync {
 #[async_trait]
 trait StepEventPublisher: Send + Sync {
     async fn publish_step_event(&self, topic: &str, event: &StepEvent) -> anyhow::Result<()>;
+
+    /// Drops any per-topic state once a finished game's topics are retired.
+    fn forget_topic(&self, _topic: &str) {}
 }
 
 #[async_trait]
@@ -106,7 +382,11 @@ trait BotAssigner: Send + Sync {
         &self,
         game: &GameInstance,
         requested_bot_players: Option<Vec<PlayerName>>,
+        llm_overrides: Option<HashMap<PlayerName, LlmProfile>>,
     ) -> anyhow::Result<()>;
+
+    /// Hands an existing player slot to a (possibly new) bot.
+    async fn bind_bot(&self, game_id: &str, player_id: &str) -> anyhow::Result<()>;
 }
 
 #[derive(Clone)]
@@ -170,6 +450,7 @@ impl BotAssigner for BotManagerAssigner {
         &self,
         game: &GameInstance,
         requested_bot_players: Option<Vec<PlayerName>>,
+        llm_overrides: Option<HashMap<PlayerName, LlmProfile>>,
     ) -> anyhow::Result<()> {
         if requested_bot_players.is_none() {
             let url = self.endpoint(&format!(
@@ -178,7 +459,8 @@ impl BotAssigner for BotManagerAssigner {
             ));
             let payload = serde_json::json!({
                 "apply_immediately": true,
-                "force_recreate": true
+                "force_recreate": true,
+                "llm_overrides": llm_overrides
             });
             return self.post_json(url, payload).await;
         }
@@ -215,11 +497,21 @@ impl BotAssigner for BotManagerAssigner {
         let payload = serde_json::json!({
             "human_player_ids": human_player_ids,
             "bot_player_ids": bot_player_ids,
-            "force_recreate": true
+            "force_recreate": true,
+            "llm_overrides": llm_overrides
         });
 
         self.post_json(url, payload).await
     }
+
+    async fn bind_bot(&self, game_id: &str, player_id: &str) -> anyhow::Result<()> {
+        let url = self.endpoint(&format!("internal/v3/games/{game_id}/bindings"));
+        let payload = serde_json::json!({
+            "player_id": player_id,
+            "create_bot_if_missing": true
+        });
+        self.post_json(url, payload).await
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -227,6 +519,18 @@ struct KafkaTopicProvisioner {
     bootstrap_servers: Vec<String>,
     input_topic_prefix: String,
     output_topic_prefix: String,
+    partitions: i32,
+}
+
+/// Partition count for newly created per-game topics. Kafka fixes it at topic
+/// creation, so changing `GAME_TOPIC_PARTITIONS` only affects games created
+/// afterwards; consumers of existing topics keep their old assignment.
+fn topic_partitions_from_env() -> i32 {
+    std::env::var("GAME_TOPIC_PARTITIONS")
+        .ok()
+        .and_then(|value| value.trim().parse::<i32>().ok())
+        .filter(|partitions| *partitions > 0)
+        .unwrap_or(1)
 }
 
 impl KafkaTopicProvisioner {
@@ -252,6 +556,7 @@ impl KafkaTopicProvisioner {
             output_topic_prefix: std::env::var("GAME_OUTPUT_TOPIC_PREFIX")
                 .ok()
                 .unwrap_or_else(|| "game.output".to_string()),
+            partitions: topic_partitions_from_env(),
         }
     }
 
@@ -274,6 +579,7 @@ impl KafkaTopicProvisioner {
 #[derive(Clone)]
 struct KafkaStepEventPublisher {
     producer: FutureProducer,
+    router: Arc<StepRecordRouter>,
 }
 
 impl KafkaStepEventPublisher {
@@ -286,24 +592,27 @@ impl KafkaStepEventPublisher {
             .set("message.timeout.ms", "5000")
             .create()
             .context("failed to create Kafka step-event producer")?;
-        Ok(Self { producer })
+        let strategy = cowboy_common::partition_strategy_from_env().unwrap_or_else(|error| {
+            warn!(error = %error, "ignoring GAME_PARTITION_STRATEGY");
+            PartitionStrategy::default()
+        });
+        Ok(Self {
+            producer,
+            router: Arc::new(StepRecordRouter::new(strategy)),
+        })
     }
 }
 
 #[async_trait]
 impl StepEventPublisher for KafkaStepEventPublisher {
     async fn publish_step_event(&self, topic: &str, event: &StepEvent) -> anyhow::Result<()> {
-        let payload = serde_json::to_string(event).context("failed to encode step event")?;
-        self.producer
-            .send(
-                FutureRecord::to(topic)
-                    .key(&event.game_id)
-                    .payload(&payload),
-                std::time::Duration::from_secs(5),
-            )
+        self.router
+            .publish_step_event(&self.producer, topic, event)
             .await
-            .map_err(|(error, _)| anyhow::anyhow!("Kafka publish failed: {error:?}"))?;
-        Ok(())
+    }
+
+    fn forget_topic(&self, topic: &str) {
+        self.router.forget_topic(topic);
     }
 }
 
@@ -313,9 +622,13 @@ impl TopicProvisioner for KafkaTopicProvisioner {
         let game_topics = self.game_topics(game_id);
         let admin_client = self.admin_client()?;
 
-        let topics = vec![
+        let topics = [
             NewTopic::new(&game_topics.input_topic, 1, TopicReplication::Fixed(1)),
-            NewTopic::new(&game_topics.output_topic, 1, TopicReplication::Fixed(1)),
+            NewTopic::new(
+                &game_topics.output_topic,
+                self.partitions,
+                TopicReplication::Fixed(1),
+            ),
         ];
         let results = admin_client
             .create_topics(topics.iter(), &AdminOptions::new())
@@ -343,15 +656,11 @@ impl TopicProvisioner for KafkaTopicProvisioner {
 
     async fn delete_game_topics(&self, game_topics: &GameTopics) -> anyhow::Result<()> {
         let admin_client = self.admin_client()?;
+        let topic_names = retired_topic_names(game_topics);
+        let topic_names: Vec<&str> = topic_names.iter().map(String::as_str).collect();
 
         let results = admin_client
-            .delete_topics(
-                &[
-                    game_topics.input_topic.as_str(),
-                    game_topics.output_topic.as_str(),
-                ],
-                &AdminOptions::new(),
-            )
+            .delete_topics(&topic_names, &AdminOptions::new())
             .await
             .context("failed to send Kafka topic deletion request")?;
 
@@ -375,15 +684,161 @@ impl TopicProvisioner for KafkaTopicProvisioner {
     }
 }
 
+/// A game's topics plus the dead-letter topics consumers may have created
+/// for them.
+fn retired_topic_names(game_topics: &GameTopics) -> [String; 4] {
+    [
+        game_topics.input_topic.clone(),
+        game_topics.output_topic.clone(),
+        dead_letter_topic(&game_topics.input_topic),
+        dead_letter_topic(&game_topics.output_topic),
+    ]
+}
+
+/// `KAFKA_OPTIONAL` mode: when topics can't be provisioned the game still
+/// gets the topic names it would have had, and deletion failures are logged.
+struct OptionalTopicProvisioner {
+    inner: Arc<dyn TopicProvisioner>,
+    input_topic_prefix: String,
+    output_topic_prefix: String,
+}
+
+#[async_trait]
+impl TopicProvisioner for OptionalTopicProvisioner {
+    async fn ensure_game_topics(&self, game_id: &str) -> anyhow::Result<GameTopics> {
+        match self.inner.ensure_game_topics(game_id).await {
+            Ok(game_topics) => Ok(game_topics),
+            Err(error) => {
+                warn!(
+                    game_id = %game_id,
+                    error = %error,
+                    "Kafka unavailable; creating game with unprovisioned topics"
+                );
+                Ok(GameTopics {
+                    input_topic: format!("{}.{}.v1", self.input_topic_prefix, game_id),
+                    output_topic: format!("{}.{}.v1", self.output_topic_prefix, game_id),
+                })
+            }
+        }
+    }
+
+    async fn delete_game_topics(&self, game_topics: &GameTopics) -> anyhow::Result<()> {
+        if let Err(error) = self.inner.delete_game_topics(game_topics).await {
+            warn!(
+                input_topic = %game_topics.input_topic,
+                output_topic = %game_topics.output_topic,
+                error = %error,
+                "Kafka unavailable; skipping per-game topic deletion"
+            );
+        }
+        Ok(())
+    }
+}
+
+/// `KAFKA_OPTIONAL` mode: publish failures (or a producer that could not be
+/// created at all) are logged instead of failing the request.
+struct OptionalStepEventPublisher {
+    inner: Option<Arc<dyn StepEventPublisher>>,
+}
+
+#[async_trait]
+impl StepEventPublisher for OptionalStepEventPublisher {
+    async fn publish_step_event(&self, topic: &str, event: &StepEvent) -> anyhow::Result<()> {
+        let Some(inner) = self.inner.as_ref() else {
+            info!(
+                topic = %topic,
+                game_id = %event.game_id,
+                step_seq = event.step_seq,
+                "Kafka unavailable; dropping step event"
+            );
+            return Ok(());
+        };
+        if let Err(error) = inner.publish_step_event(topic, event).await {
+            warn!(
+                topic = %topic,
+                game_id = %event.game_id,
+                step_seq = event.step_seq,
+                error = %error,
+                "Kafka unavailable; dropping step event"
+            );
+        }
+        Ok(())
+    }
+
+    fn forget_topic(&self, topic: &str) {
+        if let Some(inner) = self.inner.as_ref() {
+            inner.forget_topic(topic);
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct ApplyCommandResponse {
     accepted: bool,
     applied: bool,
-    reason: Option<String>,
+    reason: Option<RejectReason>,
     turn_no: u64,
     round_no: u64,
     current_player_id: PlayerId,
     status: GameStatus,
+    #[serde(default)]
+    eliminations: Vec<Elimination>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    arena_shrink: Option<ArenaShrink>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    laser_path: Option<LaserPath>,
+    /// The command as applied, attributed to the source it arrived from, so
+    /// the step built from this response can name its actor.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    command: Option<CommandEnvelope>,
+    /// The `step_seq` the command's step takes, set when it was applied.
+    /// A rejected command changes nothing, so whoever publishes its step
+    /// reserves a seq from `reserve_step_seq_handler` instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    step_seq: Option<u64>,
+    /// Steps the command set off (elimination narration, queued commands
+    /// that fired), numbered after `step_seq`. The caller publishes them
+    /// after the command's own step so consumers see them in seq order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    follow_up_steps: Vec<StepEvent>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ReserveStepSeqResponse {
+    game_id: String,
+    step_seq: u64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct ApplyBatchQuery {
+    #[serde(default)]
+    stop_on_reject: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SkipTurnResponse {
+    game_id: String,
+    skipped_player_id: PlayerId,
+    step_seq: u64,
+    turn_no: u64,
+    round_no: u64,
+    current_player_id: PlayerId,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct QueueCommandResponse {
+    game_id: String,
+    player_id: PlayerId,
+    command_id: String,
+    turn_no: u64,
+    /// Whether this replaced a command the player had already queued.
+    replaced: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ValidateCommandResponse {
+    legal: bool,
+    reason: Option<RejectReason>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -400,6 +855,60 @@ struct FinishGameResponse {
     turn_no: u64,
     round_no: u64,
     current_player_id: PlayerId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    finish_reason: Option<FinishReason>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ForfeitPlayerRequest {
+    /// Keep the player alive and rebind the slot to a bot instead.
+    #[serde(default)]
+    replace_with_bot: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ForfeitPlayerResponse {
+    game_id: String,
+    player_id: PlayerId,
+    forfeited: bool,
+    rebound_to_bot: bool,
+    status: GameStatus,
+    turn_no: u64,
+    round_no: u64,
+    current_player_id: PlayerId,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct SimulateGameRequest {
+    #[serde(flatten)]
+    game: CreateGameRequest,
+    /// Seeds the built-in policy so a request replays move for move; random
+    /// when absent.
+    #[serde(default)]
+    policy_seed: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SimulateGameResponse {
+    policy_seed: u64,
+    winner_player_id: Option<PlayerId>,
+    finish_reason: FinishReason,
+    turn_no: u64,
+    round_no: u64,
+    final_state: GameStateSnapshot,
+    actions: Vec<SimulatedAction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SimulatedAction {
+    turn_no: u64,
+    round_no: u64,
+    player_id: PlayerId,
+    command_type: PlayerCommandType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    direction: Option<Direction>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    eliminated: Vec<PlayerId>,
 }
 
 #[tokio::main]
@@ -411,20 +920,123 @@ async fn main() -> anyhow::Result<()> {
         )
         .init();
 
-    let mut store = InMemoryStore::default();
-    if let Some(map) = load_default_map_config() {
-        info!(rows = map.rows, cols = map.cols, "loaded default map from YAML config");
-        store.default_map = Some(map);
+    let default_map = load_default_map_config();
+    if let Some(map) = default_map.as_ref() {
+        info!(
+            rows = map.rows,
+            cols = map.cols,
+            "loaded default map from YAML config"
+        );
+    }
+
+    // Only an explicit GAME_TABLE opts into DynamoDB; DYNAMODB_ENDPOINT alone
+    // (set for other services in docker-compose) keeps the in-memory store.
+    let store: Arc<dyn GameStore> = match std::env::var("GAME_TABLE")
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+    {
+        Some(table_name) => {
+            let mut loader = aws_config::defaults(BehaviorVersion::latest());
+            if let Ok(endpoint) = std::env::var("DYNAMODB_ENDPOINT") {
+                loader = loader.endpoint_url(endpoint);
+            }
+            let config = loader.load().await;
+            info!(table_name = %table_name, "game-manager DynamoDB game store enabled");
+            Arc::new(DynamoGameStore {
+                client: DynamoClient::new(&config),
+                table_name,
+            })
+        }
+        None => Arc::new(InMemoryGameStore::default()),
+    };
+
+    let snapshot_path = std::env::var("GAME_STATE_SNAPSHOT_PATH")
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from);
+    if let Some(path) = snapshot_path.as_deref() {
+        let games = load_state_snapshot(path)?;
+        let restored = games.len();
+        for mut game in games.into_values() {
+            store.put(&mut game).await?;
+        }
+        info!(
+            path = %path.display(),
+            games = restored,
+            "restored games from state snapshot"
+        );
     }
 
+    let kafka_optional = cowboy_common::env_flag("KAFKA_OPTIONAL");
+    let kafka_provisioner = KafkaTopicProvisioner::from_env();
+    let topic_provisioner: Arc<dyn TopicProvisioner> = if kafka_optional {
+        Arc::new(OptionalTopicProvisioner {
+            input_topic_prefix: kafka_provisioner.input_topic_prefix.clone(),
+            output_topic_prefix: kafka_provisioner.output_topic_prefix.clone(),
+            inner: Arc::new(kafka_provisioner),
+        })
+    } else {
+        Arc::new(kafka_provisioner)
+    };
+    let step_event_publisher: Arc<dyn StepEventPublisher> =
+        match KafkaStepEventPublisher::from_env() {
+            Ok(publisher) if kafka_optional => Arc::new(OptionalStepEventPublisher {
+                inner: Some(Arc::new(publisher)),
+            }),
+            Ok(publisher) => Arc::new(publisher),
+            Err(error) if kafka_optional => {
+                warn!(error = %error, "Kafka producer unavailable; step events will be dropped");
+                Arc::new(OptionalStepEventPublisher { inner: None })
+            }
+            Err(error) => return Err(error),
+        };
+
     let state = AppState {
-        store: Arc::new(RwLock::new(store)),
-        topic_provisioner: Arc::new(KafkaTopicProvisioner::from_env()),
-        step_event_publisher: Arc::new(KafkaStepEventPublisher::from_env()?),
+        store,
+        default_map: Arc::new(RwLock::new(default_map)),
+        mutation_lock: Arc::default(),
+        topic_provisioner,
+        step_event_publisher,
         bot_assigner: Arc::new(BotManagerAssigner::from_env()),
+        metrics: Arc::default(),
+        snapshot_path,
+        snapshot_writes: Arc::default(),
+        idempotency: Arc::new(IdempotencyCache::new(Duration::from_secs(
+            std::env::var("GAME_IDEMPOTENCY_TTL_SECS")
+                .ok()
+                .and_then(|value| value.parse::<u64>().ok())
+                .unwrap_or(DEFAULT_IDEMPOTENCY_TTL_SECS),
+        ))),
+        speak_filter: Arc::new(SpeakFilter::from_env()),
+        finished_topic_retention: Duration::from_secs(
+            std::env::var("FINISHED_TOPIC_RETENTION_SECS")
+                .ok()
+                .and_then(|value| value.parse::<u64>().ok())
+                .unwrap_or(DEFAULT_FINISHED_TOPIC_RETENTION_SECS),
+        ),
+        debug_endpoints: cowboy_common::env_flag("DEBUG_ENDPOINTS"),
     };
 
-    let app = build_router(state);
+    if state.snapshot_path.is_some() {
+        let interval_secs = std::env::var("GAME_STATE_SNAPSHOT_INTERVAL_SECS")
+            .ok()
+            .and_then(|value| value.parse::<u64>().ok())
+            .filter(|value| *value > 0)
+            .unwrap_or(30);
+        let snapshot_state = state.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+            loop {
+                ticker.tick().await;
+                persist_state(&snapshot_state).await;
+            }
+        });
+    }
+    spawn_topic_sweeper(state.clone());
+
+    let app = build_router(state.clone());
 
     if std::env::var("AWS_LAMBDA_RUNTIME_API").is_ok() {
         info!("AWS Lambda runtime detected; running game-manager-service in lambda mode");
@@ -437,10 +1049,91 @@ async fn main() -> anyhow::Result<()> {
     let bind_addr = parse_bind_addr("GAME_MANAGER_BIND", "0.0.0.0:8081")?;
     info!(%bind_addr, "game-manager-service listening");
     let listener = tokio::net::TcpListener::bind(bind_addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(cowboy_common::shutdown_signal())
+        .await?;
+    // Keep the last moves of in-flight games for the next start.
+    persist_state(&state).await;
+    info!("game-manager-service shut down");
+    Ok(())
+}
+
+/// Writes every game to the snapshot directory, if persistence is enabled.
+/// Failures are logged rather than surfaced so a full disk never fails a move.
+async fn persist_state(state: &AppState) {
+    let Some(dir) = state.snapshot_path.as_deref() else {
+        return;
+    };
+    let _writes = state.snapshot_writes.lock().await;
+    let games = match state.store.list().await {
+        Ok(games) => games,
+        Err(error) => {
+            warn!(path = %dir.display(), error = %error, "failed to list games for state snapshot");
+            return;
+        }
+    };
+    for game in &games {
+        if let Err(error) = write_game_snapshot(dir, game).await {
+            warn!(game_id = %game.game_id, error = %error, "failed to write state snapshot");
+        }
+    }
+}
+
+/// Writes one just-changed game to its snapshot file, if persistence is
+/// enabled. Reads the game back under `snapshot_writes` so the newest copy
+/// is always the one left on disk.
+async fn persist_game(state: &AppState, game_id: &str) {
+    let Some(dir) = state.snapshot_path.as_deref() else {
+        return;
+    };
+    let _writes = state.snapshot_writes.lock().await;
+    let result = match state.store.get(game_id).await {
+        Ok(Some(game)) => write_game_snapshot(dir, &game).await,
+        Ok(None) => Ok(()),
+        Err(error) => Err(error),
+    };
+    if let Err(error) = result {
+        warn!(game_id = %game_id, error = %error, "failed to write state snapshot");
+    }
+}
+
+/// Serializes a game to `<dir>/<game_id>.json` via a temp file and rename so
+/// a crash mid-write never leaves a truncated snapshot behind.
+async fn write_game_snapshot(dir: &FsPath, game: &GameInstance) -> anyhow::Result<()> {
+    let payload = serde_json::to_vec(game).context("failed to encode state snapshot")?;
+    let path = dir.join(format!("{}.json", game.game_id));
+    let tmp_path = path.with_extension("json.tmp");
+    tokio::fs::write(&tmp_path, payload)
+        .await
+        .with_context(|| format!("failed to write {}", tmp_path.display()))?;
+    tokio::fs::rename(&tmp_path, &path)
+        .await
+        .with_context(|| format!("failed to replace {}", path.display()))?;
     Ok(())
 }
 
+/// Loads games from a snapshot directory, creating it on a fresh start.
+fn load_state_snapshot(dir: &FsPath) -> anyhow::Result<HashMap<String, GameInstance>> {
+    std::fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    let entries =
+        std::fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))?;
+    let mut games = HashMap::new();
+    for entry in entries {
+        let path = entry
+            .with_context(|| format!("failed to read {}", dir.display()))?
+            .path();
+        if path.extension().is_none_or(|extension| extension != "json") {
+            continue;
+        }
+        let raw =
+            std::fs::read(&path).with_context(|| format!("failed to read {}", path.display()))?;
+        let game: GameInstance = serde_json::from_slice(&raw)
+            .with_context(|| format!("failed to decode state snapshot {}", path.display()))?;
+        games.insert(game.game_id.clone(), game);
+    }
+    Ok(games)
+}
+
 fn load_default_map_config() -> Option<MapData> {
     let path = std::env::var("DEFAULT_MAP_CONFIG_PATH")
         .ok()
@@ -472,18 +1165,60 @@ fn load_default_map_config() -> Option<MapData> {
 fn build_router(state: AppState) -> Router {
     Router::new()
         .route("/health", get(health))
+        .route("/metrics", get(metrics_handler))
         .route("/v2/maps/default", get(get_default_map_handler))
-        .route("/v2/games", post(create_game_handler))
+        .route("/v2/maps/encode", post(encode_map_handler))
+        .route("/v2/maps/decode", post(decode_map_handler))
+        .route("/v2/schema", get(schema_handler))
+        .route("/v2/games", post(create_game_route))
         .route("/v2/games/{game_id}", get(get_game_handler))
+        .route("/v2/games/{game_id}/config", get(get_game_config_handler))
+        .route("/v2/games/{game_id}/map", post(replace_map_handler))
+        .route("/v2/games/{game_id}/join", post(join_game_handler))
         .route("/v2/games/{game_id}/start", post(start_game_handler))
+        .route("/v2/games/{game_id}/pause", post(pause_game_handler))
+        .route("/v2/games/{game_id}/resume", post(resume_game_handler))
+        .route(
+            "/v2/games/{game_id}/players/{player_id}/view",
+            get(player_view_handler),
+        )
         .route(
             "/internal/v2/games/{game_id}/commands/apply",
             post(apply_command_handler),
         )
+        .route(
+            "/internal/v2/games/{game_id}/commands/apply-batch",
+            post(apply_batch_handler),
+        )
+        .route(
+            "/internal/v2/games/{game_id}/commands/queue",
+            post(queue_command_handler),
+        )
+        .route(
+            "/internal/v2/games/{game_id}/commands/validate",
+            post(validate_command_handler),
+        )
+        .route(
+            "/internal/v2/games/{game_id}/steps/reserve-seq",
+            post(reserve_step_seq_handler),
+        )
+        .route(
+            "/internal/v2/games/{game_id}/turn/skip",
+            post(skip_turn_handler),
+        )
         .route(
             "/internal/v2/games/{game_id}/finish",
             post(finish_game_handler),
         )
+        .route(
+            "/internal/v2/games/{game_id}/players/{player_id}/forfeit",
+            post(forfeit_player_handler),
+        )
+        .route(
+            "/internal/v2/games/{game_id}/debug",
+            get(debug_game_handler),
+        )
+        .route("/internal/v2/simulate", post(simulate_handler))
         .with_state(state)
         .layer(CorsLayer::permissive())
         .layer(TraceLayer::new_for_http())
@@ -500,37 +1235,173 @@ async fn health() -> Json<serde_json::Value> {
     Json(serde_json::json!({"ok": true, "service": "game-manager-service"}))
 }
 
+async fn schema_handler() -> Json<serde_json::Value> {
+    Json(cowboy_common::schema::command_api_schema())
+}
+
+async fn metrics_handler(State(state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
+    let games = state.store.list().await.map_err(store_error)?;
+    Ok((
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        render_metrics(&games, &state.metrics),
+    ))
+}
+
+/// Renders the Prometheus text exposition format by hand to stay dependency-free.
+fn render_metrics(games: &[GameInstance], metrics: &CommandMetrics) -> String {
+    let mut out = String::new();
+
+    let _ = writeln!(out, "# HELP cowboy_games Games currently held by status.");
+    let _ = writeln!(out, "# TYPE cowboy_games gauge");
+    for status in [
+        GameStatus::Created,
+        GameStatus::Running,
+        GameStatus::Paused,
+        GameStatus::Finished,
+    ] {
+        let label = game_status_label(status);
+        let count = games.iter().filter(|game| game.status == status).count();
+        let _ = writeln!(out, "cowboy_games{{status=\"{label}\"}} {count}");
+    }
+
+    for (name, help, counter) in [
+        (
+            "cowboy_commands_applied_total",
+            "Commands applied to a game.",
+            &metrics.applied,
+        ),
+        (
+            "cowboy_commands_rejected_total",
+            "Commands rejected without being applied.",
+            &metrics.rejected,
+        ),
+        (
+            "cowboy_shoots_total",
+            "Shoot commands applied.",
+            &metrics.shoots,
+        ),
+        (
+            "cowboy_timeouts_total",
+            "Turn timeouts applied.",
+            &metrics.timeouts,
+        ),
+    ] {
+        let _ = writeln!(out, "# HELP {name} {help}");
+        let _ = writeln!(out, "# TYPE {name} counter");
+        let _ = writeln!(out, "{name} {}", counter.load(Ordering::Relaxed));
+    }
+
+    let mut games: Vec<&GameInstance> = games.iter().collect();
+    games.sort_by(|a, b| a.game_id.cmp(&b.game_id));
+    let _ = writeln!(
+        out,
+        "# HELP cowboy_game_turn_no Current turn number per game."
+    );
+    let _ = writeln!(out, "# TYPE cowboy_game_turn_no gauge");
+    for game in games {
+        let _ = writeln!(
+            out,
+            "cowboy_game_turn_no{{game_id=\"{}\"}} {}",
+            game.game_id, game.turn_no
+        );
+    }
+
+    out
+}
+
+/// Exhaustive so a new status can't go missing from the metrics.
+fn game_status_label(status: GameStatus) -> &'static str {
+    match status {
+        GameStatus::Created => "created",
+        GameStatus::Running => "running",
+        GameStatus::Paused => "paused",
+        GameStatus::Finished => "finished",
+    }
+}
+
 async fn get_default_map_handler(State(state): State<AppState>) -> Result<Json<MapData>, ApiError> {
-    let mut store = state.store.write().await;
-    if store.default_map.is_none() {
-        store.default_map = Some(default_map());
+    let mut cached = state.default_map.write().await;
+    if cached.is_none() {
+        *cached = Some(default_map());
     }
 
-    let map = store
-        .default_map
+    let map = cached
         .clone()
         .ok_or_else(|| ApiError::internal("default map unavailable"))?;
     Ok(Json(map))
 }
 
-async fn create_game_handler(
-    State(state): State<AppState>,
-    Json(request): Json<CreateGameRequest>,
-) -> Result<Json<CreateGameResponse>, ApiError> {
-    let CreateGameRequest {
-        turn_timeout_seconds,
-        map,
-        bot_players,
-        num_players,
-    } = request;
+/// A map in the compact text format of `encode_map`.
+#[derive(Debug, Serialize, Deserialize)]
+struct EncodedMap {
+    encoded: String,
+}
 
-    let timeout = turn_timeout_seconds
-        .unwrap_or(DEFAULT_TURN_TIMEOUT_SECONDS)
-        .max(1);
-    let num_players = num_players
-        .unwrap_or(DEFAULT_NUM_PLAYERS)
-        .max(MIN_NUM_PLAYERS)
-        .min(MAX_NUM_PLAYERS);
+async fn encode_map_handler(Json(map): Json<MapData>) -> Result<Json<EncodedMap>, ApiError> {
+    validate_map(&map).map_err(|reason| ApiError::bad_request(format!("invalid map: {reason}")))?;
+    Ok(Json(EncodedMap {
+        encoded: encode_map(&map),
+    }))
+}
+
+async fn decode_map_handler(Json(request): Json<EncodedMap>) -> Result<Json<MapData>, ApiError> {
+    decode_map(&request.encoded)
+        .map(Json)
+        .map_err(|reason| ApiError::bad_request(format!("invalid encoded map: {reason}")))
+}
+
+/// Accepts the key from either the `Idempotency-Key` header or the body.
+async fn create_game_route(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(mut request): Json<CreateGameRequest>,
+) -> Result<Json<CreateGameResponse>, ApiError> {
+    if request.idempotency_key.is_none() {
+        request.idempotency_key = headers
+            .get(IDEMPOTENCY_KEY_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+    }
+    create_game_handler(State(state), Json(request)).await
+}
+
+async fn create_game_handler(
+    State(state): State<AppState>,
+    Json(request): Json<CreateGameRequest>,
+) -> Result<Json<CreateGameResponse>, ApiError> {
+    let Some(key) = request
+        .idempotency_key
+        .as_deref()
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .map(str::to_string)
+    else {
+        return create_new_game(&state, request).await.map(Json);
+    };
+
+    let slot = state.idempotency.slot(&key);
+    let mut entry = slot.lock().await;
+    if let Some((created_at, response)) = entry.as_ref()
+        && created_at.elapsed() < state.idempotency.ttl
+    {
+        info!(
+            idempotency_key = %key,
+            game_id = %response.game_id,
+            "returning existing game for repeated idempotency key"
+        );
+        return Ok(Json(response.clone()));
+    }
+
+    let response = create_new_game(&state, request).await?;
+    *entry = Some((Instant::now(), response.clone()));
+    Ok(Json(response))
+}
+
+async fn create_new_game(
+    state: &AppState,
+    request: CreateGameRequest,
+) -> Result<CreateGameResponse, ApiError> {
+    let options = game_options(request)?;
 
     let game_id = Uuid::new_v4().to_string();
     let game_topics = state
@@ -543,69 +1414,26 @@ async fn create_game_handler(
             ))
         })?;
 
-    let game = {
-        let mut store = state.store.write().await;
-
-        let (map_source, map) = if let Some(map) = map {
-            (MapSource::Custom, map)
-        } else {
-            let selected = if let Some(existing) = store.default_map.clone() {
-                existing
-            } else {
-                let generated = generate_default_map(11, 11, num_players);
-                store.default_map = Some(generated.clone());
-                generated
-            };
-            (MapSource::Default, selected)
-        };
-
-        let created_at = Utc::now();
-
-        let state_snapshot = GameStateSnapshot {
-            players: initial_players(map.rows, map.cols, DEFAULT_PLAYER_HP, num_players),
-            map,
-        };
-
-        let game = GameInstance {
-            game_id: game_id.clone(),
-            status: GameStatus::Created,
-            map_source,
-            turn_timeout_seconds: timeout,
-            turn_no: 1,
-            round_no: 1,
-            current_player_id: state_snapshot
-                .players
-                .first()
-                .map(|player| player.player_id.clone())
-                .ok_or_else(|| ApiError::internal("no players in game"))?,
-            created_at,
-            started_at: None,
-            turn_started_at: None,
-            state: state_snapshot,
-            last_step_seq: 0,
-            input_topic: game_topics.input_topic.clone(),
-            output_topic: game_topics.output_topic.clone(),
-        };
-
-        info!(
-            game_id = %game.game_id,
-            input_topic = %game.input_topic,
-            output_topic = %game.output_topic,
-            "provisioned per-game Kafka topics"
-        );
-
-        store.games.insert(game_id.clone(), game.clone());
-        game
-    };
+    let mut game = build_game(state, game_id.clone(), &game_topics, &options).await?;
+    info!(
+        game_id = %game.game_id,
+        input_topic = %game.input_topic,
+        output_topic = %game.output_topic,
+        "provisioned per-game Kafka topics"
+    );
+    state.store.put(&mut game).await.map_err(store_error)?;
 
     if let Err(error) = state
         .bot_assigner
-        .assign_for_new_game(&game, bot_players)
+        .assign_for_new_game(&game, options.bot_players, options.llm_overrides)
         .await
     {
-        {
-            let mut store = state.store.write().await;
-            store.games.remove(&game_id);
+        if let Err(remove_error) = state.store.remove(&game_id).await {
+            warn!(
+                game_id = %game_id,
+                error = %remove_error,
+                "failed to remove game after bot assignment error"
+            );
         }
 
         if let Err(cleanup_error) = state
@@ -627,7 +1455,9 @@ async fn create_game_handler(
         )));
     }
 
-    Ok(Json(CreateGameResponse {
+    persist_game(state, &game_id).await;
+
+    Ok(CreateGameResponse {
         game_id,
         status: game.status,
         map_source: game.map_source,
@@ -638,28 +1468,590 @@ async fn create_game_handler(
             .state
             .players
             .iter()
-            .map(|player| PlayerIdentity {
-                player_name: player.player_name,
-                player_id: player.player_id.clone(),
-            })
+            .map(|player| player_identity(&game, player))
             .collect(),
         turn_timeout_seconds: game.turn_timeout_seconds,
         created_at: game.created_at,
-    }))
+    })
+}
+
+/// A validated `CreateGameRequest` with its defaults filled in.
+struct GameOptions {
+    turn_timeout_seconds: u64,
+    num_players: u8,
+    bot_players: Option<Vec<PlayerName>>,
+    map: Option<MapData>,
+    symmetric_map: Option<bool>,
+    map_seed: Option<u64>,
+    map_density: Option<MapDensity>,
+    spawn_layout: SpawnLayout,
+    shield_mode: ShieldMode,
+    winner_policy: WinnerPolicy,
+    max_rounds: Option<u64>,
+    laser_pierces: bool,
+    laser_range: Option<usize>,
+    wall_regen_rounds: u64,
+    arena_shrink_rounds: u64,
+    require_player_token: bool,
+    llm_overrides: Option<HashMap<PlayerName, LlmProfile>>,
+    body_block: bool,
+    knockback: bool,
+    auto_start_when_full: bool,
+    vision_radius: Option<usize>,
+    spawn_clear_radius: usize,
+    narrate_eliminations: bool,
+    allow_rubble_move: bool,
+    first_turn_grace_seconds: u64,
+}
+
+fn game_options(request: CreateGameRequest) -> Result<GameOptions, ApiError> {
+    let CreateGameRequest {
+        turn_timeout_seconds,
+        map,
+        bot_players,
+        num_bots,
+        num_players,
+        laser_pierces,
+        laser_range,
+        map_seed,
+        symmetric_map,
+        wall_regen_rounds,
+        map_density,
+        arena_shrink_rounds,
+        idempotency_key: _,
+        llm_overrides,
+        require_player_token,
+        spawn_layout,
+        max_rounds,
+        shield_mode,
+        winner_policy,
+        body_block,
+        knockback,
+        auto_start_when_full,
+        vision_radius,
+        spawn_clear_radius,
+        narrate_eliminations,
+        allow_rubble_move,
+        first_turn_grace_seconds,
+    } = request;
+
+    let num_players = num_players
+        .unwrap_or(DEFAULT_NUM_PLAYERS)
+        .clamp(MIN_NUM_PLAYERS, MAX_NUM_PLAYERS);
+    let bot_players = resolve_bot_players(bot_players, num_bots, num_players)?;
+
+    let spawn_layout = spawn_layout
+        .as_deref()
+        .map(str::parse::<SpawnLayout>)
+        .transpose()
+        .map_err(|reason| ApiError::bad_request(format!("invalid spawn_layout: {reason}")))?
+        .unwrap_or_default();
+    // Reject malformed custom maps before any topics are provisioned.
+    if let Some(map) = map.as_ref() {
+        validate_custom_map(map, num_players, spawn_layout)?;
+    }
+    if let Some(density) = map_density.as_ref() {
+        density
+            .validate()
+            .map_err(|reason| ApiError::bad_request(format!("invalid map_density: {reason}")))?;
+    }
+    let shield_mode = shield_mode
+        .as_deref()
+        .map(str::parse::<ShieldMode>)
+        .transpose()
+        .map_err(|reason| ApiError::bad_request(format!("invalid shield_mode: {reason}")))?
+        .unwrap_or_default();
+    let winner_policy = winner_policy
+        .as_deref()
+        .map(str::parse::<WinnerPolicy>)
+        .transpose()
+        .map_err(|reason| ApiError::bad_request(format!("invalid winner_policy: {reason}")))?
+        .unwrap_or_default();
+    if max_rounds == Some(0) {
+        return Err(ApiError::bad_request("max_rounds must be at least 1"));
+    }
+    if laser_range == Some(0) {
+        return Err(ApiError::bad_request("laser_range must be at least 1"));
+    }
+    if first_turn_grace_seconds.is_some_and(|grace| grace > MAX_FIRST_TURN_GRACE_SECONDS) {
+        return Err(ApiError::bad_request(format!(
+            "first_turn_grace_seconds must be at most {MAX_FIRST_TURN_GRACE_SECONDS}"
+        )));
+    }
+
+    Ok(GameOptions {
+        turn_timeout_seconds: turn_timeout_seconds
+            .unwrap_or(DEFAULT_TURN_TIMEOUT_SECONDS)
+            .max(1),
+        num_players,
+        bot_players,
+        map,
+        symmetric_map,
+        map_seed,
+        map_density,
+        spawn_layout,
+        shield_mode,
+        winner_policy,
+        max_rounds,
+        laser_pierces: laser_pierces.unwrap_or(false),
+        laser_range,
+        wall_regen_rounds: wall_regen_rounds.unwrap_or(0),
+        arena_shrink_rounds: arena_shrink_rounds.unwrap_or(0),
+        require_player_token: require_player_token.unwrap_or(false),
+        llm_overrides,
+        body_block: body_block.unwrap_or(true),
+        knockback: knockback.unwrap_or(false),
+        auto_start_when_full: auto_start_when_full.unwrap_or(false),
+        vision_radius,
+        spawn_clear_radius: spawn_clear_radius.unwrap_or(0),
+        narrate_eliminations: narrate_eliminations.unwrap_or(false),
+        allow_rubble_move: allow_rubble_move.unwrap_or(false),
+        first_turn_grace_seconds: first_turn_grace_seconds.unwrap_or(0),
+    })
+}
+
+/// Lays out a new, not yet stored game from `options`.
+async fn build_game(
+    state: &AppState,
+    game_id: String,
+    game_topics: &GameTopics,
+    options: &GameOptions,
+) -> Result<GameInstance, ApiError> {
+    let (map_source, map) = choose_map(
+        state,
+        options.map.clone(),
+        options.symmetric_map,
+        options.map_seed,
+        options.map_density,
+        options.spawn_clear_radius,
+        options.num_players,
+    )
+    .await;
+
+    let created_at = Utc::now();
+    let state_snapshot = seat_players(
+        map_source,
+        map,
+        options.num_players,
+        options.spawn_layout,
+        options.shield_mode,
+        &[],
+    );
+
+    if map_source == MapSource::Custom
+        && !spawn_reachability(&state_snapshot.map, &state_snapshot.players)
+    {
+        warn!(
+            game_id = %game_id,
+            "custom map leaves at least one spawn unreachable from the others"
+        );
+    }
+
+    let player_tokens = if options.require_player_token {
+        state_snapshot
+            .players
+            .iter()
+            .map(|player| (player.player_id.clone(), generate_player_token()))
+            .collect()
+    } else {
+        HashMap::new()
+    };
+
+    Ok(GameInstance {
+        game_id,
+        status: GameStatus::Created,
+        map_source,
+        turn_timeout_seconds: options.turn_timeout_seconds,
+        turn_no: 1,
+        round_no: 1,
+        current_player_id: state_snapshot
+            .players
+            .first()
+            .map(|player| player.player_id.clone())
+            .ok_or_else(|| ApiError::internal("no players in game"))?,
+        created_at,
+        started_at: None,
+        turn_started_at: None,
+        original_cells: state_snapshot.map.cells.clone(),
+        state: state_snapshot,
+        last_step_seq: 0,
+        input_topic: game_topics.input_topic.clone(),
+        output_topic: game_topics.output_topic.clone(),
+        laser_pierces: options.laser_pierces,
+        laser_range: options.laser_range,
+        wall_regen_rounds: options.wall_regen_rounds,
+        arena_shrink_rounds: options.arena_shrink_rounds,
+        arena_rings_closed: 0,
+        require_player_token: options.require_player_token,
+        player_tokens,
+        spawn_layout: options.spawn_layout,
+        paused_at: None,
+        max_rounds: options.max_rounds,
+        shield_mode: options.shield_mode,
+        winner_policy: options.winner_policy,
+        bot_slots: options.bot_players.clone().unwrap_or_default(),
+        claimed_slots: HashMap::new(),
+        queued_commands: HashMap::new(),
+        body_block: options.body_block,
+        knockback: options.knockback,
+        auto_start_when_full: options.auto_start_when_full,
+        vision_radius: options.vision_radius,
+        spawn_clear_radius: options.spawn_clear_radius,
+        narrate_eliminations: options.narrate_eliminations,
+        allow_rubble_move: options.allow_rubble_move,
+        first_turn_grace_seconds: options.first_turn_grace_seconds,
+        rebound_bot_players: HashSet::new(),
+        finish_reason: None,
+        topics_retire_at: None,
+        version: 0,
+    })
+}
+
+fn player_identity(game: &GameInstance, player: &PlayerState) -> PlayerIdentity {
+    PlayerIdentity {
+        player_name: player.player_name,
+        player_id: player.player_id.clone(),
+        player_token: game.player_tokens.get(&player.player_id).cloned(),
+        color: player.color.clone(),
+        display_name: player.display_name.clone(),
+    }
+}
+
+/// Turns a bot count into the seats the bots take: always the last ones, so
+/// the host keeps seat A. An explicit `bot_players` list is passed through.
+fn resolve_bot_players(
+    bot_players: Option<Vec<PlayerName>>,
+    num_bots: Option<u8>,
+    num_players: u8,
+) -> Result<Option<Vec<PlayerName>>, ApiError> {
+    match (bot_players, num_bots) {
+        (Some(_), Some(_)) => Err(ApiError::bad_request(
+            "bot_players and num_bots cannot both be set",
+        )),
+        (None, Some(num_bots)) if num_bots > num_players => Err(ApiError::bad_request(format!(
+            "num_bots must be at most num_players ({num_players})"
+        ))),
+        (None, Some(num_bots)) => Ok(Some(
+            ALL_PLAYER_NAMES[usize::from(num_players - num_bots)..usize::from(num_players)]
+                .to_vec(),
+        )),
+        (bot_players, None) => Ok(bot_players),
+    }
+}
+
+/// Longest display name a joining player may use.
+const MAX_DISPLAY_NAME_CHARS: usize = 32;
+
+async fn join_game_handler(
+    State(state): State<AppState>,
+    Path(game_id): Path<String>,
+    Json(request): Json<JoinGameRequest>,
+) -> Result<Json<JoinGameResponse>, ApiError> {
+    let display_name = request.display_name.trim().to_string();
+    if display_name.is_empty() || display_name.chars().count() > MAX_DISPLAY_NAME_CHARS {
+        return Err(ApiError::bad_request(format!(
+            "display_name must be 1-{MAX_DISPLAY_NAME_CHARS} characters"
+        )));
+    }
+
+    let (response, started) = {
+        let _mutation = state.mutation_lock.lock().await;
+        let mut game = load_game(&state, &game_id).await?;
+        if game.status != GameStatus::Created {
+            return Err(ApiError::conflict(format!(
+                "game {game_id} has already started; joining is closed"
+            )));
+        }
+        let Some(player) = open_human_slot(&game).cloned() else {
+            return Err(ApiError::conflict(format!(
+                "game {game_id} has no open player slots"
+            )));
+        };
+
+        game.claimed_slots
+            .insert(player.player_id.clone(), display_name.clone());
+        let started_event = (game.auto_start_when_full && open_human_slot(&game).is_none())
+            .then(|| start_game_internal(&mut game));
+        state.store.put(&mut game).await.map_err(store_error)?;
+        info!(
+            game_id = %game_id,
+            player_name = ?player.player_name,
+            display_name = %display_name,
+            auto_started = started_event.is_some(),
+            "player joined game"
+        );
+
+        (
+            JoinGameResponse {
+                game_id: game_id.clone(),
+                display_name,
+                player: player_identity(&game, &player),
+                started: started_event.is_some(),
+            },
+            started_event.map(|event| (game.output_topic.clone(), event)),
+        )
+    };
+
+    if let Some((output_topic, started_event)) = started {
+        publish_game_started(&state, &output_topic, &started_event).await?;
+    }
+    persist_game(&state, &game_id).await;
+
+    Ok(Json(response))
+}
+
+/// The first seat, in player order, that is neither a bot's nor claimed.
+fn open_human_slot(game: &GameInstance) -> Option<&PlayerState> {
+    game.state.players.iter().find(|player| {
+        !game.bot_slots.contains(&player.player_name)
+            && !game.claimed_slots.contains_key(&player.player_id)
+    })
+}
+
+/// Tokens go back to whoever starts a token game; other games list nobody.
+fn token_holders(game: &GameInstance) -> Vec<PlayerIdentity> {
+    if !game.require_player_token {
+        return Vec::new();
+    }
+    game.state
+        .players
+        .iter()
+        .map(|player| player_identity(game, player))
+        .collect()
+}
+
+/// Picks the map for a game: the custom map when given, otherwise a
+/// generated one. Only plain requests share the cached default map.
+async fn choose_map(
+    state: &AppState,
+    map: Option<MapData>,
+    symmetric_map: Option<bool>,
+    map_seed: Option<u64>,
+    map_density: Option<MapDensity>,
+    spawn_clear_radius: usize,
+    num_players: u8,
+) -> (MapSource, MapData) {
+    if let Some(map) = map {
+        (MapSource::Custom, map)
+    } else if symmetric_map.unwrap_or(false) {
+        let seed = map_seed.unwrap_or_else(random_map_seed);
+        let density = map_density.unwrap_or_default();
+        (
+            MapSource::Default,
+            generate_symmetric_map_with_density(
+                11,
+                11,
+                num_players,
+                &density,
+                seed,
+                spawn_clear_radius,
+            ),
+        )
+    } else if map_seed.is_some() || map_density.is_some() || spawn_clear_radius > 0 {
+        // Seeded or tuned maps are per-game and never replace the shared default.
+        let seed = map_seed.unwrap_or_else(random_map_seed);
+        let density = map_density.unwrap_or_default();
+        (
+            MapSource::Default,
+            generate_map_with_density(11, 11, num_players, &density, seed, spawn_clear_radius),
+        )
+    } else {
+        let mut cached = state.default_map.write().await;
+        let selected = if let Some(existing) = cached.clone() {
+            existing
+        } else {
+            let generated = generate_default_map(11, 11, num_players);
+            *cached = Some(generated.clone());
+            generated
+        };
+        (MapSource::Default, selected)
+    }
+}
+
+/// Places players on `map`'s spawns. Players in `previous` keep their ids,
+/// in order, so tokens and bot bindings stay valid when a map is replaced.
+fn seat_players(
+    map_source: MapSource,
+    mut map: MapData,
+    num_players: u8,
+    spawn_layout: SpawnLayout,
+    shield_mode: ShieldMode,
+    previous: &[PlayerState],
+) -> GameStateSnapshot {
+    let mut players =
+        initial_players_with_layout(&map, DEFAULT_PLAYER_HP, num_players, spawn_layout);
+    for (player, old) in players.iter_mut().zip(previous) {
+        player.player_id = old.player_id.clone();
+        if old.color.is_some() {
+            player.color = old.color.clone();
+        }
+        if old.display_name.is_some() {
+            player.display_name = old.display_name.clone();
+        }
+    }
+    for player in &mut players {
+        player.shield_charges = shield_mode.raised_charges().unwrap_or(0);
+    }
+    if map_source == MapSource::Default {
+        // Generated maps only keep the edge centers open; free the other spawns.
+        for player in &players {
+            map.cells[player.row][player.col] = 0;
+        }
+    }
+    let stats = players
+        .iter()
+        .map(|player| PlayerStats {
+            player_id: player.player_id.clone(),
+            ..Default::default()
+        })
+        .collect();
+    GameStateSnapshot {
+        players,
+        map,
+        stats,
+    }
+}
+
+async fn replace_map_handler(
+    State(state): State<AppState>,
+    Path(game_id): Path<String>,
+    Json(request): Json<ReplaceMapRequest>,
+) -> Result<Json<GameInstanceResponse>, ApiError> {
+    let ReplaceMapRequest {
+        map,
+        map_seed,
+        map_density,
+        symmetric_map,
+    } = request;
+    if let Some(density) = map_density.as_ref() {
+        density
+            .validate()
+            .map_err(|reason| ApiError::bad_request(format!("invalid map_density: {reason}")))?;
+    }
+
+    let _mutation = state.mutation_lock.lock().await;
+    let mut game = load_game(&state, &game_id).await?;
+    if game.status != GameStatus::Created {
+        return Err(ApiError::conflict(format!(
+            "game {game_id} has already started; its map can no longer change"
+        )));
+    }
+    let num_players = game.state.players.len() as u8;
+    if let Some(map) = map.as_ref() {
+        validate_custom_map(map, num_players, game.spawn_layout)?;
+    }
+
+    // A reroll must not hand back the shared default map the host just rejected.
+    let map_seed = if map.is_none() {
+        Some(map_seed.unwrap_or_else(random_map_seed))
+    } else {
+        map_seed
+    };
+    let (map_source, map) = choose_map(
+        &state,
+        map,
+        symmetric_map,
+        map_seed,
+        map_density,
+        game.spawn_clear_radius,
+        num_players,
+    )
+    .await;
+    let state_snapshot = seat_players(
+        map_source,
+        map,
+        num_players,
+        game.spawn_layout,
+        game.shield_mode,
+        &game.state.players,
+    );
+    if map_source == MapSource::Custom
+        && !spawn_reachability(&state_snapshot.map, &state_snapshot.players)
+    {
+        warn!(
+            game_id = %game_id,
+            "custom map leaves at least one spawn unreachable from the others"
+        );
+    }
+
+    game.map_source = map_source;
+    game.original_cells = state_snapshot.map.cells.clone();
+    game.state = state_snapshot;
+    state.store.put(&mut game).await.map_err(store_error)?;
+    persist_game(&state, &game_id).await;
+    info!(game_id = %game.game_id, map_source = ?map_source, "game map replaced");
+
+    Ok(Json(game_response(game, Utc::now())))
+}
+
+/// The checks a host-supplied map must pass, on create and on replace alike.
+fn validate_custom_map(
+    map: &MapData,
+    num_players: u8,
+    spawn_layout: SpawnLayout,
+) -> Result<(), ApiError> {
+    validate_map(map)
+        .and_then(|()| check_map_fits_players(map, num_players))
+        .and_then(|()| validate_map_for_players(map, num_players, spawn_layout))
+        .map_err(|reason| ApiError::bad_request(format!("invalid map: {reason}")))
 }
 
 async fn get_game_handler(
     State(state): State<AppState>,
     Path(game_id): Path<String>,
 ) -> Result<Json<GameInstanceResponse>, ApiError> {
-    let store = state.store.read().await;
-    let game = store
-        .games
-        .get(&game_id)
-        .cloned()
-        .ok_or_else(|| ApiError::not_found(format!("game {} not found", game_id)))?;
-
-    Ok(Json(GameInstanceResponse {
+    let game = load_game(&state, &game_id).await?;
+    Ok(Json(game_response(game, Utc::now())))
+}
+
+/// The whole stored `GameInstance`, including what `get_game_handler`
+/// leaves out (topics, `last_step_seq`, queued commands, tokens). Answers
+/// 404 unless `DEBUG_ENDPOINTS` is set.
+async fn debug_game_handler(
+    State(state): State<AppState>,
+    Path(game_id): Path<String>,
+) -> Result<Json<GameInstance>, ApiError> {
+    if !state.debug_endpoints {
+        return Err(ApiError::not_found("debug endpoints are disabled"));
+    }
+    Ok(Json(load_game(&state, &game_id).await?))
+}
+
+/// One player's view of the board, with other players' shields hidden and,
+/// under fog of war, only what is in sight. In token games the player's own
+/// token is required.
+async fn player_view_handler(
+    State(state): State<AppState>,
+    Path((game_id, player_id)): Path<(String, PlayerId)>,
+    headers: HeaderMap,
+) -> Result<Json<PlayerViewResponse>, ApiError> {
+    let game = load_game(&state, &game_id).await?;
+    let policy = ViewPolicy::default();
+    let Some(view) = redact_snapshot_for(&player_id, &game.state, policy, game.vision_radius)
+    else {
+        return Err(ApiError::not_found(format!(
+            "player {player_id} is not in game {game_id}"
+        )));
+    };
+    check_presented_token(&game, &player_id, &headers)?;
+
+    Ok(Json(PlayerViewResponse {
+        game_id: game.game_id,
+        status: game.status,
+        turn_no: game.turn_no,
+        round_no: game.round_no,
+        current_player_id: game.current_player_id,
+        policy,
+        vision_radius: game.vision_radius,
+        view,
+    }))
+}
+
+fn game_response(game: GameInstance, now: DateTime<Utc>) -> GameInstanceResponse {
+    let turn_seconds_remaining = turn_seconds_left(&game, now);
+    let turn_grace_seconds = turn_grace_seconds(&game);
+
+    GameInstanceResponse {
         game_id: game.game_id,
         status: game.status,
         map_source: game.map_source,
@@ -673,7 +2065,74 @@ async fn get_game_handler(
         input_topic: Some(game.input_topic),
         output_topic: Some(game.output_topic),
         state: game.state,
-    }))
+        last_step_seq: game.last_step_seq,
+        turn_seconds_remaining,
+        turn_grace_seconds,
+    }
+}
+
+/// The turn clock only runs while the game does; a paused game reports the
+/// time that was left when it was paused.
+fn turn_seconds_left(game: &GameInstance, now: DateTime<Utc>) -> Option<i64> {
+    let timeout_seconds = game
+        .turn_timeout_seconds
+        .saturating_add(turn_grace_seconds(game));
+    match game.status {
+        GameStatus::Running => turn_seconds_remaining(game.turn_started_at, timeout_seconds, now),
+        GameStatus::Paused => turn_seconds_remaining(
+            game.turn_started_at,
+            timeout_seconds,
+            game.paused_at.unwrap_or(now),
+        ),
+        GameStatus::Created | GameStatus::Finished => None,
+    }
+}
+
+/// Extra seconds the current turn gets on top of `turn_timeout_seconds`:
+/// the first-turn grace on turn 1, when every bot's agent is cold, and on
+/// the first turn of a player just rebound to a bot.
+fn turn_grace_seconds(game: &GameInstance) -> u64 {
+    if game.turn_no == 1 || game.rebound_bot_players.contains(&game.current_player_id) {
+        game.first_turn_grace_seconds
+    } else {
+        0
+    }
+}
+
+async fn get_game_config_handler(
+    State(state): State<AppState>,
+    Path(game_id): Path<String>,
+) -> Result<Json<GameConfig>, ApiError> {
+    let game = load_game(&state, &game_id).await?;
+    Ok(Json(game_config(&game)))
+}
+
+fn game_config(game: &GameInstance) -> GameConfig {
+    GameConfig {
+        game_id: game.game_id.clone(),
+        map_source: game.map_source,
+        map_rows: game.state.map.rows,
+        map_cols: game.state.map.cols,
+        num_players: game.state.players.len() as u8,
+        player_hp: DEFAULT_PLAYER_HP,
+        turn_timeout_seconds: game.turn_timeout_seconds,
+        laser_pierces: game.laser_pierces,
+        laser_range: game.laser_range,
+        wall_regen_rounds: game.wall_regen_rounds,
+        arena_shrink_rounds: game.arena_shrink_rounds,
+        spawn_layout: game.spawn_layout,
+        max_rounds: game.max_rounds,
+        shield_mode: game.shield_mode,
+        winner_policy: game.winner_policy,
+        body_block: game.body_block,
+        knockback: game.knockback,
+        auto_start_when_full: game.auto_start_when_full,
+        vision_radius: game.vision_radius,
+        spawn_clear_radius: game.spawn_clear_radius,
+        narrate_eliminations: game.narrate_eliminations,
+        allow_rubble_move: game.allow_rubble_move,
+        first_turn_grace_seconds: game.first_turn_grace_seconds,
+    }
 }
 
 async fn start_game_handler(
@@ -681,11 +2140,9 @@ async fn start_game_handler(
     Path(game_id): Path<String>,
 ) -> Result<Json<StartGameResponse>, ApiError> {
     let (response, output_topic, started_event) = {
-        let mut store = state.store.write().await;
-        let game = store
-            .games
-            .get_mut(&game_id)
-            .ok_or_else(|| ApiError::not_found(format!("game {} not found", game_id)))?;
+        let _mutation = state.mutation_lock.lock().await;
+        let mut loaded = load_game(&state, &game_id).await?;
+        let game = &mut loaded;
 
         if game.status == GameStatus::Running {
             return Ok(Json(StartGameResponse {
@@ -697,6 +2154,21 @@ async fn start_game_handler(
                 round_no: game.round_no,
                 current_player_id: game.current_player_id.clone(),
                 started_at: game.started_at,
+                players: token_holders(game),
+            }));
+        }
+
+        if game.status == GameStatus::Paused {
+            return Ok(Json(StartGameResponse {
+                game_id: game.game_id.clone(),
+                status: game.status,
+                started: false,
+                reason: Some("GAME_PAUSED".to_string()),
+                turn_no: game.turn_no,
+                round_no: game.round_no,
+                current_player_id: game.current_player_id.clone(),
+                started_at: game.started_at,
+                players: token_holders(game),
             }));
         }
 
@@ -710,26 +2182,12 @@ async fn start_game_handler(
                 round_no: game.round_no,
                 current_player_id: game.current_player_id.clone(),
                 started_at: game.started_at,
+                players: token_holders(game),
             }));
         }
 
-        let now = Utc::now();
-        game.status = GameStatus::Running;
-        game.started_at = Some(now);
-        game.turn_started_at = Some(now);
-        game.last_step_seq += 1;
-
-        let started_event = StepEvent {
-            game_id: game.game_id.clone(),
-            step_seq: game.last_step_seq,
-            turn_no: game.turn_no,
-            round_no: game.round_no,
-            event_type: StepEventType::GameStarted,
-            result_status: ResultStatus::Applied,
-            command: None,
-            state_after: game.state.clone(),
-            created_at: now,
-        };
+        let started_event = start_game_internal(game);
+        state.store.put(game).await.map_err(store_error)?;
 
         (
             StartGameResponse {
@@ -741,20 +2199,62 @@ async fn start_game_handler(
                 round_no: game.round_no,
                 current_player_id: game.current_player_id.clone(),
                 started_at: game.started_at,
+                players: token_holders(game),
             },
             game.output_topic.clone(),
             started_event,
         )
     };
 
-    state
-        .step_event_publisher
-        .publish_step_event(&output_topic, &started_event)
-        .await
-        .map_err(|error| {
-            ApiError::internal(format!(
-                "failed to publish GAME_STARTED event for game {game_id}: {error:#}"
-            ))
+    publish_game_started(&state, &output_topic, &started_event).await?;
+    persist_game(&state, &game_id).await;
+
+    Ok(Json(response))
+}
+
+/// Moves a created game to `Running` and returns its `GameStarted` event.
+/// The caller stores the game and then publishes the event.
+fn start_game_internal(game: &mut GameInstance) -> StepEvent {
+    let now = Utc::now();
+    game.status = GameStatus::Running;
+    game.started_at = Some(now);
+    game.turn_started_at = Some(now);
+    game.last_step_seq += 1;
+
+    StepEvent {
+        game_id: game.game_id.clone(),
+        step_seq: game.last_step_seq,
+        turn_no: game.turn_no,
+        round_no: game.round_no,
+        event_type: StepEventType::GameStarted,
+        result_status: ResultStatus::Applied,
+        command: None,
+        state_after: game.state.clone(),
+        created_at: now,
+        eliminations: Vec::new(),
+        arena_shrink: None,
+        laser_path: None,
+        player_id: None,
+        reject_reason: None,
+        reasoning: None,
+        finish_reason: None,
+    }
+}
+
+async fn publish_game_started(
+    state: &AppState,
+    output_topic: &str,
+    started_event: &StepEvent,
+) -> Result<(), ApiError> {
+    state
+        .step_event_publisher
+        .publish_step_event(output_topic, started_event)
+        .await
+        .map_err(|error| {
+            ApiError::internal(format!(
+                "failed to publish GAME_STARTED event for game {}: {error:#}",
+                started_event.game_id
+            ))
         })?;
 
     info!(
@@ -763,107 +2263,593 @@ async fn start_game_handler(
         output_topic = %output_topic,
         "published GAME_STARTED event"
     );
+    Ok(())
+}
 
-    Ok(Json(response))
+async fn pause_game_handler(
+    State(state): State<AppState>,
+    Path(game_id): Path<String>,
+) -> Result<Json<PauseGameResponse>, ApiError> {
+    let _mutation = state.mutation_lock.lock().await;
+    let mut game = load_game(&state, &game_id).await?;
+
+    let changed = pause_game(&mut game, Utc::now());
+    if changed.is_ok() {
+        state.store.put(&mut game).await.map_err(store_error)?;
+        persist_game(&state, &game_id).await;
+        info!(game_id = %game.game_id, turn_no = game.turn_no, "game paused");
+    }
+    Ok(Json(pause_response(&game, changed)))
+}
+
+async fn resume_game_handler(
+    State(state): State<AppState>,
+    Path(game_id): Path<String>,
+) -> Result<Json<PauseGameResponse>, ApiError> {
+    let _mutation = state.mutation_lock.lock().await;
+    let mut game = load_game(&state, &game_id).await?;
+
+    let changed = resume_game(&mut game, Utc::now());
+    if changed.is_ok() {
+        state.store.put(&mut game).await.map_err(store_error)?;
+        persist_game(&state, &game_id).await;
+        info!(game_id = %game.game_id, turn_no = game.turn_no, "game resumed");
+    }
+    Ok(Json(pause_response(&game, changed)))
+}
+
+/// Freezes a running game. The error is the reason code when it isn't running.
+fn pause_game(game: &mut GameInstance, now: DateTime<Utc>) -> Result<(), &'static str> {
+    match game.status {
+        GameStatus::Running => {
+            game.status = GameStatus::Paused;
+            game.paused_at = Some(now);
+            Ok(())
+        }
+        GameStatus::Paused => Err("ALREADY_PAUSED"),
+        GameStatus::Created => Err("GAME_NOT_STARTED"),
+        GameStatus::Finished => Err("GAME_FINISHED"),
+    }
+}
+
+/// Unfreezes a paused game, pushing `turn_started_at` forward by the time
+/// spent paused so the current player keeps the turn budget they had left.
+fn resume_game(game: &mut GameInstance, now: DateTime<Utc>) -> Result<(), &'static str> {
+    if game.status != GameStatus::Paused {
+        return Err("GAME_NOT_PAUSED");
+    }
+    if let (Some(paused_at), Some(turn_started_at)) = (game.paused_at, game.turn_started_at) {
+        let paused_for = (now - paused_at).max(chrono::Duration::zero());
+        game.turn_started_at = Some(turn_started_at + paused_for);
+    }
+    game.status = GameStatus::Running;
+    game.paused_at = None;
+    Ok(())
+}
+
+fn pause_response(game: &GameInstance, changed: Result<(), &'static str>) -> PauseGameResponse {
+    PauseGameResponse {
+        game_id: game.game_id.clone(),
+        status: game.status,
+        changed: changed.is_ok(),
+        reason: changed.err().map(str::to_string),
+        turn_no: game.turn_no,
+        turn_started_at: game.turn_started_at,
+    }
 }
 
 async fn apply_command_handler(
     State(state): State<AppState>,
     Path(game_id): Path<String>,
+    headers: HeaderMap,
     Json(request): Json<SubmitCommandRequest>,
 ) -> Result<Json<ApplyCommandResponse>, ApiError> {
-    let mut store = state.store.write().await;
-    let game = store
-        .games
-        .get_mut(&game_id)
-        .ok_or_else(|| ApiError::not_found(format!("game {} not found", game_id)))?;
+    let _mutation = state.mutation_lock.lock().await;
+    let mut game = load_game(&state, &game_id).await?;
+    check_player_token(&game, &request, &headers)?;
+
+    let mut response = apply_command_to_game(&state, &mut game, &request);
+    response.command = Some(command_envelope(
+        &game.game_id,
+        &request,
+        command_source(&headers),
+    ));
+    response.follow_up_steps = elimination_narration_steps(&mut game, &response);
+    if response.applied {
+        response
+            .follow_up_steps
+            .extend(fire_queued_commands(&state, &mut game));
+    }
+    if response.accepted {
+        state.store.put(&mut game).await.map_err(store_error)?;
+        persist_game(&state, &game_id).await;
+    }
 
-    let mut response = ApplyCommandResponse {
-        accepted: false,
-        applied: false,
-        reason: None,
-        turn_no: game.turn_no,
-        round_no: game.round_no,
-        current_player_id: game.current_player_id.clone(),
-        status: game.status,
-    };
+    Ok(Json(response))
+}
 
-    if game.status != GameStatus::Running {
-        response.reason = Some("GAME_NOT_RUNNING".to_string());
-        return Ok(Json(response));
+/// Applies a sequence of commands in order under one hold of the mutation
+/// lock. Each command is checked against the game as left by the previous
+/// one, so turn ownership moves along exactly as with separate calls. No
+/// game-service publishes for a batch, so each command's step goes out here,
+/// followed by the steps it set off, in seq order.
+async fn apply_batch_handler(
+    State(state): State<AppState>,
+    Path(game_id): Path<String>,
+    Query(query): Query<ApplyBatchQuery>,
+    headers: HeaderMap,
+    Json(requests): Json<Vec<SubmitCommandRequest>>,
+) -> Result<Json<Vec<ApplyCommandResponse>>, ApiError> {
+    let _mutation = state.mutation_lock.lock().await;
+    let mut game = load_game(&state, &game_id).await?;
+    for request in &requests {
+        check_player_token(&game, request, &headers)?;
     }
 
-    if request.player_id != game.current_player_id {
-        response.reason = Some("INVALID_TURN_PLAYER".to_string());
-        return Ok(Json(response));
+    let source = command_source(&headers);
+    let mut responses = Vec::with_capacity(requests.len());
+    let mut steps = Vec::new();
+    for request in &requests {
+        let mut response = apply_command_to_game(&state, &mut game, request);
+        response.command = Some(command_envelope(&game.game_id, request, source));
+        if !response.applied {
+            game.last_step_seq += 1;
+            response.step_seq = Some(game.last_step_seq);
+        }
+        steps.push(command_step_event(&game, request, &response, source));
+        steps.extend(elimination_narration_steps(&mut game, &response));
+        if response.applied {
+            steps.extend(fire_queued_commands(&state, &mut game));
+        }
+        let rejected = !response.applied;
+        responses.push(response);
+        if rejected && query.stop_on_reject {
+            break;
+        }
     }
 
-    if request.turn_no != game.turn_no {
-        response.reason = Some("STALE_TURN_NO".to_string());
-        return Ok(Json(response));
-    }
+    // Every command took a step seq, so the game always changed.
+    state.store.put(&mut game).await.map_err(store_error)?;
+    persist_game(&state, &game_id).await;
+    publish_steps(&state, &game.output_topic, &steps).await;
 
-    let player_idx = game
+    Ok(Json(responses))
+}
+
+/// Stores `request` as the player's pre-committed command for a later turn,
+/// replacing any command they had queued before. It is only checked for
+/// legality when it fires.
+async fn queue_command_handler(
+    State(state): State<AppState>,
+    Path(game_id): Path<String>,
+    headers: HeaderMap,
+    Json(request): Json<SubmitCommandRequest>,
+) -> Result<Json<QueueCommandResponse>, ApiError> {
+    let _mutation = state.mutation_lock.lock().await;
+    let mut game = load_game(&state, &game_id).await?;
+    check_player_token(&game, &request, &headers)?;
+
+    if game.status == GameStatus::Finished {
+        return Err(ApiError::conflict(format!("game {game_id} is finished")));
+    }
+    if request.command_type == PlayerCommandType::Timeout {
+        return Err(ApiError::bad_request("timeout commands cannot be queued"));
+    }
+    if request.turn_no <= game.turn_no {
+        return Err(ApiError::bad_request(format!(
+            "turn_no must be after the current turn {}; submit current-turn commands directly",
+            game.turn_no
+        )));
+    }
+    let player = game
         .state
         .players
         .iter()
-        .position(|p| p.player_id == request.player_id)
-        .ok_or_else(|| ApiError::internal("player not found in state"))?;
+        .find(|player| player.player_id == request.player_id)
+        .ok_or_else(|| {
+            ApiError::not_found(format!(
+                "player {} not found in game {game_id}",
+                request.player_id
+            ))
+        })?;
+    if !player.alive {
+        return Err(ApiError::conflict(format!(
+            "player {} is already eliminated",
+            request.player_id
+        )));
+    }
 
-    if !game.state.players[player_idx].alive {
-        response.reason = Some("PLAYER_DEAD".to_string());
-        return Ok(Json(response));
+    let response = QueueCommandResponse {
+        game_id: game_id.clone(),
+        player_id: request.player_id.clone(),
+        command_id: request.command_id.clone(),
+        turn_no: request.turn_no,
+        replaced: false,
+    };
+    let replaced = game
+        .queued_commands
+        .insert(request.player_id.clone(), request)
+        .is_some();
+    state.store.put(&mut game).await.map_err(store_error)?;
+    persist_game(&state, &game_id).await;
+
+    Ok(Json(QueueCommandResponse {
+        replaced,
+        ..response
+    }))
+}
+
+/// Applies the current player's queued command while one is due, following
+/// the turn as it moves on. Each queued command is checked afresh; one that
+/// is no longer legal is dropped and the player keeps their turn. Returns a
+/// step event for every command fired, applied or not.
+fn fire_queued_commands(state: &AppState, game: &mut GameInstance) -> Vec<StepEvent> {
+    let mut steps = Vec::new();
+    while game.status == GameStatus::Running {
+        let due = game
+            .queued_commands
+            .get(&game.current_player_id)
+            .is_some_and(|queued| queued.turn_no <= game.turn_no);
+        if !due {
+            break;
+        }
+        let Some(mut request) = game.queued_commands.remove(&game.current_player_id) else {
+            break;
+        };
+        request.turn_no = game.turn_no;
+
+        let response = apply_command_to_game(state, game, &request);
+        if !response.applied {
+            game.last_step_seq += 1;
+        }
+        info!(
+            game_id = %game.game_id,
+            player_id = %request.player_id,
+            command_id = %request.command_id,
+            applied = response.applied,
+            reason = ?response.reason,
+            "fired queued command"
+        );
+        steps.push(command_step_event(
+            game,
+            &request,
+            &response,
+            CommandSource::User,
+        ));
+        steps.extend(elimination_narration_steps(game, &response));
+        if !response.applied {
+            break;
+        }
+    }
+    steps
+}
+
+/// A system SPEAK step for each elimination in `response`, in games that
+/// narrate them, so the SPEAK feed tells who fell. Each takes the next
+/// `step_seq`.
+fn elimination_narration_steps(
+    game: &mut GameInstance,
+    response: &ApplyCommandResponse,
+) -> Vec<StepEvent> {
+    if !game.narrate_eliminations {
+        return Vec::new();
+    }
+    let mut steps = Vec::with_capacity(response.eliminations.len());
+    for elimination in &response.eliminations {
+        game.last_step_seq += 1;
+        let text = format!(
+            "{} was eliminated by {}",
+            player_label(game, &elimination.victim_player_id),
+            player_label(game, &elimination.killer_player_id)
+        );
+        steps.push(StepEvent {
+            game_id: game.game_id.clone(),
+            step_seq: game.last_step_seq,
+            turn_no: game.turn_no,
+            round_no: game.round_no,
+            event_type: StepEventType::StepApplied,
+            result_status: ResultStatus::Applied,
+            command: Some(CommandEnvelope {
+                command_id: format!(
+                    "narrate-{}-{}",
+                    game.last_step_seq, elimination.victim_player_id
+                ),
+                source: CommandSource::System,
+                game_id: game.game_id.clone(),
+                player_id: None,
+                command_type: PlayerCommandType::Speak,
+                direction: None,
+                speak_text: Some(text),
+                turn_no: game.turn_no,
+                sent_at: Utc::now(),
+                player_token: None,
+            }),
+            state_after: game.state.clone(),
+            created_at: Utc::now(),
+            eliminations: Vec::new(),
+            arena_shrink: None,
+            laser_path: None,
+            player_id: None,
+            reject_reason: None,
+            reasoning: None,
+            finish_reason: None,
+        });
+    }
+    steps
+}
+
+/// A player's display name, or their seat when they have none.
+fn player_label(game: &GameInstance, player_id: &str) -> String {
+    game.state
+        .players
+        .iter()
+        .find(|player| player.player_id == player_id)
+        .map(|player| {
+            player
+                .display_name
+                .clone()
+                .unwrap_or_else(|| default_display_name(player.player_name))
+        })
+        .unwrap_or_else(|| player_id.to_string())
+}
+
+/// The step for a command game-manager applied itself (a batch entry or a
+/// fired queued command), numbered with the seq it just took.
+fn command_step_event(
+    game: &GameInstance,
+    request: &SubmitCommandRequest,
+    response: &ApplyCommandResponse,
+    source: CommandSource,
+) -> StepEvent {
+    StepEvent {
+        game_id: game.game_id.clone(),
+        step_seq: game.last_step_seq,
+        turn_no: game.turn_no,
+        round_no: game.round_no,
+        event_type: StepEventType::StepApplied,
+        result_status: if response.applied {
+            ResultStatus::Applied
+        } else {
+            ResultStatus::InvalidCommand
+        },
+        command: Some(command_envelope(&game.game_id, request, source)),
+        state_after: game.state.clone(),
+        created_at: Utc::now(),
+        eliminations: response.eliminations.clone(),
+        arena_shrink: response.arena_shrink.clone(),
+        laser_path: response.laser_path.clone(),
+        player_id: None,
+        reject_reason: response.reason,
+        reasoning: None,
+        finish_reason: None,
+    }
+}
+
+/// Builds the envelope a step event carries for `request`. The player token
+/// is never copied in, since step events go out to every viewer.
+fn command_envelope(
+    game_id: &str,
+    request: &SubmitCommandRequest,
+    source: CommandSource,
+) -> CommandEnvelope {
+    CommandEnvelope {
+        command_id: request.command_id.clone(),
+        source,
+        game_id: game_id.to_string(),
+        player_id: Some(request.player_id.clone()),
+        command_type: request.command_type,
+        direction: request.direction,
+        speak_text: request.speak_text.clone(),
+        turn_no: request.turn_no,
+        sent_at: request.client_sent_at,
+        player_token: None,
+    }
+}
+
+/// Where a command came from, as game-service reports it. Requests without
+/// the header come straight from a user.
+fn command_source(headers: &HeaderMap) -> CommandSource {
+    headers
+        .get(COMMAND_SOURCE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| {
+            serde_json::from_value::<CommandSource>(serde_json::Value::String(value.to_string()))
+                .ok()
+        })
+        .unwrap_or(CommandSource::User)
+}
+
+/// Publishes steps game-manager produced on its own, in the order given,
+/// which callers keep in seq order.
+async fn publish_steps(state: &AppState, output_topic: &str, steps: &[StepEvent]) {
+    for step in steps {
+        if let Err(error) = state
+            .step_event_publisher
+            .publish_step_event(output_topic, step)
+            .await
+        {
+            warn!(
+                game_id = %step.game_id,
+                output_topic = %output_topic,
+                step_seq = step.step_seq,
+                error = %error,
+                "failed to publish step"
+            );
+        }
+    }
+}
+
+/// In games created with `require_player_token`, user commands must carry
+/// the acting player's token. Commands game-service marks as coming from a
+/// bot or the timer are trusted, since those never pass through web-service.
+fn check_player_token(
+    game: &GameInstance,
+    request: &SubmitCommandRequest,
+    headers: &HeaderMap,
+) -> Result<(), ApiError> {
+    if !game.require_player_token {
+        return Ok(());
+    }
+    if command_source(headers) != CommandSource::User {
+        return Ok(());
     }
+    check_presented_token(game, &request.player_id, headers)
+}
+
+/// Requires the player's token header when the game issues tokens.
+fn check_presented_token(
+    game: &GameInstance,
+    player_id: &str,
+    headers: &HeaderMap,
+) -> Result<(), ApiError> {
+    if !game.require_player_token {
+        return Ok(());
+    }
+    let presented = headers
+        .get(PLAYER_TOKEN_HEADER)
+        .and_then(|value| value.to_str().ok());
+    let expected = game.player_tokens.get(player_id);
+    match (presented, expected) {
+        (Some(presented), Some(expected)) if presented == expected => Ok(()),
+        _ => Err(ApiError::unauthorized(format!(
+            "missing or invalid player token for player {player_id}"
+        ))),
+    }
+}
+
+fn apply_command_to_game(
+    state: &AppState,
+    game: &mut GameInstance,
+    request: &SubmitCommandRequest,
+) -> ApplyCommandResponse {
+    let mut response = ApplyCommandResponse {
+        accepted: false,
+        applied: false,
+        reason: None,
+        turn_no: game.turn_no,
+        round_no: game.round_no,
+        current_player_id: game.current_player_id.clone(),
+        status: game.status,
+        eliminations: Vec::new(),
+        arena_shrink: None,
+        laser_path: None,
+        command: None,
+        step_seq: None,
+        follow_up_steps: Vec::new(),
+    };
+
+    let player_idx = match check_turn_owner(game, request) {
+        Ok(player_idx) => player_idx,
+        Err(reason) => {
+            response.reason = Some(reason);
+            state.metrics.record(request.command_type, false);
+            return response;
+        }
+    };
 
     let direction = request.direction;
+    let mut eliminated: Vec<PlayerId> = Vec::new();
     let (applied, consume_turn, reason) = match request.command_type {
-        CommandType::Move => match direction {
+        PlayerCommandType::Move => match direction {
             Some(dir) => apply_move(game, player_idx, dir),
-            None => (false, false, Some("MISSING_DIRECTION".to_string())),
+            None => (false, false, Some(RejectReason::MissingDirection)),
         },
-        CommandType::Shield => match direction {
+        PlayerCommandType::Shield => match direction {
             Some(dir) => {
-                game.state.players[player_idx].shield = dir;
+                let player = &mut game.state.players[player_idx];
+                player.shield = dir;
+                player.shield_charges = game.shield_mode.raised_charges().unwrap_or(0);
                 (true, true, None)
             }
-            None => (false, false, Some("MISSING_DIRECTION".to_string())),
+            None => (false, false, Some(RejectReason::MissingDirection)),
         },
-        CommandType::Shoot => match direction {
-            Some(dir) => apply_shoot(game, player_idx, dir),
-            None => (false, false, Some("MISSING_DIRECTION".to_string())),
+        PlayerCommandType::Shoot => match direction {
+            Some(dir) => match apply_shoot(game, player_idx, dir) {
+                Ok((victims, path)) => {
+                    eliminated = victims;
+                    response.laser_path = Some(path);
+                    (true, true, None)
+                }
+                Err(reason) => (false, false, Some(reason)),
+            },
+            None => (false, false, Some(RejectReason::MissingDirection)),
         },
-        CommandType::Speak => {
-            let has_text = request
-                .speak_text
-                .as_deref()
-                .map(str::trim)
-                .filter(|text| !text.is_empty())
-                .is_some();
-            if has_text {
-                (true, true, None)
-            } else {
-                (false, false, Some("MISSING_SPEAK_TEXT".to_string()))
+        PlayerCommandType::Speak => {
+            match check_speak(&state.speak_filter, request.speak_text.as_deref()) {
+                Ok(text) => {
+                    game.state.players[player_idx].last_words = Some(text);
+                    (true, true, None)
+                }
+                Err(reason) => (false, false, Some(reason)),
             }
         }
-        CommandType::Timeout => (true, true, None),
-        CommandType::GameStarted => (false, false, Some("RESERVED_COMMAND_TYPE".to_string())),
+        PlayerCommandType::Charge => {
+            game.state.players[player_idx].charge_level = 1;
+            (true, true, None)
+        }
+        PlayerCommandType::Pass | PlayerCommandType::Timeout => (true, true, None),
     };
 
+    state.metrics.record(request.command_type, applied);
+    if applied {
+        record_command_stats(game, player_idx, request.command_type);
+    }
+
     response.accepted = true;
     response.applied = applied;
     response.reason = reason;
+    response.eliminations = eliminated
+        .into_iter()
+        .map(|victim_player_id| Elimination {
+            victim_player_id,
+            killer_player_id: request.player_id.clone(),
+            turn_no: request.turn_no,
+        })
+        .collect();
 
     if consume_turn {
-        advance_turn(game);
+        response.arena_shrink = advance_turn(game);
         game.last_step_seq += 1;
+        response.step_seq = Some(game.last_step_seq);
     }
 
     response.turn_no = game.turn_no;
     response.round_no = game.round_no;
     response.current_player_id = game.current_player_id.clone();
     response.status = game.status;
+    response
+}
 
-    Ok(Json(response))
+/// Hands out the game's next `step_seq` for a step that changes nothing,
+/// such as a rejected or duplicate command, so every step of a game is
+/// numbered here and no two share a seq.
+async fn reserve_step_seq_handler(
+    State(state): State<AppState>,
+    Path(game_id): Path<String>,
+) -> Result<Json<ReserveStepSeqResponse>, ApiError> {
+    let _mutation = state.mutation_lock.lock().await;
+    let mut game = load_game(&state, &game_id).await?;
+    game.last_step_seq += 1;
+    state.store.put(&mut game).await.map_err(store_error)?;
+    persist_game(&state, &game_id).await;
+    Ok(Json(ReserveStepSeqResponse {
+        game_id,
+        step_seq: game.last_step_seq,
+    }))
+}
+
+async fn validate_command_handler(
+    State(state): State<AppState>,
+    Path(game_id): Path<String>,
+    Json(request): Json<SubmitCommandRequest>,
+) -> Result<Json<ValidateCommandResponse>, ApiError> {
+    let game = load_game(&state, &game_id).await?;
+    let verdict = check_turn_owner(&game, &request)
+        .and_then(|player_idx| check_command(&state.speak_filter, &game, player_idx, &request));
+    Ok(Json(ValidateCommandResponse {
+        legal: verdict.is_ok(),
+        reason: verdict.err(),
+    }))
 }
 
 async fn finish_game_handler(
@@ -872,11 +2858,9 @@ async fn finish_game_handler(
     Json(request): Json<FinishGameRequest>,
 ) -> Result<Json<FinishGameResponse>, ApiError> {
     let (response, game_topics, finished_event) = {
-        let mut store = state.store.write().await;
-        let game = store
-            .games
-            .get_mut(&game_id)
-            .ok_or_else(|| ApiError::not_found(format!("game {} not found", game_id)))?;
+        let _mutation = state.mutation_lock.lock().await;
+        let mut loaded = load_game(&state, &game_id).await?;
+        let game = &mut loaded;
 
         if let Some(expected_turn_no) = request.expected_turn_no
             && game.turn_no != expected_turn_no
@@ -889,6 +2873,7 @@ async fn finish_game_handler(
                 turn_no: game.turn_no,
                 round_no: game.round_no,
                 current_player_id: game.current_player_id.clone(),
+                finish_reason: None,
             }));
         }
 
@@ -901,6 +2886,7 @@ async fn finish_game_handler(
                 turn_no: game.turn_no,
                 round_no: game.round_no,
                 current_player_id: game.current_player_id.clone(),
+                finish_reason: game.finish_reason,
             }));
         }
 
@@ -913,10 +2899,12 @@ async fn finish_game_handler(
                 turn_no: game.turn_no,
                 round_no: game.round_no,
                 current_player_id: game.current_player_id.clone(),
+                finish_reason: None,
             }));
         }
 
-        game.status = GameStatus::Finished;
+        let finished_event = finish_game(game, state.finished_topic_retention);
+        state.store.put(game).await.map_err(store_error)?;
 
         (
             FinishGameResponse {
@@ -927,28 +2915,64 @@ async fn finish_game_handler(
                 turn_no: game.turn_no,
                 round_no: game.round_no,
                 current_player_id: game.current_player_id.clone(),
+                finish_reason: game.finish_reason,
             },
             GameTopics {
                 input_topic: game.input_topic.clone(),
                 output_topic: game.output_topic.clone(),
             },
-            StepEvent {
-                game_id: game.game_id.clone(),
-                step_seq: game.last_step_seq.saturating_add(1),
-                turn_no: game.turn_no,
-                round_no: game.round_no,
-                event_type: StepEventType::GameFinished,
-                result_status: ResultStatus::Applied,
-                command: None,
-                state_after: game.state.clone(),
-                created_at: Utc::now(),
-            },
+            finished_event,
         )
     };
 
+    announce_game_finished(&state, game_topics, &finished_event).await;
+    persist_game(&state, &game_id).await;
+
+    Ok(Json(response))
+}
+
+/// Marks `game` finished and returns its GAME_FINISHED step, which takes the
+/// next step seq. A non-zero `topic_retention` schedules the topics'
+/// deletion for the sweeper.
+fn finish_game(game: &mut GameInstance, topic_retention: Duration) -> StepEvent {
+    game.status = GameStatus::Finished;
+    game.finish_reason = Some(finish_reason(game));
+    game.last_step_seq += 1;
+    if !topic_retention.is_zero() {
+        game.topics_retire_at = chrono::Duration::from_std(topic_retention)
+            .ok()
+            .and_then(|retention| Utc::now().checked_add_signed(retention));
+    }
+    StepEvent {
+        game_id: game.game_id.clone(),
+        step_seq: game.last_step_seq,
+        turn_no: game.turn_no,
+        round_no: game.round_no,
+        event_type: StepEventType::GameFinished,
+        result_status: ResultStatus::Applied,
+        command: None,
+        state_after: game.state.clone(),
+        created_at: Utc::now(),
+        eliminations: Vec::new(),
+        arena_shrink: None,
+        laser_path: None,
+        player_id: None,
+        reject_reason: None,
+        reasoning: None,
+        finish_reason: game.finish_reason,
+    }
+}
+
+/// Publishes a stored finish's GAME_FINISHED step and retires the game's
+/// topics.
+async fn announce_game_finished(
+    state: &AppState,
+    game_topics: GameTopics,
+    finished_event: &StepEvent,
+) {
     if let Err(error) = state
         .step_event_publisher
-        .publish_step_event(&game_topics.output_topic, &finished_event)
+        .publish_step_event(&game_topics.output_topic, finished_event)
         .await
     {
         warn!(
@@ -965,290 +2989,1465 @@ async fn finish_game_handler(
         );
     }
 
-    if let Err(error) = state
-        .topic_provisioner
-        .delete_game_topics(&game_topics)
-        .await
+    retire_game_topics(state, game_topics).await;
+}
+
+/// Deletes a finished game's topics once `finished_topic_retention` has
+/// passed, so late viewers and the bot-manager can still drain them. With a
+/// retention the game carries `topics_retire_at` and the topic sweeper does
+/// the deletion, so it survives a restart.
+async fn retire_game_topics(state: &AppState, game_topics: GameTopics) {
+    state
+        .step_event_publisher
+        .forget_topic(&game_topics.output_topic);
+    let retention = state.finished_topic_retention;
+    if retention.is_zero() {
+        delete_finished_game_topics(state.topic_provisioner.as_ref(), &game_topics).await;
+        return;
+    }
+
+    info!(
+        input_topic = %game_topics.input_topic,
+        output_topic = %game_topics.output_topic,
+        retention_secs = retention.as_secs(),
+        "retaining per-game Kafka topics after game finish"
+    );
+}
+
+/// How often finished games are checked for topics past their retention.
+const TOPIC_SWEEP_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Sweeps for retired topics right away, which picks up games a restart
+/// interrupted, and then every `TOPIC_SWEEP_INTERVAL`.
+fn spawn_topic_sweeper(state: AppState) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(TOPIC_SWEEP_INTERVAL);
+        loop {
+            ticker.tick().await;
+            sweep_retired_topics(&state).await;
+        }
+    })
+}
+
+/// Deletes the topics of every game whose `topics_retire_at` has passed and
+/// clears it. A failed deletion keeps the date so the next sweep retries.
+async fn sweep_retired_topics(state: &AppState) {
+    let now = Utc::now();
+    let games = match state.store.list().await {
+        Ok(games) => games,
+        Err(error) => {
+            warn!(error = %error, "failed to list games for the topic sweep");
+            return;
+        }
+    };
+    for game in games
+        .into_iter()
+        .filter(|game| game.topics_retire_at.is_some_and(|at| at <= now))
     {
+        let game_topics = GameTopics {
+            input_topic: game.input_topic.clone(),
+            output_topic: game.output_topic.clone(),
+        };
+        if !delete_finished_game_topics(state.topic_provisioner.as_ref(), &game_topics).await {
+            continue;
+        }
+        {
+            let _mutation = state.mutation_lock.lock().await;
+            let cleared = match state.store.get(&game.game_id).await {
+                Ok(Some(mut game)) => {
+                    game.topics_retire_at = None;
+                    state.store.put(&mut game).await
+                }
+                Ok(None) => Ok(()),
+                Err(error) => Err(error),
+            };
+            if let Err(error) = cleared {
+                warn!(
+                    game_id = %game.game_id,
+                    error = %error,
+                    "failed to clear topic retirement after deleting topics"
+                );
+            }
+        }
+        persist_game(state, &game.game_id).await;
+    }
+}
+
+/// Returns whether the topics are gone.
+async fn delete_finished_game_topics(
+    topic_provisioner: &dyn TopicProvisioner,
+    game_topics: &GameTopics,
+) -> bool {
+    if let Err(error) = topic_provisioner.delete_game_topics(game_topics).await {
         warn!(
             input_topic = %game_topics.input_topic,
             output_topic = %game_topics.output_topic,
             error = %error,
             "failed to delete per-game Kafka topics after game finish"
         );
+        false
     } else {
         info!(
             input_topic = %game_topics.input_topic,
             output_topic = %game_topics.output_topic,
             "deleted per-game Kafka topics after game finish"
         );
+        true
     }
-
-    Ok(Json(response))
 }
 
-fn apply_move(
-    game: &mut GameInstance,
-    player_idx: usize,
-    direction: Direction,
-) -> (bool, bool, Option<String>) {
-    let (dr, dc) = delta(direction);
-    let next_row = game.state.players[player_idx].row as i32 + dr;
-    let next_col = game.state.players[player_idx].col as i32 + dc;
-
-    if !in_bounds(&game.state.map, next_row, next_col) {
-        return (false, false, Some("MOVE_OUT_OF_BOUNDS".to_string()));
-    }
+/// Times out the current player on demand, for recovering games whose turn
+/// owner is wedged. Goes through the same path as a timer timeout.
+async fn skip_turn_handler(
+    State(state): State<AppState>,
+    Path(game_id): Path<String>,
+) -> Result<Json<SkipTurnResponse>, ApiError> {
+    let (response, output_topic, steps) = {
+        let _mutation = state.mutation_lock.lock().await;
+        let mut game = load_game(&state, &game_id).await?;
+        if game.status != GameStatus::Running {
+            return Err(ApiError::conflict(format!("game {game_id} is not running")));
+        }
 
-    let nr = next_row as usize;
-    let nc = next_col as usize;
+        let request = SubmitCommandRequest {
+            command_id: format!(
+                "skip-{game_id}-{}-{}",
+                game.turn_no,
+                Utc::now().timestamp_millis()
+            ),
+            player_id: game.current_player_id.clone(),
+            command_type: PlayerCommandType::Timeout,
+            direction: None,
+            speak_text: None,
+            turn_no: game.turn_no,
+            client_sent_at: Utc::now(),
+        };
+        let applied = apply_command_to_game(&state, &mut game, &request);
+        if !applied.applied {
+            return Err(ApiError::conflict(format!(
+                "could not skip turn {} of game {game_id}: {:?}",
+                request.turn_no, applied.reason
+            )));
+        }
 
-    if game.state.map.cells[nr][nc] != 0 {
-        return (false, false, Some("MOVE_BLOCKED_BY_BLOCK".to_string()));
-    }
+        let skipped = StepEvent {
+            game_id: game.game_id.clone(),
+            step_seq: game.last_step_seq,
+            turn_no: game.turn_no,
+            round_no: game.round_no,
+            event_type: StepEventType::TimeoutApplied,
+            result_status: ResultStatus::TimeoutApplied,
+            command: Some(CommandEnvelope {
+                command_id: request.command_id.clone(),
+                source: CommandSource::System,
+                game_id: game.game_id.clone(),
+                player_id: Some(request.player_id.clone()),
+                command_type: PlayerCommandType::Timeout,
+                direction: None,
+                speak_text: None,
+                turn_no: request.turn_no,
+                sent_at: request.client_sent_at,
+                player_token: None,
+            }),
+            state_after: game.state.clone(),
+            created_at: Utc::now(),
+            eliminations: Vec::new(),
+            arena_shrink: applied.arena_shrink,
+            laser_path: None,
+            player_id: None,
+            reject_reason: None,
+            reasoning: None,
+            finish_reason: None,
+        };
+        let mut steps = vec![skipped];
+        steps.extend(fire_queued_commands(&state, &mut game));
+        state.store.put(&mut game).await.map_err(store_error)?;
 
-    if player_at(game, nr, nc).is_some() {
-        return (false, false, Some("MOVE_BLOCKED_BY_PLAYER".to_string()));
-    }
+        (
+            SkipTurnResponse {
+                game_id: game.game_id.clone(),
+                skipped_player_id: request.player_id,
+                step_seq: steps[0].step_seq,
+                turn_no: game.turn_no,
+                round_no: game.round_no,
+                current_player_id: game.current_player_id.clone(),
+            },
+            game.output_topic.clone(),
+            steps,
+        )
+    };
 
-    game.state.players[player_idx].row = nr;
-    game.state.players[player_idx].col = nc;
-    (true, true, None)
-}
+    state
+        .step_event_publisher
+        .publish_step_event(&output_topic, &steps[0])
+        .await
+        .map_err(|error| {
+            ApiError::internal(format!(
+                "failed to publish skip step for game {game_id}: {error:#}"
+            ))
+        })?;
+    publish_steps(&state, &output_topic, &steps[1..]).await;
+    info!(
+        game_id = %game_id,
+        player_id = %response.skipped_player_id,
+        step_seq = response.step_seq,
+        "skipped turn"
+    );
+    persist_game(&state, &game_id).await;
 
-fn apply_shoot(
-    game: &mut GameInstance,
-    player_idx: usize,
-    direction: Direction,
-) -> (bool, bool, Option<String>) {
-    let (shooter_row, shooter_col, shooter_shield) = {
-        let shooter = &game.state.players[player_idx];
-        (shooter.row, shooter.col, shooter.shield)
-    };
+    Ok(Json(response))
+}
 
-    // Cannot shoot through own shield.
-    if direction == shooter_shield {
-        return (
-            false,
-            false,
-            Some("CANNOT_SHOOT_THROUGH_OWN_SHIELD".to_string()),
-        );
-    }
+async fn forfeit_player_handler(
+    State(state): State<AppState>,
+    Path((game_id, player_id)): Path<(String, PlayerId)>,
+    request: Option<Json<ForfeitPlayerRequest>>,
+) -> Result<Json<ForfeitPlayerResponse>, ApiError> {
+    let replace_with_bot = request
+        .and_then(|Json(request)| request.replace_with_bot)
+        .unwrap_or(false);
+
+    let (mut response, forfeit_steps) = {
+        let _mutation = state.mutation_lock.lock().await;
+        let mut loaded = load_game(&state, &game_id).await?;
+        let game = &mut loaded;
+
+        if game.status != GameStatus::Running {
+            return Err(ApiError::conflict(format!("game {game_id} is not running")));
+        }
 
-    // The laser enters the adjacent cell in the shoot direction.
-    let (dr, dc) = delta(direction);
-    let entry_row = shooter_row as i32 + dr;
-    let entry_col = shooter_col as i32 + dc;
+        let player_idx = game
+            .state
+            .players
+            .iter()
+            .position(|player| player.player_id == player_id)
+            .ok_or_else(|| {
+                ApiError::not_found(format!("player {player_id} not found in game {game_id}"))
+            })?;
 
-    // Entry cell must be in bounds.
-    if !in_bounds(&game.state.map, entry_row, entry_col) {
-        return (
-            false,
-            false,
-            Some("SHOOT_BLOCKED_BY_EDGE".to_string()),
-        );
-    }
+        if !game.state.players[player_idx].alive {
+            return Err(ApiError::conflict(format!(
+                "player {player_id} is already eliminated"
+            )));
+        }
 
-    let er = entry_row as usize;
-    let ec = entry_col as usize;
+        let mut response = ForfeitPlayerResponse {
+            game_id: game.game_id.clone(),
+            player_id: player_id.clone(),
+            forfeited: false,
+            rebound_to_bot: false,
+            status: game.status,
+            turn_no: game.turn_no,
+            round_no: game.round_no,
+            current_player_id: game.current_player_id.clone(),
+        };
+        if replace_with_bot {
+            if game.first_turn_grace_seconds > 0 {
+                game.rebound_bot_players.insert(player_id.clone());
+                state.store.put(game).await.map_err(store_error)?;
+            }
+            (response, None)
+        } else {
+            let player = &mut game.state.players[player_idx];
+            player.alive = false;
+            player.hp = 0;
+            let elimination = Elimination {
+                victim_player_id: player_id.clone(),
+                killer_player_id: player_id.clone(),
+                turn_no: game.turn_no,
+            };
+            let last_player_left = alive_player_count(game) == 1;
+            let arena_shrink = if last_player_left {
+                game.finish_reason = Some(FinishReason::Forfeit);
+                None
+            } else if game.current_player_id == player_id {
+                advance_turn(game)
+            } else {
+                None
+            };
+            game.last_step_seq += 1;
+            let forfeit = StepEvent {
+                game_id: game.game_id.clone(),
+                step_seq: game.last_step_seq,
+                turn_no: game.turn_no,
+                round_no: game.round_no,
+                event_type: StepEventType::StepApplied,
+                result_status: ResultStatus::Applied,
+                command: None,
+                state_after: game.state.clone(),
+                created_at: Utc::now(),
+                eliminations: vec![elimination],
+                arena_shrink,
+                laser_path: None,
+                player_id: None,
+                reject_reason: None,
+                reasoning: None,
+                finish_reason: None,
+            };
+            // The turn may have passed to a player with a queued command,
+            // which can in turn leave a single survivor.
+            let queued = if last_player_left {
+                Vec::new()
+            } else {
+                fire_queued_commands(&state, game)
+            };
+            let finished = (alive_player_count(game) == 1).then(|| {
+                let game_topics = GameTopics {
+                    input_topic: game.input_topic.clone(),
+                    output_topic: game.output_topic.clone(),
+                };
+                (
+                    game_topics,
+                    finish_game(game, state.finished_topic_retention),
+                )
+            });
+            state.store.put(game).await.map_err(store_error)?;
+
+            response.forfeited = true;
+            response.status = game.status;
+            response.turn_no = game.turn_no;
+            response.round_no = game.round_no;
+            response.current_player_id = game.current_player_id.clone();
+            let forfeit_steps = ForfeitSteps {
+                output_topic: game.output_topic.clone(),
+                forfeit,
+                queued,
+                finished,
+            };
+            (response, Some(forfeit_steps))
+        }
+    };
 
-    // Entry cell must be empty — no wall, no player.
-    if game.state.map.cells[er][ec] != 0 {
-        return (
-            false,
-            false,
-            Some("SHOOT_BLOCKED_BY_BLOCK".to_string()),
-        );
+    if replace_with_bot {
+        state
+            .bot_assigner
+            .bind_bot(&game_id, &player_id)
+            .await
+            .map_err(|error| {
+                ApiError::bad_gateway(format!(
+                    "failed to bind bot to player {player_id} in game {game_id}: {error:#}"
+                ))
+            })?;
+        response.rebound_to_bot = true;
+        info!(game_id = %game_id, player_id = %player_id, "rebound player slot to a bot");
     }
-    if player_at(game, er, ec).is_some() {
-        return (
-            false,
-            false,
-            Some("SHOOT_BLOCKED_BY_PLAYER".to_string()),
+
+    if let Some(steps) = forfeit_steps {
+        state
+            .step_event_publisher
+            .publish_step_event(&steps.output_topic, &steps.forfeit)
+            .await
+            .map_err(|error| {
+                ApiError::internal(format!(
+                    "failed to publish forfeit step for game {game_id}: {error:#}"
+                ))
+            })?;
+        info!(
+            game_id = %game_id,
+            player_id = %player_id,
+            step_seq = steps.forfeit.step_seq,
+            "player forfeited"
         );
+        publish_steps(&state, &steps.output_topic, &steps.queued).await;
+        if let Some((game_topics, finished_event)) = steps.finished {
+            announce_game_finished(&state, game_topics, &finished_event).await;
+        }
+        persist_game(&state, &game_id).await;
     }
 
-    // From the entry cell, sweep a laser in both perpendicular directions.
-    let (perp1, perp2) = perpendicular_directions(direction);
-    sweep_laser(game, er, ec, perp1);
-    sweep_laser(game, er, ec, perp2);
+    Ok(Json(response))
+}
 
-    (true, true, None)
+/// What a forfeit publishes, in seq order.
+struct ForfeitSteps {
+    output_topic: String,
+    forfeit: StepEvent,
+    /// Queued commands that fired as the turn moved on.
+    queued: Vec<StepEvent>,
+    /// Set when the forfeit left a single survivor.
+    finished: Option<(GameTopics, StepEvent)>,
 }
 
-/// Returns the two directions perpendicular to the given direction.
-fn perpendicular_directions(direction: Direction) -> (Direction, Direction) {
-    match direction {
-        Direction::Up | Direction::Down => (Direction::Left, Direction::Right),
-        Direction::Left | Direction::Right => (Direction::Up, Direction::Down),
-    }
+/// Round limit for simulations that don't set `max_rounds`, so sudden death
+/// always ends them.
+const DEFAULT_SIMULATION_MAX_ROUNDS: u64 = 200;
+/// Largest `max_rounds` a simulation accepts, so one request can't tie up a
+/// worker for long.
+const MAX_SIMULATION_ROUNDS: u64 = 1000;
+/// Backstop on turns played, in case a game outlives its round limit.
+const MAX_SIMULATION_ACTIONS: usize = MAX_SIMULATION_ROUNDS as usize * MAX_NUM_PLAYERS as usize;
+
+const ALL_DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Left,
+    Direction::Down,
+    Direction::Right,
+];
+
+async fn simulate_handler(
+    State(state): State<AppState>,
+    Json(request): Json<SimulateGameRequest>,
+) -> Result<Json<SimulateGameResponse>, ApiError> {
+    simulate(&state, request).await.map(Json)
 }
 
-/// Sweep a laser beam from (start_row, start_col) in the given direction,
-/// damaging the first wall or player it hits, then stopping.
-fn sweep_laser(
-    game: &mut GameInstance,
-    start_row: usize,
-    start_col: usize,
-    direction: Direction,
-) {
-    let (dr, dc) = delta(direction);
-    let mut row = start_row as i32 + dr;
-    let mut col = start_col as i32 + dc;
+/// Plays a whole game in process with the built-in policy in every seat,
+/// until one player is left. Nothing is stored or published and no bots or
+/// topics are involved.
+async fn simulate(
+    state: &AppState,
+    request: SimulateGameRequest,
+) -> Result<SimulateGameResponse, ApiError> {
+    let mut options = game_options(request.game)?;
+    let max_rounds = *options
+        .max_rounds
+        .get_or_insert(DEFAULT_SIMULATION_MAX_ROUNDS);
+    if max_rounds > MAX_SIMULATION_ROUNDS {
+        return Err(ApiError::bad_request(format!(
+            "max_rounds must be at most {MAX_SIMULATION_ROUNDS} for a simulation"
+        )));
+    }
+    let topics = GameTopics {
+        input_topic: String::new(),
+        output_topic: String::new(),
+    };
+    let game_id = format!("simulation-{}", Uuid::new_v4());
+    let mut game = build_game(state, game_id, &topics, &options).await?;
+    let now = Utc::now();
+    game.status = GameStatus::Running;
+    game.started_at = Some(now);
+    game.turn_started_at = Some(now);
+
+    let policy_seed = request.policy_seed.unwrap_or_else(random_map_seed);
+    // The game runs to the end without yielding, so keep it off the runtime.
+    let (game, actions) = tokio::task::spawn_blocking(move || {
+        let mut rng = StdRng::seed_from_u64(policy_seed);
+        let mut actions = Vec::new();
+        while alive_player_count(&game) > 1 && actions.len() < MAX_SIMULATION_ACTIONS {
+            let Some(action) = simulate_turn(&mut game, &mut rng) else {
+                break;
+            };
+            actions.push(action);
+        }
+        game.status = GameStatus::Finished;
+        (game, actions)
+    })
+    .await
+    .map_err(|error| ApiError::internal(format!("simulation failed: {error}")))?;
+
+    Ok(SimulateGameResponse {
+        policy_seed,
+        winner_player_id: winner_player_id(&game),
+        finish_reason: finish_reason(&game),
+        turn_no: game.turn_no,
+        round_no: game.round_no,
+        final_state: game.state,
+        actions,
+    })
+}
 
-    while in_bounds(&game.state.map, row, col) {
-        let r = row as usize;
-        let c = col as usize;
+/// Lets the current player act with the built-in policy, then hands the
+/// turn on.
+fn simulate_turn(game: &mut GameInstance, rng: &mut StdRng) -> Option<SimulatedAction> {
+    let player_idx = game
+        .state
+        .players
+        .iter()
+        .position(|player| player.player_id == game.current_player_id)?;
+    let (command_type, direction) = simulation_policy(game, player_idx, rng);
 
-        // Hit a wall — damage it if destructible, then stop.
-        let block = game.state.map.cells[r][c];
-        if block != 0 {
-            if block > 0 {
-                let next = block - 1;
-                game.state.map.cells[r][c] = if next <= 0 { 0 } else { next };
-            }
-            return;
+    let mut eliminated = Vec::new();
+    match (command_type, direction) {
+        (PlayerCommandType::Move, Some(direction)) => {
+            apply_move(game, player_idx, direction);
         }
-
-        // Hit a player — check shield, apply damage, then stop.
-        if let Some(target_idx) = player_at(game, r, c) {
-            let incoming = opposite(direction);
-            let target = &mut game.state.players[target_idx];
-            if target.shield != incoming {
-                target.hp = (target.hp - 1).max(0);
-                if target.hp == 0 {
-                    target.alive = false;
-                }
+        (PlayerCommandType::Shoot, Some(direction)) => {
+            if let Ok((victims, _)) = apply_shoot(game, player_idx, direction) {
+                eliminated = victims;
             }
-            return;
         }
+        _ => {}
+    }
+    record_command_stats(game, player_idx, command_type);
 
-        row += dr;
-        col += dc;
+    let action = SimulatedAction {
+        turn_no: game.turn_no,
+        round_no: game.round_no,
+        player_id: game.current_player_id.clone(),
+        command_type,
+        direction,
+        eliminated,
+    };
+    advance_turn(game);
+    game.last_step_seq += 1;
+    Some(action)
+}
+
+/// Shoot when a shot would hurt an opponent, otherwise take the open step
+/// that ends nearest one (ties broken by `rng`), otherwise pass. Only ever
+/// picks commands the rules accept.
+fn simulation_policy(
+    game: &GameInstance,
+    player_idx: usize,
+    rng: &mut StdRng,
+) -> (PlayerCommandType, Option<Direction>) {
+    let mut directions = ALL_DIRECTIONS;
+    directions.shuffle(rng);
+
+    if let Some(direction) = directions
+        .into_iter()
+        .find(|&direction| shot_hurts_opponent(game, player_idx, direction))
+    {
+        return (PlayerCommandType::Shoot, Some(direction));
     }
+
+    directions
+        .into_iter()
+        .filter_map(|direction| {
+            let (row, col) = check_move(game, player_idx, direction).ok()?;
+            Some((
+                direction,
+                nearest_opponent_distance(game, player_idx, row, col),
+            ))
+        })
+        .min_by_key(|&(_, distance)| distance)
+        .map_or((PlayerCommandType::Pass, None), |(direction, _)| {
+            (PlayerCommandType::Move, Some(direction))
+        })
 }
 
-fn player_at(game: &GameInstance, row: usize, col: usize) -> Option<usize> {
-    game.state
+fn shot_hurts_opponent(game: &GameInstance, player_idx: usize, direction: Direction) -> bool {
+    let mut trial = game.clone();
+    if apply_shoot(&mut trial, player_idx, direction).is_err() {
+        return false;
+    }
+    trial
+        .state
         .players
         .iter()
-        .position(|p| p.alive && p.row == row && p.col == col)
-}
-
-fn alive_player_count(game: &GameInstance) -> usize {
-    game.state.players.iter().filter(|p| p.alive).count()
+        .zip(&game.state.players)
+        .enumerate()
+        .any(|(idx, (after, before))| idx != player_idx && after.hp < before.hp)
 }
 
-fn winner_player_id(game: &GameInstance) -> Option<PlayerId> {
+/// Manhattan distance from (`row`, `col`) to the closest living opponent.
+fn nearest_opponent_distance(
+    game: &GameInstance,
+    player_idx: usize,
+    row: usize,
+    col: usize,
+) -> usize {
     game.state
         .players
         .iter()
-        .find(|p| p.alive)
-        .map(|p| p.player_id.clone())
+        .enumerate()
+        .filter(|&(idx, player)| idx != player_idx && player.alive)
+        .map(|(_, player)| player.row.abs_diff(row) + player.col.abs_diff(col))
+        .min()
+        .unwrap_or(usize::MAX)
 }
 
-fn in_bounds(map: &MapData, row: i32, col: i32) -> bool {
-    row >= 0 && col >= 0 && (row as usize) < map.rows && (col as usize) < map.cols
+async fn load_game(state: &AppState, game_id: &str) -> Result<GameInstance, ApiError> {
+    state
+        .store
+        .get(game_id)
+        .await
+        .map_err(store_error)?
+        .ok_or_else(|| ApiError::not_found(format!("game {} not found", game_id)))
 }
 
-fn delta(direction: Direction) -> (i32, i32) {
-    match direction {
-        Direction::Up => (-1, 0),
-        Direction::Left => (0, -1),
-        Direction::Down => (1, 0),
-        Direction::Right => (0, 1),
+fn store_error(error: anyhow::Error) -> ApiError {
+    if let Some(conflict) = error.downcast_ref::<StoreConflict>() {
+        return ApiError::conflict(conflict.to_string());
     }
+    ApiError::internal(format!("game store error: {error:#}"))
 }
 
-fn opposite(direction: Direction) -> Direction {
-    match direction {
-        Direction::Up => Direction::Down,
-        Direction::Down => Direction::Up,
-        Direction::Left => Direction::Right,
-        Direction::Right => Direction::Left,
+#[async_trait]
+impl GameStore for InMemoryGameStore {
+    async fn get(&self, game_id: &str) -> anyhow::Result<Option<GameInstance>> {
+        Ok(self.games.read().await.get(game_id).cloned())
     }
-}
 
-fn advance_turn(game: &mut GameInstance) {
-    let player_count = game.state.players.len();
-    if player_count == 0 {
-        return;
+    async fn put(&self, game: &mut GameInstance) -> anyhow::Result<()> {
+        let mut games = self.games.write().await;
+        if games
+            .get(&game.game_id)
+            .is_some_and(|stored| stored.version != game.version)
+        {
+            return Err(StoreConflict {
+                game_id: game.game_id.clone(),
+            }
+            .into());
+        }
+        game.version += 1;
+        games.insert(game.game_id.clone(), game.clone());
+        Ok(())
     }
 
-    let Some(current_index) = game
-        .state
-        .players
-        .iter()
-        .position(|player| player.player_id == game.current_player_id)
-    else {
-        return;
-    };
+    async fn remove(&self, game_id: &str) -> anyhow::Result<()> {
+        self.games.write().await.remove(game_id);
+        Ok(())
+    }
 
-    let mut next_index = current_index;
-    for _ in 0..player_count {
-        next_index = (next_index + 1) % player_count;
-        let next_player = &game.state.players[next_index];
-        if next_player.alive {
-            if next_index <= current_index {
-                game.round_no += 1;
-            }
-            game.current_player_id = next_player.player_id.clone();
-            game.turn_no += 1;
-            game.turn_started_at = Some(Utc::now());
-            return;
-        }
+    async fn list(&self) -> anyhow::Result<Vec<GameInstance>> {
+        Ok(self.games.read().await.values().cloned().collect())
     }
 }
 
-#[derive(Debug)]
-struct ApiError {
-    status: StatusCode,
-    message: String,
-}
+#[async_trait]
+impl GameStore for DynamoGameStore {
+    async fn get(&self, game_id: &str) -> anyhow::Result<Option<GameInstance>> {
+        let output = self
+            .client
+            .get_item()
+            .table_name(&self.table_name)
+            .key("game_id", AttributeValue::S(game_id.to_string()))
+            .consistent_read(true)
+            .send()
+            .await
+            .context("failed to read game_instances item")?;
+        output.item().map(game_from_item).transpose()
+    }
 
-impl ApiError {
-    fn internal(message: impl Into<String>) -> Self {
-        Self {
-            status: StatusCode::INTERNAL_SERVER_ERROR,
-            message: message.into(),
-        }
+    async fn put(&self, game: &mut GameInstance) -> anyhow::Result<()> {
+        let expected = game.version;
+        let mut next = game.clone();
+        next.version += 1;
+        let payload = serde_json::to_string(&next).context("failed to encode game")?;
+        self.client
+            .put_item()
+            .table_name(&self.table_name)
+            .item("game_id", AttributeValue::S(game.game_id.clone()))
+            .item("game", AttributeValue::S(payload))
+            .item("version", AttributeValue::N(next.version.to_string()))
+            .condition_expression("attribute_not_exists(game_id) OR version = :expected")
+            .expression_attribute_values(":expected", AttributeValue::N(expected.to_string()))
+            .send()
+            .await
+            .map_err(|error| put_item_error(&game.game_id, error))?;
+        game.version = next.version;
+        Ok(())
     }
 
-    fn bad_gateway(message: impl Into<String>) -> Self {
-        Self {
-            status: StatusCode::BAD_GATEWAY,
-            message: message.into(),
-        }
+    async fn remove(&self, game_id: &str) -> anyhow::Result<()> {
+        self.client
+            .delete_item()
+            .table_name(&self.table_name)
+            .key("game_id", AttributeValue::S(game_id.to_string()))
+            .send()
+            .await
+            .context("failed to delete game_instances item")?;
+        Ok(())
     }
 
-    fn not_found(message: impl Into<String>) -> Self {
-        Self {
-            status: StatusCode::NOT_FOUND,
-            message: message.into(),
+    async fn list(&self) -> anyhow::Result<Vec<GameInstance>> {
+        let mut games = Vec::new();
+        let mut start_key = None;
+        loop {
+            let output = self
+                .client
+                .scan()
+                .table_name(&self.table_name)
+                .set_exclusive_start_key(start_key)
+                .send()
+                .await
+                .context("failed to scan game_instances table")?;
+            for item in output.items() {
+                games.push(game_from_item(item)?);
+            }
+            start_key = output.last_evaluated_key().cloned();
+            if start_key.is_none() {
+                break;
+            }
         }
+        Ok(games)
     }
 }
 
-impl IntoResponse for ApiError {
-    fn into_response(self) -> Response {
-        warn!(status = %self.status, message = %self.message, "request failed");
-        (
-            self.status,
-            Json(serde_json::json!({"error": self.message})),
-        )
-            .into_response()
+/// A failed version condition becomes a [`StoreConflict`]; anything else is
+/// a plain write failure.
+fn put_item_error<R>(game_id: &str, error: SdkError<PutItemError, R>) -> anyhow::Error
+where
+    R: std::fmt::Debug + Send + Sync + 'static,
+{
+    if error
+        .as_service_error()
+        .is_some_and(PutItemError::is_conditional_check_failed_exception)
+    {
+        return StoreConflict {
+            game_id: game_id.to_string(),
+        }
+        .into();
     }
+    anyhow::Error::new(error).context("failed to write game_instances item")
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use axum::extract::{Path, State};
-    use std::sync::Mutex;
-
-    struct NoopTopicProvisioner;
+fn game_from_item(item: &HashMap<String, AttributeValue>) -> anyhow::Result<GameInstance> {
+    let payload = item
+        .get("game")
+        .and_then(|value| value.as_s().ok())
+        .context("game_instances item missing game payload")?;
+    serde_json::from_str(payload).context("failed to decode game_instances item")
+}
 
-    #[async_trait]
+/// Ownership checks run from most to least specific so clients can tell
+/// "unknown player" and "eliminated" apart from "not your turn".
+/// Returns the index of the player whose turn it is.
+fn check_turn_owner(
+    game: &GameInstance,
+    request: &SubmitCommandRequest,
+) -> Result<usize, RejectReason> {
+    if game.status == GameStatus::Paused {
+        return Err(RejectReason::GamePaused);
+    }
+    if game.status != GameStatus::Running {
+        return Err(RejectReason::GameNotRunning);
+    }
+
+    let player_idx = game
+        .state
+        .players
+        .iter()
+        .position(|p| p.player_id == request.player_id)
+        .ok_or(RejectReason::PlayerNotInGame)?;
+
+    if !game.state.players[player_idx].alive {
+        return Err(RejectReason::PlayerDead);
+    }
+    if request.player_id != game.current_player_id {
+        return Err(RejectReason::InvalidTurnPlayer);
+    }
+    if request.turn_no != game.turn_no {
+        return Err(RejectReason::StaleTurnNo);
+    }
+    Ok(player_idx)
+}
+
+/// Whether `request` would be applied for the player at `player_idx`, without
+/// touching the game.
+fn check_command(
+    speak_filter: &SpeakFilter,
+    game: &GameInstance,
+    player_idx: usize,
+    request: &SubmitCommandRequest,
+) -> Result<(), RejectReason> {
+    let direction = request.direction.ok_or(RejectReason::MissingDirection);
+    match request.command_type {
+        PlayerCommandType::Move => check_move(game, player_idx, direction?).map(|_| ()),
+        PlayerCommandType::Shield => direction.map(|_| ()),
+        PlayerCommandType::Shoot => check_shoot(game, player_idx, direction?).map(|_| ()),
+        PlayerCommandType::Speak => {
+            check_speak(speak_filter, request.speak_text.as_deref()).map(|_| ())
+        }
+        PlayerCommandType::Pass | PlayerCommandType::Timeout | PlayerCommandType::Charge => Ok(()),
+    }
+}
+
+/// Which map cells a move may end on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MovableCellPredicate {
+    /// Only empty cells; any wall blocks.
+    EmptyOnly,
+    /// Empty cells and rubble, a destructible wall down to strength 1.
+    /// Stepping onto rubble clears it.
+    EmptyOrRubble,
+}
+
+impl MovableCellPredicate {
+    fn for_game(game: &GameInstance) -> Self {
+        if game.allow_rubble_move {
+            Self::EmptyOrRubble
+        } else {
+            Self::EmptyOnly
+        }
+    }
+
+    fn allows(self, cell: i32) -> bool {
+        match self {
+            Self::EmptyOnly => cell == 0,
+            Self::EmptyOrRubble => cell == 0 || cell == 1,
+        }
+    }
+}
+
+/// Returns the cell the player would move into.
+fn check_move(
+    game: &GameInstance,
+    player_idx: usize,
+    direction: Direction,
+) -> Result<(usize, usize), RejectReason> {
+    let (dr, dc) = delta(direction);
+    let next_row = game.state.players[player_idx].row as i32 + dr;
+    let next_col = game.state.players[player_idx].col as i32 + dc;
+
+    if !in_bounds(&game.state.map, next_row, next_col) {
+        return Err(RejectReason::MoveOutOfBounds);
+    }
+
+    let nr = next_row as usize;
+    let nc = next_col as usize;
+
+    if !MovableCellPredicate::for_game(game).allows(game.state.map.cells[nr][nc]) {
+        return Err(RejectReason::MoveBlockedByBlock);
+    }
+
+    if game.body_block && player_at(game, nr, nc).is_some() {
+        return Err(RejectReason::MoveBlockedByPlayer);
+    }
+
+    Ok((nr, nc))
+}
+
+/// Returns the cell the laser would enter.
+fn check_shoot(
+    game: &GameInstance,
+    player_idx: usize,
+    direction: Direction,
+) -> Result<(usize, usize), RejectReason> {
+    let shooter = &game.state.players[player_idx];
+
+    // Cannot shoot through own shield while it is up.
+    if direction == shooter.shield && shield_is_up(game.shield_mode, shooter) {
+        return Err(RejectReason::CannotShootThroughOwnShield);
+    }
+
+    // The laser enters the adjacent cell in the shoot direction.
+    let (dr, dc) = delta(direction);
+    let entry_row = shooter.row as i32 + dr;
+    let entry_col = shooter.col as i32 + dc;
+
+    // Entry cell must be in bounds.
+    if !in_bounds(&game.state.map, entry_row, entry_col) {
+        return Err(RejectReason::ShootBlockedByEdge);
+    }
+
+    let er = entry_row as usize;
+    let ec = entry_col as usize;
+
+    // Entry cell must be empty — no wall, no player.
+    if game.state.map.cells[er][ec] != 0 {
+        return Err(RejectReason::ShootBlockedByBlock);
+    }
+    if player_at(game, er, ec).is_some() {
+        return Err(RejectReason::ShootBlockedByPlayer);
+    }
+
+    Ok((er, ec))
+}
+
+/// The speak text to store: trimmed, with runs of whitespace collapsed to
+/// one space.
+fn check_speak(
+    speak_filter: &SpeakFilter,
+    speak_text: Option<&str>,
+) -> Result<String, RejectReason> {
+    let text = speak_text
+        .unwrap_or_default()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if text.is_empty() {
+        return Err(RejectReason::MissingSpeakText);
+    }
+    if text.chars().count() > MAX_SPEAK_CHARS {
+        return Err(RejectReason::SpeakTooLong);
+    }
+    if !speak_filter.allows(&text) {
+        return Err(RejectReason::SpeakRejected);
+    }
+    Ok(text)
+}
+
+fn apply_move(
+    game: &mut GameInstance,
+    player_idx: usize,
+    direction: Direction,
+) -> (bool, bool, Option<RejectReason>) {
+    match check_move(game, player_idx, direction) {
+        Ok((nr, nc)) => {
+            game.state.map.cells[nr][nc] = 0;
+            let player = &mut game.state.players[player_idx];
+            player.row = nr;
+            player.col = nc;
+            player.charge_level = 0;
+            (true, true, None)
+        }
+        Err(reason) => (false, false, Some(reason)),
+    }
+}
+
+/// Returns the eliminated player ids and the path the laser took.
+fn apply_shoot(
+    game: &mut GameInstance,
+    player_idx: usize,
+    direction: Direction,
+) -> Result<(Vec<PlayerId>, LaserPath), RejectReason> {
+    let (er, ec) = check_shoot(game, player_idx, direction)?;
+    let charged = std::mem::take(&mut game.state.players[player_idx].charge_level) > 0;
+
+    // From the entry cell, sweep a laser in both perpendicular directions.
+    let (perp1, perp2) = perpendicular_directions(direction);
+    let (mut eliminated, arm1) = sweep_laser(game, player_idx, er, ec, perp1, charged);
+    let (eliminated2, arm2) = sweep_laser(game, player_idx, er, ec, perp2, charged);
+    eliminated.extend(eliminated2);
+
+    let path = LaserPath {
+        entry: LaserCell { row: er, col: ec },
+        arms: vec![arm1, arm2],
+    };
+    Ok((eliminated, path))
+}
+
+/// Returns the two directions perpendicular to the given direction.
+fn perpendicular_directions(direction: Direction) -> (Direction, Direction) {
+    match direction {
+        Direction::Up | Direction::Down => (Direction::Left, Direction::Right),
+        Direction::Left | Direction::Right => (Direction::Up, Direction::Down),
+    }
+}
+
+/// Sweep a laser beam from (start_row, start_col) in the given direction,
+/// damaging the first wall or player it hits, then stopping.
+///
+/// In piercing mode the beam keeps travelling through walls it destroys and
+/// through players it hits; indestructible walls and surviving walls still
+/// stop it.
+///
+/// A charged beam deals 2 damage to players and carries on past the first
+/// destructible wall it hits.
+///
+/// Hits and damage are credited to the shooter at `shooter_idx`.
+///
+/// With a `laser_range`, the beam stops after crossing that many cells.
+///
+/// With `knockback`, a hit that damages but doesn't kill pushes the target
+/// one cell further along the beam; a piercing beam doesn't hit them twice.
+///
+/// Returns the ids of players this beam took from alive to dead, along with
+/// the cells it crossed.
+fn sweep_laser(
+    game: &mut GameInstance,
+    shooter_idx: usize,
+    start_row: usize,
+    start_col: usize,
+    direction: Direction,
+    charged: bool,
+) -> (Vec<PlayerId>, LaserArm) {
+    let damage = if charged { 2 } else { 1 };
+    let mut walls_to_pierce = u8::from(charged);
+    let (dr, dc) = delta(direction);
+    let mut row = start_row as i32 + dr;
+    let mut col = start_col as i32 + dc;
+    let mut eliminated = Vec::new();
+    let mut knocked_back = Vec::new();
+    let mut arm = LaserArm {
+        direction,
+        cells: Vec::new(),
+        stopped_by: LaserStop::Edge,
+    };
+
+    while in_bounds(&game.state.map, row, col) {
+        if game
+            .laser_range
+            .is_some_and(|range| arm.cells.len() >= range)
+        {
+            arm.stopped_by = LaserStop::Range;
+            break;
+        }
+        let r = row as usize;
+        let c = col as usize;
+        arm.cells.push(LaserCell { row: r, col: c });
+
+        // Hit a wall — damage it if destructible, then stop (unless piercing
+        // and the wall was destroyed, or a charged beam can still punch through).
+        let block = game.state.map.cells[r][c];
+        if block != 0 {
+            if block > 0 {
+                let next = block - 1;
+                game.state.map.cells[r][c] = if next <= 0 { 0 } else { next };
+            }
+            let destroyed = game.state.map.cells[r][c] == 0;
+            if !(game.laser_pierces && destroyed) {
+                if block > 0 && walls_to_pierce > 0 {
+                    walls_to_pierce -= 1;
+                } else {
+                    arm.stopped_by = LaserStop::Wall;
+                    return (eliminated, arm);
+                }
+            }
+            row += dr;
+            col += dc;
+            continue;
+        }
+
+        // Hit a player — check shield, apply damage, then stop (unless piercing).
+        if let Some(target_idx) = player_at(game, r, c)
+            && !knocked_back.contains(&target_idx)
+        {
+            let incoming = opposite(direction);
+            let shield_mode = game.shield_mode;
+            let target = &mut game.state.players[target_idx];
+            if target.shield == incoming && shield_is_up(shield_mode, target) {
+                // Limited shields spend a charge on every block.
+                if shield_mode.raised_charges().is_some() {
+                    target.shield_charges -= 1;
+                }
+            } else {
+                let hp_before = target.hp;
+                target.hp = (target.hp - damage).max(0);
+                target.charge_level = 0;
+                if target.hp == 0 {
+                    target.alive = false;
+                    eliminated.push(target.player_id.clone());
+                }
+                let dealt = (hp_before - target.hp) as u32;
+                let target_id = target.player_id.clone();
+                record_hit_stats(game, shooter_idx, &target_id, dealt);
+                if game.knockback && knock_back(game, target_idx, direction) {
+                    knocked_back.push(target_idx);
+                }
+            }
+            if !game.laser_pierces {
+                arm.stopped_by = LaserStop::Player;
+                return (eliminated, arm);
+            }
+        }
+
+        row += dr;
+        col += dc;
+    }
+
+    (eliminated, arm)
+}
+
+/// Pushes a living player one cell in `direction` when that cell is in
+/// bounds, wall-free and unoccupied. Returns whether they moved.
+fn knock_back(game: &mut GameInstance, player_idx: usize, direction: Direction) -> bool {
+    let player = &game.state.players[player_idx];
+    if !player.alive {
+        return false;
+    }
+    let (dr, dc) = delta(direction);
+    let row = player.row as i32 + dr;
+    let col = player.col as i32 + dc;
+    if !in_bounds(&game.state.map, row, col) {
+        return false;
+    }
+    let (row, col) = (row as usize, col as usize);
+    if game.state.map.cells[row][col] != 0 || player_at(game, row, col).is_some() {
+        return false;
+    }
+    let player = &mut game.state.players[player_idx];
+    player.row = row;
+    player.col = col;
+    true
+}
+
+/// Permanent shields never drop; limited ones are down once out of charges.
+fn shield_is_up(shield_mode: ShieldMode, player: &PlayerState) -> bool {
+    shield_mode.raised_charges().is_none() || player.shield_charges > 0
+}
+
+/// The stats entry for `player_id`, added on first use so games stored
+/// before stats existed still get counted.
+fn player_stats<'a>(game: &'a mut GameInstance, player_id: &str) -> &'a mut PlayerStats {
+    let stats = &mut game.state.stats;
+    let idx = match stats.iter().position(|entry| entry.player_id == player_id) {
+        Some(idx) => idx,
+        None => {
+            stats.push(PlayerStats {
+                player_id: player_id.to_string(),
+                ..Default::default()
+            });
+            stats.len() - 1
+        }
+    };
+    &mut stats[idx]
+}
+
+fn record_command_stats(
+    game: &mut GameInstance,
+    player_idx: usize,
+    command_type: PlayerCommandType,
+) {
+    let player_id = game.state.players[player_idx].player_id.clone();
+    let stats = player_stats(game, &player_id);
+    match command_type {
+        PlayerCommandType::Move => stats.moves += 1,
+        PlayerCommandType::Shield => stats.shields += 1,
+        PlayerCommandType::Shoot => stats.shots_fired += 1,
+        PlayerCommandType::Speak => stats.speaks += 1,
+        PlayerCommandType::Charge | PlayerCommandType::Pass | PlayerCommandType::Timeout => {}
+    }
+}
+
+fn record_hit_stats(game: &mut GameInstance, shooter_idx: usize, target_id: &str, damage: u32) {
+    let shooter_id = game.state.players[shooter_idx].player_id.clone();
+    let shooter = player_stats(game, &shooter_id);
+    shooter.hits_landed += 1;
+    shooter.damage_dealt += damage;
+    player_stats(game, target_id).damage_taken += damage;
+}
+
+/// Restores 1 strength to every damaged destructible wall each time a
+/// `wall_regen_rounds` boundary is crossed. Cells a player now stands on stay
+/// open so nobody gets walled in.
+fn regenerate_walls(game: &mut GameInstance) {
+    let period = game.wall_regen_rounds;
+    if period == 0 || !(game.round_no - 1).is_multiple_of(period) {
+        return;
+    }
+
+    for row in 0..game.state.map.rows {
+        for col in 0..game.state.map.cols {
+            let original = game.original_cells[row][col];
+            let current = game.state.map.cells[row][col];
+            if original <= 0
+                || current < 0
+                || current >= original
+                || player_at(game, row, col).is_some()
+            {
+                continue;
+            }
+            game.state.map.cells[row][col] = current + 1;
+        }
+    }
+}
+
+fn player_at(game: &GameInstance, row: usize, col: usize) -> Option<usize> {
+    game.state
+        .players
+        .iter()
+        .position(|p| p.alive && p.row == row && p.col == col)
+}
+
+fn alive_player_count(game: &GameInstance) -> usize {
+    game.state.players.iter().filter(|p| p.alive).count()
+}
+
+fn winner_player_id(game: &GameInstance) -> Option<PlayerId> {
+    rank_survivors(game.winner_policy, &game.state)
+        .map(|idx| game.state.players[idx].player_id.clone())
+}
+
+/// Index of the living player `policy` ranks first; ties go to the earliest
+/// seat.
+fn rank_survivors(policy: WinnerPolicy, state: &GameStateSnapshot) -> Option<usize> {
+    let damage_taken = |player: &PlayerState| {
+        state
+            .stats
+            .iter()
+            .find(|stats| stats.player_id == player.player_id)
+            .map_or(0, |stats| stats.damage_taken)
+    };
+    let alive = state
+        .players
+        .iter()
+        .enumerate()
+        .filter(|(_, player)| player.alive);
+    let mut leader: Option<(usize, &PlayerState)> = None;
+    for (idx, player) in alive {
+        let ahead = match (policy, leader) {
+            (_, None) => true,
+            (WinnerPolicy::FirstAlive, Some(_)) => false,
+            (WinnerPolicy::MostHp, Some((_, best))) => player.hp > best.hp,
+            (WinnerPolicy::FewestDamageTaken, Some((_, best))) => {
+                damage_taken(player) < damage_taken(best)
+            }
+        };
+        if ahead {
+            leader = Some((idx, player));
+        }
+    }
+    leader.map(|(idx, _)| idx)
+}
+
+fn in_bounds(map: &MapData, row: i32, col: i32) -> bool {
+    row >= 0 && col >= 0 && (row as usize) < map.rows && (col as usize) < map.cols
+}
+
+fn delta(direction: Direction) -> (i32, i32) {
+    match direction {
+        Direction::Up => (-1, 0),
+        Direction::Left => (0, -1),
+        Direction::Down => (1, 0),
+        Direction::Right => (0, 1),
+    }
+}
+
+fn opposite(direction: Direction) -> Direction {
+    match direction {
+        Direction::Up => Direction::Down,
+        Direction::Down => Direction::Up,
+        Direction::Left => Direction::Right,
+        Direction::Right => Direction::Left,
+    }
+}
+
+/// Hands the turn to the next living player, running round-boundary effects
+/// (wall regeneration, arena shrink) when the order wraps around.
+///
+/// A new round starts only when the search passes the end of the seat order,
+/// measured from the seat that just acted. The acting player may already be
+/// dead (e.g. they just forfeited), and any number of dead seats may be
+/// skipped; neither counts as an extra round.
+fn advance_turn(game: &mut GameInstance) -> Option<ArenaShrink> {
+    game.rebound_bot_players.remove(&game.current_player_id);
+    let player_count = game.state.players.len();
+    if player_count == 0 {
+        return None;
+    }
+
+    let current_index = game
+        .state
+        .players
+        .iter()
+        .position(|player| player.player_id == game.current_player_id)?;
+
+    let mut shrink = None;
+    let mut round_advanced = false;
+    for step in 1..=player_count {
+        let next_index = (current_index + step) % player_count;
+        if !game.state.players[next_index].alive {
+            continue;
+        }
+        let wrapped = current_index + step >= player_count;
+        if wrapped && !round_advanced {
+            game.round_no += 1;
+            round_advanced = true;
+            regenerate_walls(game);
+            shrink = shrink_arena(game);
+            sudden_death(game);
+            // The storm or sudden death may have just eliminated this player.
+            if !game.state.players[next_index].alive {
+                continue;
+            }
+        }
+        game.current_player_id = game.state.players[next_index].player_id.clone();
+        game.turn_no += 1;
+        game.turn_started_at = Some(Utc::now());
+        return shrink;
+    }
+    shrink
+}
+
+/// Why a game with one survivor left is ending: a forfeit recorded earlier,
+/// sudden death after the round limit, or a plain last player standing.
+fn finish_reason(game: &GameInstance) -> FinishReason {
+    if let Some(reason) = game.finish_reason {
+        return reason;
+    }
+    if game
+        .max_rounds
+        .is_some_and(|max_rounds| game.round_no > max_rounds)
+    {
+        return FinishReason::MaxRounds;
+    }
+    FinishReason::LastPlayerStanding
+}
+
+/// Once `round_no` passes `max_rounds` with more than one survivor, every
+/// survivor but the one the game's `winner_policy` ranks first is eliminated,
+/// so the normal last-player-standing finish picks them. `FirstAlive` can't
+/// tell survivors apart, so under it the most HP wins (ties go to the
+/// earliest player).
+fn sudden_death(game: &mut GameInstance) {
+    let Some(max_rounds) = game.max_rounds else {
+        return;
+    };
+    if game.round_no <= max_rounds || alive_player_count(game) <= 1 {
+        return;
+    }
+
+    let policy = match game.winner_policy {
+        WinnerPolicy::FirstAlive => WinnerPolicy::MostHp,
+        policy => policy,
+    };
+    let leader = rank_survivors(policy, &game.state);
+    for (idx, player) in game.state.players.iter_mut().enumerate() {
+        if player.alive && Some(idx) != leader {
+            player.alive = false;
+        }
+    }
+    info!(
+        game_id = %game.game_id,
+        round_no = game.round_no,
+        max_rounds,
+        "sudden death after round limit"
+    );
+}
+
+/// Storm mode: each `arena_shrink_rounds` boundary turns the next ring
+/// (0 = outermost) into indestructible walls, always leaving an open core.
+/// Players caught on the ring lose 1 HP and are pushed one tile inward when
+/// that tile is free; a survivor who can't be pushed keeps their tile open.
+fn shrink_arena(game: &mut GameInstance) -> Option<ArenaShrink> {
+    let period = game.arena_shrink_rounds;
+    if period == 0 || !(game.round_no - 1).is_multiple_of(period) {
+        return None;
+    }
+
+    let rows = game.state.map.rows;
+    let cols = game.state.map.cols;
+    let ring = game.arena_rings_closed;
+    if rows.min(cols) <= 2 * (ring + 1) {
+        return None;
+    }
+    game.arena_rings_closed += 1;
+
+    let mut shrink = ArenaShrink {
+        round_no: game.round_no,
+        ring,
+        damaged_player_ids: Vec::new(),
+        eliminated_player_ids: Vec::new(),
+    };
+    let mut spared: Vec<(usize, usize)> = Vec::new();
+    for idx in 0..game.state.players.len() {
+        let (row, col) = {
+            let player = &game.state.players[idx];
+            if !player.alive || ring_index(rows, cols, player.row, player.col) != ring {
+                continue;
+            }
+            (player.row, player.col)
+        };
+
+        let player = &mut game.state.players[idx];
+        player.hp -= 1;
+        shrink.damaged_player_ids.push(player.player_id.clone());
+        if player.hp <= 0 {
+            player.hp = 0;
+            player.alive = false;
+            shrink.eliminated_player_ids.push(player.player_id.clone());
+            continue;
+        }
+
+        let mut inward = Vec::with_capacity(2);
+        if row == ring {
+            inward.push((row + 1, col));
+        }
+        if row == rows - 1 - ring {
+            inward.push((row - 1, col));
+        }
+        if col == ring {
+            inward.push((row, col + 1));
+        }
+        if col == cols - 1 - ring {
+            inward.push((row, col - 1));
+        }
+        let target = inward.into_iter().find(|&(r, c)| {
+            game.state.map.cells[r][c] == 0 && (!game.body_block || player_at(game, r, c).is_none())
+        });
+        match target {
+            Some((r, c)) => {
+                let player = &mut game.state.players[idx];
+                player.row = r;
+                player.col = c;
+            }
+            None => spared.push((row, col)),
+        }
+    }
+
+    for row in 0..rows {
+        for col in 0..cols {
+            if ring_index(rows, cols, row, col) == ring && !spared.contains(&(row, col)) {
+                game.state.map.cells[row][col] = -1;
+            }
+        }
+    }
+
+    Some(shrink)
+}
+
+/// Distance of a tile from the nearest map edge; 0 is the outer ring.
+fn ring_index(rows: usize, cols: usize, row: usize, col: usize) -> usize {
+    row.min(col).min(rows - 1 - row).min(cols - 1 - col)
+}
+
+#[derive(Debug)]
+struct ApiError {
+    status: StatusCode,
+    /// Stable machine-readable error code, e.g. `NOT_FOUND`.
+    code: &'static str,
+    message: String,
+}
+
+impl ApiError {
+    fn internal(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            code: "INTERNAL",
+            message: message.into(),
+        }
+    }
+
+    fn bad_gateway(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::BAD_GATEWAY,
+            code: "BAD_GATEWAY",
+            message: message.into(),
+        }
+    }
+
+    fn not_found(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::NOT_FOUND,
+            code: "NOT_FOUND",
+            message: message.into(),
+        }
+    }
+
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            code: "BAD_REQUEST",
+            message: message.into(),
+        }
+    }
+
+    fn unauthorized(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::UNAUTHORIZED,
+            code: "UNAUTHORIZED",
+            message: message.into(),
+        }
+    }
+
+    fn conflict(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::CONFLICT,
+            code: "CONFLICT",
+            message: message.into(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        warn!(status = %self.status, code = self.code, message = %self.message, "request failed");
+        (
+            self.status,
+            Json(serde_json::json!({
+                "error": {"code": self.code, "message": self.message}
+            })),
+        )
+            .into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::{Path, State};
+    use cowboy_common::{
+        DEGRADING_SHIELD_CHARGES, FOG_CELL, generate_map_seeded,
+        testkit::{LocalBus, Recorder, ScriptedPlayer},
+    };
+    use std::sync::Mutex;
+
+    struct NoopTopicProvisioner;
+
+    #[async_trait]
     impl TopicProvisioner for NoopTopicProvisioner {
         async fn ensure_game_topics(&self, game_id: &str) -> anyhow::Result<GameTopics> {
             Ok(GameTopics {
@@ -1257,575 +4456,5021 @@ mod tests {
             })
         }
 
-        async fn delete_game_topics(&self, _game_topics: &GameTopics) -> anyhow::Result<()> {
-            Ok(())
+        async fn delete_game_topics(&self, _game_topics: &GameTopics) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct NoopStepEventPublisher;
+
+    #[async_trait]
+    impl StepEventPublisher for NoopStepEventPublisher {
+        async fn publish_step_event(&self, _topic: &str, _event: &StepEvent) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    struct NoopBotAssigner;
+
+    #[derive(Default)]
+    struct RecordingBotAssigner {
+        requested: Mutex<Vec<Option<Vec<PlayerName>>>>,
+    }
+
+    #[async_trait]
+    impl BotAssigner for RecordingBotAssigner {
+        async fn assign_for_new_game(
+            &self,
+            _game: &GameInstance,
+            requested_bot_players: Option<Vec<PlayerName>>,
+            _llm_overrides: Option<HashMap<PlayerName, LlmProfile>>,
+        ) -> anyhow::Result<()> {
+            self.requested.lock().unwrap().push(requested_bot_players);
+            Ok(())
+        }
+
+        async fn bind_bot(&self, _game_id: &str, _player_id: &str) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl BotAssigner for NoopBotAssigner {
+        async fn assign_for_new_game(
+            &self,
+            _game: &GameInstance,
+            _requested_bot_players: Option<Vec<PlayerName>>,
+            _llm_overrides: Option<HashMap<PlayerName, LlmProfile>>,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn bind_bot(&self, _game_id: &str, _player_id: &str) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingStepEventPublisher {
+        published: Mutex<Vec<(String, StepEvent)>>,
+    }
+
+    #[async_trait]
+    impl StepEventPublisher for RecordingStepEventPublisher {
+        async fn publish_step_event(&self, topic: &str, event: &StepEvent) -> anyhow::Result<()> {
+            self.published
+                .lock()
+                .unwrap()
+                .push((topic.to_string(), event.clone()));
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingTopicProvisioner {
+        game_ids: Mutex<Vec<String>>,
+        deleted_topics: Mutex<Vec<GameTopics>>,
+    }
+
+    #[async_trait]
+    impl TopicProvisioner for RecordingTopicProvisioner {
+        async fn ensure_game_topics(&self, game_id: &str) -> anyhow::Result<GameTopics> {
+            self.game_ids.lock().unwrap().push(game_id.to_string());
+            Ok(GameTopics {
+                input_topic: format!("record.commands.{game_id}.v1"),
+                output_topic: format!("record.output.{game_id}.v1"),
+            })
+        }
+
+        async fn delete_game_topics(&self, game_topics: &GameTopics) -> anyhow::Result<()> {
+            self.deleted_topics
+                .lock()
+                .unwrap()
+                .push(game_topics.clone());
+            Ok(())
+        }
+    }
+
+    fn app_state() -> AppState {
+        AppState {
+            store: Arc::new(InMemoryGameStore::default()),
+            default_map: Arc::default(),
+            mutation_lock: Arc::default(),
+            topic_provisioner: Arc::new(NoopTopicProvisioner),
+            step_event_publisher: Arc::new(NoopStepEventPublisher),
+            bot_assigner: Arc::new(NoopBotAssigner),
+            metrics: Arc::default(),
+            snapshot_path: None,
+            snapshot_writes: Arc::default(),
+            idempotency: Arc::default(),
+            speak_filter: Arc::default(),
+            finished_topic_retention: Duration::ZERO,
+            debug_endpoints: false,
+        }
+    }
+
+    fn pid(response: &CreateGameResponse, name: PlayerName) -> PlayerId {
+        response
+            .players
+            .iter()
+            .find(|p| p.player_name == name)
+            .unwrap_or_else(|| panic!("player {:?} not found in response", name))
+            .player_id
+            .clone()
+    }
+
+    fn custom_map(rows: usize, cols: usize) -> MapData {
+        MapData {
+            rows,
+            cols,
+            cells: vec![vec![0; cols]; rows],
+        }
+    }
+
+    /// A running game with no store behind it, for driving turn order
+    /// directly. Players are named A, B, C, D with ids "A".."D".
+    fn turn_order_game(alive: &[bool], current: usize) -> GameInstance {
+        let map = custom_map(5, 5);
+        let players: Vec<PlayerState> = ALL_PLAYER_NAMES
+            .iter()
+            .zip(alive)
+            .enumerate()
+            .map(|(idx, (&player_name, &alive))| PlayerState {
+                player_name,
+                player_id: format!("{player_name:?}"),
+                hp: if alive { DEFAULT_PLAYER_HP } else { 0 },
+                row: idx,
+                col: 0,
+                shield: Direction::Up,
+                alive,
+                charge_level: 0,
+                shield_charges: 0,
+                last_words: None,
+                color: None,
+                display_name: None,
+            })
+            .collect();
+        GameInstance {
+            game_id: "turn-order".to_string(),
+            status: GameStatus::Running,
+            map_source: MapSource::Custom,
+            turn_timeout_seconds: DEFAULT_TURN_TIMEOUT_SECONDS,
+            turn_no: 1,
+            round_no: 1,
+            current_player_id: players[current].player_id.clone(),
+            created_at: Utc::now(),
+            started_at: Some(Utc::now()),
+            turn_started_at: Some(Utc::now()),
+            original_cells: map.cells.clone(),
+            state: GameStateSnapshot {
+                map,
+                players,
+                stats: Vec::new(),
+            },
+            last_step_seq: 0,
+            input_topic: "in".to_string(),
+            output_topic: "out".to_string(),
+            laser_pierces: false,
+            laser_range: None,
+            wall_regen_rounds: 0,
+            arena_shrink_rounds: 0,
+            arena_rings_closed: 0,
+            require_player_token: false,
+            player_tokens: HashMap::new(),
+            spawn_layout: SpawnLayout::EdgeCenters,
+            paused_at: None,
+            max_rounds: None,
+            shield_mode: ShieldMode::Permanent,
+            winner_policy: WinnerPolicy::FirstAlive,
+            bot_slots: Vec::new(),
+            claimed_slots: HashMap::new(),
+            queued_commands: HashMap::new(),
+            body_block: true,
+            knockback: false,
+            auto_start_when_full: false,
+            vision_radius: None,
+            spawn_clear_radius: 0,
+            narrate_eliminations: false,
+            allow_rubble_move: false,
+            first_turn_grace_seconds: 0,
+            rebound_bot_players: HashSet::new(),
+            finish_reason: None,
+            topics_retire_at: None,
+            version: 0,
+        }
+    }
+
+    /// Advances once per expected `(current_player_id, round_no)` pair,
+    /// killing the listed seat first when one is given.
+    fn drive_turns(game: &mut GameInstance, steps: &[(Option<usize>, &str, u64)]) {
+        for (n, &(kill, expected_player, expected_round)) in steps.iter().enumerate() {
+            if let Some(idx) = kill {
+                game.state.players[idx].alive = false;
+            }
+            let turn_before = game.turn_no;
+            advance_turn(game);
+            assert_eq!(
+                (game.current_player_id.as_str(), game.round_no),
+                (expected_player, expected_round),
+                "transition {n}"
+            );
+            assert_eq!(game.turn_no, turn_before + 1, "transition {n}");
+        }
+    }
+
+    async fn stored_game(state: &AppState, game_id: &str) -> GameInstance {
+        state
+            .store
+            .get(game_id)
+            .await
+            .unwrap()
+            .expect("game must be stored")
+    }
+
+    async fn update_game(state: &AppState, game_id: &str, update: impl FnOnce(&mut GameInstance)) {
+        let mut game = stored_game(state, game_id).await;
+        update(&mut game);
+        state.store.put(&mut game).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn create_game_without_map_uses_default_map() {
+        let state = app_state();
+        let response = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                turn_timeout_seconds: None,
+                map: None,
+                bot_players: None,
+                num_players: None,
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        assert_eq!(response.status, GameStatus::Created);
+        assert_eq!(response.map_source, MapSource::Default);
+        assert_eq!(response.turn_no, 1);
+        assert_eq!(response.round_no, 1);
+        assert_eq!(response.current_player_id, pid(&response, PlayerName::A));
+        assert_eq!(response.turn_timeout_seconds, DEFAULT_TURN_TIMEOUT_SECONDS);
+        assert_eq!(response.players.len(), 2);
+
+        let game = get_game_handler(State(state), Path(response.game_id.clone()))
+            .await
+            .unwrap()
+            .0;
+        assert_eq!(game.state.map.rows, 11);
+        assert_eq!(game.state.map.cols, 11);
+        assert_eq!(game.state.players.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn create_game_provisions_per_game_topics() {
+        let recorder = Arc::new(RecordingTopicProvisioner::default());
+        let state = AppState {
+            store: Arc::new(InMemoryGameStore::default()),
+            default_map: Arc::default(),
+            mutation_lock: Arc::default(),
+            topic_provisioner: recorder.clone(),
+            step_event_publisher: Arc::new(NoopStepEventPublisher),
+            bot_assigner: Arc::new(NoopBotAssigner),
+            metrics: Arc::default(),
+            snapshot_path: None,
+            snapshot_writes: Arc::default(),
+            idempotency: Arc::default(),
+            speak_filter: Arc::default(),
+            finished_topic_retention: Duration::ZERO,
+            debug_endpoints: false,
+        };
+
+        let response = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                turn_timeout_seconds: None,
+                map: Some(custom_map(5, 5)),
+                bot_players: None,
+                num_players: Some(4),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        let game_ids = recorder.game_ids.lock().unwrap().clone();
+        assert_eq!(game_ids.len(), 1);
+        assert_eq!(game_ids[0], response.game_id);
+
+        let game = stored_game(&state, &response.game_id).await;
+        assert_eq!(
+            game.input_topic,
+            format!("record.commands.{}.v1", response.game_id)
+        );
+        assert_eq!(
+            game.output_topic,
+            format!("record.output.{}.v1", response.game_id)
+        );
+    }
+
+    #[tokio::test]
+    async fn create_game_with_custom_map_uses_custom_source() {
+        let state = app_state();
+        let response = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                turn_timeout_seconds: Some(15),
+                map: Some(custom_map(5, 7)),
+                bot_players: None,
+                num_players: Some(4),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        assert_eq!(response.map_source, MapSource::Custom);
+        assert_eq!(response.turn_timeout_seconds, 15);
+
+        let game = get_game_handler(State(state), Path(response.game_id.clone()))
+            .await
+            .unwrap()
+            .0;
+        assert_eq!(game.state.map.rows, 5);
+        assert_eq!(game.state.map.cols, 7);
+    }
+
+    #[tokio::test]
+    async fn create_game_rejects_malformed_custom_map() {
+        let state = app_state();
+        let mut map = custom_map(5, 5);
+        map.cells[2].pop();
+        let err = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map: Some(map),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(err.status, StatusCode::BAD_REQUEST);
+        assert!(state.store.list().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn create_game_with_same_map_seed_gets_same_map() {
+        let state = app_state();
+        let mut maps = Vec::new();
+        for _ in 0..2 {
+            let created = create_game_handler(
+                State(state.clone()),
+                Json(CreateGameRequest {
+                    map_seed: Some(1234),
+                    ..Default::default()
+                }),
+            )
+            .await
+            .unwrap()
+            .0;
+            let game = get_game_handler(State(state.clone()), Path(created.game_id))
+                .await
+                .unwrap()
+                .0;
+            maps.push(game.state.map.cells);
+        }
+
+        assert_eq!(maps[0], maps[1]);
+        assert_eq!(
+            maps[0],
+            generate_map_seeded(11, 11, DEFAULT_NUM_PLAYERS, 1234).cells
+        );
+    }
+
+    #[tokio::test]
+    async fn create_game_applies_map_density() {
+        let state = app_state();
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map_density: Some(MapDensity {
+                    empty_pct: 100,
+                    wall1_pct: 0,
+                    wall2_pct: 0,
+                    indestructible_pct: 0,
+                }),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        let game = stored_game(&state, &created.game_id).await;
+        assert!(game.state.map.cells.iter().flatten().all(|cell| *cell == 0));
+        assert!(state.default_map.read().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn create_game_rejects_map_density_not_summing_to_100() {
+        let state = app_state();
+        let err = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map_density: Some(MapDensity {
+                    empty_pct: 50,
+                    wall1_pct: 0,
+                    wall2_pct: 0,
+                    indestructible_pct: 0,
+                }),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(err.status, StatusCode::BAD_REQUEST);
+        assert!(state.store.list().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn start_game_is_idempotent_for_running_game() {
+        let state = app_state();
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                turn_timeout_seconds: None,
+                map: None,
+                bot_players: None,
+                num_players: Some(4),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        let first = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap()
+            .0;
+        assert!(first.started);
+        assert_eq!(first.status, GameStatus::Running);
+        assert!(first.started_at.is_some());
+
+        let second = start_game_handler(State(state), Path(created.game_id))
+            .await
+            .unwrap()
+            .0;
+        assert!(!second.started);
+        assert_eq!(second.reason.as_deref(), Some("ALREADY_RUNNING"));
+    }
+
+    #[tokio::test]
+    async fn start_game_publishes_game_started_event_to_output_topic() {
+        let publisher = Arc::new(RecordingStepEventPublisher::default());
+        let state = AppState {
+            store: Arc::new(InMemoryGameStore::default()),
+            default_map: Arc::default(),
+            mutation_lock: Arc::default(),
+            topic_provisioner: Arc::new(NoopTopicProvisioner),
+            step_event_publisher: publisher.clone(),
+            bot_assigner: Arc::new(NoopBotAssigner),
+            metrics: Arc::default(),
+            snapshot_path: None,
+            snapshot_writes: Arc::default(),
+            idempotency: Arc::default(),
+            speak_filter: Arc::default(),
+            finished_topic_retention: Duration::ZERO,
+            debug_endpoints: false,
+        };
+
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                turn_timeout_seconds: None,
+                map: Some(custom_map(5, 5)),
+                bot_players: None,
+                num_players: Some(4),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        let started = start_game_handler(State(state), Path(created.game_id.clone()))
+            .await
+            .unwrap()
+            .0;
+        assert!(started.started);
+
+        let published = publisher.published.lock().unwrap();
+        assert_eq!(published.len(), 1);
+        assert_eq!(
+            published[0].0,
+            format!("test.output.{}.v1", created.game_id)
+        );
+        assert_eq!(published[0].1.event_type, StepEventType::GameStarted);
+        assert_eq!(published[0].1.turn_no, 1);
+    }
+
+    async fn started_game(state: &AppState, num_players: u8) -> CreateGameResponse {
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map: Some(custom_map(5, 5)),
+                num_players: Some(num_players),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        let _ = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap();
+        created
+    }
+
+    #[tokio::test]
+    async fn forfeiting_current_player_advances_turn() {
+        let publisher = Arc::new(RecordingStepEventPublisher::default());
+        let state = AppState {
+            step_event_publisher: publisher.clone(),
+            ..app_state()
+        };
+        let created = started_game(&state, 3).await;
+        let player_a = pid(&created, PlayerName::A);
+        let player_b = pid(&created, PlayerName::B);
+
+        let response = forfeit_player_handler(
+            State(state.clone()),
+            Path((created.game_id.clone(), player_a.clone())),
+            None,
+        )
+        .await
+        .unwrap()
+        .0;
+
+        assert!(response.forfeited);
+        assert_eq!(response.turn_no, 2);
+        assert_eq!(response.current_player_id, player_b);
+
+        let game = stored_game(&state, &created.game_id).await;
+        assert!(!game.state.players[0].alive);
+        assert_eq!(game.current_player_id, player_b);
+
+        let published = publisher.published.lock().unwrap();
+        let last = &published.last().unwrap().1;
+        assert_eq!(last.event_type, StepEventType::StepApplied);
+        assert_eq!(last.turn_no, 2);
+        assert!(!last.state_after.players[0].alive);
+    }
+
+    #[tokio::test]
+    async fn forfeiting_other_player_keeps_turn() {
+        let state = app_state();
+        let created = started_game(&state, 3).await;
+        let player_a = pid(&created, PlayerName::A);
+        let player_c = pid(&created, PlayerName::C);
+
+        let response = forfeit_player_handler(
+            State(state.clone()),
+            Path((created.game_id.clone(), player_c.clone())),
+            None,
+        )
+        .await
+        .unwrap()
+        .0;
+
+        assert!(response.forfeited);
+        assert_eq!(response.turn_no, 1);
+        assert_eq!(response.current_player_id, player_a);
+
+        let game = stored_game(&state, &created.game_id).await;
+        let c = game
+            .state
+            .players
+            .iter()
+            .find(|p| p.player_id == player_c)
+            .unwrap();
+        assert!(!c.alive);
+        assert_eq!(game.turn_no, 1);
+    }
+
+    #[tokio::test]
+    async fn forfeit_rejects_unknown_player_and_idle_game() {
+        let state = app_state();
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map: Some(custom_map(5, 5)),
+                num_players: Some(2),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        let player_a = pid(&created, PlayerName::A);
+
+        let err = forfeit_player_handler(
+            State(state.clone()),
+            Path((created.game_id.clone(), player_a)),
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.status, StatusCode::CONFLICT);
+
+        let _ = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap();
+        let err = forfeit_player_handler(
+            State(state),
+            Path((created.game_id, "nobody".to_string())),
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn forfeit_with_replace_with_bot_keeps_player_alive() {
+        let state = app_state();
+        let created = started_game(&state, 2).await;
+        let player_a = pid(&created, PlayerName::A);
+
+        let response = forfeit_player_handler(
+            State(state.clone()),
+            Path((created.game_id.clone(), player_a)),
+            Some(Json(ForfeitPlayerRequest {
+                replace_with_bot: Some(true),
+            })),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        assert!(!response.forfeited);
+        assert!(response.rebound_to_bot);
+        let game = stored_game(&state, &created.game_id).await;
+        assert!(game.state.players.iter().all(|p| p.alive));
+    }
+
+    #[tokio::test]
+    async fn damaged_wall_regenerates_after_configured_rounds() {
+        let state = app_state();
+        let mut map = custom_map(5, 5);
+        map.cells[2][2] = 2;
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map: Some(map),
+                num_players: Some(2),
+                wall_regen_rounds: Some(2),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        let _ = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap();
+
+        // A shot in round 1 knocked the wall down to 1.
+        update_game(&state, &created.game_id, |game| {
+            game.state.map.cells[2][2] = 1;
+        })
+        .await;
+
+        let mut wall_by_round = Vec::new();
+        for turn_no in 1..=4 {
+            let current = stored_game(&state, &created.game_id)
+                .await
+                .current_player_id;
+            let _ = apply_command_handler(
+                State(state.clone()),
+                Path(created.game_id.clone()),
+                HeaderMap::new(),
+                Json(SubmitCommandRequest {
+                    command_id: format!("cmd-timeout-{turn_no}"),
+                    player_id: current,
+                    command_type: PlayerCommandType::Timeout,
+                    direction: None,
+                    speak_text: None,
+                    turn_no,
+                    client_sent_at: Utc::now(),
+                }),
+            )
+            .await
+            .unwrap();
+            let game = stored_game(&state, &created.game_id).await;
+            wall_by_round.push((game.round_no, game.state.map.cells[2][2]));
+        }
+
+        assert_eq!(wall_by_round, vec![(1, 1), (2, 1), (2, 1), (3, 2)]);
+    }
+
+    async fn pass_current_turn(state: &AppState, game_id: &str) -> ApplyCommandResponse {
+        let game = stored_game(state, game_id).await;
+        apply_command_handler(
+            State(state.clone()),
+            Path(game_id.to_string()),
+            HeaderMap::new(),
+            Json(SubmitCommandRequest {
+                command_id: format!("cmd-pass-{}", game.turn_no),
+                player_id: game.current_player_id,
+                command_type: PlayerCommandType::Pass,
+                direction: None,
+                speak_text: None,
+                turn_no: game.turn_no,
+                client_sent_at: Utc::now(),
+            }),
+        )
+        .await
+        .unwrap()
+        .0
+    }
+
+    #[tokio::test]
+    async fn arena_rings_close_on_schedule() {
+        let state = app_state();
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map: Some(custom_map(7, 7)),
+                num_players: Some(2),
+                arena_shrink_rounds: Some(2),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        let _ = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap();
+
+        let mut timeline = Vec::new();
+        for _ in 0..8 {
+            let response = pass_current_turn(&state, &created.game_id).await;
+            let game = stored_game(&state, &created.game_id).await;
+            timeline.push((
+                game.round_no,
+                game.state.map.cells[0][0],
+                game.state.map.cells[1][1],
+                response.arena_shrink.map(|shrink| shrink.ring),
+            ));
+        }
+
+        assert_eq!(
+            timeline,
+            vec![
+                (1, 0, 0, None),
+                (2, 0, 0, None),
+                (2, 0, 0, None),
+                (3, -1, 0, Some(0)),
+                (3, -1, 0, None),
+                (4, -1, 0, None),
+                (4, -1, 0, None),
+                (5, -1, -1, Some(1)),
+            ]
+        );
+
+        let game = stored_game(&state, &created.game_id).await;
+        assert_eq!(game.state.map.cells[3][3], 0);
+        assert_eq!(game.arena_rings_closed, 2);
+    }
+
+    #[tokio::test]
+    async fn arena_shrink_damages_and_pushes_caught_players() {
+        let state = app_state();
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map: Some(custom_map(5, 5)),
+                num_players: Some(2),
+                arena_shrink_rounds: Some(1),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        let _ = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap();
+        let player_a = pid(&created, PlayerName::A);
+        let player_b = pid(&created, PlayerName::B);
+
+        let _ = pass_current_turn(&state, &created.game_id).await;
+        let response = pass_current_turn(&state, &created.game_id).await;
+
+        let shrink = response
+            .arena_shrink
+            .expect("round 2 closes the outer ring");
+        assert_eq!(shrink.ring, 0);
+        assert_eq!(shrink.damaged_player_ids, vec![player_a, player_b]);
+        assert!(shrink.eliminated_player_ids.is_empty());
+
+        let game = stored_game(&state, &created.game_id).await;
+        let a = &game.state.players[0];
+        let b = &game.state.players[1];
+        assert_eq!((a.row, a.col, a.hp), (1, 2, DEFAULT_PLAYER_HP - 1));
+        assert_eq!((b.row, b.col, b.hp), (2, 1, DEFAULT_PLAYER_HP - 1));
+        assert_eq!(game.state.map.cells[0][2], -1);
+        assert_eq!(game.state.map.cells[2][0], -1);
+    }
+
+    #[tokio::test]
+    async fn arena_shrink_eliminates_player_on_last_hp() {
+        let state = app_state();
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map: Some(custom_map(5, 5)),
+                num_players: Some(3),
+                arena_shrink_rounds: Some(1),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        let _ = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap();
+        let player_a = pid(&created, PlayerName::A);
+        let player_b = pid(&created, PlayerName::B);
+        update_game(&state, &created.game_id, |game| {
+            game.state.players[0].hp = 1;
+        })
+        .await;
+
+        let _ = pass_current_turn(&state, &created.game_id).await;
+        let _ = pass_current_turn(&state, &created.game_id).await;
+        let response = pass_current_turn(&state, &created.game_id).await;
+
+        let shrink = response
+            .arena_shrink
+            .expect("round 2 closes the outer ring");
+        assert_eq!(shrink.eliminated_player_ids, vec![player_a]);
+        // A would have been next; the turn skips the storm's victim.
+        assert_eq!(response.current_player_id, player_b);
+        let game = stored_game(&state, &created.game_id).await;
+        assert!(!game.state.players[0].alive);
+        assert_eq!(game.state.map.cells[0][2], -1);
+    }
+
+    #[tokio::test]
+    async fn state_snapshot_restores_running_game() {
+        let path = std::env::temp_dir().join(format!("cowboy-state-{}", Uuid::new_v4()));
+        load_state_snapshot(&path).unwrap();
+        let state = AppState {
+            snapshot_path: Some(path.clone()),
+            snapshot_writes: Arc::default(),
+            ..app_state()
+        };
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map: Some(custom_map(5, 5)),
+                num_players: Some(2),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        let _ = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap();
+        let _ = apply_command_handler(
+            State(state.clone()),
+            Path(created.game_id.clone()),
+            HeaderMap::new(),
+            Json(SubmitCommandRequest {
+                command_id: "cmd-move".to_string(),
+                player_id: pid(&created, PlayerName::A),
+                command_type: PlayerCommandType::Shield,
+                direction: Some(Direction::Left),
+                speak_text: None,
+                turn_no: 1,
+                client_sent_at: Utc::now(),
+            }),
+        )
+        .await
+        .unwrap();
+
+        let restored = app_state();
+        for mut game in load_state_snapshot(&path).unwrap().into_values() {
+            restored.store.put(&mut game).await.unwrap();
+        }
+        let files: Vec<_> = std::fs::read_dir(&path)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        assert_eq!(files, vec![format!("{}.json", created.game_id)]);
+        std::fs::remove_dir_all(&path).unwrap();
+
+        let original = serde_json::to_value(stored_game(&state, &created.game_id).await).unwrap();
+        let mut recovered =
+            serde_json::to_value(stored_game(&restored, &created.game_id).await).unwrap();
+        // Restoring is itself a store write, so only the version moved on.
+        assert_eq!(
+            recovered["version"],
+            original["version"].as_u64().unwrap() + 1
+        );
+        recovered["version"] = original["version"].clone();
+        assert_eq!(original, recovered);
+
+        let game = get_game_handler(State(restored), Path(created.game_id))
+            .await
+            .unwrap()
+            .0;
+        assert_eq!(game.status, GameStatus::Running);
+        assert_eq!(game.turn_no, 2);
+    }
+
+    #[test]
+    fn missing_state_snapshot_starts_empty() {
+        let path = std::env::temp_dir().join(format!("cowboy-missing-{}", Uuid::new_v4()));
+        assert!(load_state_snapshot(&path).unwrap().is_empty());
+        assert!(path.is_dir());
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn repeated_idempotency_key_returns_original_game() {
+        let recorder = Arc::new(RecordingTopicProvisioner::default());
+        let state = AppState {
+            topic_provisioner: recorder.clone(),
+            ..app_state()
+        };
+        let request = CreateGameRequest {
+            map: Some(custom_map(5, 5)),
+            num_players: Some(2),
+            idempotency_key: Some("retry-1".to_string()),
+            ..Default::default()
+        };
+
+        let first = create_game_handler(State(state.clone()), Json(request.clone()))
+            .await
+            .unwrap()
+            .0;
+        let second = create_game_handler(State(state.clone()), Json(request))
+            .await
+            .unwrap()
+            .0;
+
+        assert_eq!(
+            serde_json::to_value(&first).unwrap(),
+            serde_json::to_value(&second).unwrap()
+        );
+        assert_eq!(recorder.game_ids.lock().unwrap().len(), 1);
+        assert_eq!(state.store.list().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn idempotency_header_is_used_when_body_has_no_key() {
+        let state = app_state();
+        let mut headers = HeaderMap::new();
+        headers.insert(IDEMPOTENCY_KEY_HEADER, "header-key".parse().unwrap());
+        let request = CreateGameRequest {
+            map: Some(custom_map(5, 5)),
+            num_players: Some(2),
+            ..Default::default()
+        };
+
+        let first = create_game_route(State(state.clone()), headers.clone(), Json(request.clone()))
+            .await
+            .unwrap()
+            .0;
+        let second = create_game_route(State(state.clone()), headers, Json(request))
+            .await
+            .unwrap()
+            .0;
+
+        assert_eq!(first.game_id, second.game_id);
+        assert_eq!(state.store.list().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn expired_idempotency_key_creates_new_game() {
+        let state = AppState {
+            idempotency: Arc::new(IdempotencyCache::new(Duration::ZERO)),
+            ..app_state()
+        };
+        let request = CreateGameRequest {
+            map: Some(custom_map(5, 5)),
+            num_players: Some(2),
+            idempotency_key: Some("short-lived".to_string()),
+            ..Default::default()
+        };
+
+        let first = create_game_handler(State(state.clone()), Json(request.clone()))
+            .await
+            .unwrap()
+            .0;
+        let second = create_game_handler(State(state.clone()), Json(request))
+            .await
+            .unwrap()
+            .0;
+
+        assert_ne!(first.game_id, second.game_id);
+    }
+
+    #[tokio::test]
+    async fn in_memory_store_round_trips_games() {
+        let state = app_state();
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map: Some(custom_map(5, 5)),
+                num_players: Some(2),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        let store = InMemoryGameStore::default();
+        assert!(store.get(&created.game_id).await.unwrap().is_none());
+
+        let mut game = stored_game(&state, &created.game_id).await;
+        store.put(&mut game).await.unwrap();
+        game.turn_no = 7;
+        store.put(&mut game).await.unwrap();
+
+        let listed = store.list().await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].turn_no, 7);
+        assert_eq!(
+            store.get(&created.game_id).await.unwrap().unwrap().turn_no,
+            7
+        );
+
+        store.remove(&created.game_id).await.unwrap();
+        assert!(store.get(&created.game_id).await.unwrap().is_none());
+        assert!(store.list().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn store_refuses_a_write_from_a_stale_copy() {
+        let state = app_state();
+        let created = started_game(&state, 2).await;
+        let mut first = stored_game(&state, &created.game_id).await;
+        let mut stale = first.clone();
+
+        first.turn_no += 1;
+        state.store.put(&mut first).await.unwrap();
+        stale.round_no += 1;
+        let error = state.store.put(&mut stale).await.unwrap_err();
+
+        assert!(error.downcast_ref::<StoreConflict>().is_some());
+        assert_eq!(store_error(error).status, StatusCode::CONFLICT);
+        let stored = stored_game(&state, &created.game_id).await;
+        assert_eq!(stored.turn_no, first.turn_no);
+        assert_eq!(stored.version, first.version);
+    }
+
+    #[test]
+    fn dynamo_condition_failure_is_a_store_conflict() {
+        let failed = SdkError::<PutItemError, ()>::service_error(
+            PutItemError::ConditionalCheckFailedException(
+                aws_sdk_dynamodb::types::error::ConditionalCheckFailedException::builder().build(),
+            ),
+            (),
+        );
+        let conflict = put_item_error("g-1", failed);
+        assert_eq!(store_error(conflict).status, StatusCode::CONFLICT);
+
+        let other =
+            SdkError::<PutItemError, ()>::service_error(PutItemError::unhandled("throttled"), ());
+        assert_eq!(
+            store_error(put_item_error("g-1", other)).status,
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+
+    #[tokio::test]
+    async fn handlers_report_not_found_through_store() {
+        let state = app_state();
+        let err = start_game_handler(State(state), Path("missing".to_string()))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn metrics_report_games_and_command_counters() {
+        let state = app_state();
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map: Some(custom_map(5, 5)),
+                num_players: Some(2),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        let _ = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap();
+
+        let _ = apply_command_handler(
+            State(state.clone()),
+            Path(created.game_id.clone()),
+            HeaderMap::new(),
+            Json(SubmitCommandRequest {
+                command_id: "cmd-timeout".to_string(),
+                player_id: pid(&created, PlayerName::A),
+                command_type: PlayerCommandType::Timeout,
+                direction: None,
+                speak_text: None,
+                turn_no: 1,
+                client_sent_at: Utc::now(),
+            }),
+        )
+        .await
+        .unwrap();
+        let _ = apply_command_handler(
+            State(state.clone()),
+            Path(created.game_id.clone()),
+            HeaderMap::new(),
+            Json(SubmitCommandRequest {
+                command_id: "cmd-stale".to_string(),
+                player_id: pid(&created, PlayerName::B),
+                command_type: PlayerCommandType::Shield,
+                direction: Some(Direction::Up),
+                speak_text: None,
+                turn_no: 1,
+                client_sent_at: Utc::now(),
+            }),
+        )
+        .await
+        .unwrap();
+
+        update_game(&state, &created.game_id, |game| {
+            game.state.players[1].alive = false;
+        })
+        .await;
+        let finished = finish_game_handler(
+            State(state.clone()),
+            Path(created.game_id.clone()),
+            Json(FinishGameRequest {
+                expected_turn_no: None,
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert!(finished.finished);
+
+        let text = render_metrics(&state.store.list().await.unwrap(), &state.metrics);
+        for name in [
+            "# TYPE cowboy_games gauge",
+            "# TYPE cowboy_commands_applied_total counter",
+            "# TYPE cowboy_commands_rejected_total counter",
+            "# TYPE cowboy_shoots_total counter",
+            "# TYPE cowboy_timeouts_total counter",
+            "# TYPE cowboy_game_turn_no gauge",
+        ] {
+            assert!(text.contains(name), "missing {name} in:\n{text}");
+        }
+        assert!(text.contains("cowboy_games{status=\"finished\"} 1\n"));
+        assert!(text.contains("cowboy_games{status=\"running\"} 0\n"));
+        assert!(text.contains("cowboy_games{status=\"paused\"} 0\n"));
+        assert!(text.contains("cowboy_commands_applied_total 1\n"));
+        assert!(text.contains("cowboy_commands_rejected_total 1\n"));
+        assert!(text.contains("cowboy_timeouts_total 1\n"));
+        assert!(text.contains("cowboy_shoots_total 0\n"));
+        assert!(text.contains(&format!(
+            "cowboy_game_turn_no{{game_id=\"{}\"}} 2\n",
+            created.game_id
+        )));
+    }
+
+    #[tokio::test]
+    async fn get_game_returns_not_found_for_unknown_id() {
+        let state = app_state();
+        let err = get_game_handler(State(state), Path("missing-game".to_string()))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn get_default_map_returns_stable_map() {
+        let state = app_state();
+        let first = get_default_map_handler(State(state.clone()))
+            .await
+            .unwrap()
+            .0;
+        let second = get_default_map_handler(State(state)).await.unwrap().0;
+
+        assert_eq!(first.rows, second.rows);
+        assert_eq!(first.cols, second.cols);
+        assert_eq!(first.cells, second.cells);
+    }
+
+    #[tokio::test]
+    async fn shoot_toward_own_shield_is_rejected_without_turn_advance() {
+        let state = app_state();
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                turn_timeout_seconds: None,
+                map: Some(custom_map(5, 5)),
+                bot_players: None,
+                num_players: Some(4),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        let _ = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap();
+
+        let player_a = pid(&created, PlayerName::A);
+        let response = apply_command_handler(
+            State(state),
+            Path(created.game_id),
+            HeaderMap::new(),
+            Json(SubmitCommandRequest {
+                command_id: "cmd-own-shield".to_string(),
+                player_id: player_a.clone(),
+                command_type: PlayerCommandType::Shoot,
+                direction: Some(Direction::Up),
+                speak_text: None,
+                turn_no: 1,
+                client_sent_at: Utc::now(),
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        assert!(response.accepted);
+        assert!(!response.applied);
+        assert_eq!(
+            response.reason,
+            Some(RejectReason::CannotShootThroughOwnShield)
+        );
+        assert_eq!(response.turn_no, 1);
+        assert_eq!(response.current_player_id, player_a);
+    }
+
+    #[tokio::test]
+    async fn shoot_hits_player_and_advances_turn() {
+        let state = app_state();
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                turn_timeout_seconds: None,
+                map: Some(custom_map(5, 5)),
+                bot_players: None,
+                num_players: Some(4),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        let _ = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap();
+
+        let player_a = pid(&created, PlayerName::A);
+        let player_b = pid(&created, PlayerName::B);
+        let player_c = pid(&created, PlayerName::C);
+        let response = apply_command_handler(
+            State(state.clone()),
+            Path(created.game_id.clone()),
+            HeaderMap::new(),
+            Json(SubmitCommandRequest {
+                command_id: "cmd-shoot-down".to_string(),
+                player_id: player_a,
+                command_type: PlayerCommandType::Shoot,
+                direction: Some(Direction::Down),
+                speak_text: None,
+                turn_no: 1,
+                client_sent_at: Utc::now(),
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        assert!(response.accepted);
+        assert!(response.applied);
+        assert_eq!(response.turn_no, 2);
+        assert_eq!(response.round_no, 1);
+        assert_eq!(response.current_player_id, player_b);
+
+        let game = get_game_handler(State(state), Path(created.game_id))
+            .await
+            .unwrap()
+            .0;
+        let down = game
+            .state
+            .players
+            .iter()
+            .find(|p| p.player_id == player_c)
+            .expect("down player must exist");
+        assert_eq!(down.hp, DEFAULT_PLAYER_HP - 1);
+        assert!(down.alive);
+    }
+
+    #[tokio::test]
+    async fn shoot_downing_player_records_shooter_as_killer() {
+        let state = app_state();
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map: Some(custom_map(5, 5)),
+                num_players: Some(4),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        let _ = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap();
+
+        let player_a = pid(&created, PlayerName::A);
+        let player_c = pid(&created, PlayerName::C);
+        update_game(&state, &created.game_id, |game| {
+            let c = game
+                .state
+                .players
+                .iter_mut()
+                .find(|p| p.player_id == player_c)
+                .unwrap();
+            // Park C in the laser's sweep row, one hit from elimination.
+            c.row = 1;
+            c.col = 0;
+            c.hp = 1;
+        })
+        .await;
+
+        let response = apply_command_handler(
+            State(state),
+            Path(created.game_id),
+            HeaderMap::new(),
+            Json(SubmitCommandRequest {
+                command_id: "cmd-fatal-shot".to_string(),
+                player_id: player_a.clone(),
+                command_type: PlayerCommandType::Shoot,
+                direction: Some(Direction::Down),
+                speak_text: None,
+                turn_no: 1,
+                client_sent_at: Utc::now(),
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        assert!(response.applied);
+        assert_eq!(
+            response.eliminations,
+            vec![Elimination {
+                victim_player_id: player_c,
+                killer_player_id: player_a,
+                turn_no: 1,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn speak_advances_turn_without_state_damage() {
+        let state = app_state();
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                turn_timeout_seconds: None,
+                map: Some(custom_map(5, 5)),
+                bot_players: None,
+                num_players: Some(4),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        let _ = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap();
+
+        let player_a = pid(&created, PlayerName::A);
+        let player_b = pid(&created, PlayerName::B);
+        let player_c = pid(&created, PlayerName::C);
+        let response = apply_command_handler(
+            State(state.clone()),
+            Path(created.game_id.clone()),
+            HeaderMap::new(),
+            Json(SubmitCommandRequest {
+                command_id: "cmd-speak".to_string(),
+                player_id: player_a,
+                command_type: PlayerCommandType::Speak,
+                direction: None,
+                speak_text: Some("hello".to_string()),
+                turn_no: 1,
+                client_sent_at: Utc::now(),
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        assert!(response.accepted);
+        assert!(response.applied);
+        assert_eq!(response.turn_no, 2);
+        assert_eq!(response.current_player_id, player_b);
+
+        let game = get_game_handler(State(state), Path(created.game_id))
+            .await
+            .unwrap()
+            .0;
+        let down = game
+            .state
+            .players
+            .iter()
+            .find(|p| p.player_id == player_c)
+            .expect("down player must exist");
+        assert_eq!(down.hp, DEFAULT_PLAYER_HP);
+    }
+
+    #[tokio::test]
+    async fn speak_without_text_is_rejected_without_turn_advance() {
+        let state = app_state();
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                turn_timeout_seconds: None,
+                map: Some(custom_map(5, 5)),
+                bot_players: None,
+                num_players: Some(4),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        let _ = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap();
+
+        let player_a = pid(&created, PlayerName::A);
+        let response = apply_command_handler(
+            State(state),
+            Path(created.game_id),
+            HeaderMap::new(),
+            Json(SubmitCommandRequest {
+                command_id: "cmd-speak-empty".to_string(),
+                player_id: player_a.clone(),
+                command_type: PlayerCommandType::Speak,
+                direction: None,
+                speak_text: Some("   ".to_string()),
+                turn_no: 1,
+                client_sent_at: Utc::now(),
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        assert!(response.accepted);
+        assert!(!response.applied);
+        assert_eq!(response.reason, Some(RejectReason::MissingSpeakText));
+        assert_eq!(response.turn_no, 1);
+        assert_eq!(response.current_player_id, player_a);
+    }
+
+    fn command(
+        game: &GameInstance,
+        command_type: PlayerCommandType,
+        direction: Option<Direction>,
+        speak_text: Option<&str>,
+    ) -> SubmitCommandRequest {
+        SubmitCommandRequest {
+            command_id: format!("cmd-{command_type:?}-{direction:?}"),
+            player_id: game.current_player_id.clone(),
+            command_type,
+            direction,
+            speak_text: speak_text.map(str::to_string),
+            turn_no: game.turn_no,
+            client_sent_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn validate_matches_apply_for_every_command() {
+        let state = app_state();
+        let created = started_game(&state, 2).await;
+        let directions = [
+            Some(Direction::Up),
+            Some(Direction::Down),
+            Some(Direction::Left),
+            Some(Direction::Right),
+            None,
+        ];
+        let mut commands = Vec::new();
+        for direction in directions {
+            commands.push((PlayerCommandType::Move, direction, None));
+            commands.push((PlayerCommandType::Shoot, direction, None));
+            commands.push((PlayerCommandType::Shield, direction, None));
+        }
+        commands.push((PlayerCommandType::Speak, None, Some("howdy")));
+        commands.push((PlayerCommandType::Speak, None, Some("   ")));
+        commands.push((PlayerCommandType::Speak, None, None));
+        commands.push((PlayerCommandType::Pass, None, None));
+
+        let mut seen_reasons = Vec::new();
+        for (row, col) in [(0, 0), (2, 2), (4, 4), (1, 3), (3, 0)] {
+            update_game(&state, &created.game_id, |game| {
+                let (me, other) = if game.state.players[0].player_id == game.current_player_id {
+                    (0, 1)
+                } else {
+                    (1, 0)
+                };
+                game.state.map.cells[2][3] = 2;
+                game.state.players[me].row = row;
+                game.state.players[me].col = col;
+                game.state.players[me].shield = Direction::Up;
+                game.state.players[other].row = 1;
+                game.state.players[other].col = 2;
+            })
+            .await;
+            let baseline = stored_game(&state, &created.game_id).await;
+
+            for (command_type, direction, speak_text) in &commands {
+                let mut reset = baseline.clone();
+                reset.version = stored_game(&state, &created.game_id).await.version;
+                state.store.put(&mut reset).await.unwrap();
+                let request = command(&baseline, *command_type, *direction, *speak_text);
+
+                let preview = validate_command_handler(
+                    State(state.clone()),
+                    Path(created.game_id.clone()),
+                    Json(request.clone()),
+                )
+                .await
+                .unwrap()
+                .0;
+                assert_eq!(
+                    stored_game(&state, &created.game_id).await.turn_no,
+                    baseline.turn_no,
+                    "validate must not advance the turn"
+                );
+
+                let applied = apply_command_handler(
+                    State(state.clone()),
+                    Path(created.game_id.clone()),
+                    HeaderMap::new(),
+                    Json(request),
+                )
+                .await
+                .unwrap()
+                .0;
+                assert_eq!(
+                    (preview.legal, preview.reason),
+                    (applied.applied, applied.reason),
+                    "{command_type:?} {direction:?} from ({row}, {col})"
+                );
+                seen_reasons.push(preview.reason);
+            }
+        }
+
+        for reason in [
+            RejectReason::MoveOutOfBounds,
+            RejectReason::MoveBlockedByBlock,
+            RejectReason::MoveBlockedByPlayer,
+            RejectReason::CannotShootThroughOwnShield,
+            RejectReason::ShootBlockedByEdge,
+            RejectReason::ShootBlockedByBlock,
+            RejectReason::ShootBlockedByPlayer,
+            RejectReason::MissingDirection,
+            RejectReason::MissingSpeakText,
+        ] {
+            assert!(
+                seen_reasons.contains(&Some(reason)),
+                "{reason:?} was never exercised"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn shot_into_open_space_records_both_laser_arms() {
+        let state = app_state();
+        let created = started_game(&state, 2).await;
+        update_game(&state, &created.game_id, |game| {
+            game.state.players[0].row = 2;
+            game.state.players[0].col = 0;
+            game.state.players[1].row = 4;
+            game.state.players[1].col = 4;
+        })
+        .await;
+        let game = stored_game(&state, &created.game_id).await;
+
+        let response = apply_command_handler(
+            State(state.clone()),
+            Path(created.game_id.clone()),
+            HeaderMap::new(),
+            Json(command(
+                &game,
+                PlayerCommandType::Shoot,
+                Some(Direction::Right),
+                None,
+            )),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        assert!(response.applied);
+        let cell = |row, col| LaserCell { row, col };
+        assert_eq!(
+            response.laser_path,
+            Some(LaserPath {
+                entry: cell(2, 1),
+                arms: vec![
+                    LaserArm {
+                        direction: Direction::Up,
+                        cells: vec![cell(1, 1), cell(0, 1)],
+                        stopped_by: LaserStop::Edge,
+                    },
+                    LaserArm {
+                        direction: Direction::Down,
+                        cells: vec![cell(3, 1), cell(4, 1)],
+                        stopped_by: LaserStop::Edge,
+                    },
+                ],
+            })
+        );
+    }
+
+    async fn apply(
+        state: &AppState,
+        game_id: &str,
+        command_type: PlayerCommandType,
+        direction: Option<Direction>,
+    ) -> ApplyCommandResponse {
+        let game = stored_game(state, game_id).await;
+        apply_command_handler(
+            State(state.clone()),
+            Path(game_id.to_string()),
+            HeaderMap::new(),
+            Json(command(&game, command_type, direction, None)),
+        )
+        .await
+        .unwrap()
+        .0
+    }
+
+    /// Puts A at (2, 0) facing up and B at `target`, shielded away from the
+    /// beam A fires to the right.
+    async fn shooting_range(state: &AppState, target: (usize, usize)) -> CreateGameResponse {
+        let created = started_game(state, 2).await;
+        update_game(state, &created.game_id, |game| {
+            game.state.players[0].row = 2;
+            game.state.players[0].col = 0;
+            game.state.players[1].row = target.0;
+            game.state.players[1].col = target.1;
+            game.state.players[1].shield = Direction::Left;
+        })
+        .await;
+        created
+    }
+
+    #[tokio::test]
+    async fn charged_shot_deals_two_damage() {
+        let state = app_state();
+        let created = shooting_range(&state, (1, 1)).await;
+
+        let charged = apply(&state, &created.game_id, PlayerCommandType::Charge, None).await;
+        assert!(charged.applied);
+        assert_eq!(
+            stored_game(&state, &created.game_id).await.state.players[0].charge_level,
+            1
+        );
+        pass_current_turn(&state, &created.game_id).await;
+        let shot = apply(
+            &state,
+            &created.game_id,
+            PlayerCommandType::Shoot,
+            Some(Direction::Right),
+        )
+        .await;
+
+        assert!(shot.applied);
+        let game = stored_game(&state, &created.game_id).await;
+        assert_eq!(game.state.players[1].hp, DEFAULT_PLAYER_HP - 2);
+        assert_eq!(game.state.players[0].charge_level, 0);
+    }
+
+    #[tokio::test]
+    async fn charged_shot_pierces_one_wall() {
+        let state = app_state();
+        let created = shooting_range(&state, (0, 1)).await;
+        update_game(&state, &created.game_id, |game| {
+            game.state.map.cells[1][1] = 3;
+        })
+        .await;
+
+        apply(&state, &created.game_id, PlayerCommandType::Charge, None).await;
+        pass_current_turn(&state, &created.game_id).await;
+        let shot = apply(
+            &state,
+            &created.game_id,
+            PlayerCommandType::Shoot,
+            Some(Direction::Right),
+        )
+        .await;
+
+        let game = stored_game(&state, &created.game_id).await;
+        assert_eq!(game.state.map.cells[1][1], 2);
+        assert_eq!(game.state.players[1].hp, DEFAULT_PLAYER_HP - 2);
+        assert_eq!(
+            shot.laser_path.unwrap().arms[0].stopped_by,
+            LaserStop::Player
+        );
+    }
+
+    #[tokio::test]
+    async fn last_words_survive_elimination() {
+        let state = app_state();
+        let created = shooting_range(&state, (1, 1)).await;
+        pass_current_turn(&state, &created.game_id).await;
+
+        let game = stored_game(&state, &created.game_id).await;
+        let spoken = apply_command_handler(
+            State(state.clone()),
+            Path(created.game_id.clone()),
+            HeaderMap::new(),
+            Json(command(
+                &game,
+                PlayerCommandType::Speak,
+                None,
+                Some("tell my horse"),
+            )),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert!(spoken.applied);
+
+        update_game(&state, &created.game_id, |game| {
+            game.state.players[1].hp = 1;
+        })
+        .await;
+        let shot = apply(
+            &state,
+            &created.game_id,
+            PlayerCommandType::Shoot,
+            Some(Direction::Right),
+        )
+        .await;
+        assert!(shot.applied);
+
+        let public = get_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap()
+            .0;
+        let victim = &public.state.players[1];
+        assert!(!victim.alive);
+        assert_eq!(victim.last_words.as_deref(), Some("tell my horse"));
+        assert_eq!(public.state.players[0].last_words, None);
+    }
+
+    #[tokio::test]
+    async fn landed_shot_is_credited_to_shooter_and_victim() {
+        let state = app_state();
+        let created = shooting_range(&state, (1, 1)).await;
+
+        let shot = apply(
+            &state,
+            &created.game_id,
+            PlayerCommandType::Shoot,
+            Some(Direction::Right),
+        )
+        .await;
+        assert!(shot.applied);
+
+        let game = stored_game(&state, &created.game_id).await;
+        let stats_for = |idx: usize| {
+            game.state
+                .stats
+                .iter()
+                .find(|entry| entry.player_id == game.state.players[idx].player_id)
+                .cloned()
+                .unwrap()
+        };
+        let shooter = stats_for(0);
+        assert_eq!(shooter.shots_fired, 1);
+        assert_eq!(shooter.hits_landed, 1);
+        assert_eq!(shooter.damage_dealt, 1);
+        assert_eq!(shooter.damage_taken, 0);
+        let victim = stats_for(1);
+        assert_eq!(victim.damage_taken, 1);
+        assert_eq!(victim.hits_landed, 0);
+    }
+
+    #[tokio::test]
+    async fn applied_commands_are_counted_per_player() {
+        let state = app_state();
+        let created = shooting_range(&state, (4, 4)).await;
+
+        apply(
+            &state,
+            &created.game_id,
+            PlayerCommandType::Shield,
+            Some(Direction::Up),
+        )
+        .await;
+        let moved = apply(
+            &state,
+            &created.game_id,
+            PlayerCommandType::Move,
+            Some(Direction::Up),
+        )
+        .await;
+        assert!(moved.applied);
+
+        let game = stored_game(&state, &created.game_id).await;
+        let shields: Vec<u32> = game.state.stats.iter().map(|entry| entry.shields).collect();
+        let moves: Vec<u32> = game.state.stats.iter().map(|entry| entry.moves).collect();
+        assert_eq!(shields, vec![1, 0]);
+        assert_eq!(moves, vec![0, 1]);
+    }
+
+    /// `shooting_range` with B's shield facing A's beam under `shield_mode`.
+    async fn shielded_range(state: &AppState, shield_mode: ShieldMode) -> CreateGameResponse {
+        let created = shooting_range(state, (1, 1)).await;
+        update_game(state, &created.game_id, |game| {
+            game.shield_mode = shield_mode;
+            game.state.players[1].shield = Direction::Down;
+            game.state.players[1].shield_charges = shield_mode.raised_charges().unwrap_or(0);
+        })
+        .await;
+        created
+    }
+
+    async fn shoot_right_and_pass(state: &AppState, game_id: &str) -> i32 {
+        let shot = apply(
+            state,
+            game_id,
+            PlayerCommandType::Shoot,
+            Some(Direction::Right),
+        )
+        .await;
+        assert!(shot.applied);
+        pass_current_turn(state, game_id).await;
+        stored_game(state, game_id).await.state.players[1].hp
+    }
+
+    #[tokio::test]
+    async fn one_shot_shield_absorbs_one_hit_then_drops() {
+        let state = app_state();
+        let created = shielded_range(&state, ShieldMode::OneShot).await;
+
+        assert_eq!(
+            shoot_right_and_pass(&state, &created.game_id).await,
+            DEFAULT_PLAYER_HP
+        );
+        let game = stored_game(&state, &created.game_id).await;
+        assert_eq!(game.state.players[1].shield_charges, 0);
+        assert_eq!(game.state.players[1].shield, Direction::Down);
+
+        assert_eq!(
+            shoot_right_and_pass(&state, &created.game_id).await,
+            DEFAULT_PLAYER_HP - 1
+        );
+    }
+
+    #[tokio::test]
+    async fn degrading_shield_absorbs_until_spent_and_reraising_restores_it() {
+        let state = app_state();
+        let created = shielded_range(&state, ShieldMode::Degrading).await;
+
+        for _ in 0..DEGRADING_SHIELD_CHARGES {
+            assert_eq!(
+                shoot_right_and_pass(&state, &created.game_id).await,
+                DEFAULT_PLAYER_HP
+            );
+        }
+        assert_eq!(
+            shoot_right_and_pass(&state, &created.game_id).await,
+            DEFAULT_PLAYER_HP - 1
+        );
+
+        pass_current_turn(&state, &created.game_id).await;
+        let raised = apply(
+            &state,
+            &created.game_id,
+            PlayerCommandType::Shield,
+            Some(Direction::Down),
+        )
+        .await;
+        assert!(raised.applied);
+        assert_eq!(
+            stored_game(&state, &created.game_id).await.state.players[1].shield_charges,
+            DEGRADING_SHIELD_CHARGES
+        );
+    }
+
+    #[tokio::test]
+    async fn permanent_shield_never_drops() {
+        let state = app_state();
+        let created = shielded_range(&state, ShieldMode::Permanent).await;
+        for _ in 0..3 {
+            assert_eq!(
+                shoot_right_and_pass(&state, &created.game_id).await,
+                DEFAULT_PLAYER_HP
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn charge_is_lost_on_move() {
+        let state = app_state();
+        let created = shooting_range(&state, (4, 4)).await;
+
+        apply(&state, &created.game_id, PlayerCommandType::Charge, None).await;
+        pass_current_turn(&state, &created.game_id).await;
+        let moved = apply(
+            &state,
+            &created.game_id,
+            PlayerCommandType::Move,
+            Some(Direction::Down),
+        )
+        .await;
+
+        assert!(moved.applied);
+        let game = stored_game(&state, &created.game_id).await;
+        assert_eq!(game.state.players[0].charge_level, 0);
+    }
+
+    #[tokio::test]
+    async fn validate_reports_turn_ownership_rejections() {
+        let state = app_state();
+        let created = started_game(&state, 2).await;
+        let game = stored_game(&state, &created.game_id).await;
+        let mut request = command(&game, PlayerCommandType::Pass, None, None);
+        request.player_id = pid(&created, PlayerName::B);
+
+        let preview = validate_command_handler(
+            State(state.clone()),
+            Path(created.game_id.clone()),
+            Json(request),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        assert!(!preview.legal);
+        assert_eq!(preview.reason, Some(RejectReason::InvalidTurnPlayer));
+    }
+
+    async fn apply_as(
+        state: &AppState,
+        game_id: &str,
+        player_id: &str,
+        turn_no: u64,
+    ) -> ApplyCommandResponse {
+        apply_command_handler(
+            State(state.clone()),
+            Path(game_id.to_string()),
+            HeaderMap::new(),
+            Json(SubmitCommandRequest {
+                command_id: format!("cmd-{player_id}-{turn_no}"),
+                player_id: player_id.to_string(),
+                command_type: PlayerCommandType::Pass,
+                direction: None,
+                speak_text: None,
+                turn_no,
+                client_sent_at: Utc::now(),
+            }),
+        )
+        .await
+        .unwrap()
+        .0
+    }
+
+    #[tokio::test]
+    async fn dead_current_player_is_rejected_as_player_dead() {
+        let state = app_state();
+        let created = started_game(&state, 2).await;
+        let player_a = pid(&created, PlayerName::A);
+        update_game(&state, &created.game_id, |game| {
+            game.state.players[0].alive = false;
+        })
+        .await;
+
+        let response = apply_as(&state, &created.game_id, &player_a, 1).await;
+        assert!(!response.applied);
+        assert_eq!(response.reason, Some(RejectReason::PlayerDead));
+    }
+
+    #[tokio::test]
+    async fn live_non_current_player_is_rejected_as_invalid_turn_player() {
+        let state = app_state();
+        let created = started_game(&state, 2).await;
+        let player_b = pid(&created, PlayerName::B);
+
+        let response = apply_as(&state, &created.game_id, &player_b, 1).await;
+        assert!(!response.applied);
+        assert_eq!(response.reason, Some(RejectReason::InvalidTurnPlayer));
+    }
+
+    #[tokio::test]
+    async fn unknown_player_is_rejected_as_not_in_game() {
+        let state = app_state();
+        let created = started_game(&state, 2).await;
+
+        let response = apply_as(&state, &created.game_id, "stranger", 1).await;
+        assert!(!response.applied);
+        assert_eq!(response.reason, Some(RejectReason::PlayerNotInGame));
+        assert_eq!(stored_game(&state, &created.game_id).await.turn_no, 1);
+    }
+
+    #[tokio::test]
+    async fn pass_advances_turn_without_changing_state() {
+        let state = app_state();
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map: Some(custom_map(5, 5)),
+                num_players: Some(2),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        let _ = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap();
+        let before = get_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap()
+            .0;
+
+        let response = apply_command_handler(
+            State(state.clone()),
+            Path(created.game_id.clone()),
+            HeaderMap::new(),
+            Json(SubmitCommandRequest {
+                command_id: "cmd-pass".to_string(),
+                player_id: pid(&created, PlayerName::A),
+                command_type: PlayerCommandType::Pass,
+                direction: None,
+                speak_text: None,
+                turn_no: 1,
+                client_sent_at: Utc::now(),
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        assert!(response.accepted);
+        assert!(response.applied);
+        assert_eq!(response.reason, None);
+        assert_eq!(response.turn_no, 2);
+        assert_eq!(response.current_player_id, pid(&created, PlayerName::B));
+
+        let after = get_game_handler(State(state), Path(created.game_id))
+            .await
+            .unwrap()
+            .0;
+        for (old, new) in before.state.players.iter().zip(&after.state.players) {
+            assert_eq!((old.hp, old.row, old.col), (new.hp, new.row, new.col));
+            assert_eq!(old.shield, new.shield);
+        }
+        assert_eq!(before.state.map.cells, after.state.map.cells);
+    }
+
+    async fn shoot_across_stacked_walls(laser_pierces: Option<bool>) -> GameInstanceResponse {
+        let state = app_state();
+        let mut map = custom_map(5, 7);
+        map.cells[1][1] = 1;
+        map.cells[1][2] = 1;
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map: Some(map),
+                num_players: Some(2),
+                laser_pierces,
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        let _ = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap();
+
+        let player_b = pid(&created, PlayerName::B);
+        update_game(&state, &created.game_id, |game| {
+            let b = game
+                .state
+                .players
+                .iter_mut()
+                .find(|p| p.player_id == player_b)
+                .unwrap();
+            b.row = 1;
+            b.col = 0;
+        })
+        .await;
+
+        let response = apply_command_handler(
+            State(state.clone()),
+            Path(created.game_id.clone()),
+            HeaderMap::new(),
+            Json(SubmitCommandRequest {
+                command_id: "cmd-shoot-walls".to_string(),
+                player_id: pid(&created, PlayerName::A),
+                command_type: PlayerCommandType::Shoot,
+                direction: Some(Direction::Down),
+                speak_text: None,
+                turn_no: 1,
+                client_sent_at: Utc::now(),
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert!(response.applied);
+
+        get_game_handler(State(state), Path(created.game_id))
+            .await
+            .unwrap()
+            .0
+    }
+
+    #[tokio::test]
+    async fn piercing_laser_destroys_stacked_walls_and_hits_player_behind() {
+        let game = shoot_across_stacked_walls(Some(true)).await;
+
+        assert_eq!(game.state.map.cells[1][2], 0);
+        assert_eq!(game.state.map.cells[1][1], 0);
+        let b = game
+            .state
+            .players
+            .iter()
+            .find(|p| p.player_name == PlayerName::B)
+            .unwrap();
+        assert_eq!(b.hp, DEFAULT_PLAYER_HP - 1);
+    }
+
+    #[tokio::test]
+    async fn non_piercing_laser_stops_at_first_wall() {
+        let game = shoot_across_stacked_walls(None).await;
+
+        assert_eq!(game.state.map.cells[1][2], 0);
+        assert_eq!(game.state.map.cells[1][1], 1);
+        let b = game
+            .state
+            .players
+            .iter()
+            .find(|p| p.player_name == PlayerName::B)
+            .unwrap();
+        assert_eq!(b.hp, DEFAULT_PLAYER_HP);
+    }
+
+    #[tokio::test]
+    async fn finish_game_marks_status_when_one_player_left() {
+        let recorder = Arc::new(RecordingTopicProvisioner::default());
+        let step_publisher = Arc::new(RecordingStepEventPublisher::default());
+        let state = AppState {
+            store: Arc::new(InMemoryGameStore::default()),
+            default_map: Arc::default(),
+            mutation_lock: Arc::default(),
+            topic_provisioner: recorder.clone(),
+            step_event_publisher: step_publisher.clone(),
+            bot_assigner: Arc::new(NoopBotAssigner),
+            metrics: Arc::default(),
+            snapshot_path: None,
+            snapshot_writes: Arc::default(),
+            idempotency: Arc::default(),
+            speak_filter: Arc::default(),
+            finished_topic_retention: Duration::ZERO,
+            debug_endpoints: false,
+        };
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                turn_timeout_seconds: None,
+                map: Some(custom_map(5, 5)),
+                bot_players: None,
+                num_players: Some(4),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        let _ = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap();
+        let game_id = created.game_id.clone();
+        let player_a = pid(&created, PlayerName::A);
+
+        update_game(&state, &created.game_id, |game| {
+            for player in &mut game.state.players {
+                if player.player_id != player_a {
+                    player.alive = false;
+                    player.hp = 0;
+                }
+            }
+        })
+        .await;
+
+        let finished = finish_game_handler(
+            State(state),
+            Path(game_id.clone()),
+            Json(FinishGameRequest {
+                expected_turn_no: Some(1),
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        assert!(finished.finished);
+        assert_eq!(finished.status, GameStatus::Finished);
+        assert_eq!(finished.winner_player_id, Some(player_a));
+        assert_eq!(
+            finished.finish_reason,
+            Some(FinishReason::LastPlayerStanding)
+        );
+
+        let deleted_topics = recorder.deleted_topics.lock().unwrap();
+        assert_eq!(deleted_topics.len(), 1);
+        assert_eq!(
+            deleted_topics[0].input_topic,
+            format!("record.commands.{}.v1", game_id)
+        );
+        assert_eq!(
+            deleted_topics[0].output_topic,
+            format!("record.output.{}.v1", game_id)
+        );
+
+        let published = step_publisher.published.lock().unwrap();
+        assert_eq!(published.len(), 2);
+        assert_eq!(published[1].0, format!("record.output.{}.v1", game_id));
+        assert_eq!(published[1].1.event_type, StepEventType::GameFinished);
+        assert_eq!(
+            published[1].1.finish_reason,
+            Some(FinishReason::LastPlayerStanding)
+        );
+    }
+
+    #[tokio::test]
+    async fn apply_batch_plays_a_full_round_in_one_call() {
+        let state = app_state();
+        let created = started_game(&state, 2).await;
+        update_game(&state, &created.game_id, |game| {
+            game.state.players[0].row = 1;
+            game.state.players[0].col = 1;
+            game.state.players[1].row = 3;
+            game.state.players[1].col = 3;
+        })
+        .await;
+        let game = stored_game(&state, &created.game_id).await;
+        let first = game
+            .state
+            .players
+            .iter()
+            .position(|player| player.player_id == game.current_player_id)
+            .unwrap();
+        let second = 1 - first;
+        let directions = [Direction::Right, Direction::Left];
+        let requests: Vec<SubmitCommandRequest> = [first, second]
+            .into_iter()
+            .enumerate()
+            .map(|(offset, player_idx)| SubmitCommandRequest {
+                command_id: format!("batch-{offset}"),
+                player_id: game.state.players[player_idx].player_id.clone(),
+                command_type: PlayerCommandType::Move,
+                direction: Some(directions[player_idx]),
+                speak_text: None,
+                turn_no: game.turn_no + offset as u64,
+                client_sent_at: Utc::now(),
+            })
+            .collect();
+
+        let responses = apply_batch_handler(
+            State(state.clone()),
+            Path(created.game_id.clone()),
+            Query(ApplyBatchQuery::default()),
+            HeaderMap::new(),
+            Json(requests),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        assert_eq!(responses.len(), 2);
+        assert!(responses.iter().all(|response| response.applied));
+        assert_eq!(
+            responses[0].current_player_id,
+            game.state.players[second].player_id
+        );
+        assert_eq!(
+            responses[1].current_player_id,
+            game.state.players[first].player_id
+        );
+        assert_eq!(responses[1].round_no, game.round_no + 1);
+
+        let after = stored_game(&state, &created.game_id).await;
+        assert_eq!(after.turn_no, game.turn_no + 2);
+        assert_eq!(
+            (after.state.players[0].row, after.state.players[0].col),
+            (1, 2)
+        );
+        assert_eq!(
+            (after.state.players[1].row, after.state.players[1].col),
+            (3, 2)
+        );
+    }
+
+    #[tokio::test]
+    async fn apply_batch_stops_on_reject_when_asked() {
+        let state = app_state();
+        let created = started_game(&state, 2).await;
+        let game = stored_game(&state, &created.game_id).await;
+        // Both commands claim the first turn, so the second is out of turn.
+        let requests = vec![
+            command(&game, PlayerCommandType::Pass, None, None),
+            command(&game, PlayerCommandType::Pass, None, None),
+            command(&game, PlayerCommandType::Pass, None, None),
+        ];
+
+        let stopped = apply_batch_handler(
+            State(state.clone()),
+            Path(created.game_id.clone()),
+            Query(ApplyBatchQuery {
+                stop_on_reject: true,
+            }),
+            HeaderMap::new(),
+            Json(requests.clone()),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert_eq!(stopped.len(), 2);
+        assert!(stopped[0].applied);
+        assert!(!stopped[1].applied);
+
+        let continued = apply_batch_handler(
+            State(state.clone()),
+            Path(created.game_id.clone()),
+            Query(ApplyBatchQuery::default()),
+            HeaderMap::new(),
+            Json(requests),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert_eq!(continued.len(), 3);
+        assert!(continued.iter().all(|response| !response.applied));
+        assert_eq!(
+            stored_game(&state, &created.game_id).await.turn_no,
+            game.turn_no + 1
+        );
+    }
+
+    #[tokio::test]
+    async fn apply_batch_publishes_each_step_before_the_steps_it_set_off() {
+        let publisher = Arc::new(RecordingStepEventPublisher::default());
+        let state = AppState {
+            step_event_publisher: publisher.clone(),
+            ..app_state()
+        };
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map: Some(custom_map(5, 5)),
+                num_players: Some(4),
+                narrate_eliminations: Some(true),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        let _ = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap();
+        let player_c = pid(&created, PlayerName::C);
+        update_game(&state, &created.game_id, |game| {
+            let c = game
+                .state
+                .players
+                .iter_mut()
+                .find(|p| p.player_id == player_c)
+                .unwrap();
+            c.row = 1;
+            c.col = 0;
+            c.hp = 1;
+        })
+        .await;
+        publisher.published.lock().unwrap().clear();
+        let game = stored_game(&state, &created.game_id).await;
+        let shot = command(&game, PlayerCommandType::Shoot, Some(Direction::Down), None);
+        let out_of_turn = command(&game, PlayerCommandType::Pass, None, None);
+
+        let responses = apply_batch_handler(
+            State(state.clone()),
+            Path(created.game_id.clone()),
+            Query(ApplyBatchQuery::default()),
+            HeaderMap::new(),
+            Json(vec![shot, out_of_turn]),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        assert!(responses[0].applied);
+        assert!(!responses[1].applied);
+        let published = publisher.published.lock().unwrap().clone();
+        let kinds: Vec<_> = published
+            .iter()
+            .map(|(_, step)| {
+                let command = step.command.as_ref().unwrap();
+                (step.step_seq, command.source, command.command_type)
+            })
+            .collect();
+        let first = responses[0].step_seq.unwrap();
+        assert_eq!(
+            kinds,
+            vec![
+                (first, CommandSource::User, PlayerCommandType::Shoot),
+                (first + 1, CommandSource::System, PlayerCommandType::Speak),
+                (first + 2, CommandSource::User, PlayerCommandType::Pass),
+            ]
+        );
+        assert_eq!(responses[1].step_seq, Some(first + 2));
+        assert_eq!(published[2].1.result_status, ResultStatus::InvalidCommand);
+        assert_eq!(
+            stored_game(&state, &created.game_id).await.last_step_seq,
+            first + 2
+        );
+    }
+
+    #[tokio::test]
+    async fn game_config_reflects_custom_timeout_and_player_count() {
+        let state = app_state();
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                turn_timeout_seconds: Some(45),
+                map: Some(custom_map(6, 7)),
+                num_players: Some(3),
+                laser_pierces: Some(true),
+                wall_regen_rounds: Some(4),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        let config = get_game_config_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap()
+            .0;
+        assert_eq!(
+            config,
+            GameConfig {
+                game_id: created.game_id,
+                map_source: MapSource::Custom,
+                map_rows: 6,
+                map_cols: 7,
+                num_players: 3,
+                player_hp: DEFAULT_PLAYER_HP,
+                turn_timeout_seconds: 45,
+                laser_pierces: true,
+                laser_range: None,
+                wall_regen_rounds: 4,
+                arena_shrink_rounds: 0,
+                spawn_layout: SpawnLayout::EdgeCenters,
+                max_rounds: None,
+                shield_mode: ShieldMode::Permanent,
+                winner_policy: WinnerPolicy::FirstAlive,
+                body_block: true,
+                knockback: false,
+                auto_start_when_full: false,
+                vision_radius: None,
+                spawn_clear_radius: 0,
+                narrate_eliminations: false,
+                allow_rubble_move: false,
+                first_turn_grace_seconds: 0,
+            }
+        );
+    }
+
+    async fn token_game(state: &AppState, require_player_token: bool) -> CreateGameResponse {
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map: Some(custom_map(5, 5)),
+                num_players: Some(2),
+                require_player_token: Some(require_player_token),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        let _ = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap();
+        created
+    }
+
+    fn token_headers(token: Option<&str>, source: Option<&str>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(token) = token {
+            headers.insert(PLAYER_TOKEN_HEADER, token.parse().unwrap());
+        }
+        if let Some(source) = source {
+            headers.insert(COMMAND_SOURCE_HEADER, source.parse().unwrap());
+        }
+        headers
+    }
+
+    #[tokio::test]
+    async fn token_required_game_rejects_commands_without_the_players_token() {
+        let state = app_state();
+        let created = token_game(&state, true).await;
+        let tokens: HashMap<PlayerId, String> = created
+            .players
+            .iter()
+            .map(|player| {
+                (
+                    player.player_id.clone(),
+                    player.player_token.clone().expect("token issued"),
+                )
+            })
+            .collect();
+        assert_eq!(tokens.len(), 2);
+
+        let game = stored_game(&state, &created.game_id).await;
+        let request = command(&game, PlayerCommandType::Pass, None, None);
+        let other_token = tokens
+            .iter()
+            .find(|(player_id, _)| **player_id != request.player_id)
+            .map(|(_, token)| token.as_str());
+
+        for headers in [
+            token_headers(None, None),
+            token_headers(Some("not-the-token"), None),
+            token_headers(other_token, Some("user")),
+        ] {
+            let error = apply_command_handler(
+                State(state.clone()),
+                Path(created.game_id.clone()),
+                headers,
+                Json(request.clone()),
+            )
+            .await
+            .unwrap_err();
+            assert_eq!(error.status, StatusCode::UNAUTHORIZED);
+        }
+        assert_eq!(
+            stored_game(&state, &created.game_id).await.turn_no,
+            game.turn_no
+        );
+
+        let applied = apply_command_handler(
+            State(state.clone()),
+            Path(created.game_id.clone()),
+            token_headers(Some(&tokens[&request.player_id]), None),
+            Json(request),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert!(applied.applied);
+
+        // Bot and timer commands arrive from inside the cluster without a token.
+        let game = stored_game(&state, &created.game_id).await;
+        let timeout = apply_command_handler(
+            State(state.clone()),
+            Path(created.game_id.clone()),
+            token_headers(None, Some("timer")),
+            Json(command(&game, PlayerCommandType::Timeout, None, None)),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert!(timeout.applied);
+
+        // Tokens never appear in the public game view.
+        let public = get_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap()
+            .0;
+        let public_json = serde_json::to_string(&public).unwrap();
+        assert!(tokens.values().all(|token| !public_json.contains(token)));
+    }
+
+    #[tokio::test]
+    async fn token_optional_game_accepts_commands_without_a_token() {
+        let state = app_state();
+        let created = token_game(&state, false).await;
+        assert!(
+            created
+                .players
+                .iter()
+                .all(|player| player.player_token.is_none())
+        );
+
+        let started = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap()
+            .0;
+        assert!(started.players.is_empty());
+
+        let game = stored_game(&state, &created.game_id).await;
+        let applied = apply_command_handler(
+            State(state.clone()),
+            Path(created.game_id.clone()),
+            HeaderMap::new(),
+            Json(command(&game, PlayerCommandType::Pass, None, None)),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert!(applied.applied);
+    }
+
+    struct FailingTopicProvisioner;
+
+    #[async_trait]
+    impl TopicProvisioner for FailingTopicProvisioner {
+        async fn ensure_game_topics(&self, _game_id: &str) -> anyhow::Result<GameTopics> {
+            Err(anyhow::anyhow!("broker unreachable"))
+        }
+
+        async fn delete_game_topics(&self, _game_topics: &GameTopics) -> anyhow::Result<()> {
+            Err(anyhow::anyhow!("broker unreachable"))
+        }
+    }
+
+    struct FailingStepEventPublisher;
+
+    #[async_trait]
+    impl StepEventPublisher for FailingStepEventPublisher {
+        async fn publish_step_event(&self, _topic: &str, _event: &StepEvent) -> anyhow::Result<()> {
+            Err(anyhow::anyhow!("broker unreachable"))
+        }
+    }
+
+    #[tokio::test]
+    async fn failing_provisioner_fails_create_unless_kafka_is_optional() {
+        let strict = AppState {
+            topic_provisioner: Arc::new(FailingTopicProvisioner),
+            ..app_state()
+        };
+        let error = create_game_handler(State(strict), Json(CreateGameRequest::default()))
+            .await
+            .unwrap_err();
+        assert_eq!(error.status, StatusCode::INTERNAL_SERVER_ERROR);
+
+        let optional = AppState {
+            topic_provisioner: Arc::new(OptionalTopicProvisioner {
+                inner: Arc::new(FailingTopicProvisioner),
+                input_topic_prefix: "game.commands".to_string(),
+                output_topic_prefix: "game.output".to_string(),
+            }),
+            step_event_publisher: Arc::new(OptionalStepEventPublisher {
+                inner: Some(Arc::new(FailingStepEventPublisher)),
+            }),
+            ..app_state()
+        };
+        let created =
+            create_game_handler(State(optional.clone()), Json(CreateGameRequest::default()))
+                .await
+                .unwrap()
+                .0;
+        let game = stored_game(&optional, &created.game_id).await;
+        assert_eq!(
+            game.input_topic,
+            format!("game.commands.{}.v1", created.game_id)
+        );
+        assert_eq!(
+            game.output_topic,
+            format!("game.output.{}.v1", created.game_id)
+        );
+
+        // Publishing GAME_STARTED and deleting topics on finish don't fail either.
+        let started = start_game_handler(State(optional.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap()
+            .0;
+        assert!(started.started);
+        update_game(&optional, &created.game_id, |game| {
+            game.state.players[1].alive = false;
+        })
+        .await;
+        let finished = finish_game_handler(
+            State(optional.clone()),
+            Path(created.game_id.clone()),
+            Json(FinishGameRequest {
+                expected_turn_no: None,
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert!(finished.finished);
+    }
+
+    #[tokio::test]
+    async fn create_game_honours_spawn_layout() {
+        let state = app_state();
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                num_players: Some(4),
+                map_seed: Some(7),
+                spawn_layout: Some("corners".to_string()),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        let game = stored_game(&state, &created.game_id).await;
+        let corners: Vec<(usize, usize)> = game
+            .state
+            .players
+            .iter()
+            .map(|player| (player.row, player.col))
+            .collect();
+        assert_eq!(corners, vec![(0, 0), (10, 0), (10, 10), (0, 10)]);
+        assert!(
+            corners
+                .iter()
+                .all(|&(row, col)| game.state.map.cells[row][col] == 0)
+        );
+        assert_eq!(game.spawn_layout, SpawnLayout::Corners);
+
+        let error = create_game_handler(
+            State(state),
+            Json(CreateGameRequest {
+                spawn_layout: Some("diagonal".to_string()),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn paused_game_rejects_commands_until_resumed() {
+        let state = app_state();
+        let created = started_game(&state, 2).await;
+
+        let paused = pause_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap()
+            .0;
+        assert!(paused.changed);
+        assert_eq!(paused.status, GameStatus::Paused);
+
+        let again = pause_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap()
+            .0;
+        assert!(!again.changed);
+        assert_eq!(again.reason.as_deref(), Some("ALREADY_PAUSED"));
+
+        let game = stored_game(&state, &created.game_id).await;
+        let rejected = apply_command_handler(
+            State(state.clone()),
+            Path(created.game_id.clone()),
+            HeaderMap::new(),
+            Json(command(&game, PlayerCommandType::Pass, None, None)),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert!(!rejected.accepted);
+        assert_eq!(rejected.reason, Some(RejectReason::GamePaused));
+        assert_eq!(
+            stored_game(&state, &created.game_id).await.turn_no,
+            game.turn_no
+        );
+
+        let resumed = resume_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap()
+            .0;
+        assert!(resumed.changed);
+        assert_eq!(resumed.status, GameStatus::Running);
+
+        let applied = apply_command_handler(
+            State(state.clone()),
+            Path(created.game_id.clone()),
+            HeaderMap::new(),
+            Json(command(&game, PlayerCommandType::Pass, None, None)),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert!(applied.applied);
+    }
+
+    #[tokio::test]
+    async fn resume_shifts_turn_start_by_the_paused_duration() {
+        let state = app_state();
+        let created = started_game(&state, 2).await;
+        let mut game = stored_game(&state, &created.game_id).await;
+        let turn_started_at = Utc::now() - chrono::Duration::seconds(4);
+        game.turn_started_at = Some(turn_started_at);
+
+        let paused_at = turn_started_at + chrono::Duration::seconds(4);
+        pause_game(&mut game, paused_at).unwrap();
+        assert_eq!(
+            resume_game(&mut game, paused_at + chrono::Duration::seconds(30)),
+            Ok(())
+        );
+
+        assert_eq!(game.status, GameStatus::Running);
+        assert_eq!(game.paused_at, None);
+        assert_eq!(
+            game.turn_started_at,
+            Some(turn_started_at + chrono::Duration::seconds(30))
+        );
+        assert_eq!(resume_game(&mut game, Utc::now()), Err("GAME_NOT_PAUSED"));
+    }
+
+    #[tokio::test]
+    async fn turn_seconds_remaining_shrinks_as_the_turn_ages() {
+        let state = app_state();
+        let created = started_game(&state, 2).await;
+        let timeout = stored_game(&state, &created.game_id)
+            .await
+            .turn_timeout_seconds as i64;
+
+        update_game(&state, &created.game_id, |game| {
+            game.turn_started_at = Some(Utc::now() - chrono::Duration::seconds(3));
+        })
+        .await;
+        let earlier = get_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap()
+            .0
+            .turn_seconds_remaining
+            .unwrap();
+        assert!(earlier <= timeout - 3);
+
+        update_game(&state, &created.game_id, |game| {
+            game.turn_started_at = game
+                .turn_started_at
+                .map(|started| started - chrono::Duration::seconds(2));
+        })
+        .await;
+        let later = get_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap()
+            .0
+            .turn_seconds_remaining
+            .unwrap();
+        assert!(later < earlier);
+
+        update_game(&state, &created.game_id, |game| {
+            game.turn_started_at = Some(Utc::now() - chrono::Duration::seconds(timeout + 60));
+        })
+        .await;
+        let expired = get_game_handler(State(state), Path(created.game_id))
+            .await
+            .unwrap()
+            .0;
+        assert_eq!(expired.turn_seconds_remaining, Some(0));
+    }
+
+    /// Plays one full round of passes in a two-player game capped at one
+    /// round, with the players on the given HP.
+    async fn play_past_round_limit(state: &AppState, hp: [i32; 2]) -> GameInstance {
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map: Some(custom_map(5, 5)),
+                max_rounds: Some(1),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        let _ = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap();
+        update_game(state, &created.game_id, |game| {
+            game.state.players[0].hp = hp[0];
+            game.state.players[1].hp = hp[1];
+        })
+        .await;
+
+        pass_current_turn(state, &created.game_id).await;
+        assert_eq!(stored_game(state, &created.game_id).await.round_no, 1);
+        pass_current_turn(state, &created.game_id).await;
+        stored_game(state, &created.game_id).await
+    }
+
+    async fn finish(state: &AppState, game_id: &str) -> FinishGameResponse {
+        finish_game_handler(
+            State(state.clone()),
+            Path(game_id.to_string()),
+            Json(FinishGameRequest {
+                expected_turn_no: None,
+            }),
+        )
+        .await
+        .unwrap()
+        .0
+    }
+
+    #[tokio::test]
+    async fn sudden_death_crowns_the_hp_leader() {
+        let state = app_state();
+        let game = play_past_round_limit(&state, [3, 7]).await;
+
+        assert_eq!(game.round_no, 2);
+        assert!(!game.state.players[0].alive);
+        assert!(game.state.players[1].alive);
+        let leader = game.state.players[1].player_id.clone();
+        assert_eq!(game.current_player_id, leader);
+
+        let finished = finish(&state, &game.game_id).await;
+        assert!(finished.finished);
+        assert_eq!(finished.winner_player_id, Some(leader));
+        assert_eq!(finished.finish_reason, Some(FinishReason::MaxRounds));
+    }
+
+    #[tokio::test]
+    async fn sudden_death_tie_goes_to_the_earliest_player() {
+        let state = app_state();
+        let game = play_past_round_limit(&state, [5, 5]).await;
+
+        assert!(game.state.players[0].alive);
+        assert!(!game.state.players[1].alive);
+        let finished = finish(&state, &game.game_id).await;
+        assert_eq!(
+            finished.winner_player_id,
+            Some(game.state.players[0].player_id.clone())
+        );
+    }
+
+    #[tokio::test]
+    async fn api_error_body_carries_code_and_message() {
+        let response = ApiError::conflict("game g-1 is already running").into_response();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({"error": {"code": "CONFLICT", "message": "game g-1 is already running"}})
+        );
+    }
+
+    #[tokio::test]
+    async fn reroll_reseats_players_on_open_spawns_and_keeps_their_ids() {
+        let state = app_state();
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map: Some(custom_map(5, 5)),
+                num_players: Some(4),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        let before = stored_game(&state, &created.game_id).await;
+
+        let rerolled = replace_map_handler(
+            State(state.clone()),
+            Path(created.game_id.clone()),
+            Json(ReplaceMapRequest {
+                map_seed: Some(42),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        assert_eq!(rerolled.map_source, MapSource::Default);
+        assert_eq!((rerolled.state.map.rows, rerolled.state.map.cols), (11, 11));
+        assert_eq!(rerolled.state.players.len(), 4);
+        for (player, old) in rerolled.state.players.iter().zip(&before.state.players) {
+            assert_eq!(player.player_id, old.player_id);
+            assert_eq!(rerolled.state.map.cells[player.row][player.col], 0);
+        }
+        let stored = stored_game(&state, &created.game_id).await;
+        assert_eq!(stored.original_cells, stored.state.map.cells);
+        assert_eq!(stored.current_player_id, before.current_player_id);
+    }
+
+    #[tokio::test]
+    async fn map_cannot_be_replaced_after_start() {
+        let state = app_state();
+        let created = started_game(&state, 2).await;
+
+        let error = replace_map_handler(
+            State(state.clone()),
+            Path(created.game_id.clone()),
+            Json(ReplaceMapRequest {
+                map: Some(custom_map(7, 7)),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(error.status, StatusCode::CONFLICT);
+        let game = stored_game(&state, &created.game_id).await;
+        assert_eq!(game.state.map.rows, 5);
+    }
+
+    #[test]
+    fn full_table_counts_one_round_per_lap() {
+        let mut game = turn_order_game(&[true; 4], 0);
+        drive_turns(
+            &mut game,
+            &[
+                (None, "B", 1),
+                (None, "C", 1),
+                (None, "D", 1),
+                (None, "A", 2),
+                (None, "B", 2),
+            ],
+        );
+    }
+
+    #[test]
+    fn skipping_dead_seats_does_not_add_rounds() {
+        let mut game = turn_order_game(&[true, false, false, true], 0);
+        drive_turns(
+            &mut game,
+            &[
+                (None, "D", 1),
+                (None, "A", 2),
+                (None, "D", 2),
+                (None, "A", 3),
+            ],
+        );
+    }
+
+    #[test]
+    fn forfeiting_current_player_advances_without_double_counting() {
+        // D forfeits on their own turn: the lap wraps exactly once.
+        let mut game = turn_order_game(&[true; 4], 3);
+        drive_turns(
+            &mut game,
+            &[
+                (Some(3), "A", 2),
+                (None, "B", 2),
+                (None, "C", 2),
+                (None, "A", 3),
+            ],
+        );
+
+        // A forfeits on their own turn with B already gone: no wrap at all.
+        let mut game = turn_order_game(&[true, false, true, true], 0);
+        drive_turns(
+            &mut game,
+            &[(Some(0), "C", 1), (None, "D", 1), (Some(3), "C", 2)],
+        );
+    }
+
+    #[test]
+    fn sole_survivor_starts_a_new_round_every_turn() {
+        let mut game = turn_order_game(&[false, true, false, false], 1);
+        drive_turns(&mut game, &[(None, "B", 2), (None, "B", 3)]);
+    }
+
+    async fn join(
+        state: &AppState,
+        game_id: &str,
+        name: &str,
+    ) -> Result<JoinGameResponse, ApiError> {
+        join_game_handler(
+            State(state.clone()),
+            Path(game_id.to_string()),
+            Json(JoinGameRequest {
+                display_name: name.to_string(),
+            }),
+        )
+        .await
+        .map(|response| response.0)
+    }
+
+    #[tokio::test]
+    async fn joins_fill_human_slots_in_order_skipping_bots() {
+        let state = app_state();
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map: Some(custom_map(5, 5)),
+                num_players: Some(3),
+                bot_players: Some(vec![PlayerName::C]),
+                require_player_token: Some(true),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        let first = join(&state, &created.game_id, "  Ada ").await.unwrap();
+        let second = join(&state, &created.game_id, "Grace").await.unwrap();
+
+        assert_eq!(first.player.player_name, PlayerName::A);
+        assert_eq!(first.display_name, "Ada");
+        assert!(first.player.player_token.is_some());
+        assert_eq!(second.player.player_name, PlayerName::B);
+        let game = stored_game(&state, &created.game_id).await;
+        assert_eq!(
+            game.claimed_slots.get(&second.player.player_id),
+            Some(&"Grace".to_string())
+        );
+        assert_eq!(
+            game.player_tokens.get(&first.player.player_id),
+            first.player.player_token.as_ref()
+        );
+    }
+
+    #[tokio::test]
+    async fn join_is_rejected_once_human_slots_are_full() {
+        let state = app_state();
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map: Some(custom_map(5, 5)),
+                num_players: Some(2),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        join(&state, &created.game_id, "Ada").await.unwrap();
+        join(&state, &created.game_id, "Grace").await.unwrap();
+
+        let error = join(&state, &created.game_id, "Linus").await.unwrap_err();
+
+        assert_eq!(error.status, StatusCode::CONFLICT);
+        assert_eq!(
+            stored_game(&state, &created.game_id)
+                .await
+                .claimed_slots
+                .len(),
+            2
+        );
+    }
+
+    /// A 7x7 board where A at (5, 0) shoots Right into column 1, whose Up arm
+    /// reaches B at `target_row`.
+    async fn ranged_shot(laser_range: usize, target_row: usize) -> GameInstance {
+        let state = app_state();
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map: Some(custom_map(7, 7)),
+                num_players: Some(2),
+                laser_range: Some(laser_range),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        let _ = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap();
+        update_game(&state, &created.game_id, |game| {
+            game.state.players[0].row = 5;
+            game.state.players[0].col = 0;
+            game.state.players[1].row = target_row;
+            game.state.players[1].col = 1;
+            game.state.players[1].shield = Direction::Left;
+        })
+        .await;
+
+        let shot = apply(
+            &state,
+            &created.game_id,
+            PlayerCommandType::Shoot,
+            Some(Direction::Right),
+        )
+        .await;
+        assert!(shot.applied);
+        stored_game(&state, &created.game_id).await
+    }
+
+    #[tokio::test]
+    async fn laser_range_stops_short_of_a_distant_player() {
+        let game = ranged_shot(2, 1).await;
+
+        assert_eq!(game.state.players[1].hp, DEFAULT_PLAYER_HP);
+    }
+
+    #[tokio::test]
+    async fn laser_range_still_hits_a_player_within_range() {
+        let game = ranged_shot(2, 3).await;
+
+        assert_eq!(game.state.players[1].hp, DEFAULT_PLAYER_HP - 1);
+    }
+
+    /// A started two-player game with A at (2, 1) and B at (2, 3), where B
+    /// has queued a move Left into (2, 2) for the next turn.
+    async fn queued_move_game(
+        publisher: Arc<RecordingStepEventPublisher>,
+    ) -> (AppState, CreateGameResponse) {
+        let state = AppState {
+            step_event_publisher: publisher,
+            ..app_state()
+        };
+        let created = started_game(&state, 2).await;
+        update_game(&state, &created.game_id, |game| {
+            game.state.players[0].row = 2;
+            game.state.players[0].col = 1;
+            game.state.players[1].row = 2;
+            game.state.players[1].col = 3;
+        })
+        .await;
+        let game = stored_game(&state, &created.game_id).await;
+        let queued = queue_command_handler(
+            State(state.clone()),
+            Path(created.game_id.clone()),
+            HeaderMap::new(),
+            Json(SubmitCommandRequest {
+                command_id: "queued-move".to_string(),
+                player_id: pid(&created, PlayerName::B),
+                command_type: PlayerCommandType::Move,
+                direction: Some(Direction::Left),
+                speak_text: None,
+                turn_no: game.turn_no + 1,
+                client_sent_at: Utc::now(),
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert!(!queued.replaced);
+        (state, created)
+    }
+
+    #[tokio::test]
+    async fn queued_move_fires_on_the_players_turn() {
+        let publisher = Arc::new(RecordingStepEventPublisher::default());
+        let (state, created) = queued_move_game(publisher.clone()).await;
+        let published_before = publisher.published.lock().unwrap().len();
+
+        let passed = apply(&state, &created.game_id, PlayerCommandType::Pass, None).await;
+
+        assert!(passed.applied);
+        let game = stored_game(&state, &created.game_id).await;
+        assert_eq!(
+            (game.state.players[1].row, game.state.players[1].col),
+            (2, 2)
+        );
+        assert_eq!(game.current_player_id, pid(&created, PlayerName::A));
+        assert!(game.queued_commands.is_empty());
+        // The fired step goes back to the caller, numbered after the pass,
+        // instead of being published ahead of it.
+        assert_eq!(publisher.published.lock().unwrap().len(), published_before);
+        let [step] = passed.follow_up_steps.as_slice() else {
+            panic!("expected one follow-up step");
+        };
+        assert_eq!(step.result_status, ResultStatus::Applied);
+        assert_eq!(step.command.as_ref().unwrap().command_id, "queued-move");
+        assert_eq!(step.reject_reason, None);
+        assert_eq!(Some(step.step_seq), passed.step_seq.map(|seq| seq + 1));
+    }
+
+    #[tokio::test]
+    async fn queued_move_made_illegal_is_discarded_with_a_reason() {
+        let publisher = Arc::new(RecordingStepEventPublisher::default());
+        let (state, created) = queued_move_game(publisher.clone()).await;
+        let published_before = publisher.published.lock().unwrap().len();
+
+        let moved = apply(
+            &state,
+            &created.game_id,
+            PlayerCommandType::Move,
+            Some(Direction::Right),
+        )
+        .await;
+
+        assert!(moved.applied);
+        let game = stored_game(&state, &created.game_id).await;
+        assert_eq!(
+            (game.state.players[1].row, game.state.players[1].col),
+            (2, 3)
+        );
+        assert_eq!(game.current_player_id, pid(&created, PlayerName::B));
+        assert!(game.queued_commands.is_empty());
+        assert_eq!(publisher.published.lock().unwrap().len(), published_before);
+        let step = moved.follow_up_steps.last().unwrap();
+        assert_eq!(step.result_status, ResultStatus::InvalidCommand);
+        assert_eq!(step.reject_reason, Some(RejectReason::MoveBlockedByPlayer));
+        assert_eq!(step.step_seq, game.last_step_seq);
+    }
+
+    #[tokio::test]
+    async fn num_bots_hands_the_last_seats_to_bots() {
+        let assigner = Arc::new(RecordingBotAssigner::default());
+        let state = AppState {
+            bot_assigner: assigner.clone(),
+            ..app_state()
+        };
+
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map: Some(custom_map(5, 5)),
+                num_players: Some(4),
+                num_bots: Some(3),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        let bots = vec![PlayerName::B, PlayerName::C, PlayerName::D];
+        assert_eq!(
+            *assigner.requested.lock().unwrap(),
+            vec![Some(bots.clone())]
+        );
+        assert_eq!(stored_game(&state, &created.game_id).await.bot_slots, bots);
+    }
+
+    #[tokio::test]
+    async fn num_bots_cannot_exceed_players_or_combine_with_bot_players() {
+        let state = app_state();
+        for request in [
+            CreateGameRequest {
+                num_players: Some(2),
+                num_bots: Some(3),
+                ..Default::default()
+            },
+            CreateGameRequest {
+                bot_players: Some(vec![PlayerName::B]),
+                num_bots: Some(1),
+                ..Default::default()
+            },
+        ] {
+            let error = create_game_handler(State(state.clone()), Json(request))
+                .await
+                .unwrap_err();
+            assert_eq!(error.status, StatusCode::BAD_REQUEST);
+        }
+    }
+
+    #[tokio::test]
+    async fn skip_turn_times_out_the_current_player_and_publishes_the_step() {
+        let publisher = Arc::new(RecordingStepEventPublisher::default());
+        let state = AppState {
+            step_event_publisher: publisher.clone(),
+            ..app_state()
+        };
+        let created = started_game(&state, 2).await;
+        let before = stored_game(&state, &created.game_id).await;
+
+        let skipped = skip_turn_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap()
+            .0;
+
+        assert_eq!(skipped.skipped_player_id, pid(&created, PlayerName::A));
+        assert_eq!(skipped.current_player_id, pid(&created, PlayerName::B));
+        assert_eq!(skipped.turn_no, before.turn_no + 1);
+        let published = publisher.published.lock().unwrap();
+        let (topic, step) = published.last().unwrap();
+        assert_eq!(topic, &before.output_topic);
+        assert_eq!(step.event_type, StepEventType::TimeoutApplied);
+        assert_eq!(step.result_status, ResultStatus::TimeoutApplied);
+        assert_eq!(step.step_seq, skipped.step_seq);
+        let command = step.command.as_ref().unwrap();
+        assert_eq!(command.command_type, PlayerCommandType::Timeout);
+        assert_eq!(command.player_id, Some(skipped.skipped_player_id.clone()));
+    }
+
+    #[tokio::test]
+    async fn reserved_step_seqs_continue_the_managers_numbering() {
+        let state = app_state();
+        let created = started_game(&state, 2).await;
+        let started = stored_game(&state, &created.game_id).await.last_step_seq;
+
+        let passed = apply(&state, &created.game_id, PlayerCommandType::Pass, None).await;
+        assert_eq!(passed.step_seq, Some(started + 1));
+        let rejected = apply(&state, &created.game_id, PlayerCommandType::Move, None).await;
+        assert!(!rejected.applied);
+        assert_eq!(rejected.step_seq, None);
+        let reserved =
+            reserve_step_seq_handler(State(state.clone()), Path(created.game_id.clone()))
+                .await
+                .unwrap()
+                .0;
+        assert_eq!(reserved.step_seq, started + 2);
+        let passed = apply(&state, &created.game_id, PlayerCommandType::Pass, None).await;
+        assert_eq!(passed.step_seq, Some(started + 3));
+        assert_eq!(
+            stored_game(&state, &created.game_id).await.last_step_seq,
+            started + 3
+        );
+    }
+
+    #[tokio::test]
+    async fn skip_turn_requires_a_running_game() {
+        let state = app_state();
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map: Some(custom_map(5, 5)),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        let error = skip_turn_handler(State(state.clone()), Path(created.game_id))
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.status, StatusCode::CONFLICT);
+    }
+
+    fn simulation_request() -> SimulateGameRequest {
+        SimulateGameRequest {
+            game: CreateGameRequest {
+                num_players: Some(2),
+                map_seed: Some(7),
+                max_rounds: Some(40),
+                ..Default::default()
+            },
+            policy_seed: Some(11),
+        }
+    }
+
+    #[tokio::test]
+    async fn simulated_two_player_game_ends_with_one_consistent_winner() {
+        let state = app_state();
+        let result = simulate(&state, simulation_request()).await.unwrap();
+
+        let alive: Vec<&PlayerState> = result
+            .final_state
+            .players
+            .iter()
+            .filter(|player| player.alive)
+            .collect();
+        assert_eq!(alive.len(), 1);
+        assert_eq!(result.winner_player_id.as_ref(), Some(&alive[0].player_id));
+        assert!(result.round_no <= 41);
+        assert!(!result.actions.is_empty());
+        for pair in result.actions.windows(2) {
+            assert_eq!(pair[1].turn_no, pair[0].turn_no + 1);
+        }
+        let last_elimination = result
+            .actions
+            .iter()
+            .rev()
+            .find(|action| !action.eliminated.is_empty());
+        if let Some(action) = last_elimination {
+            assert_eq!(Some(&action.player_id), result.winner_player_id.as_ref());
         }
+        assert!(state.store.list().await.unwrap().is_empty());
     }
 
-    #[derive(Default)]
-    struct NoopStepEventPublisher;
+    #[tokio::test]
+    async fn simulation_rejects_a_round_limit_above_the_cap() {
+        let mut request = simulation_request();
+        request.game.max_rounds = Some(MAX_SIMULATION_ROUNDS + 1);
+
+        let error = simulate(&app_state(), request).await.unwrap_err();
+
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+        assert!(error.message.contains("max_rounds"));
+    }
+
+    #[tokio::test]
+    async fn simulation_replays_identically_for_the_same_seeds() {
+        let state = app_state();
+        let first = simulate(&state, simulation_request()).await.unwrap();
+        let second = simulate(&state, simulation_request()).await.unwrap();
+
+        let names = |result: &SimulateGameResponse| -> HashMap<PlayerId, PlayerName> {
+            result
+                .final_state
+                .players
+                .iter()
+                .map(|player| (player.player_id.clone(), player.player_name))
+                .collect()
+        };
+        let plays = |result: &SimulateGameResponse| {
+            let names = names(result);
+            result
+                .actions
+                .iter()
+                .map(|action| {
+                    (
+                        names[&action.player_id],
+                        action.command_type,
+                        action.direction,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(plays(&first), plays(&second));
+        assert_eq!(
+            first.winner_player_id.as_ref().map(|id| names(&first)[id]),
+            second
+                .winner_player_id
+                .as_ref()
+                .map(|id| names(&second)[id])
+        );
+    }
+
+    /// A started three-player game on a 5x5 board with A at (2, 1), B at
+    /// (2, 2) and C at (3, 0), everyone shielded away from C's beam.
+    async fn stacking_game(state: &AppState, body_block: Option<bool>) -> CreateGameResponse {
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map: Some(custom_map(5, 5)),
+                num_players: Some(3),
+                body_block,
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        let _ = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap();
+        update_game(state, &created.game_id, |game| {
+            for (player, (row, col)) in game.state.players.iter_mut().zip([(2, 1), (2, 2), (3, 0)])
+            {
+                player.row = row;
+                player.col = col;
+                player.shield = Direction::Down;
+            }
+        })
+        .await;
+        created
+    }
+
+    #[tokio::test]
+    async fn body_block_stops_a_move_onto_another_player() {
+        let state = app_state();
+        let created = stacking_game(&state, None).await;
+
+        let moved = apply(
+            &state,
+            &created.game_id,
+            PlayerCommandType::Move,
+            Some(Direction::Right),
+        )
+        .await;
+
+        assert!(!moved.applied);
+        assert_eq!(moved.reason, Some(RejectReason::MoveBlockedByPlayer));
+        let game = stored_game(&state, &created.game_id).await;
+        assert_eq!(
+            (game.state.players[0].row, game.state.players[0].col),
+            (2, 1)
+        );
+    }
+
+    #[tokio::test]
+    async fn without_body_block_players_stack_and_the_first_seated_is_hit() {
+        let state = app_state();
+        let created = stacking_game(&state, Some(false)).await;
+
+        let moved = apply(
+            &state,
+            &created.game_id,
+            PlayerCommandType::Move,
+            Some(Direction::Right),
+        )
+        .await;
+        assert!(moved.applied);
+        let game = stored_game(&state, &created.game_id).await;
+        assert_eq!(
+            (game.state.players[0].row, game.state.players[0].col),
+            (2, 2)
+        );
+        assert_eq!(
+            (game.state.players[1].row, game.state.players[1].col),
+            (2, 2)
+        );
+        assert!(!game.body_block);
+
+        apply(&state, &created.game_id, PlayerCommandType::Pass, None).await;
+        // C fires up into (2, 0); the right arm reaches the shared tile.
+        let shot = apply(
+            &state,
+            &created.game_id,
+            PlayerCommandType::Shoot,
+            Some(Direction::Up),
+        )
+        .await;
+        assert!(shot.applied);
+
+        let game = stored_game(&state, &created.game_id).await;
+        assert_eq!(game.state.players[0].hp, DEFAULT_PLAYER_HP - 1);
+        assert_eq!(game.state.players[1].hp, DEFAULT_PLAYER_HP);
+    }
+
+    #[tokio::test]
+    async fn custom_map_with_fewer_tiles_than_players_is_rejected() {
+        let state = app_state();
+        let error = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map: Some(custom_map(1, 3)),
+                num_players: Some(4),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+        assert!(error.message.contains("too few for 4 players"));
+    }
+
+    #[tokio::test]
+    async fn custom_map_with_a_walled_spawn_is_rejected() {
+        let state = app_state();
+        let mut map = custom_map(5, 5);
+        // B spawns at the left edge center.
+        map.cells[2][0] = 1;
+        let error = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map: Some(map.clone()),
+                num_players: Some(2),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+        assert!(
+            error.message.contains("player B at (2,0)"),
+            "{}",
+            error.message
+        );
+
+        map.cells[2][0] = 0;
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map: Some(map),
+                num_players: Some(2),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert_eq!(created.players.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn replacing_with_a_walled_spawn_map_is_rejected() {
+        let state = app_state();
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map: Some(custom_map(5, 5)),
+                num_players: Some(2),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        let mut map = custom_map(5, 5);
+        map.cells[2][0] = 1;
+
+        let error = replace_map_handler(
+            State(state.clone()),
+            Path(created.game_id.clone()),
+            Json(ReplaceMapRequest {
+                map: Some(map),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+        assert!(
+            error.message.contains("player B at (2,0)"),
+            "{}",
+            error.message
+        );
+        let game = stored_game(&state, &created.game_id).await;
+        assert_eq!(game.state.map.cells[2][0], 0);
+    }
+
+    #[tokio::test]
+    async fn four_players_on_a_two_by_two_map_each_get_their_own_tile() {
+        let state = app_state();
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map: Some(custom_map(2, 2)),
+                num_players: Some(4),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+
+        let game = stored_game(&state, &created.game_id).await;
+        let tiles: HashSet<(usize, usize)> = game
+            .state
+            .players
+            .iter()
+            .map(|player| (player.row, player.col))
+            .collect();
+        assert_eq!(tiles.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn forfeit_down_to_one_player_finishes_as_a_forfeit() {
+        let publisher = Arc::new(RecordingStepEventPublisher::default());
+        let state = AppState {
+            step_event_publisher: publisher.clone(),
+            ..app_state()
+        };
+        let created = started_game(&state, 2).await;
+        let player_a = pid(&created, PlayerName::A);
+        let player_b = pid(&created, PlayerName::B);
+        let published_before = publisher.published.lock().unwrap().len();
+
+        let forfeited = forfeit_player_handler(
+            State(state.clone()),
+            Path((created.game_id.clone(), player_b.clone())),
+            None,
+        )
+        .await
+        .unwrap()
+        .0;
+
+        assert!(forfeited.forfeited);
+        assert!(!forfeited.rebound_to_bot);
+        assert_eq!(forfeited.status, GameStatus::Finished);
+        let game = stored_game(&state, &created.game_id).await;
+        assert_eq!(game.status, GameStatus::Finished);
+        assert_eq!(game.finish_reason, Some(FinishReason::Forfeit));
+
+        let published = publisher.published.lock().unwrap()[published_before..].to_vec();
+        assert_eq!(published.len(), 2);
+        let (forfeit, finished) = (&published[0].1, &published[1].1);
+        assert_eq!(forfeit.event_type, StepEventType::StepApplied);
+        assert_eq!(forfeit.eliminations.len(), 1);
+        assert_eq!(forfeit.eliminations[0].victim_player_id, player_b);
+        assert_eq!(finished.event_type, StepEventType::GameFinished);
+        assert_eq!(finished.step_seq, forfeit.step_seq + 1);
+        assert_eq!(finished.finish_reason, Some(FinishReason::Forfeit));
+        assert_eq!(game.last_step_seq, finished.step_seq);
+
+        let again = finish(&state, &created.game_id).await;
+        assert!(!again.finished);
+        assert_eq!(again.reason.as_deref(), Some("ALREADY_FINISHED"));
+        assert_eq!(again.winner_player_id, Some(player_a));
+        assert_eq!(again.finish_reason, Some(FinishReason::Forfeit));
+    }
+
+    #[tokio::test]
+    async fn forfeit_fires_the_next_players_queued_command() {
+        let publisher = Arc::new(RecordingStepEventPublisher::default());
+        let state = AppState {
+            step_event_publisher: publisher.clone(),
+            ..app_state()
+        };
+        let created = started_game(&state, 3).await;
+        let player_a = pid(&created, PlayerName::A);
+        let player_b = pid(&created, PlayerName::B);
+        let game = stored_game(&state, &created.game_id).await;
+        let queued = queue_command_handler(
+            State(state.clone()),
+            Path(created.game_id.clone()),
+            HeaderMap::new(),
+            Json(SubmitCommandRequest {
+                command_id: "queued-pass".to_string(),
+                player_id: player_b.clone(),
+                command_type: PlayerCommandType::Pass,
+                direction: None,
+                speak_text: None,
+                turn_no: game.turn_no + 1,
+                client_sent_at: Utc::now(),
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert!(!queued.replaced);
+        let published_before = publisher.published.lock().unwrap().len();
+
+        let forfeited = forfeit_player_handler(
+            State(state.clone()),
+            Path((created.game_id.clone(), player_a.clone())),
+            None,
+        )
+        .await
+        .unwrap()
+        .0;
+
+        assert_eq!(forfeited.status, GameStatus::Running);
+        let game = stored_game(&state, &created.game_id).await;
+        assert!(game.queued_commands.is_empty());
+        assert_eq!(game.current_player_id, pid(&created, PlayerName::C));
+        let published = publisher.published.lock().unwrap()[published_before..].to_vec();
+        assert_eq!(published.len(), 2);
+        assert_eq!(published[0].1.eliminations[0].victim_player_id, player_a);
+        let fired = published[1].1.command.as_ref().unwrap();
+        assert_eq!(fired.command_id, "queued-pass");
+        assert_eq!(published[1].1.step_seq, published[0].1.step_seq + 1);
+        assert_eq!(game.last_step_seq, published[1].1.step_seq);
+    }
 
     #[async_trait]
-    impl StepEventPublisher for NoopStepEventPublisher {
-        async fn publish_step_event(&self, _topic: &str, _event: &StepEvent) -> anyhow::Result<()> {
-            Ok(())
+    impl TopicProvisioner for LocalBus {
+        async fn ensure_game_topics(&self, game_id: &str) -> anyhow::Result<GameTopics> {
+            let game_topics = GameTopics {
+                input_topic: format!("local.commands.{game_id}.v1"),
+                output_topic: format!("local.output.{game_id}.v1"),
+            };
+            self.create_topic(&game_topics.input_topic);
+            self.create_topic(&game_topics.output_topic);
+            Ok(game_topics)
+        }
+
+        async fn delete_game_topics(&self, game_topics: &GameTopics) -> anyhow::Result<()> {
+            self.delete_topic(&game_topics.input_topic)
+                .and_then(|()| self.delete_topic(&game_topics.output_topic))
+                .map_err(anyhow::Error::msg)
         }
     }
 
-    struct NoopBotAssigner;
+    #[async_trait]
+    impl StepEventPublisher for LocalBus {
+        async fn publish_step_event(&self, topic: &str, event: &StepEvent) -> anyhow::Result<()> {
+            self.publish(topic, event)
+                .map(|_| ())
+                .map_err(anyhow::Error::msg)
+        }
+    }
 
+    /// Records `(game_id, bot seats)` for each new game.
     #[async_trait]
-    impl BotAssigner for NoopBotAssigner {
+    impl BotAssigner for Recorder<(String, Vec<PlayerName>)> {
         async fn assign_for_new_game(
             &self,
-            _game: &GameInstance,
-            _requested_bot_players: Option<Vec<PlayerName>>,
+            game: &GameInstance,
+            requested_bot_players: Option<Vec<PlayerName>>,
+            _llm_overrides: Option<HashMap<PlayerName, LlmProfile>>,
         ) -> anyhow::Result<()> {
+            self.record((
+                game.game_id.clone(),
+                requested_bot_players.unwrap_or_default(),
+            ));
             Ok(())
         }
-    }
 
-    #[derive(Default)]
-    struct RecordingStepEventPublisher {
-        published: Mutex<Vec<(String, StepEvent)>>,
-    }
-
-    #[async_trait]
-    impl StepEventPublisher for RecordingStepEventPublisher {
-        async fn publish_step_event(&self, topic: &str, event: &StepEvent) -> anyhow::Result<()> {
-            self.published
-                .lock()
-                .unwrap()
-                .push((topic.to_string(), event.clone()));
+        async fn bind_bot(&self, _game_id: &str, _player_id: &str) -> anyhow::Result<()> {
             Ok(())
         }
     }
 
-    #[derive(Default)]
-    struct RecordingTopicProvisioner {
-        game_ids: Mutex<Vec<String>>,
-        deleted_topics: Mutex<Vec<GameTopics>>,
-    }
+    /// Plays a scripted 2-player game end to end over a `LocalBus`: create,
+    /// start, a turn loop that wakes on each output step the way the bot and
+    /// game services do (publishing applied steps as game-service would), and
+    /// the finish game-service requests once one player is left. On a 3x3
+    /// map A (top) and B (left) hit each other every shot; A fires first, so
+    /// A wins.
+    #[tokio::test]
+    async fn scripted_game_plays_to_a_winner_over_the_local_bus() {
+        let bus = Arc::new(LocalBus::default());
+        let bots = Arc::new(Recorder::<(String, Vec<PlayerName>)>::default());
+        let state = AppState {
+            topic_provisioner: bus.clone(),
+            step_event_publisher: bus.clone(),
+            bot_assigner: bots.clone(),
+            ..app_state()
+        };
 
-    #[async_trait]
-    impl TopicProvisioner for RecordingTopicProvisioner {
-        async fn ensure_game_topics(&self, game_id: &str) -> anyhow::Result<GameTopics> {
-            self.game_ids.lock().unwrap().push(game_id.to_string());
-            Ok(GameTopics {
-                input_topic: format!("record.commands.{game_id}.v1"),
-                output_topic: format!("record.output.{game_id}.v1"),
-            })
+        let created = create_game_handler(
+            State(state.clone()),
+            Json(CreateGameRequest {
+                map: Some(custom_map(3, 3)),
+                bot_players: Some(vec![PlayerName::B]),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap()
+        .0;
+        let game_id = created.game_id.clone();
+        assert_eq!(bots.items(), vec![(game_id.clone(), vec![PlayerName::B])]);
+        let output_topic = format!("local.output.{game_id}.v1");
+        assert!(bus.topic_exists(&output_topic));
+
+        let host = ScriptedPlayer::repeating(
+            pid(&created, PlayerName::A),
+            PlayerCommandType::Shoot,
+            Some(Direction::Down),
+            DEFAULT_PLAYER_HP as usize,
+        );
+        let bot = ScriptedPlayer::repeating(
+            pid(&created, PlayerName::B),
+            PlayerCommandType::Shoot,
+            Some(Direction::Right),
+            DEFAULT_PLAYER_HP as usize,
+        );
+        let started = start_game_handler(State(state.clone()), Path(game_id.clone()))
+            .await
+            .unwrap()
+            .0;
+        assert!(started.started);
+        assert_eq!(started.status, GameStatus::Running);
+
+        let mut offset = 0;
+        loop {
+            let woken: Vec<StepEvent> = bus.read(&output_topic, offset);
+            assert!(!woken.is_empty(), "no step woke the turn loop");
+            offset += woken.len();
+
+            let game = get_game_handler(State(state.clone()), Path(game_id.clone()))
+                .await
+                .unwrap()
+                .0;
+            if game.state.players.iter().filter(|p| p.alive).count() == 1 {
+                break;
+            }
+            let seat = [&host, &bot]
+                .into_iter()
+                .find(|player| player.player_id == game.current_player_id)
+                .unwrap();
+            let request = seat.next_command(game.turn_no).expect("script ran out");
+            let player_id = request.player_id.clone();
+            let applied = apply_command_handler(
+                State(state.clone()),
+                Path(game_id.clone()),
+                HeaderMap::new(),
+                Json(request),
+            )
+            .await
+            .unwrap()
+            .0;
+            assert!(applied.applied);
+
+            // Stand in for game-service, which publishes every applied step.
+            let after = stored_game(&state, &game_id).await;
+            let step = StepEvent {
+                game_id: game_id.clone(),
+                step_seq: applied.step_seq.expect("applied commands take a seq"),
+                turn_no: after.turn_no,
+                round_no: after.round_no,
+                event_type: StepEventType::StepApplied,
+                result_status: ResultStatus::Applied,
+                command: None,
+                state_after: after.state,
+                created_at: Utc::now(),
+                eliminations: Vec::new(),
+                arena_shrink: None,
+                laser_path: applied.laser_path,
+                player_id: Some(player_id),
+                reject_reason: None,
+                reasoning: None,
+                finish_reason: None,
+            };
+            bus.publish(&output_topic, &step).unwrap();
         }
 
-        async fn delete_game_topics(&self, game_topics: &GameTopics) -> anyhow::Result<()> {
-            self.deleted_topics
-                .lock()
-                .unwrap()
-                .push(game_topics.clone());
-            Ok(())
-        }
+        let finished = finish(&state, &game_id).await;
+        assert!(finished.finished);
+        assert_eq!(finished.winner_player_id, Some(host.player_id.clone()));
+
+        let steps: Vec<StepEvent> = bus.read(&output_topic, 0);
+        assert_eq!(
+            steps.first().unwrap().event_type,
+            StepEventType::GameStarted
+        );
+        let last = steps.last().unwrap();
+        assert_eq!(last.event_type, StepEventType::GameFinished);
+        assert_eq!(last.finish_reason, Some(FinishReason::LastPlayerStanding));
+        // A shot ten times and B nine: started + 19 applied + finished.
+        assert_eq!(steps.len(), 21);
+        let seqs: Vec<u64> = steps.iter().map(|step| step.step_seq).collect();
+        assert_eq!(seqs, (1..=21).collect::<Vec<u64>>());
+        assert!(!bus.topic_exists(&output_topic));
     }
 
-    fn app_state() -> AppState {
-        AppState {
-            store: Arc::new(RwLock::new(InMemoryStore::default())),
-            topic_provisioner: Arc::new(NoopTopicProvisioner),
-            step_event_publisher: Arc::new(NoopStepEventPublisher),
-            bot_assigner: Arc::new(NoopBotAssigner),
-        }
+    /// `shooting_range` with knockback on. A's shot right enters (2, 1) and
+    /// its upward arm reaches B at (1, 1), travelling up.
+    async fn knockback_range(state: &AppState, shield: Direction) -> CreateGameResponse {
+        let created = shooting_range(state, (1, 1)).await;
+        update_game(state, &created.game_id, |game| {
+            game.knockback = true;
+            game.state.players[1].shield = shield;
+        })
+        .await;
+        created
     }
 
-    fn pid(response: &CreateGameResponse, name: PlayerName) -> PlayerId {
-        response
-            .players
-            .iter()
-            .find(|p| p.player_name == name)
-            .unwrap_or_else(|| panic!("player {:?} not found in response", name))
-            .player_id
-            .clone()
+    #[tokio::test]
+    async fn knockback_pushes_a_hit_player_along_the_beam() {
+        let state = app_state();
+        let created = knockback_range(&state, Direction::Left).await;
+
+        let shot = apply(
+            &state,
+            &created.game_id,
+            PlayerCommandType::Shoot,
+            Some(Direction::Right),
+        )
+        .await;
+
+        assert!(shot.applied);
+        let target = &stored_game(&state, &created.game_id).await.state.players[1];
+        assert_eq!(target.hp, DEFAULT_PLAYER_HP - 1);
+        assert_eq!((target.row, target.col), (0, 1));
     }
 
-    fn custom_map(rows: usize, cols: usize) -> MapData {
-        MapData {
-            rows,
-            cols,
-            cells: vec![vec![0; cols]; rows],
-        }
+    #[tokio::test]
+    async fn knockback_into_a_wall_or_off_the_board_leaves_the_player() {
+        let state = app_state();
+        let created = knockback_range(&state, Direction::Left).await;
+        update_game(&state, &created.game_id, |game| {
+            game.state.map.cells[0][1] = -1;
+        })
+        .await;
+
+        apply(
+            &state,
+            &created.game_id,
+            PlayerCommandType::Shoot,
+            Some(Direction::Right),
+        )
+        .await;
+        let target = &stored_game(&state, &created.game_id).await.state.players[1];
+        assert_eq!(target.hp, DEFAULT_PLAYER_HP - 1);
+        assert_eq!((target.row, target.col), (1, 1));
+
+        let edge = started_game(&state, 2).await;
+        update_game(&state, &edge.game_id, |game| {
+            game.knockback = true;
+            game.state.players[0].row = 1;
+            game.state.players[0].col = 0;
+            game.state.players[1].row = 0;
+            game.state.players[1].col = 1;
+            game.state.players[1].shield = Direction::Left;
+        })
+        .await;
+        apply(
+            &state,
+            &edge.game_id,
+            PlayerCommandType::Shoot,
+            Some(Direction::Right),
+        )
+        .await;
+        let target = &stored_game(&state, &edge.game_id).await.state.players[1];
+        assert_eq!(target.hp, DEFAULT_PLAYER_HP - 1);
+        assert_eq!((target.row, target.col), (0, 1));
     }
 
     #[tokio::test]
-    async fn create_game_without_map_uses_default_map() {
+    async fn knockback_skips_a_target_whose_shield_faces_the_beam() {
         let state = app_state();
-        let response = create_game_handler(
+        let created = knockback_range(&state, Direction::Down).await;
+
+        apply(
+            &state,
+            &created.game_id,
+            PlayerCommandType::Shoot,
+            Some(Direction::Right),
+        )
+        .await;
+
+        let target = &stored_game(&state, &created.game_id).await.state.players[1];
+        assert_eq!(target.hp, DEFAULT_PLAYER_HP);
+        assert_eq!((target.row, target.col), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn knockback_is_off_unless_requested() {
+        let state = app_state();
+        let created = create_game_handler(
             State(state.clone()),
             Json(CreateGameRequest {
-                turn_timeout_seconds: None,
-                map: None,
-                bot_players: None,
-                num_players: None,
+                knockback: Some(true),
+                ..Default::default()
             }),
         )
         .await
         .unwrap()
         .0;
-
-        assert_eq!(response.status, GameStatus::Created);
-        assert_eq!(response.map_source, MapSource::Default);
-        assert_eq!(response.turn_no, 1);
-        assert_eq!(response.round_no, 1);
-        assert_eq!(response.current_player_id, pid(&response, PlayerName::A));
-        assert_eq!(response.turn_timeout_seconds, DEFAULT_TURN_TIMEOUT_SECONDS);
-        assert_eq!(response.players.len(), 2);
-
-        let game = get_game_handler(State(state), Path(response.game_id.clone()))
-            .await
-            .unwrap()
-            .0;
-        assert_eq!(game.state.map.rows, 11);
-        assert_eq!(game.state.map.cols, 11);
-        assert_eq!(game.state.players.len(), 2);
+        assert!(stored_game(&state, &created.game_id).await.knockback);
+        let plain = started_game(&state, 2).await;
+        assert!(!stored_game(&state, &plain.game_id).await.knockback);
     }
 
     #[tokio::test]
-    async fn create_game_provisions_per_game_topics() {
-        let recorder = Arc::new(RecordingTopicProvisioner::default());
+    async fn final_join_auto_starts_the_game_once() {
+        let publisher = Arc::new(RecordingStepEventPublisher::default());
         let state = AppState {
-            store: Arc::new(RwLock::new(InMemoryStore::default())),
-            topic_provisioner: recorder.clone(),
-            step_event_publisher: Arc::new(NoopStepEventPublisher),
-            bot_assigner: Arc::new(NoopBotAssigner),
+            step_event_publisher: publisher.clone(),
+            ..app_state()
         };
-
-        let response = create_game_handler(
+        let created = create_game_handler(
             State(state.clone()),
             Json(CreateGameRequest {
-                turn_timeout_seconds: None,
                 map: Some(custom_map(5, 5)),
-                bot_players: None,
-                num_players: Some(4),
+                num_players: Some(3),
+                bot_players: Some(vec![PlayerName::C]),
+                auto_start_when_full: Some(true),
+                ..Default::default()
             }),
         )
         .await
         .unwrap()
         .0;
 
-        let game_ids = recorder.game_ids.lock().unwrap();
-        assert_eq!(game_ids.len(), 1);
-        assert_eq!(game_ids[0], response.game_id);
-
-        let store = state.store.read().await;
-        let game = store.games.get(&response.game_id).unwrap();
-        assert_eq!(
-            game.input_topic,
-            format!("record.commands.{}.v1", response.game_id)
-        );
+        let first = join(&state, &created.game_id, "Ada").await.unwrap();
+        assert!(!first.started);
         assert_eq!(
-            game.output_topic,
-            format!("record.output.{}.v1", response.game_id)
+            stored_game(&state, &created.game_id).await.status,
+            GameStatus::Created
         );
+        let last = join(&state, &created.game_id, "Grace").await.unwrap();
+        assert!(last.started);
+
+        let game = stored_game(&state, &created.game_id).await;
+        assert_eq!(game.status, GameStatus::Running);
+        assert!(game.started_at.is_some());
+        assert!(game.turn_started_at.is_some());
+        let started: Vec<StepEvent> = publisher
+            .published
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, event)| event.clone())
+            .filter(|event| event.event_type == StepEventType::GameStarted)
+            .collect();
+        assert_eq!(started.len(), 1);
+        assert_eq!(started[0].step_seq, game.last_step_seq);
+
+        let again = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .unwrap()
+            .0;
+        assert_eq!(again.reason.as_deref(), Some("ALREADY_RUNNING"));
     }
 
     #[tokio::test]
-    async fn create_game_with_custom_map_uses_custom_source() {
+    async fn filling_the_lobby_without_auto_start_leaves_the_game_created() {
         let state = app_state();
-        let response = create_game_handler(
+        let created = create_game_handler(
             State(state.clone()),
             Json(CreateGameRequest {
-                turn_timeout_seconds: Some(15),
-                map: Some(custom_map(5, 7)),
-                bot_players: None,
-                num_players: Some(4),
+                map: Some(custom_map(5, 5)),
+                ..Default::default()
             }),
         )
         .await
         .unwrap()
         .0;
+        join(&state, &created.game_id, "Ada").await.unwrap();
+        let last = join(&state, &created.game_id, "Grace").await.unwrap();
 
-        assert_eq!(response.map_source, MapSource::Custom);
-        assert_eq!(response.turn_timeout_seconds, 15);
+        assert!(!last.started);
+        assert_eq!(
+            stored_game(&state, &created.game_id).await.status,
+            GameStatus::Created
+        );
+    }
 
-        let game = get_game_handler(State(state), Path(response.game_id.clone()))
+    async fn player_view(
+        state: &AppState,
+        game_id: &str,
+        player_id: &str,
+        headers: HeaderMap,
+    ) -> Result<PlayerViewResponse, ApiError> {
+        player_view_handler(
+            State(state.clone()),
+            Path((game_id.to_string(), player_id.to_string())),
+            headers,
+        )
+        .await
+        .map(|response| response.0)
+    }
+
+    #[tokio::test]
+    async fn player_view_hides_other_players_shields() {
+        let state = app_state();
+        let created = started_game(&state, 2).await;
+        let a = pid(&created, PlayerName::A);
+        let b = pid(&created, PlayerName::B);
+        update_game(&state, &created.game_id, |game| {
+            game.state.players[0].shield = Direction::Left;
+            game.state.players[1].shield = Direction::Right;
+        })
+        .await;
+
+        let view = player_view(&state, &created.game_id, &a, HeaderMap::new())
             .await
-            .unwrap()
-            .0;
-        assert_eq!(game.state.map.rows, 5);
-        assert_eq!(game.state.map.cols, 7);
+            .unwrap();
+        assert_eq!(view.view.player.player_id, a);
+        assert_eq!(view.view.player.shield, Direction::Left);
+        assert_eq!(view.policy, ViewPolicy::HideOtherShields);
+        assert_eq!(view.view.others.len(), 1);
+        assert_eq!(view.view.others[0].player_id, b);
+        assert_eq!(view.view.others[0].shield, None);
+
+        let err = player_view(&state, &created.game_id, "nobody", HeaderMap::new())
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
     }
 
     #[tokio::test]
-    async fn start_game_is_idempotent_for_running_game() {
+    async fn player_view_in_a_token_game_needs_that_players_token() {
+        let state = app_state();
+        let created = token_game(&state, true).await;
+        let a = &created.players[0];
+        let b = &created.players[1];
+
+        let err = player_view(&state, &created.game_id, &a.player_id, HeaderMap::new())
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, StatusCode::UNAUTHORIZED);
+        let err = player_view(
+            &state,
+            &created.game_id,
+            &a.player_id,
+            token_headers(b.player_token.as_deref(), None),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.status, StatusCode::UNAUTHORIZED);
+
+        let view = player_view(
+            &state,
+            &created.game_id,
+            &a.player_id,
+            token_headers(a.player_token.as_deref(), None),
+        )
+        .await
+        .unwrap();
+        assert_eq!(view.view.player.player_id, a.player_id);
+    }
+
+    #[tokio::test]
+    async fn fogged_player_view_only_shows_opponents_in_sight() {
         let state = app_state();
         let created = create_game_handler(
             State(state.clone()),
             Json(CreateGameRequest {
-                turn_timeout_seconds: None,
-                map: None,
-                bot_players: None,
-                num_players: Some(4),
+                map: Some(custom_map(5, 5)),
+                vision_radius: Some(1),
+                ..Default::default()
             }),
         )
         .await
         .unwrap()
         .0;
-
-        let first = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
+        let a = pid(&created, PlayerName::A);
+        let b = pid(&created, PlayerName::B);
+        update_game(&state, &created.game_id, |game| {
+            game.state.players[0].row = 2;
+            game.state.players[0].col = 2;
+            game.state.players[1].row = 3;
+            game.state.players[1].col = 3;
+        })
+        .await;
+
+        let view = player_view(&state, &created.game_id, &a, HeaderMap::new())
             .await
-            .unwrap()
-            .0;
-        assert!(first.started);
-        assert_eq!(first.status, GameStatus::Running);
-        assert!(first.started_at.is_some());
+            .unwrap();
+        assert_eq!(view.vision_radius, Some(1));
+        assert_eq!(view.view.others.len(), 1);
+        assert_eq!(view.view.others[0].player_id, b);
+        assert_eq!(view.view.map.cells[0][0], FOG_CELL);
+        assert_eq!(view.view.map.cells[1][1], 0);
+
+        update_game(&state, &created.game_id, |game| {
+            game.state.players[1].row = 4;
+            game.state.players[1].col = 0;
+        })
+        .await;
+        let view = player_view(&state, &created.game_id, &a, HeaderMap::new())
+            .await
+            .unwrap();
+        assert!(view.view.others.is_empty());
 
-        let second = start_game_handler(State(state), Path(created.game_id))
+        let spectator = get_game_handler(State(state.clone()), Path(created.game_id.clone()))
             .await
             .unwrap()
             .0;
-        assert!(!second.started);
-        assert_eq!(second.reason.as_deref(), Some("ALREADY_RUNNING"));
+        assert_eq!(spectator.state.players.len(), 2);
+        assert!(
+            spectator
+                .state
+                .map
+                .cells
+                .iter()
+                .flatten()
+                .all(|cell| *cell == 0)
+        );
     }
 
     #[tokio::test]
-    async fn start_game_publishes_game_started_event_to_output_topic() {
-        let publisher = Arc::new(RecordingStepEventPublisher::default());
-        let state = AppState {
-            store: Arc::new(RwLock::new(InMemoryStore::default())),
-            topic_provisioner: Arc::new(NoopTopicProvisioner),
-            step_event_publisher: publisher.clone(),
-            bot_assigner: Arc::new(NoopBotAssigner),
-        };
-
+    async fn spawn_clear_radius_opens_generated_maps_around_spawns() {
+        let state = app_state();
         let created = create_game_handler(
             State(state.clone()),
             Json(CreateGameRequest {
-                turn_timeout_seconds: None,
-                map: Some(custom_map(5, 5)),
-                bot_players: None,
-                num_players: Some(4),
+                map_density: Some(MapDensity {
+                    empty_pct: 0,
+                    wall1_pct: 100,
+                    wall2_pct: 0,
+                    indestructible_pct: 0,
+                }),
+                spawn_clear_radius: Some(1),
+                ..Default::default()
             }),
         )
         .await
         .unwrap()
         .0;
 
-        let started = start_game_handler(State(state), Path(created.game_id.clone()))
-            .await
-            .unwrap()
-            .0;
-        assert!(started.started);
+        let game = stored_game(&state, &created.game_id).await;
+        assert_eq!(game.spawn_clear_radius, 1);
+        let cells = &game.state.map.cells;
+        for player in &game.state.players {
+            let (r, c) = (player.row, player.col);
+            for (nr, nc) in [
+                (r.wrapping_sub(1), c),
+                (r + 1, c),
+                (r, c.wrapping_sub(1)),
+                (r, c + 1),
+            ] {
+                if nr < game.state.map.rows && nc < game.state.map.cols {
+                    assert_eq!(cells[nr][nc], 0);
+                }
+            }
+        }
+        assert!(state.default_map.read().await.is_none());
+    }
 
-        let published = publisher.published.lock().unwrap();
-        assert_eq!(published.len(), 1);
+    #[tokio::test]
+    async fn speak_is_collapsed_capped_and_filtered() {
+        let state = AppState {
+            speak_filter: Arc::new(SpeakFilter::new(["# rude words", "", "Varmint"])),
+            ..app_state()
+        };
+        let created = started_game(&state, 2).await;
+        let speak = |text: String| {
+            let state = state.clone();
+            let game_id = created.game_id.clone();
+            async move {
+                let game = stored_game(&state, &game_id).await;
+                let request = command(&game, PlayerCommandType::Speak, None, Some(text.as_str()));
+                let preview = validate_command_handler(
+                    State(state.clone()),
+                    Path(game_id.clone()),
+                    Json(request.clone()),
+                )
+                .await
+                .unwrap()
+                .0;
+                let applied = apply_command_handler(
+                    State(state),
+                    Path(game_id),
+                    HeaderMap::new(),
+                    Json(request),
+                )
+                .await
+                .unwrap()
+                .0;
+                assert_eq!(preview.reason, applied.reason);
+                applied
+            }
+        };
+
+        let too_long = speak("a".repeat(MAX_SPEAK_CHARS + 1)).await;
+        assert!(!too_long.applied);
+        assert_eq!(too_long.reason, Some(RejectReason::SpeakTooLong));
+
+        let banned = speak("you VARMINTS".to_string()).await;
+        assert!(!banned.applied);
+        assert_eq!(banned.reason, Some(RejectReason::SpeakRejected));
+        assert_eq!(stored_game(&state, &created.game_id).await.turn_no, 1);
+
+        // Collapsing brings a padded speak back under the limit.
+        let padded = format!("  howdy {}  partner\n", " ".repeat(MAX_SPEAK_CHARS));
+        let accepted = speak(padded).await;
+        assert!(accepted.applied);
+        let game = stored_game(&state, &created.game_id).await;
         assert_eq!(
-            published[0].0,
-            format!("test.output.{}.v1", created.game_id)
+            game.state.players[0].last_words.as_deref(),
+            Some("howdy partner")
         );
-        assert_eq!(published[0].1.event_type, StepEventType::GameStarted);
-        assert_eq!(published[0].1.turn_no, 1);
     }
 
     #[tokio::test]
-    async fn get_game_returns_not_found_for_unknown_id() {
+    async fn applied_commands_carry_an_envelope_with_their_source() {
         let state = app_state();
-        let err = get_game_handler(State(state), Path("missing-game".to_string()))
-            .await
-            .unwrap_err();
-        assert_eq!(err.status, StatusCode::NOT_FOUND);
+        let created = started_game(&state, 2).await;
+
+        let game = stored_game(&state, &created.game_id).await;
+        let shot = apply_command_handler(
+            State(state.clone()),
+            Path(created.game_id.clone()),
+            HeaderMap::new(),
+            Json(command(
+                &game,
+                PlayerCommandType::Shoot,
+                Some(Direction::Right),
+                None,
+            )),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert!(shot.applied);
+        let envelope = shot.command.expect("applied shoot carries its command");
+        assert_eq!(envelope.source, CommandSource::User);
+        assert_eq!(envelope.command_type, PlayerCommandType::Shoot);
+        assert_eq!(envelope.player_id, Some(pid(&created, PlayerName::A)));
+        assert!(envelope.player_token.is_none());
+
+        let game = stored_game(&state, &created.game_id).await;
+        let passed = apply_command_handler(
+            State(state.clone()),
+            Path(created.game_id.clone()),
+            token_headers(None, Some("bot")),
+            Json(command(&game, PlayerCommandType::Pass, None, None)),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert_eq!(passed.command.unwrap().source, CommandSource::Bot);
     }
 
     #[tokio::test]
-    async fn get_default_map_returns_stable_map() {
-        let state = app_state();
-        let first = get_default_map_handler(State(state.clone()))
+    async fn schema_endpoint_lists_the_command_types() {
+        let response = schema_handler().await.into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
             .await
-            .unwrap()
-            .0;
-        let second = get_default_map_handler(State(state)).await.unwrap().0;
+            .unwrap();
+        let schema: serde_json::Value = serde_json::from_slice(&body).unwrap();
 
-        assert_eq!(first.rows, second.rows);
-        assert_eq!(first.cols, second.cols);
-        assert_eq!(first.cells, second.cells);
+        let command_types: Vec<&str> = schema["$defs"]["CommandType"]["enum"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|value| value.as_str().unwrap())
+            .collect();
+        assert_eq!(
+            command_types,
+            vec![
+                "move", "shield", "shoot", "speak", "pass", "charge", "timeout"
+            ]
+        );
+        assert_eq!(
+            schema["$defs"]["SubmitCommandRequest"]["properties"]["command_type"]["$ref"],
+            "#/$defs/CommandType"
+        );
     }
 
     #[tokio::test]
-    async fn shoot_toward_own_shield_is_rejected_without_turn_advance() {
-        let state = app_state();
-        let created = create_game_handler(
-            State(state.clone()),
-            Json(CreateGameRequest {
-                turn_timeout_seconds: None,
-                map: Some(custom_map(5, 5)),
-                bot_players: None,
-                num_players: Some(4),
+    async fn finished_game_topics_are_kept_for_the_retention_window() {
+        tokio::time::pause();
+        let recorder = Arc::new(RecordingTopicProvisioner::default());
+        let state = AppState {
+            topic_provisioner: recorder.clone(),
+            finished_topic_retention: Duration::from_secs(300),
+            debug_endpoints: false,
+            ..app_state()
+        };
+        let created = started_game(&state, 2).await;
+        let winner = pid(&created, PlayerName::A);
+        update_game(&state, &created.game_id, |game| {
+            for player in &mut game.state.players {
+                if player.player_id != winner {
+                    player.alive = false;
+                    player.hp = 0;
+                }
+            }
+        })
+        .await;
+
+        let finished = finish_game_handler(
+            State(state.clone()),
+            Path(created.game_id.clone()),
+            Json(FinishGameRequest {
+                expected_turn_no: None,
             }),
         )
         .await
         .unwrap()
         .0;
 
-        let _ = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
-            .await
-            .unwrap();
+        // Finished straight away, but the topics are still there to drain.
+        assert!(finished.finished);
+        let game = stored_game(&state, &created.game_id).await;
+        assert_eq!(game.status, GameStatus::Finished);
+        let retire_at = game
+            .topics_retire_at
+            .expect("topics scheduled for retirement");
+        let retention_left = retire_at - Utc::now();
+        assert!(retention_left > chrono::Duration::seconds(290));
+        assert!(retention_left <= chrono::Duration::seconds(300));
+
+        // The startup sweep and the next tick leave topics still in retention.
+        let sweeper = spawn_topic_sweeper(state.clone());
+        for _ in 0..2 {
+            tokio::time::advance(TOPIC_SWEEP_INTERVAL).await;
+            for _ in 0..10 {
+                tokio::task::yield_now().await;
+            }
+        }
+        assert!(recorder.deleted_topics.lock().unwrap().is_empty());
+
+        // Once the retention has passed, the next tick deletes them.
+        update_game(&state, &created.game_id, |game| {
+            game.topics_retire_at = Some(Utc::now() - chrono::Duration::seconds(1));
+        })
+        .await;
+        tokio::time::advance(TOPIC_SWEEP_INTERVAL).await;
+        for _ in 0..100 {
+            if stored_game(&state, &created.game_id)
+                .await
+                .topics_retire_at
+                .is_none()
+            {
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+        sweeper.abort();
 
-        let player_a = pid(&created, PlayerName::A);
-        let response = apply_command_handler(
-            State(state),
-            Path(created.game_id),
-            Json(SubmitCommandRequest {
-                command_id: "cmd-own-shield".to_string(),
-                player_id: player_a.clone(),
-                command_type: CommandType::Shoot,
-                direction: Some(Direction::Up),
-                speak_text: None,
-                turn_no: 1,
-                client_sent_at: Utc::now(),
-            }),
-        )
-        .await
-        .unwrap()
-        .0;
+        let deleted = recorder.deleted_topics.lock().unwrap().clone();
+        assert_eq!(deleted.len(), 1);
+        assert_eq!(
+            deleted[0].output_topic,
+            format!("record.output.{}.v1", created.game_id)
+        );
+        assert!(
+            stored_game(&state, &created.game_id)
+                .await
+                .topics_retire_at
+                .is_none()
+        );
+    }
 
-        assert!(response.accepted);
-        assert!(!response.applied);
+    #[test]
+    fn retired_topics_include_their_dead_letter_topics() {
+        let names = retired_topic_names(&GameTopics {
+            input_topic: "game.input.g1.v1".to_string(),
+            output_topic: "game.output.g1.v1".to_string(),
+        });
         assert_eq!(
-            response.reason.as_deref(),
-            Some("CANNOT_SHOOT_THROUGH_OWN_SHIELD")
+            names,
+            [
+                "game.input.g1.v1",
+                "game.output.g1.v1",
+                "game.input.g1.v1.dlq",
+                "game.output.g1.v1.dlq",
+            ]
         );
-        assert_eq!(response.turn_no, 1);
-        assert_eq!(response.current_player_id, player_a);
     }
 
     #[tokio::test]
-    async fn shoot_hits_player_and_advances_turn() {
-        let state = app_state();
+    async fn eliminations_are_narrated_as_system_speak_steps() {
+        let publisher = Arc::new(RecordingStepEventPublisher::default());
+        let state = AppState {
+            store: Arc::new(InMemoryGameStore::default()),
+            default_map: Arc::default(),
+            mutation_lock: Arc::default(),
+            topic_provisioner: Arc::new(NoopTopicProvisioner),
+            step_event_publisher: publisher.clone(),
+            bot_assigner: Arc::new(NoopBotAssigner),
+            metrics: Arc::default(),
+            snapshot_path: None,
+            snapshot_writes: Arc::default(),
+            idempotency: Arc::default(),
+            speak_filter: Arc::default(),
+            finished_topic_retention: Duration::ZERO,
+            debug_endpoints: false,
+        };
         let created = create_game_handler(
             State(state.clone()),
             Json(CreateGameRequest {
-                turn_timeout_seconds: None,
                 map: Some(custom_map(5, 5)),
-                bot_players: None,
                 num_players: Some(4),
+                narrate_eliminations: Some(true),
+                ..Default::default()
             }),
         )
         .await
         .unwrap()
         .0;
-
         let _ = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
             .await
             .unwrap();
-
-        let player_a = pid(&created, PlayerName::A);
-        let player_b = pid(&created, PlayerName::B);
         let player_c = pid(&created, PlayerName::C);
+        update_game(&state, &created.game_id, |game| {
+            let c = game
+                .state
+                .players
+                .iter_mut()
+                .find(|p| p.player_id == player_c)
+                .unwrap();
+            c.row = 1;
+            c.col = 0;
+            c.hp = 1;
+        })
+        .await;
+        publisher.published.lock().unwrap().clear();
+
+        let game = stored_game(&state, &created.game_id).await;
         let response = apply_command_handler(
             State(state.clone()),
             Path(created.game_id.clone()),
-            Json(SubmitCommandRequest {
-                command_id: "cmd-shoot-down".to_string(),
-                player_id: player_a,
-                command_type: CommandType::Shoot,
-                direction: Some(Direction::Down),
-                speak_text: None,
-                turn_no: 1,
-                client_sent_at: Utc::now(),
-            }),
+            HeaderMap::new(),
+            Json(command(
+                &game,
+                PlayerCommandType::Shoot,
+                Some(Direction::Down),
+                None,
+            )),
         )
         .await
         .unwrap()
         .0;
+        assert_eq!(response.eliminations.len(), 1);
+        assert!(publisher.published.lock().unwrap().is_empty());
 
-        assert!(response.accepted);
-        assert!(response.applied);
-        assert_eq!(response.turn_no, 2);
-        assert_eq!(response.round_no, 1);
-        assert_eq!(response.current_player_id, player_b);
+        let [step] = response.follow_up_steps.as_slice() else {
+            panic!("expected one narration step");
+        };
+        assert_eq!(Some(step.step_seq), response.step_seq.map(|seq| seq + 1));
+        let narration = step.command.as_ref().unwrap();
+        assert_eq!(narration.source, CommandSource::System);
+        assert_eq!(narration.command_type, PlayerCommandType::Speak);
+        assert_eq!(narration.player_id, None);
+        assert_eq!(
+            narration.speak_text.as_deref(),
+            Some("Player C was eliminated by Player A")
+        );
+        let game = stored_game(&state, &created.game_id).await;
+        assert_eq!(step.step_seq, game.last_step_seq);
+    }
 
-        let game = get_game_handler(State(state), Path(created.game_id))
-            .await
-            .unwrap()
-            .0;
-        let down = game
-            .state
-            .players
-            .iter()
-            .find(|p| p.player_id == player_c)
-            .expect("down player must exist");
-        assert_eq!(down.hp, DEFAULT_PLAYER_HP - 1);
-        assert!(down.alive);
+    /// Puts the current player at (2, 2) with rubble (1) to its right and a
+    /// strength-2 wall above it.
+    async fn rubble_game(state: &AppState, allow_rubble_move: bool) -> GameInstance {
+        let created = started_game(state, 2).await;
+        update_game(state, &created.game_id, |game| {
+            game.allow_rubble_move = allow_rubble_move;
+            game.state.map.cells[2][3] = 1;
+            game.state.map.cells[1][2] = 2;
+            let current = game.current_player_id.clone();
+            let player = game
+                .state
+                .players
+                .iter_mut()
+                .find(|p| p.player_id == current)
+                .unwrap();
+            player.row = 2;
+            player.col = 2;
+        })
+        .await;
+        stored_game(state, &created.game_id).await
     }
 
     #[tokio::test]
-    async fn speak_advances_turn_without_state_damage() {
+    async fn rubble_move_steps_onto_strength_one_walls_and_clears_them() {
         let state = app_state();
-        let created = create_game_handler(
+        let game = rubble_game(&state, true).await;
+        let idx = game
+            .state
+            .players
+            .iter()
+            .position(|p| p.player_id == game.current_player_id)
+            .unwrap();
+        assert_eq!(
+            check_move(&game, idx, Direction::Up),
+            Err(RejectReason::MoveBlockedByBlock)
+        );
+
+        let response = apply_command_handler(
             State(state.clone()),
-            Json(CreateGameRequest {
-                turn_timeout_seconds: None,
-                map: Some(custom_map(5, 5)),
-                bot_players: None,
-                num_players: Some(4),
-            }),
+            Path(game.game_id.clone()),
+            HeaderMap::new(),
+            Json(command(
+                &game,
+                PlayerCommandType::Move,
+                Some(Direction::Right),
+                None,
+            )),
         )
         .await
         .unwrap()
         .0;
+        assert!(response.applied);
+        let game = stored_game(&state, &game.game_id).await;
+        assert_eq!(
+            (game.state.players[idx].row, game.state.players[idx].col),
+            (2, 3)
+        );
+        assert_eq!(game.state.map.cells[2][3], 0);
+    }
 
-        let _ = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
-            .await
-            .unwrap();
+    #[tokio::test]
+    async fn rubble_blocks_moves_unless_allowed() {
+        let state = app_state();
+        let game = rubble_game(&state, false).await;
 
-        let player_a = pid(&created, PlayerName::A);
-        let player_b = pid(&created, PlayerName::B);
-        let player_c = pid(&created, PlayerName::C);
         let response = apply_command_handler(
             State(state.clone()),
-            Path(created.game_id.clone()),
-            Json(SubmitCommandRequest {
-                command_id: "cmd-speak".to_string(),
-                player_id: player_a,
-                command_type: CommandType::Speak,
-                direction: None,
-                speak_text: Some("hello".to_string()),
-                turn_no: 1,
-                client_sent_at: Utc::now(),
-            }),
+            Path(game.game_id.clone()),
+            HeaderMap::new(),
+            Json(command(
+                &game,
+                PlayerCommandType::Move,
+                Some(Direction::Right),
+                None,
+            )),
         )
         .await
         .unwrap()
         .0;
+        assert!(!response.applied);
+        assert_eq!(response.reason, Some(RejectReason::MoveBlockedByBlock));
+        assert_eq!(
+            stored_game(&state, &game.game_id).await.state.map.cells[2][3],
+            1
+        );
+    }
 
-        assert!(response.accepted);
-        assert!(response.applied);
-        assert_eq!(response.turn_no, 2);
-        assert_eq!(response.current_player_id, player_b);
+    #[tokio::test]
+    async fn debug_dump_is_disabled_unless_debug_endpoints_is_set() {
+        let state = app_state();
+        let created = started_game(&state, 2).await;
 
-        let game = get_game_handler(State(state), Path(created.game_id))
+        let error = debug_game_handler(State(state.clone()), Path(created.game_id.clone()))
+            .await
+            .err()
+            .expect("debug endpoint must be off by default");
+        assert_eq!(error.status, StatusCode::NOT_FOUND);
+
+        let state = AppState {
+            debug_endpoints: true,
+            ..state
+        };
+        let dump = debug_game_handler(State(state.clone()), Path(created.game_id.clone()))
             .await
             .unwrap()
             .0;
-        let down = game
-            .state
-            .players
+        let stored = stored_game(&state, &created.game_id).await;
+        assert_eq!(dump.last_step_seq, stored.last_step_seq);
+        assert_eq!(dump.last_step_seq, 1);
+        assert_eq!(dump.output_topic, stored.output_topic);
+        assert_eq!(dump.turn_started_at, stored.turn_started_at);
+        let json = serde_json::to_value(&dump).unwrap();
+        assert!(json.get("input_topic").is_some());
+        assert!(json.get("last_step_seq").is_some());
+    }
+
+    /// A is dead; B has 2 HP and took 3 damage, C has 3 HP and took 2, D has
+    /// 3 HP and took 1.
+    fn ranked_players() -> GameStateSnapshot {
+        let mut players = cowboy_common::initial_players(5, 5, 3, 4);
+        players[0].alive = false;
+        players[0].hp = 0;
+        players[1].hp = 2;
+        let stats = players
             .iter()
-            .find(|p| p.player_id == player_c)
-            .expect("down player must exist");
-        assert_eq!(down.hp, DEFAULT_PLAYER_HP);
+            .zip([4, 3, 2, 1])
+            .map(|(player, damage_taken)| PlayerStats {
+                player_id: player.player_id.clone(),
+                damage_taken,
+                ..Default::default()
+            })
+            .collect();
+        GameStateSnapshot {
+            map: custom_map(5, 5),
+            players,
+            stats,
+        }
+    }
+
+    #[test]
+    fn first_alive_takes_the_earliest_survivor() {
+        let state = ranked_players();
+        assert_eq!(rank_survivors(WinnerPolicy::FirstAlive, &state), Some(1));
+    }
+
+    #[test]
+    fn most_hp_breaks_ties_by_seat() {
+        let state = ranked_players();
+        assert_eq!(rank_survivors(WinnerPolicy::MostHp, &state), Some(2));
+    }
+
+    #[test]
+    fn fewest_damage_taken_ignores_hp() {
+        let mut state = ranked_players();
+        assert_eq!(
+            rank_survivors(WinnerPolicy::FewestDamageTaken, &state),
+            Some(3)
+        );
+
+        // A player with no stats entry has taken no damage.
+        state
+            .stats
+            .retain(|stats| stats.player_id != state.players[1].player_id);
+        assert_eq!(
+            rank_survivors(WinnerPolicy::FewestDamageTaken, &state),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn no_survivors_means_no_winner_under_any_policy() {
+        let mut state = ranked_players();
+        for player in &mut state.players {
+            player.alive = false;
+        }
+        for policy in [
+            WinnerPolicy::FirstAlive,
+            WinnerPolicy::MostHp,
+            WinnerPolicy::FewestDamageTaken,
+        ] {
+            assert_eq!(rank_survivors(policy, &state), None);
+        }
+        assert_eq!("Most_HP".parse::<WinnerPolicy>(), Ok(WinnerPolicy::MostHp));
+        assert!("loudest".parse::<WinnerPolicy>().is_err());
     }
 
     #[tokio::test]
-    async fn speak_without_text_is_rejected_without_turn_advance() {
-        let state = app_state();
-        let created = create_game_handler(
-            State(state.clone()),
-            Json(CreateGameRequest {
-                turn_timeout_seconds: None,
-                map: Some(custom_map(5, 5)),
-                bot_players: None,
-                num_players: Some(4),
-            }),
-        )
+    async fn maps_encode_and_decode_over_the_api() {
+        let map = custom_map(3, 4);
+        let encoded = encode_map_handler(Json(map.clone())).await.unwrap().0;
+        assert_eq!(encoded.encoded, "3x4:4./4./4.");
+        let decoded = decode_map_handler(Json(encoded)).await.unwrap().0;
+        assert_eq!(decoded, map);
+
+        let error = decode_map_handler(Json(EncodedMap {
+            encoded: "3x4:4.".to_string(),
+        }))
         .await
-        .unwrap()
-        .0;
-
-        let _ = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
-            .await
-            .unwrap();
+        .unwrap_err();
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+    }
 
-        let player_a = pid(&created, PlayerName::A);
-        let response = apply_command_handler(
-            State(state),
-            Path(created.game_id),
-            Json(SubmitCommandRequest {
-                command_id: "cmd-speak-empty".to_string(),
-                player_id: player_a.clone(),
-                command_type: CommandType::Speak,
-                direction: None,
-                speak_text: Some("   ".to_string()),
-                turn_no: 1,
-                client_sent_at: Utc::now(),
+    #[tokio::test]
+    async fn first_turn_grace_above_the_limit_is_rejected() {
+        let error = create_game_handler(
+            State(app_state()),
+            Json(CreateGameRequest {
+                first_turn_grace_seconds: Some(MAX_FIRST_TURN_GRACE_SECONDS + 1),
+                ..Default::default()
             }),
         )
         .await
-        .unwrap()
-        .0;
+        .unwrap_err();
 
-        assert!(response.accepted);
-        assert!(!response.applied);
-        assert_eq!(response.reason.as_deref(), Some("MISSING_SPEAK_TEXT"));
-        assert_eq!(response.turn_no, 1);
-        assert_eq!(response.current_player_id, player_a);
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+        assert!(error.message.contains("first_turn_grace_seconds"));
     }
 
     #[tokio::test]
-    async fn finish_game_marks_status_when_one_player_left() {
-        let recorder = Arc::new(RecordingTopicProvisioner::default());
-        let step_publisher = Arc::new(RecordingStepEventPublisher::default());
-        let state = AppState {
-            store: Arc::new(RwLock::new(InMemoryStore::default())),
-            topic_provisioner: recorder.clone(),
-            step_event_publisher: step_publisher.clone(),
-            bot_assigner: Arc::new(NoopBotAssigner),
-        };
+    async fn first_turn_grace_extends_only_cold_turns() {
+        let state = app_state();
         let created = create_game_handler(
             State(state.clone()),
             Json(CreateGameRequest {
-                turn_timeout_seconds: None,
+                turn_timeout_seconds: Some(10),
                 map: Some(custom_map(5, 5)),
-                bot_players: None,
-                num_players: Some(4),
+                num_players: Some(2),
+                first_turn_grace_seconds: Some(30),
+                ..Default::default()
             }),
         )
         .await
         .unwrap()
         .0;
-
         let _ = start_game_handler(State(state.clone()), Path(created.game_id.clone()))
             .await
             .unwrap();
-        let game_id = created.game_id.clone();
         let player_a = pid(&created, PlayerName::A);
 
-        {
-            let mut store = state.store.write().await;
-            let game = store.games.get_mut(&created.game_id).unwrap();
-            for player in &mut game.state.players {
-                if player.player_id != player_a {
-                    player.alive = false;
-                    player.hp = 0;
-                }
-            }
+        async fn current_turn(state: &AppState, game_id: &str) -> GameInstanceResponse {
+            get_game_handler(State(state.clone()), Path(game_id.to_string()))
+                .await
+                .unwrap()
+                .0
+        }
+        async fn pass(state: &AppState, game_id: &str) {
+            let game = stored_game(state, game_id).await;
+            let response = apply_command_handler(
+                State(state.clone()),
+                Path(game_id.to_string()),
+                HeaderMap::new(),
+                Json(command(&game, PlayerCommandType::Pass, None, None)),
+            )
+            .await
+            .unwrap()
+            .0;
+            assert!(response.applied);
         }
+        let game_id = created.game_id.as_str();
 
-        let finished = finish_game_handler(
-            State(state),
-            Path(game_id.clone()),
-            Json(FinishGameRequest {
-                expected_turn_no: Some(1),
-            }),
+        let first = current_turn(&state, game_id).await;
+        assert_eq!(first.turn_no, 1);
+        assert_eq!(first.turn_grace_seconds, 30);
+        assert!(first.turn_seconds_remaining.unwrap() > 10);
+
+        pass(&state, game_id).await;
+        let second = current_turn(&state, game_id).await;
+        assert_eq!(second.turn_grace_seconds, 0);
+        assert!(second.turn_seconds_remaining.unwrap() <= 10);
+
+        // A's seat goes to a bot during B's turn; A's next turn is cold.
+        let rebound = forfeit_player_handler(
+            State(state.clone()),
+            Path((created.game_id.clone(), player_a.clone())),
+            Some(Json(ForfeitPlayerRequest {
+                replace_with_bot: Some(true),
+            })),
         )
         .await
         .unwrap()
         .0;
-
-        assert!(finished.finished);
-        assert_eq!(finished.status, GameStatus::Finished);
-        assert_eq!(finished.winner_player_id, Some(player_a));
-
-        let deleted_topics = recorder.deleted_topics.lock().unwrap();
-        assert_eq!(deleted_topics.len(), 1);
-        assert_eq!(
-            deleted_topics[0].input_topic,
-            format!("record.commands.{}.v1", game_id)
-        );
-        assert_eq!(
-            deleted_topics[0].output_topic,
-            format!("record.output.{}.v1", game_id)
-        );
-
-        let published = step_publisher.published.lock().unwrap();
-        assert_eq!(published.len(), 2);
-        assert_eq!(published[1].0, format!("record.output.{}.v1", game_id));
-        assert_eq!(published[1].1.event_type, StepEventType::GameFinished);
+        assert!(rebound.rebound_to_bot);
+        assert_eq!(current_turn(&state, game_id).await.turn_grace_seconds, 0);
+
+        pass(&state, game_id).await;
+        let third = current_turn(&state, game_id).await;
+        assert_eq!(third.current_player_id, player_a);
+        assert_eq!(third.turn_grace_seconds, 30);
+
+        pass(&state, game_id).await;
+        pass(&state, game_id).await;
+        let fifth = current_turn(&state, game_id).await;
+        assert_eq!(fifth.current_player_id, player_a);
+        assert_eq!(fifth.turn_grace_seconds, 0);
     }
 }
@@ -14,13 +14,10 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{HashMap, HashSet, VecDeque},
     net::SocketAddr,
-    sync::{
-        Arc,
-        atomic::{AtomicU64, Ordering},
-    },
-    time::Duration,
+    sync::Arc,
+    time::{Duration, Instant},
 };
 
 use anyhow::Context;
@@ -28,23 +25,26 @@ use aws_config::BehaviorVersion;
 use aws_sdk_dynamodb::{Client as DynamoClient, types::AttributeValue};
 use axum::{
     Json, Router,
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
     response::{IntoResponse, Response},
     routing::{get, post},
 };
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use cowboy_common::{
-    CommandEnvelope, CommandSource, CommandType, Direction, GameInstanceResponse, GameStatus,
-    PlayerId, ResultStatus, StepEvent, StepEventType, SubmitCommandRequest,
+    ArenaShrink, Backoff, COMMAND_SOURCE_HEADER, CommandEnvelope, CommandSource, DeadLetter,
+    Direction, Elimination, FinishReason, GameInstanceResponse, GameStatus, LaserPath,
+    PLAYER_TOKEN_HEADER, PartitionStrategy, PlayerCommandType, PlayerId, RejectReason,
+    ResultStatus, StepEvent, StepEventType, SubmitCommandRequest,
+    kafka::{DeadLetterPublisher, KafkaDeadLetterPublisher, StepRecordRouter},
 };
 use rdkafka::{
-    Message,
+    Message, Offset, TopicPartitionList,
     config::ClientConfig,
     consumer::{CommitMode, Consumer, StreamConsumer},
-    producer::{FutureProducer, FutureRecord},
+    producer::FutureProducer,
 };
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use tower_http::{cors::CorsLayer, trace::TraceLayer};
 use tracing::{info, warn};
 
@@ -54,10 +54,14 @@ struct AppState {
     manager_base_url: String,
     kafka: KafkaSettings,
     producer: FutureProducer,
-    dedupe: Arc<tokio::sync::Mutex<HashMap<String, HashSet<String>>>>,
-    step_seq: Arc<AtomicU64>,
+    step_router: Arc<StepRecordRouter>,
+    dedupe: Arc<tokio::sync::Mutex<CommandDedupe>>,
+    rate_limiter: Arc<tokio::sync::Mutex<CommandRateLimiter>>,
     step_store: Option<DynamoStepStore>,
     game_locks: Arc<tokio::sync::Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>>,
+    /// Set when `KAFKA_DLQ_ENABLED` is on; undecodable commands go here.
+    dead_letters: Option<Arc<dyn DeadLetterPublisher>>,
+    lane_senders: LaneSenders,
 }
 
 #[derive(Clone)]
@@ -74,15 +78,288 @@ struct DynamoStepStore {
     table_name: String,
 }
 
+const DEFAULT_STEP_HISTORY_LIMIT: usize = 200;
+const MAX_STEP_HISTORY_LIMIT: usize = 1000;
+const DEFAULT_COMMAND_DEDUPE_TTL_SECS: u64 = 3600;
+const DEFAULT_COMMAND_DEDUPE_MAX_PER_GAME: usize = 4096;
+const DEFAULT_COMMAND_RATE_PER_SEC: f64 = 10.0;
+const DEFAULT_MAX_CONCURRENCY: usize = 16;
+/// Commands a game's lane holds before the consumer waits for it to catch up.
+const LANE_CAPACITY: usize = 64;
+/// Buckets untouched this long are full again and can be dropped.
+const COMMAND_RATE_IDLE: Duration = Duration::from_secs(60);
+
+/// A command read from Kafka with the position to commit once it is done.
+struct ConsumedCommand {
+    command: CommandEnvelope,
+    topic: String,
+    partition: i32,
+    offset: i64,
+}
+
+/// Each running game's lane, shared with [`AppState`] so the lane can be
+/// closed when its game finishes. Dropping the sender lets the lane task
+/// drain what it holds and exit.
+type LaneSenders =
+    Arc<std::sync::Mutex<HashMap<String, tokio::sync::mpsc::Sender<ConsumedCommand>>>>;
+
+/// Runs consumed commands on one task per game, so different games process
+/// in parallel while each game's commands keep their arrival order. A shared
+/// semaphore caps how many commands are in flight across all games.
+struct GameLanes<P> {
+    senders: LaneSenders,
+    permits: Arc<tokio::sync::Semaphore>,
+    process: P,
+}
+
+impl<P, Fut> GameLanes<P>
+where
+    P: Fn(ConsumedCommand) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    fn new(max_concurrency: usize, senders: LaneSenders, process: P) -> Self {
+        Self {
+            senders,
+            permits: Arc::new(tokio::sync::Semaphore::new(max_concurrency.max(1))),
+            process,
+        }
+    }
+
+    fn from_env(senders: LaneSenders, process: P) -> Self {
+        let max_concurrency = std::env::var("GAME_SERVICE_MAX_CONCURRENCY")
+            .ok()
+            .and_then(|value| value.parse::<usize>().ok())
+            .unwrap_or(DEFAULT_MAX_CONCURRENCY);
+        Self::new(max_concurrency, senders, process)
+    }
+
+    /// Queues `consumed` behind any earlier commands for the same game,
+    /// starting that game's lane the first time it is seen. Waits while the
+    /// lane is full.
+    async fn dispatch(&self, consumed: ConsumedCommand) {
+        let game_id = consumed.command.game_id.clone();
+        let sender = self.senders.lock().unwrap().get(&game_id).cloned();
+        let consumed = match sender {
+            Some(sender) => match sender.send(consumed).await {
+                Ok(()) => return,
+                Err(tokio::sync::mpsc::error::SendError(consumed)) => consumed,
+            },
+            None => consumed,
+        };
+
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(LANE_CAPACITY);
+        let _ = sender.try_send(consumed);
+        let permits = self.permits.clone();
+        let process = self.process.clone();
+        tokio::spawn(async move {
+            while let Some(consumed) = receiver.recv().await {
+                let Ok(_permit) = permits.acquire().await else {
+                    return;
+                };
+                process(consumed).await;
+            }
+        });
+        self.senders.lock().unwrap().insert(game_id, sender);
+    }
+}
+
+/// Remembers recently seen command ids per game so redelivered commands are
+/// rejected as duplicates. Entries expire after `ttl`, each game keeps at most
+/// `max_per_game` ids (oldest evicted first), and finished games are dropped.
+struct CommandDedupe {
+    ttl: Duration,
+    max_per_game: usize,
+    games: HashMap<String, GameDedupe>,
+}
+
+#[derive(Default)]
+struct GameDedupe {
+    order: VecDeque<(Instant, String)>,
+    seen: HashSet<String>,
+}
+
+impl GameDedupe {
+    fn pop_oldest(&mut self) {
+        if let Some((_, command_id)) = self.order.pop_front() {
+            self.seen.remove(&command_id);
+        }
+    }
+
+    fn prune_expired(&mut self, now: Instant, ttl: Duration) {
+        while self
+            .order
+            .front()
+            .is_some_and(|(seen_at, _)| now.saturating_duration_since(*seen_at) >= ttl)
+        {
+            self.pop_oldest();
+        }
+    }
+}
+
+impl CommandDedupe {
+    fn new(ttl: Duration, max_per_game: usize) -> Self {
+        Self {
+            ttl,
+            max_per_game: max_per_game.max(1),
+            games: HashMap::new(),
+        }
+    }
+
+    fn from_env() -> Self {
+        let ttl_secs = std::env::var("COMMAND_DEDUPE_TTL_SECS")
+            .ok()
+            .and_then(|value| value.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_COMMAND_DEDUPE_TTL_SECS);
+        let max_per_game = std::env::var("COMMAND_DEDUPE_MAX_PER_GAME")
+            .ok()
+            .and_then(|value| value.parse::<usize>().ok())
+            .unwrap_or(DEFAULT_COMMAND_DEDUPE_MAX_PER_GAME);
+        Self::new(Duration::from_secs(ttl_secs), max_per_game)
+    }
+
+    /// Records `command_id` for `game_id` and returns true when it was
+    /// already seen within the retention window.
+    fn check_and_insert(&mut self, game_id: &str, command_id: &str, now: Instant) -> bool {
+        let ttl = self.ttl;
+        self.games.retain(|_, game| {
+            game.prune_expired(now, ttl);
+            !game.order.is_empty()
+        });
+
+        let game = self.games.entry(game_id.to_string()).or_default();
+        if game.seen.contains(command_id) {
+            return true;
+        }
+        while game.order.len() >= self.max_per_game {
+            game.pop_oldest();
+        }
+        game.order.push_back((now, command_id.to_string()));
+        game.seen.insert(command_id.to_string());
+        false
+    }
+
+    fn clear_game(&mut self, game_id: &str) {
+        self.games.remove(game_id);
+    }
+
+    #[cfg(test)]
+    fn len(&self, game_id: &str) -> usize {
+        self.games.get(game_id).map_or(0, |game| game.order.len())
+    }
+}
+
+/// Token bucket per `(game_id, player_id)` for HTTP command submission. Each
+/// bucket holds one second's worth of tokens and refills at `rate_per_sec`;
+/// a rate of 0 disables limiting. Complements `CommandDedupe`, which only
+/// catches repeated command ids.
+struct CommandRateLimiter {
+    rate_per_sec: f64,
+    buckets: HashMap<(String, PlayerId), TokenBucket>,
+}
+
+struct TokenBucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl CommandRateLimiter {
+    fn new(rate_per_sec: f64) -> Self {
+        Self {
+            rate_per_sec: rate_per_sec.max(0.0),
+            buckets: HashMap::new(),
+        }
+    }
+
+    fn from_env() -> Self {
+        let rate_per_sec = std::env::var("COMMAND_RATE_PER_SEC")
+            .ok()
+            .and_then(|value| value.parse::<f64>().ok())
+            .filter(|rate| rate.is_finite())
+            .unwrap_or(DEFAULT_COMMAND_RATE_PER_SEC);
+        Self::new(rate_per_sec)
+    }
+
+    /// Takes a token for the player and returns false when their bucket is
+    /// empty.
+    fn try_acquire(&mut self, game_id: &str, player_id: &str, now: Instant) -> bool {
+        if self.rate_per_sec == 0.0 {
+            return true;
+        }
+        self.buckets.retain(|_, bucket| {
+            now.saturating_duration_since(bucket.refilled_at) < COMMAND_RATE_IDLE
+        });
+
+        let capacity = self.rate_per_sec.max(1.0);
+        let bucket = self
+            .buckets
+            .entry((game_id.to_string(), player_id.to_string()))
+            .or_insert(TokenBucket {
+                tokens: capacity,
+                refilled_at: now,
+            });
+        let elapsed = now
+            .saturating_duration_since(bucket.refilled_at)
+            .as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.rate_per_sec).min(capacity);
+        bucket.refilled_at = now;
+        if bucket.tokens < 1.0 {
+            return false;
+        }
+        bucket.tokens -= 1.0;
+        true
+    }
+
+    fn clear_game(&mut self, game_id: &str) {
+        self.buckets.retain(|(game, _), _| game != game_id);
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct StepHistoryQuery {
+    from_seq: Option<u64>,
+    limit: Option<usize>,
+}
+
+impl StepHistoryQuery {
+    fn start_seq(&self) -> u64 {
+        self.from_seq.unwrap_or(0)
+    }
+
+    fn limit(&self) -> usize {
+        self.limit
+            .unwrap_or(DEFAULT_STEP_HISTORY_LIMIT)
+            .clamp(1, MAX_STEP_HISTORY_LIMIT)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct ApplyCommandResponse {
     accepted: bool,
     applied: bool,
-    reason: Option<String>,
+    reason: Option<RejectReason>,
     turn_no: u64,
     round_no: u64,
     current_player_id: PlayerId,
     status: GameStatus,
+    #[serde(default)]
+    eliminations: Vec<Elimination>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    arena_shrink: Option<ArenaShrink>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    laser_path: Option<LaserPath>,
+    /// Set by game-manager when the command was applied; a rejected
+    /// command's step reserves its seq separately.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    step_seq: Option<u64>,
+    /// Narration and fired queued commands, numbered by game-manager after
+    /// `step_seq`; published right after the command's own step.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    follow_up_steps: Vec<StepEvent>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ReserveStepSeqResponse {
+    step_seq: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -100,16 +377,22 @@ struct FinishGameResponse {
     turn_no: u64,
     round_no: u64,
     current_player_id: PlayerId,
+    #[serde(default)]
+    finish_reason: Option<FinishReason>,
 }
 
 #[derive(Debug, Clone)]
 struct ProcessedOutcome {
     accepted: bool,
     applied: bool,
-    reason: Option<String>,
+    reason: Option<RejectReason>,
     game: GameInstanceResponse,
     #[allow(dead_code)]
     result_status: ResultStatus,
+    eliminations: Vec<Elimination>,
+    arena_shrink: Option<ArenaShrink>,
+    laser_path: Option<LaserPath>,
+    step_seq: u64,
 }
 
 impl AppState {
@@ -129,7 +412,7 @@ impl AppState {
                 .unwrap_or_else(|| "game-service-v1".to_string()),
         };
 
-        let producer = ClientConfig::new()
+        let producer: FutureProducer = ClientConfig::new()
             .set("bootstrap.servers", &kafka.bootstrap_servers)
             .set("message.timeout.ms", "5000")
             .create()
@@ -152,6 +435,18 @@ impl AppState {
                 None
             };
 
+        let partition_strategy =
+            cowboy_common::partition_strategy_from_env().unwrap_or_else(|error| {
+                warn!(error = %error, "ignoring GAME_PARTITION_STRATEGY");
+                PartitionStrategy::default()
+            });
+
+        let dead_letters = cowboy_common::env_flag("KAFKA_DLQ_ENABLED").then(|| {
+            Arc::new(KafkaDeadLetterPublisher {
+                producer: producer.clone(),
+            }) as Arc<dyn DeadLetterPublisher>
+        });
+
         Ok(Self {
             client: reqwest::Client::new(),
             manager_base_url: std::env::var("GAME_MANAGER_BASE_URL")
@@ -159,12 +454,13 @@ impl AppState {
                 .unwrap_or_else(|| "http://game-manager-service:8081".to_string()),
             kafka,
             producer,
-            dedupe: Arc::new(tokio::sync::Mutex::new(HashMap::new())),
-            step_seq: Arc::new(AtomicU64::new(
-                Utc::now().timestamp_micros().unsigned_abs().max(1),
-            )),
+            step_router: Arc::new(StepRecordRouter::new(partition_strategy)),
+            dedupe: Arc::new(tokio::sync::Mutex::new(CommandDedupe::from_env())),
+            rate_limiter: Arc::new(tokio::sync::Mutex::new(CommandRateLimiter::from_env())),
             step_store,
             game_locks: Arc::new(tokio::sync::Mutex::new(HashMap::new())),
+            dead_letters,
+            lane_senders: LaneSenders::default(),
         })
     }
 
@@ -176,10 +472,6 @@ impl AppState {
             .clone()
     }
 
-    fn next_step_seq(&self) -> u64 {
-        self.step_seq.fetch_add(1, Ordering::Relaxed)
-    }
-
     fn input_topic_pattern(&self) -> String {
         format!(
             "^{}\\..*\\.v1$",
@@ -217,7 +509,10 @@ async fn main() -> anyhow::Result<()> {
     let bind_addr = parse_bind_addr("GAME_SERVICE_BIND", "0.0.0.0:8084")?;
     info!(%bind_addr, "game-service listening");
     let listener = tokio::net::TcpListener::bind(bind_addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(cowboy_common::shutdown_signal())
+        .await?;
+    info!("game-service shut down");
     Ok(())
 }
 
@@ -228,6 +523,10 @@ fn build_router(state: AppState) -> Router {
             "/internal/v2/games/{game_id}/commands/process",
             post(process_command_handler),
         )
+        .route(
+            "/internal/v2/games/{game_id}/steps",
+            get(list_steps_handler),
+        )
         .with_state(state)
         .layer(CorsLayer::permissive())
         .layer(TraceLayer::new_for_http())
@@ -247,8 +546,21 @@ async fn health() -> Json<serde_json::Value> {
 async fn process_command_handler(
     State(state): State<AppState>,
     Path(game_id): Path<String>,
+    headers: HeaderMap,
     Json(request): Json<SubmitCommandRequest>,
 ) -> Result<Json<ApplyCommandResponse>, ApiError> {
+    let allowed =
+        state
+            .rate_limiter
+            .lock()
+            .await
+            .try_acquire(&game_id, &request.player_id, Instant::now());
+    if !allowed {
+        return Err(ApiError::too_many_requests(format!(
+            "player {} is submitting commands too quickly",
+            request.player_id
+        )));
+    }
     let command = CommandEnvelope {
         command_id: request.command_id.clone(),
         source: CommandSource::User,
@@ -259,6 +571,10 @@ async fn process_command_handler(
         speak_text: request.speak_text.clone(),
         turn_no: request.turn_no,
         sent_at: request.client_sent_at,
+        player_token: headers
+            .get(PLAYER_TOKEN_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string),
     };
     let lock = state.game_lock(&command.game_id).await;
     let _guard = lock.lock().await;
@@ -271,9 +587,49 @@ async fn process_command_handler(
         round_no: outcome.game.round_no,
         current_player_id: outcome.game.current_player_id.clone(),
         status: outcome.game.status,
+        eliminations: outcome.eliminations,
+        arena_shrink: outcome.arena_shrink,
+        laser_path: outcome.laser_path,
+        step_seq: Some(outcome.step_seq),
+        follow_up_steps: Vec::new(),
     }))
 }
 
+async fn list_steps_handler(
+    State(state): State<AppState>,
+    Path(game_id): Path<String>,
+    Query(query): Query<StepHistoryQuery>,
+) -> Result<Json<Vec<StepEvent>>, ApiError> {
+    let store = state.step_store.as_ref().ok_or_else(|| {
+        ApiError::not_implemented(
+            "step history requires a DynamoDB step store (set DYNAMODB_ENDPOINT or AWS_REGION)",
+        )
+    })?;
+
+    let steps = query_step_records(store, &game_id, query.start_seq(), query.limit())
+        .await
+        .map_err(|error| {
+            ApiError::bad_gateway(format!(
+                "failed to query step history for game {game_id}: {error:#}"
+            ))
+        })?;
+
+    Ok(Json(filter_steps_from_seq(
+        steps,
+        query.start_seq(),
+        query.limit(),
+    )))
+}
+
+/// Order steps by `step_seq`, drop anything before `from_seq` and cap the
+/// result at `limit` entries.
+fn filter_steps_from_seq(mut steps: Vec<StepEvent>, from_seq: u64, limit: usize) -> Vec<StepEvent> {
+    steps.retain(|step| step.step_seq >= from_seq);
+    steps.sort_by_key(|step| step.step_seq);
+    steps.truncate(limit);
+    steps
+}
+
 async fn run_command_consumer(state: AppState) -> anyhow::Result<()> {
     let consumer: StreamConsumer = ClientConfig::new()
         .set("bootstrap.servers", &state.kafka.bootstrap_servers)
@@ -291,12 +647,38 @@ async fn run_command_consumer(state: AppState) -> anyhow::Result<()> {
         .context("failed to subscribe to game command topics")?;
     info!(pattern = %pattern, "game-service Kafka consumer subscribed");
 
+    let consumer = Arc::new(consumer);
+    let mut backoff = Backoff::default();
+    let lane_state = state.clone();
+    let lane_consumer = consumer.clone();
+    let lanes = GameLanes::from_env(
+        state.lane_senders.clone(),
+        move |consumed: ConsumedCommand| {
+            let state = lane_state.clone();
+            let consumer = lane_consumer.clone();
+            async move {
+                process_consumed_command(&state, consumed.command).await;
+                // A game's commands share its input topic and run in order
+                // on its lane, so each partition's offsets finish in order.
+                commit_consumed_offset(
+                    &consumer,
+                    &consumed.topic,
+                    consumed.partition,
+                    consumed.offset,
+                );
+            }
+        },
+    );
+
     loop {
         let message = match consumer.recv().await {
-            Ok(message) => message,
+            Ok(message) => {
+                backoff.reset();
+                message
+            }
             Err(error) => {
                 warn!(?error, "game-service Kafka receive error");
-                tokio::time::sleep(Duration::from_millis(400)).await;
+                tokio::time::sleep(backoff.next_delay()).await;
                 continue;
             }
         };
@@ -312,15 +694,19 @@ async fn run_command_consumer(state: AppState) -> anyhow::Result<()> {
             }
         };
 
-        let command = match serde_json::from_slice::<CommandEnvelope>(payload) {
-            Ok(command) => command,
-            Err(error) => {
-                warn!(?error, "invalid command payload in Kafka");
-                if let Err(commit_err) = consumer.commit_message(&message, CommitMode::Async) {
-                    warn!(?commit_err, "failed to commit invalid payload message");
-                }
-                continue;
+        let Some(command) = decode_or_dead_letter::<CommandEnvelope>(
+            state.dead_letters.as_deref(),
+            message.topic(),
+            message.partition(),
+            message.offset(),
+            payload,
+        )
+        .await
+        else {
+            if let Err(commit_err) = consumer.commit_message(&message, CommitMode::Async) {
+                warn!(?commit_err, "failed to commit invalid payload message");
             }
+            continue;
         };
 
         info!(
@@ -335,99 +721,163 @@ async fn run_command_consumer(state: AppState) -> anyhow::Result<()> {
             "game-service received command from Kafka"
         );
 
-        let lock = state.game_lock(&command.game_id).await;
-        let _guard = lock.lock().await;
-        match process_command(&state, command).await {
-            Ok(outcome) => {
-                info!(
-                    game_id = %outcome.game.game_id,
-                    accepted = outcome.accepted,
-                    applied = outcome.applied,
-                    reason = outcome.reason.as_deref().unwrap_or("none"),
-                    result_status = ?outcome.result_status,
-                    current_turn_no = outcome.game.turn_no,
-                    current_player_id = %outcome.game.current_player_id,
-                    "game-service processed command"
-                );
-            }
-            Err(error) => {
-                warn!(?error, "game-service failed to process command");
-            }
+        // The offset is committed by the lane once the command is processed,
+        // so a crash before then redelivers it.
+        lanes
+            .dispatch(ConsumedCommand {
+                command,
+                topic: message.topic().to_string(),
+                partition: message.partition(),
+                offset: message.offset(),
+            })
+            .await;
+    }
+}
+
+fn commit_consumed_offset(consumer: &StreamConsumer, topic: &str, partition: i32, offset: i64) {
+    let mut offsets = TopicPartitionList::new();
+    let committed = offsets
+        .add_partition_offset(topic, partition, Offset::Offset(offset + 1))
+        .and_then(|()| consumer.commit(&offsets, CommitMode::Async));
+    if let Err(error) = committed {
+        warn!(
+            ?error,
+            topic = %topic,
+            partition,
+            offset,
+            "failed to commit consumed command message"
+        );
+    }
+}
+
+async fn process_consumed_command(state: &AppState, command: CommandEnvelope) {
+    let lock = state.game_lock(&command.game_id).await;
+    let _guard = lock.lock().await;
+    match process_command(state, command).await {
+        Ok(outcome) => {
+            info!(
+                game_id = %outcome.game.game_id,
+                accepted = outcome.accepted,
+                applied = outcome.applied,
+                reason = outcome.reason.map(RejectReason::as_str).unwrap_or("none"),
+                result_status = ?outcome.result_status,
+                current_turn_no = outcome.game.turn_no,
+                current_player_id = %outcome.game.current_player_id,
+                "game-service processed command"
+            );
+        }
+        Err(error) => {
+            warn!(?error, "game-service failed to process command");
         }
-        drop(_guard);
+    }
+}
+
+/// Decodes a consumed payload. One that does not parse is republished to the
+/// dead-letter topic (when enabled) so the caller can commit past it without
+/// losing it.
+async fn decode_or_dead_letter<T: DeserializeOwned>(
+    dead_letters: Option<&dyn DeadLetterPublisher>,
+    source_topic: &str,
+    partition: i32,
+    offset: i64,
+    payload: &[u8],
+) -> Option<T> {
+    let error = match serde_json::from_slice::<T>(payload) {
+        Ok(value) => return Some(value),
+        Err(error) => error,
+    };
+    warn!(
+        ?error,
+        topic = source_topic,
+        offset,
+        "invalid command payload in Kafka"
+    );
 
-        if let Err(error) = consumer.commit_message(&message, CommitMode::Async) {
-            warn!(?error, "failed to commit consumed command message");
+    if let Some(dead_letters) = dead_letters {
+        let letter = DeadLetter {
+            source_topic: source_topic.to_string(),
+            partition,
+            offset,
+            error: error.to_string(),
+            payload: payload.to_vec(),
+        };
+        if let Err(error) = dead_letters.publish_dead_letter(&letter).await {
+            warn!(
+                ?error,
+                topic = source_topic,
+                offset,
+                "failed to dead-letter invalid payload"
+            );
         }
     }
+    None
 }
 
 async fn process_command(
     state: &AppState,
     command: CommandEnvelope,
 ) -> Result<ProcessedOutcome, ApiError> {
-    if command.command_type == CommandType::GameStarted {
-        let game = manager_get_game(state, &command.game_id).await?;
-        let event = build_step_event(
-            state,
-            &game,
-            command,
-            StepEventType::StepApplied,
-            ResultStatus::InvalidCommand,
-        );
-        publish_and_persist(state, event, Some("RESERVED_COMMAND_TYPE")).await;
-        return Ok(ProcessedOutcome {
-            accepted: false,
-            applied: false,
-            reason: Some("RESERVED_COMMAND_TYPE".to_string()),
-            game,
-            result_status: ResultStatus::InvalidCommand,
-        });
-    }
-
     if is_duplicate_command(state, &command.game_id, &command.command_id).await {
         let game = manager_get_game(state, &command.game_id).await?;
+        let step_seq = manager_reserve_step_seq(state, &command.game_id).await?;
         let event = build_step_event(
-            state,
             &game,
+            step_seq,
             command,
             StepEventType::StepApplied,
             ResultStatus::DuplicateCommand,
         );
-        publish_and_persist(state, event, Some("DUPLICATE_COMMAND")).await;
+        publish_and_persist(state, event, Some(RejectReason::DuplicateCommand)).await;
         return Ok(ProcessedOutcome {
             accepted: false,
             applied: false,
-            reason: Some("DUPLICATE_COMMAND".to_string()),
+            reason: Some(RejectReason::DuplicateCommand),
             game,
             result_status: ResultStatus::DuplicateCommand,
+            eliminations: Vec::new(),
+            arena_shrink: None,
+            laser_path: None,
+            step_seq,
         });
     }
 
     let before = manager_get_game(state, &command.game_id).await?;
     if before.status != GameStatus::Running {
+        forget_finished_game(state, &before).await;
+        let step_seq = manager_reserve_step_seq(state, &command.game_id).await?;
         let event = build_step_event(
-            state,
             &before,
+            step_seq,
             command,
             StepEventType::StepApplied,
             ResultStatus::InvalidTurn,
         );
-        publish_and_persist(state, event, Some("GAME_NOT_RUNNING")).await;
+        let reason = if before.status == GameStatus::Paused {
+            RejectReason::GamePaused
+        } else {
+            RejectReason::GameNotRunning
+        };
+        publish_and_persist(state, event, Some(reason)).await;
         return Ok(ProcessedOutcome {
             accepted: false,
             applied: false,
-            reason: Some("GAME_NOT_RUNNING".to_string()),
+            reason: Some(reason),
             game: before,
             result_status: ResultStatus::InvalidTurn,
+            eliminations: Vec::new(),
+            arena_shrink: None,
+            laser_path: None,
+            step_seq,
         });
     }
 
-    if command.command_type == CommandType::Timeout {
-        return process_timeout_command(state, command, before).await;
-    }
-
-    process_user_command(state, command, before).await
+    let outcome = if command.command_type == PlayerCommandType::Timeout {
+        process_timeout_command(state, command, before).await?
+    } else {
+        process_user_command(state, command, before).await?
+    };
+    forget_finished_game(state, &outcome.game).await;
+    Ok(outcome)
 }
 
 async fn process_user_command(
@@ -436,20 +886,25 @@ async fn process_user_command(
     before: GameInstanceResponse,
 ) -> Result<ProcessedOutcome, ApiError> {
     if command.turn_no < before.turn_no {
+        let step_seq = manager_reserve_step_seq(state, &command.game_id).await?;
         let event = build_step_event(
-            state,
             &before,
+            step_seq,
             command,
             StepEventType::StepApplied,
             ResultStatus::IgnoredTimeout,
         );
-        publish_and_persist(state, event, Some("LATE_COMMAND_IGNORED")).await;
+        publish_and_persist(state, event, Some(RejectReason::LateCommandIgnored)).await;
         return Ok(ProcessedOutcome {
             accepted: false,
             applied: false,
-            reason: Some("LATE_COMMAND_IGNORED".to_string()),
+            reason: Some(RejectReason::LateCommandIgnored),
             game: before,
             result_status: ResultStatus::IgnoredTimeout,
+            eliminations: Vec::new(),
+            arena_shrink: None,
+            laser_path: None,
+            step_seq,
         });
     }
 
@@ -468,17 +923,20 @@ async fn process_user_command(
         client_sent_at: command.sent_at,
     };
 
-    let mut apply = manager_apply_command(state, &command.game_id, &request).await?;
+    let mut apply = manager_apply_command(state, &command, &request).await?;
 
     // If the command was invalid (not stale turn, wrong player, dead, or game not running),
     // convert it to a speak command so the turn always advances.
     if !apply.applied {
         let is_convertible = !matches!(
-            apply.reason.as_deref(),
-            Some("STALE_TURN_NO")
-                | Some("INVALID_TURN_PLAYER")
-                | Some("PLAYER_DEAD")
-                | Some("GAME_NOT_RUNNING")
+            apply.reason,
+            Some(
+                RejectReason::StaleTurnNo
+                    | RejectReason::InvalidTurnPlayer
+                    | RejectReason::PlayerDead
+                    | RejectReason::PlayerNotInGame
+                    | RejectReason::GameNotRunning
+            )
         );
 
         if is_convertible {
@@ -489,7 +947,7 @@ async fn process_user_command(
                 game_id = %command.game_id,
                 player_id = %player_id,
                 original_command = %original_desc,
-                rejection_reason = apply.reason.as_deref().unwrap_or("unknown"),
+                rejection_reason = apply.reason.map(RejectReason::as_str).unwrap_or("unknown"),
                 converted_speak_text = %speak_text,
                 "converting invalid command to speak"
             );
@@ -497,17 +955,17 @@ async fn process_user_command(
             let speak_request = SubmitCommandRequest {
                 command_id: command.command_id.clone(),
                 player_id: player_id.clone(),
-                command_type: CommandType::Speak,
+                command_type: PlayerCommandType::Speak,
                 direction: None,
                 speak_text: Some(speak_text.clone()),
                 turn_no: command.turn_no,
                 client_sent_at: command.sent_at,
             };
 
-            apply = manager_apply_command(state, &command.game_id, &speak_request).await?;
+            apply = manager_apply_command(state, &command, &speak_request).await?;
 
             // Update the command envelope to reflect the conversion
-            command.command_type = CommandType::Speak;
+            command.command_type = PlayerCommandType::Speak;
             command.speak_text = Some(speak_text);
             command.direction = None;
         }
@@ -518,44 +976,37 @@ async fn process_user_command(
     let (result_status, event_reason) = if apply.applied {
         (ResultStatus::Applied, None)
     } else {
-        match apply.reason.as_deref() {
-            Some("STALE_TURN_NO") => (ResultStatus::IgnoredTimeout, Some("STALE_TURN_NO")),
-            Some("INVALID_TURN_PLAYER") | Some("PLAYER_DEAD") | Some("GAME_NOT_RUNNING") => {
-                (ResultStatus::InvalidTurn, apply.reason.as_deref())
-            }
-            _ => (ResultStatus::InvalidCommand, apply.reason.as_deref()),
+        match apply.reason {
+            Some(RejectReason::StaleTurnNo) => (ResultStatus::IgnoredTimeout, apply.reason),
+            Some(
+                RejectReason::InvalidTurnPlayer
+                | RejectReason::PlayerDead
+                | RejectReason::PlayerNotInGame
+                | RejectReason::GameNotRunning,
+            ) => (ResultStatus::InvalidTurn, apply.reason),
+            _ => (ResultStatus::InvalidCommand, apply.reason),
         }
     };
 
-    let event = build_step_event(
-        state,
+    let step_seq = match apply.step_seq {
+        Some(step_seq) => step_seq,
+        None => manager_reserve_step_seq(state, &command.game_id).await?,
+    };
+    let mut event = build_step_event(
         &after,
+        step_seq,
         command.clone(),
         StepEventType::StepApplied,
         result_status,
     );
+    event.eliminations = apply.eliminations.clone();
+    event.arena_shrink = apply.arena_shrink.clone();
+    event.laser_path = apply.laser_path.clone();
     publish_and_persist(state, event, event_reason).await;
+    publish_follow_up_steps(state, apply.follow_up_steps).await;
 
     if apply.applied {
-        let alive_players = after.state.players.iter().filter(|p| p.alive).count();
-        if after.status != GameStatus::Finished && alive_players == 1 {
-            let finish = manager_finish_game(state, &after.game_id, after.turn_no).await?;
-            if finish.finished {
-                info!(
-                    game_id = %after.game_id,
-                    winner = ?finish.winner_player_id,
-                    turn_no = finish.turn_no,
-                    "game-service marked game as FINISHED"
-                );
-                after = manager_get_game(state, &after.game_id).await?;
-            } else {
-                warn!(
-                    game_id = %after.game_id,
-                    reason = ?finish.reason,
-                    "game-service finish request did not transition game"
-                );
-            }
-        }
+        after = finish_if_last_player_left(state, after).await?;
     }
 
     Ok(ProcessedOutcome {
@@ -564,17 +1015,22 @@ async fn process_user_command(
         reason: apply.reason,
         game: after,
         result_status,
+        eliminations: apply.eliminations,
+        arena_shrink: apply.arena_shrink,
+        laser_path: apply.laser_path,
+        step_seq,
     })
 }
 
 fn format_command_description(command: &CommandEnvelope) -> String {
     let cmd_type = match command.command_type {
-        CommandType::Move => "move",
-        CommandType::Shield => "shield",
-        CommandType::Shoot => "shoot",
-        CommandType::Speak => "speak",
-        CommandType::Timeout => "timeout",
-        CommandType::GameStarted => "game_started",
+        PlayerCommandType::Move => "move",
+        PlayerCommandType::Shield => "shield",
+        PlayerCommandType::Shoot => "shoot",
+        PlayerCommandType::Speak => "speak",
+        PlayerCommandType::Pass => "pass",
+        PlayerCommandType::Charge => "charge",
+        PlayerCommandType::Timeout => "timeout",
     };
 
     let dir = command.direction.map(|d| match d {
@@ -596,20 +1052,25 @@ async fn process_timeout_command(
     before: GameInstanceResponse,
 ) -> Result<ProcessedOutcome, ApiError> {
     if command.turn_no < before.turn_no {
+        let step_seq = manager_reserve_step_seq(state, &command.game_id).await?;
         let event = build_step_event(
-            state,
             &before,
+            step_seq,
             command,
             StepEventType::StepApplied,
             ResultStatus::IgnoredTimeout,
         );
-        publish_and_persist(state, event, Some("LATE_TIMEOUT_IGNORED")).await;
+        publish_and_persist(state, event, Some(RejectReason::LateTimeoutIgnored)).await;
         return Ok(ProcessedOutcome {
             accepted: false,
             applied: false,
-            reason: Some("LATE_TIMEOUT_IGNORED".to_string()),
+            reason: Some(RejectReason::LateTimeoutIgnored),
             game: before,
             result_status: ResultStatus::IgnoredTimeout,
+            eliminations: Vec::new(),
+            arena_shrink: None,
+            laser_path: None,
+            step_seq,
         });
     }
 
@@ -620,15 +1081,15 @@ async fn process_timeout_command(
     let request = SubmitCommandRequest {
         command_id: command.command_id.clone(),
         player_id,
-        command_type: CommandType::Timeout,
+        command_type: PlayerCommandType::Timeout,
         direction: None,
         speak_text: None,
         turn_no: command.turn_no,
         client_sent_at: command.sent_at,
     };
 
-    let apply = manager_apply_command(state, &command.game_id, &request).await?;
-    let after = manager_get_game(state, &command.game_id).await?;
+    let apply = manager_apply_command(state, &command, &request).await?;
+    let mut after = manager_get_game(state, &command.game_id).await?;
     let (event_type, result_status, event_reason) = if apply.applied {
         (
             StepEventType::TimeoutApplied,
@@ -636,22 +1097,37 @@ async fn process_timeout_command(
             None,
         )
     } else {
-        match apply.reason.as_deref() {
-            Some("STALE_TURN_NO") | Some("INVALID_TURN_PLAYER") => (
+        match apply.reason {
+            // A timer firing for a player eliminated since is just as stale.
+            Some(
+                RejectReason::StaleTurnNo
+                | RejectReason::InvalidTurnPlayer
+                | RejectReason::PlayerDead,
+            ) => (
                 StepEventType::StepApplied,
                 ResultStatus::IgnoredTimeout,
-                apply.reason.as_deref(),
+                apply.reason,
             ),
             _ => (
                 StepEventType::StepApplied,
                 ResultStatus::InvalidTurn,
-                apply.reason.as_deref(),
+                apply.reason,
             ),
         }
     };
 
-    let event = build_step_event(state, &after, command, event_type, result_status);
+    let step_seq = match apply.step_seq {
+        Some(step_seq) => step_seq,
+        None => manager_reserve_step_seq(state, &command.game_id).await?,
+    };
+    let mut event = build_step_event(&after, step_seq, command, event_type, result_status);
+    event.arena_shrink = apply.arena_shrink.clone();
     publish_and_persist(state, event, event_reason).await;
+    publish_follow_up_steps(state, apply.follow_up_steps).await;
+
+    // A timeout can still end the game: the arena storm or sudden death may
+    // eliminate someone.
+    after = finish_if_last_player_left(state, after).await?;
 
     Ok(ProcessedOutcome {
         accepted: apply.accepted,
@@ -659,30 +1135,83 @@ async fn process_timeout_command(
         reason: apply.reason,
         game: after,
         result_status,
+        eliminations: Vec::new(),
+        arena_shrink: apply.arena_shrink,
+        laser_path: None,
+        step_seq,
     })
 }
 
-fn build_step_event(
+async fn finish_if_last_player_left(
     state: &AppState,
+    after: GameInstanceResponse,
+) -> Result<GameInstanceResponse, ApiError> {
+    let alive_players = after.state.players.iter().filter(|p| p.alive).count();
+    if after.status == GameStatus::Finished || alive_players != 1 {
+        return Ok(after);
+    }
+
+    let finish = manager_finish_game(state, &after.game_id, after.turn_no).await?;
+    if finish.finished {
+        info!(
+            game_id = %after.game_id,
+            winner = ?finish.winner_player_id,
+            finish_reason = ?finish.finish_reason,
+            turn_no = finish.turn_no,
+            "game-service marked game as FINISHED"
+        );
+        return manager_get_game(state, &after.game_id).await;
+    }
+
+    warn!(
+        game_id = %after.game_id,
+        reason = ?finish.reason,
+        "game-service finish request did not transition game"
+    );
+    Ok(after)
+}
+
+fn build_step_event(
     game: &GameInstanceResponse,
+    step_seq: u64,
     command: CommandEnvelope,
     event_type: StepEventType,
     result_status: ResultStatus,
 ) -> StepEvent {
     StepEvent {
         game_id: game.game_id.clone(),
-        step_seq: state.next_step_seq(),
+        step_seq,
         turn_no: game.turn_no,
         round_no: game.round_no,
         event_type,
         result_status,
-        command: Some(command),
+        // The token is a secret; step events are broadcast to every viewer.
+        command: Some(CommandEnvelope {
+            player_token: None,
+            ..command
+        }),
         state_after: game.state.clone(),
         created_at: Utc::now(),
+        eliminations: Vec::new(),
+        arena_shrink: None,
+        laser_path: None,
+        player_id: None,
+        reject_reason: None,
+        reasoning: None,
+        finish_reason: None,
+    }
+}
+
+/// Publishes the steps game-manager produced alongside a command, after the
+/// command's own step so they follow it in seq order.
+async fn publish_follow_up_steps(state: &AppState, steps: Vec<StepEvent>) {
+    for step in steps {
+        let reason = step.reject_reason;
+        publish_and_persist(state, step, reason).await;
     }
 }
 
-async fn publish_and_persist(state: &AppState, step: StepEvent, reason: Option<&str>) {
+async fn publish_and_persist(state: &AppState, step: StepEvent, reason: Option<RejectReason>) {
     let topic = state.output_topic_for_game(&step.game_id);
     if let Err(error) = publish_step_event(state, &topic, &step).await {
         warn!(game_id = %step.game_id, topic = %topic, error = %error, "failed to publish step event");
@@ -695,23 +1224,35 @@ async fn publish_and_persist(state: &AppState, step: StepEvent, reason: Option<&
 }
 
 async fn publish_step_event(state: &AppState, topic: &str, step: &StepEvent) -> anyhow::Result<()> {
-    let payload = serde_json::to_string(step).context("failed to encode step event")?;
     state
-        .producer
-        .send(
-            FutureRecord::to(topic).key(&step.game_id).payload(&payload),
-            Duration::from_secs(5),
-        )
+        .step_router
+        .publish_step_event(&state.producer, topic, step)
         .await
-        .map_err(|(error, _)| anyhow::anyhow!("Kafka publish failed: {error:?}"))?;
-    Ok(())
 }
 
 async fn persist_step_record(
     store: &DynamoStepStore,
     step: &StepEvent,
-    reason: Option<&str>,
+    reason: Option<RejectReason>,
 ) -> anyhow::Result<()> {
+    store
+        .client
+        .put_item()
+        .table_name(&store.table_name)
+        .set_item(Some(step_record(step, reason)?))
+        .send()
+        .await
+        .context("failed to put item into game_steps table")?;
+    Ok(())
+}
+
+/// The `game_steps` item for `step`. Steps without a command still get a
+/// `system-` command id, source and type so every item has the same key
+/// attributes; `no_command` marks them so reads don't invent one.
+fn step_record(
+    step: &StepEvent,
+    reason: Option<RejectReason>,
+) -> anyhow::Result<HashMap<String, AttributeValue>> {
     let command_id = step
         .command
         .as_ref()
@@ -813,50 +1354,232 @@ async fn persist_step_record(
     {
         item.insert("speak_text".to_string(), AttributeValue::S(value));
     }
-    if let Some(value) = reason {
+    if let Some(command) = step.command.as_ref() {
+        item.insert(
+            "command_turn_no".to_string(),
+            AttributeValue::N(command.turn_no.to_string()),
+        );
+        item.insert(
+            "command_sent_at".to_string(),
+            AttributeValue::S(command.sent_at.to_rfc3339()),
+        );
+    } else {
+        item.insert("no_command".to_string(), AttributeValue::Bool(true));
+    }
+    if let Some(value) = step.player_id.as_ref() {
+        item.insert(
+            "event_player_id".to_string(),
+            AttributeValue::S(value.clone()),
+        );
+    }
+    if let Some(value) = reason.or(step.reject_reason) {
         item.insert(
             "result_reason".to_string(),
-            AttributeValue::S(value.to_string()),
+            AttributeValue::S(value.as_str().to_string()),
+        );
+    }
+    if let Some(value) = step.reasoning.as_ref() {
+        item.insert("reasoning".to_string(), AttributeValue::S(value.clone()));
+    }
+    if let Some(value) = step.finish_reason {
+        item.insert(
+            "finish_reason".to_string(),
+            AttributeValue::S(serde_json::to_string(&value)?.trim_matches('"').to_string()),
+        );
+    }
+    if !step.eliminations.is_empty() {
+        item.insert(
+            "eliminations".to_string(),
+            AttributeValue::S(serde_json::to_string(&step.eliminations)?),
         );
     }
+    if let Some(shrink) = step.arena_shrink.as_ref() {
+        item.insert(
+            "arena_shrink".to_string(),
+            AttributeValue::S(serde_json::to_string(shrink)?),
+        );
+    }
+    if let Some(path) = step.laser_path.as_ref() {
+        item.insert(
+            "laser_path".to_string(),
+            AttributeValue::S(serde_json::to_string(path)?),
+        );
+    }
+    Ok(item)
+}
 
-    store
-        .client
-        .put_item()
-        .table_name(&store.table_name)
-        .set_item(Some(item))
-        .send()
-        .await
-        .context("failed to put item into game_steps table")?;
-    Ok(())
+async fn query_step_records(
+    store: &DynamoStepStore,
+    game_id: &str,
+    from_seq: u64,
+    limit: usize,
+) -> anyhow::Result<Vec<StepEvent>> {
+    let mut steps = Vec::new();
+    let mut start_key = None;
+
+    loop {
+        let output = store
+            .client
+            .query()
+            .table_name(&store.table_name)
+            .key_condition_expression("game_id = :game_id AND step_seq >= :from_seq")
+            .expression_attribute_values(":game_id", AttributeValue::S(game_id.to_string()))
+            .expression_attribute_values(":from_seq", AttributeValue::N(from_seq.to_string()))
+            .scan_index_forward(true)
+            .limit((limit - steps.len()) as i32)
+            .set_exclusive_start_key(start_key)
+            .send()
+            .await
+            .context("failed to query game_steps table")?;
+
+        for item in output.items() {
+            steps.push(step_from_record(item)?);
+        }
+
+        start_key = output.last_evaluated_key().cloned();
+        if start_key.is_none() || steps.len() >= limit {
+            break;
+        }
+    }
+
+    Ok(steps)
+}
+
+/// Rebuild a `StepEvent` from a `game_steps` item built by `step_record`.
+fn step_from_record(item: &HashMap<String, AttributeValue>) -> anyhow::Result<StepEvent> {
+    let string_attr = |key: &str| -> Option<String> {
+        item.get(key).and_then(|value| value.as_s().ok()).cloned()
+    };
+    let number_attr = |key: &str| -> anyhow::Result<u64> {
+        item.get(key)
+            .and_then(|value| value.as_n().ok())
+            .with_context(|| format!("step record missing {key}"))?
+            .parse::<u64>()
+            .with_context(|| format!("step record has invalid {key}"))
+    };
+    let enum_value = |key: &str| string_attr(key).map(serde_json::Value::String);
+    let enum_attr = |key: &str| -> anyhow::Result<serde_json::Value> {
+        enum_value(key).with_context(|| format!("step record missing {key}"))
+    };
+
+    let game_id = string_attr("game_id").context("step record missing game_id")?;
+    let turn_no = number_attr("turn_no")?;
+    let created_at = string_attr("created_at")
+        .context("step record missing created_at")?
+        .parse::<DateTime<Utc>>()
+        .context("step record has invalid created_at")?;
+
+    let no_command = item
+        .get("no_command")
+        .and_then(|value| value.as_bool().ok())
+        .is_some_and(|value| *value);
+    let command = if no_command {
+        None
+    } else {
+        Some(CommandEnvelope {
+            command_id: string_attr("command_id").context("step record missing command_id")?,
+            source: serde_json::from_value(enum_attr("source")?)?,
+            game_id: game_id.clone(),
+            player_id: string_attr("player_id"),
+            command_type: serde_json::from_value(enum_attr("command_type")?)?,
+            direction: string_attr("direction")
+                .map(|value| serde_json::from_value(serde_json::Value::String(value)))
+                .transpose()?,
+            speak_text: string_attr("speak_text"),
+            // Records written before these were stored fall back to the step's.
+            turn_no: item
+                .contains_key("command_turn_no")
+                .then(|| number_attr("command_turn_no"))
+                .transpose()?
+                .unwrap_or(turn_no),
+            sent_at: string_attr("command_sent_at")
+                .map(|value| value.parse::<DateTime<Utc>>())
+                .transpose()
+                .context("step record has invalid command_sent_at")?
+                .unwrap_or(created_at),
+            player_token: None,
+        })
+    };
+
+    Ok(StepEvent {
+        game_id,
+        step_seq: number_attr("step_seq")?,
+        turn_no,
+        round_no: number_attr("round_no")?,
+        event_type: serde_json::from_value(enum_attr("event_type")?)?,
+        result_status: serde_json::from_value(enum_attr("result_status")?)?,
+        command,
+        state_after: serde_json::from_str(
+            &string_attr("state_after").context("step record missing state_after")?,
+        )?,
+        created_at,
+        eliminations: string_attr("eliminations")
+            .map(|value| serde_json::from_str(&value))
+            .transpose()?
+            .unwrap_or_default(),
+        arena_shrink: string_attr("arena_shrink")
+            .map(|value| serde_json::from_str(&value))
+            .transpose()?,
+        laser_path: string_attr("laser_path")
+            .map(|value| serde_json::from_str(&value))
+            .transpose()?,
+        player_id: string_attr("event_player_id"),
+        reject_reason: enum_value("result_reason")
+            .map(serde_json::from_value)
+            .transpose()?,
+        reasoning: string_attr("reasoning"),
+        finish_reason: enum_value("finish_reason")
+            .map(serde_json::from_value)
+            .transpose()?,
+    })
 }
 
 async fn is_duplicate_command(state: &AppState, game_id: &str, command_id: &str) -> bool {
     let mut dedupe = state.dedupe.lock().await;
-    let set = dedupe
-        .entry(game_id.to_string())
-        .or_insert_with(HashSet::new);
-    !set.insert(command_id.to_string())
+    dedupe.check_and_insert(game_id, command_id, Instant::now())
+}
+
+async fn forget_finished_game(state: &AppState, game: &GameInstanceResponse) {
+    if game.status == GameStatus::Finished {
+        state.dedupe.lock().await.clear_game(&game.game_id);
+        state.rate_limiter.lock().await.clear_game(&game.game_id);
+        state.lane_senders.lock().unwrap().remove(&game.game_id);
+        state
+            .step_router
+            .forget_topic(&state.output_topic_for_game(&game.game_id));
+    }
 }
 
+/// Forwards `request` to game-manager along with where `command` came from
+/// and any player token the client presented.
 async fn manager_apply_command(
     state: &AppState,
-    game_id: &str,
+    command: &CommandEnvelope,
     request: &SubmitCommandRequest,
 ) -> Result<ApplyCommandResponse, ApiError> {
     let url = format!(
         "{}/internal/v2/games/{}/commands/apply",
-        state.manager_base_url, game_id
+        state.manager_base_url, command.game_id
     );
 
-    let response = state
-        .client
-        .post(url)
-        .json(request)
+    let mut builder = state.client.post(url).json(request);
+    if let Ok(serde_json::Value::String(source)) = serde_json::to_value(command.source) {
+        builder = builder.header(COMMAND_SOURCE_HEADER, source);
+    }
+    if let Some(token) = command.player_token.as_deref() {
+        builder = builder.header(PLAYER_TOKEN_HEADER, token);
+    }
+    let response = builder
         .send()
         .await
         .map_err(|e| ApiError::bad_gateway(format!("manager apply request failed: {e}")))?;
 
+    if response.status() == StatusCode::UNAUTHORIZED {
+        let body = response.text().await.unwrap_or_else(|_| "".to_string());
+        return Err(ApiError::unauthorized(format!(
+            "manager rejected player token: {body}"
+        )));
+    }
     if !response.status().is_success() {
         let status = response.status();
         let body = response.text().await.unwrap_or_else(|_| "".to_string());
@@ -872,6 +1595,35 @@ async fn manager_apply_command(
         .map_err(|e| ApiError::bad_gateway(format!("invalid manager apply response: {e}")))
 }
 
+/// Takes the game's next `step_seq` from game-manager for a step the
+/// manager did not number itself, like a rejected or duplicate command.
+async fn manager_reserve_step_seq(state: &AppState, game_id: &str) -> Result<u64, ApiError> {
+    let url = format!(
+        "{}/internal/v2/games/{}/steps/reserve-seq",
+        state.manager_base_url, game_id
+    );
+
+    let response =
+        state.client.post(url).send().await.map_err(|e| {
+            ApiError::bad_gateway(format!("manager reserve seq request failed: {e}"))
+        })?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_else(|_| "".to_string());
+        return Err(ApiError::bad_gateway(format!(
+            "manager reserve seq returned {}: {}",
+            status, body
+        )));
+    }
+
+    response
+        .json::<ReserveStepSeqResponse>()
+        .await
+        .map(|reserved| reserved.step_seq)
+        .map_err(|e| ApiError::bad_gateway(format!("invalid manager reserve seq response: {e}")))
+}
+
 async fn manager_get_game(
     state: &AppState,
     game_id: &str,
@@ -942,6 +1694,8 @@ async fn manager_finish_game(
 #[derive(Debug)]
 struct ApiError {
     status: StatusCode,
+    /// Stable machine-readable error code, e.g. `NOT_FOUND`.
+    code: &'static str,
     message: String,
 }
 
@@ -949,6 +1703,15 @@ impl ApiError {
     fn bad_gateway(message: impl Into<String>) -> Self {
         Self {
             status: StatusCode::BAD_GATEWAY,
+            code: "BAD_GATEWAY",
+            message: message.into(),
+        }
+    }
+
+    fn unauthorized(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::UNAUTHORIZED,
+            code: "UNAUTHORIZED",
             message: message.into(),
         }
     }
@@ -956,6 +1719,7 @@ impl ApiError {
     fn not_found(message: impl Into<String>) -> Self {
         Self {
             status: StatusCode::NOT_FOUND,
+            code: "NOT_FOUND",
             message: message.into(),
         }
     }
@@ -963,6 +1727,23 @@ impl ApiError {
     fn bad_request(message: impl Into<String>) -> Self {
         Self {
             status: StatusCode::BAD_REQUEST,
+            code: "BAD_REQUEST",
+            message: message.into(),
+        }
+    }
+
+    fn not_implemented(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::NOT_IMPLEMENTED,
+            code: "NOT_IMPLEMENTED",
+            message: message.into(),
+        }
+    }
+
+    fn too_many_requests(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::TOO_MANY_REQUESTS,
+            code: "TOO_MANY_REQUESTS",
             message: message.into(),
         }
     }
@@ -970,11 +1751,539 @@ impl ApiError {
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
-        warn!(status = %self.status, message = %self.message, "request failed");
+        warn!(status = %self.status, code = self.code, message = %self.message, "request failed");
         (
             self.status,
-            Json(serde_json::json!({"error": self.message})),
+            Json(serde_json::json!({
+                "error": {"code": self.code, "message": self.message}
+            })),
         )
             .into_response()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use axum::http::Uri;
+    use cowboy_common::{GameStateSnapshot, MapData};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn make_step(step_seq: u64) -> StepEvent {
+        StepEvent {
+            game_id: "game-1".to_string(),
+            step_seq,
+            turn_no: step_seq,
+            round_no: 1,
+            event_type: StepEventType::StepApplied,
+            result_status: ResultStatus::Applied,
+            command: None,
+            state_after: GameStateSnapshot {
+                map: MapData {
+                    rows: 1,
+                    cols: 1,
+                    cells: vec![vec![0]],
+                },
+                players: vec![],
+                stats: Vec::new(),
+            },
+            created_at: Utc::now(),
+            eliminations: Vec::new(),
+            arena_shrink: None,
+            laser_path: None,
+            player_id: None,
+            reject_reason: None,
+            reasoning: None,
+            finish_reason: None,
+        }
+    }
+
+    #[test]
+    fn step_records_read_back_as_the_steps_written() {
+        let mut rejected = make_step(4);
+        rejected.result_status = ResultStatus::InvalidCommand;
+        rejected.reject_reason = Some(RejectReason::MoveBlockedByBlock);
+        rejected.command = Some(CommandEnvelope {
+            command_id: "cmd-4".to_string(),
+            source: CommandSource::User,
+            game_id: rejected.game_id.clone(),
+            player_id: Some("p1".to_string()),
+            command_type: PlayerCommandType::Move,
+            direction: Some(Direction::Left),
+            speak_text: None,
+            // The step carries the turn after the command's, and the client
+            // sent it before game-service stamped the step.
+            turn_no: rejected.turn_no - 1,
+            sent_at: rejected.created_at - chrono::Duration::milliseconds(250),
+            player_token: None,
+        });
+        let mut finished = make_step(5);
+        finished.event_type = StepEventType::GameFinished;
+        finished.finish_reason = Some(FinishReason::Forfeit);
+        let mut reasoning = make_step(6);
+        reasoning.event_type = StepEventType::BotReasoning;
+        reasoning.result_status = ResultStatus::NoChange;
+        reasoning.player_id = Some("p2".to_string());
+        reasoning.reasoning = Some("closing in".to_string());
+
+        // Records from before the command's own turn and send time were
+        // stored read back with the step's.
+        let mut legacy = step_record(&rejected, rejected.reject_reason).unwrap();
+        legacy.remove("command_turn_no");
+        legacy.remove("command_sent_at");
+        let legacy_command = step_from_record(&legacy).unwrap().command.unwrap();
+        assert_eq!(legacy_command.turn_no, rejected.turn_no);
+        assert_eq!(legacy_command.sent_at, rejected.created_at);
+
+        for step in [rejected, finished, reasoning] {
+            let record = step_record(&step, step.reject_reason).unwrap();
+            let read_back = step_from_record(&record).unwrap();
+            assert_eq!(
+                serde_json::to_value(&read_back).unwrap(),
+                serde_json::to_value(&step).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn command_dedupe_evicts_oldest_beyond_cap() {
+        let mut dedupe = CommandDedupe::new(Duration::from_secs(60), 2);
+        let now = Instant::now();
+        assert!(!dedupe.check_and_insert("game-1", "cmd-1", now));
+        assert!(!dedupe.check_and_insert("game-1", "cmd-2", now));
+        assert!(dedupe.check_and_insert("game-1", "cmd-2", now));
+        assert!(!dedupe.check_and_insert("game-1", "cmd-3", now));
+        assert_eq!(dedupe.len("game-1"), 2);
+        // cmd-1 was evicted to make room, so it is no longer a duplicate.
+        assert!(!dedupe.check_and_insert("game-1", "cmd-1", now));
+        assert!(dedupe.check_and_insert("game-1", "cmd-3", now));
+    }
+
+    #[test]
+    fn command_dedupe_expires_entries_after_ttl() {
+        let mut dedupe = CommandDedupe::new(Duration::from_secs(60), 16);
+        let start = Instant::now();
+        assert!(!dedupe.check_and_insert("game-1", "cmd-1", start));
+        assert!(!dedupe.check_and_insert("game-2", "cmd-1", start));
+        assert!(dedupe.check_and_insert("game-1", "cmd-1", start + Duration::from_secs(59)));
+
+        let later = start + Duration::from_secs(61);
+        assert!(!dedupe.check_and_insert("game-1", "cmd-1", later));
+        assert_eq!(dedupe.len("game-1"), 1);
+        assert_eq!(dedupe.len("game-2"), 0);
+        assert!(!dedupe.games.contains_key("game-2"));
+    }
+
+    #[test]
+    fn command_dedupe_clears_finished_game() {
+        let mut dedupe = CommandDedupe::new(Duration::from_secs(60), 16);
+        let now = Instant::now();
+        assert!(!dedupe.check_and_insert("game-1", "cmd-1", now));
+        assert!(!dedupe.check_and_insert("game-2", "cmd-1", now));
+
+        dedupe.clear_game("game-1");
+        assert_eq!(dedupe.len("game-1"), 0);
+        assert_eq!(dedupe.len("game-2"), 1);
+        assert!(!dedupe.check_and_insert("game-1", "cmd-1", now));
+    }
+
+    #[test]
+    fn command_rate_limiter_rejects_the_burst_overflow_and_refills() {
+        let mut limiter = CommandRateLimiter::new(3.0);
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter.try_acquire("g1", "p1", start));
+        }
+        assert!(!limiter.try_acquire("g1", "p1", start));
+        // Other players and games have their own buckets.
+        assert!(limiter.try_acquire("g1", "p2", start));
+        assert!(limiter.try_acquire("g2", "p1", start));
+
+        let later = start + Duration::from_millis(340);
+        assert!(limiter.try_acquire("g1", "p1", later));
+        assert!(!limiter.try_acquire("g1", "p1", later));
+
+        let refilled = later + Duration::from_secs(1);
+        for _ in 0..3 {
+            assert!(limiter.try_acquire("g1", "p1", refilled));
+        }
+        assert!(!limiter.try_acquire("g1", "p1", refilled));
+    }
+
+    #[test]
+    fn command_rate_limiter_is_off_at_zero_and_forgets_finished_games() {
+        let mut unlimited = CommandRateLimiter::new(0.0);
+        let now = Instant::now();
+        assert!((0..100).all(|_| unlimited.try_acquire("g1", "p1", now)));
+
+        let mut limiter = CommandRateLimiter::new(1.0);
+        assert!(limiter.try_acquire("g1", "p1", now));
+        assert!(!limiter.try_acquire("g1", "p1", now));
+        limiter.clear_game("g1");
+        assert!(limiter.try_acquire("g1", "p1", now));
+    }
+
+    #[test]
+    fn step_history_query_parses_from_seq_and_limit() {
+        let uri: Uri = "/internal/v2/games/g/steps?from_seq=42&limit=10"
+            .parse()
+            .unwrap();
+        let Query(query) = Query::<StepHistoryQuery>::try_from_uri(&uri).unwrap();
+        assert_eq!(query.start_seq(), 42);
+        assert_eq!(query.limit(), 10);
+    }
+
+    #[test]
+    fn step_history_query_defaults_and_clamps_limit() {
+        let uri: Uri = "/internal/v2/games/g/steps".parse().unwrap();
+        let Query(query) = Query::<StepHistoryQuery>::try_from_uri(&uri).unwrap();
+        assert_eq!(query.start_seq(), 0);
+        assert_eq!(query.limit(), DEFAULT_STEP_HISTORY_LIMIT);
+
+        let uri: Uri = "/internal/v2/games/g/steps?limit=999999".parse().unwrap();
+        let Query(query) = Query::<StepHistoryQuery>::try_from_uri(&uri).unwrap();
+        assert_eq!(query.limit(), MAX_STEP_HISTORY_LIMIT);
+
+        let uri: Uri = "/internal/v2/games/g/steps?limit=0".parse().unwrap();
+        let Query(query) = Query::<StepHistoryQuery>::try_from_uri(&uri).unwrap();
+        assert_eq!(query.limit(), 1);
+    }
+
+    #[test]
+    fn step_history_query_rejects_non_numeric_from_seq() {
+        let uri: Uri = "/internal/v2/games/g/steps?from_seq=abc".parse().unwrap();
+        assert!(Query::<StepHistoryQuery>::try_from_uri(&uri).is_err());
+    }
+
+    #[test]
+    fn filter_steps_from_seq_orders_filters_and_limits() {
+        let steps = vec![make_step(5), make_step(2), make_step(9), make_step(7)];
+        let filtered = filter_steps_from_seq(steps, 5, 2);
+        let seqs: Vec<u64> = filtered.iter().map(|step| step.step_seq).collect();
+        assert_eq!(seqs, vec![5, 7]);
+    }
+
+    #[tokio::test]
+    async fn api_error_body_carries_code_and_message() {
+        let response = ApiError::unauthorized("missing player token").into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({"error": {"code": "UNAUTHORIZED", "message": "missing player token"}})
+        );
+    }
+
+    #[derive(Default)]
+    struct RecordingDeadLetterPublisher {
+        letters: std::sync::Mutex<Vec<DeadLetter>>,
+    }
+
+    #[async_trait]
+    impl DeadLetterPublisher for RecordingDeadLetterPublisher {
+        async fn publish_dead_letter(&self, letter: &DeadLetter) -> anyhow::Result<()> {
+            self.letters.lock().unwrap().push(letter.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn malformed_command_payload_is_dead_lettered() {
+        let publisher = RecordingDeadLetterPublisher::default();
+        let decoded = decode_or_dead_letter::<CommandEnvelope>(
+            Some(&publisher),
+            "game.commands.game-1.v1",
+            2,
+            17,
+            b"{not json",
+        )
+        .await;
+        assert!(decoded.is_none());
+
+        let letters = publisher.letters.lock().unwrap();
+        assert_eq!(letters.len(), 1);
+        let letter = &letters[0];
+        assert_eq!(letter.source_topic, "game.commands.game-1.v1");
+        assert_eq!((letter.partition, letter.offset), (2, 17));
+        assert_eq!(letter.payload, b"{not json".to_vec());
+        assert!(!letter.error.is_empty());
+        assert_eq!(
+            cowboy_common::dead_letter_topic(&letter.source_topic),
+            "game.commands.game-1.v1.dlq"
+        );
+    }
+
+    #[tokio::test]
+    async fn dead_letters_are_skipped_when_disabled_or_payload_is_valid() {
+        let publisher = RecordingDeadLetterPublisher::default();
+        let garbage =
+            decode_or_dead_letter::<CommandEnvelope>(None, "t.v1", 0, 1, b"garbage").await;
+        assert!(garbage.is_none());
+
+        let step = serde_json::to_vec(&make_step(3)).unwrap();
+        let decoded =
+            decode_or_dead_letter::<StepEvent>(Some(&publisher), "t.v1", 0, 2, &step).await;
+        assert_eq!(decoded.map(|step| step.step_seq), Some(3));
+        assert!(publisher.letters.lock().unwrap().is_empty());
+    }
+
+    fn lane_command(game_id: &str, command_id: &str) -> ConsumedCommand {
+        ConsumedCommand {
+            command: CommandEnvelope {
+                command_id: command_id.to_string(),
+                source: CommandSource::User,
+                game_id: game_id.to_string(),
+                player_id: Some("p1".to_string()),
+                command_type: PlayerCommandType::Pass,
+                direction: None,
+                speak_text: None,
+                turn_no: 1,
+                sent_at: Utc::now(),
+                player_token: None,
+            },
+            topic: format!("game.commands.{game_id}.v1"),
+            partition: 0,
+            offset: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn game_lanes_interleave_games_but_keep_each_game_in_order() {
+        let processed = Arc::new(std::sync::Mutex::new(Vec::<(String, String)>::new()));
+        let recorded = processed.clone();
+        let lanes = GameLanes::new(
+            4,
+            LaneSenders::default(),
+            move |consumed: ConsumedCommand| {
+                let recorded = recorded.clone();
+                let command = consumed.command;
+                async move {
+                    // game-1 is the slow one, so game-2 overtakes it.
+                    let delay = if command.game_id == "game-1" { 20 } else { 1 };
+                    tokio::time::sleep(Duration::from_millis(delay)).await;
+                    recorded
+                        .lock()
+                        .unwrap()
+                        .push((command.game_id, command.command_id));
+                }
+            },
+        );
+
+        for n in 0..3 {
+            lanes
+                .dispatch(lane_command("game-1", &format!("slow-{n}")))
+                .await;
+            lanes
+                .dispatch(lane_command("game-2", &format!("fast-{n}")))
+                .await;
+        }
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while processed.lock().unwrap().len() < 6 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("lanes did not drain");
+
+        let processed = processed.lock().unwrap();
+        let order = |game_id: &str| -> Vec<String> {
+            processed
+                .iter()
+                .filter(|(game, _)| game == game_id)
+                .map(|(_, command_id)| command_id.clone())
+                .collect()
+        };
+        assert_eq!(order("game-1"), vec!["slow-0", "slow-1", "slow-2"]);
+        assert_eq!(order("game-2"), vec!["fast-0", "fast-1", "fast-2"]);
+        // The fast game finished before the slow game's first command did.
+        assert_eq!(processed[0].0, "game-2");
+        assert_eq!(processed[5].0, "game-1");
+    }
+
+    #[tokio::test]
+    async fn a_closed_lane_stops_and_a_later_command_starts_a_fresh_one() {
+        let processed = Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
+        let recorded = processed.clone();
+        let senders = LaneSenders::default();
+        let lanes = GameLanes::new(4, senders.clone(), move |consumed: ConsumedCommand| {
+            let recorded = recorded.clone();
+            async move {
+                recorded.lock().unwrap().push(consumed.command.command_id);
+            }
+        });
+
+        // Each running lane task holds its own copy of `process`.
+        let running_lanes = || Arc::strong_count(&processed) - 2;
+        lanes.dispatch(lane_command("game-1", "before")).await;
+        assert_eq!(running_lanes(), 1);
+
+        senders.lock().unwrap().remove("game-1");
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while running_lanes() > 0 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("closed lane kept running");
+
+        lanes.dispatch(lane_command("game-1", "after")).await;
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while processed.lock().unwrap().len() < 2 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("fresh lane did not run");
+
+        assert_eq!(*processed.lock().unwrap(), vec!["before", "after"]);
+        assert!(senders.lock().unwrap().contains_key("game-1"));
+    }
+
+    fn player_json(name: &str, player_id: &str, alive: bool) -> serde_json::Value {
+        serde_json::json!({
+            "player_name": name,
+            "player_id": player_id,
+            "hp": if alive { 3 } else { 0 },
+            "row": 0,
+            "col": if name == "A" { 0 } else { 2 },
+            "shield": "up",
+            "alive": alive,
+        })
+    }
+
+    fn game_json(status: GameStatus, turn_no: u64, b_alive: bool) -> serde_json::Value {
+        serde_json::json!({
+            "game_id": "game-1",
+            "status": status,
+            "map_source": "CUSTOM",
+            "turn_timeout_seconds": 10,
+            "turn_no": turn_no,
+            "round_no": 1,
+            "current_player_id": "p1",
+            "created_at": Utc::now(),
+            "started_at": Utc::now(),
+            "state": {
+                "map": {"rows": 1, "cols": 3, "cells": [[0, 0, 0]]},
+                "players": [player_json("A", "p1", true), player_json("B", "p2", b_alive)],
+            },
+        })
+    }
+
+    /// Serves the game-manager calls a timeout makes: the timeout is applied
+    /// and leaves only p1 alive (as sudden death would, with no arena
+    /// shrink), and finish requests are counted.
+    async fn spawn_manager_with_lone_survivor(finishes: Arc<AtomicUsize>) -> String {
+        let get_finishes = finishes.clone();
+        let app = Router::new()
+            .route(
+                "/internal/v2/games/{game_id}/commands/apply",
+                post(|| async {
+                    Json(serde_json::json!({
+                        "accepted": true,
+                        "applied": true,
+                        "reason": null,
+                        "turn_no": 4,
+                        "round_no": 1,
+                        "current_player_id": "p1",
+                        "status": GameStatus::Running,
+                        "step_seq": 7,
+                    }))
+                }),
+            )
+            .route(
+                "/v2/games/{game_id}",
+                get(move || {
+                    let finished = get_finishes.load(Ordering::SeqCst) > 0;
+                    async move {
+                        let status = if finished {
+                            GameStatus::Finished
+                        } else {
+                            GameStatus::Running
+                        };
+                        Json(game_json(status, 4, false))
+                    }
+                }),
+            )
+            .route(
+                "/internal/v2/games/{game_id}/finish",
+                post(move || {
+                    finishes.fetch_add(1, Ordering::SeqCst);
+                    async {
+                        Json(serde_json::json!({
+                            "finished": true,
+                            "reason": null,
+                            "status": GameStatus::Finished,
+                            "winner_player_id": "p1",
+                            "turn_no": 4,
+                            "round_no": 1,
+                            "current_player_id": "p1",
+                        }))
+                    }
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        format!("http://{addr}")
+    }
+
+    /// An app state pointed at `manager_base_url`, with a producer whose
+    /// publishes fail fast since no broker is listening.
+    fn test_state(manager_base_url: String) -> AppState {
+        let producer: FutureProducer = ClientConfig::new()
+            .set("bootstrap.servers", "127.0.0.1:9")
+            .set("message.timeout.ms", "10")
+            .create()
+            .unwrap();
+        AppState {
+            client: reqwest::Client::new(),
+            manager_base_url,
+            kafka: KafkaSettings {
+                input_topic_prefix: "game.commands".to_string(),
+                output_topic_prefix: "game.output".to_string(),
+                bootstrap_servers: "127.0.0.1:9".to_string(),
+                consumer_group_id: "test".to_string(),
+            },
+            producer,
+            step_router: Arc::new(StepRecordRouter::new(PartitionStrategy::GameId)),
+            dedupe: Arc::new(tokio::sync::Mutex::new(CommandDedupe::new(
+                Duration::from_secs(60),
+                16,
+            ))),
+            rate_limiter: Arc::new(tokio::sync::Mutex::new(CommandRateLimiter::new(0.0))),
+            step_store: None,
+            game_locks: Arc::default(),
+            dead_letters: None,
+            lane_senders: LaneSenders::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn timeout_that_leaves_one_player_finishes_the_game_without_a_shrink() {
+        let finishes = Arc::new(AtomicUsize::new(0));
+        let state = test_state(spawn_manager_with_lone_survivor(finishes.clone()).await);
+        let before: GameInstanceResponse =
+            serde_json::from_value(game_json(GameStatus::Running, 3, true)).unwrap();
+        let command = CommandEnvelope {
+            command_type: PlayerCommandType::Timeout,
+            source: CommandSource::Timer,
+            turn_no: 3,
+            ..lane_command("game-1", "timeout-3").command
+        };
+
+        let outcome = process_timeout_command(&state, command, before)
+            .await
+            .unwrap();
+
+        assert!(outcome.applied);
+        assert!(outcome.arena_shrink.is_none());
+        assert_eq!(finishes.load(Ordering::SeqCst), 1);
+        assert_eq!(outcome.game.status, GameStatus::Finished);
+    }
+}
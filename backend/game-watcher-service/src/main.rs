@@ -13,39 +13,67 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use std::{net::SocketAddr, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    convert::Infallible,
+    net::SocketAddr,
+    sync::Arc,
+    time::Duration,
+};
 
 use anyhow::Context;
+use async_trait::async_trait;
 use axum::{
     Json, Router,
+    body::Body,
     extract::{Path, Query, State, WebSocketUpgrade},
-    http::StatusCode,
+    http::{StatusCode, header},
     response::{IntoResponse, Response},
     routing::get,
 };
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use cowboy_common::{
-    CommandType, GameInstanceResponse, GameStatus, SnapshotResponse, StepEvent, StepEventType,
+    Backoff, DeadLetter, Elimination, GameInstanceResponse, GameStatus, MapData, PlayerCommandType,
+    PlayerState, PlayerStats, SnapshotResponse, StepEvent, StepEventType,
+    kafka::{DeadLetterPublisher, KafkaDeadLetterPublisher},
+    turn_seconds_remaining,
 };
 use lambda_http::run as lambda_run;
 use rdkafka::{
     Message,
     config::ClientConfig,
     consumer::{CommitMode, Consumer, StreamConsumer},
+    producer::FutureProducer,
 };
 use serde::{Deserialize, Serialize};
 use tokio::{
-    sync::{broadcast, mpsc},
-    time::{MissedTickBehavior, interval},
+    sync::{Mutex, broadcast, mpsc},
+    time::{Instant, Interval, MissedTickBehavior, interval, interval_at},
 };
+use tokio_stream::wrappers::ReceiverStream;
 use tower_http::{cors::CorsLayer, trace::TraceLayer};
 use tracing::{info, warn};
 
 #[derive(Clone)]
 struct AppState {
     client: reqwest::Client,
-    manager_base_url: String,
+    snapshot_source: Arc<dyn SnapshotSource>,
+    /// Shared per-game snapshots so many viewers of one game share one upstream poll.
+    snapshot_cache: Arc<SnapshotCache>,
+    /// game-service base URL used to replay step history on reconnect.
+    game_service_base_url: String,
     watch_events_tx: broadcast::Sender<WatcherBroadcastEvent>,
+    /// Every consumed step, untouched, for `/raw-stream` subscribers.
+    raw_steps_tx: broadcast::Sender<Arc<StepEvent>>,
+    /// How often idle sockets get a PING frame so proxies keep them open.
+    heartbeat_period: Duration,
+    /// Which games the consumer saw running and which it saw finish; steps
+    /// after a broadcast GAME_FINISHED are dropped.
+    game_phases: Arc<std::sync::Mutex<GamePhases>>,
+    /// Games seen running, for the roster `/v2/stream` opens with.
+    active_games: Arc<ActiveGames>,
+    /// Open watch connections per game.
+    viewers: Arc<ViewerCounts>,
 }
 
 #[derive(Debug, Clone)]
@@ -54,6 +82,287 @@ struct WatcherBroadcastEvent {
     ws_event_type: String,
     ws_payload: String,
     snapshot: Option<SnapshotResponse>,
+    eliminations: Vec<Elimination>,
+}
+
+/// One row of the aggregate stream's roster.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+struct ActiveGame {
+    game_id: String,
+    status: GameStatus,
+    turn_no: u64,
+    alive_players: usize,
+}
+
+/// How long the consumer remembers a game after its last step.
+const GAME_PHASE_TTL: Duration = Duration::from_secs(3600);
+
+/// What the consumer saw of a game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GamePhase {
+    /// A step arrived while the game was still running.
+    Live,
+    /// Its GAME_FINISHED step was broadcast.
+    Finished,
+}
+
+/// The phase of every game the consumer saw a step for. Entries expire
+/// `ttl` after the game's last step, so a long-running watcher does not keep
+/// every game it ever saw.
+struct GamePhases {
+    ttl: Duration,
+    games: HashMap<String, (Instant, GamePhase)>,
+}
+
+impl GamePhases {
+    fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            games: HashMap::new(),
+        }
+    }
+
+    fn phase(&self, game_id: &str) -> Option<GamePhase> {
+        self.games.get(game_id).map(|(_, phase)| *phase)
+    }
+
+    /// Records `phase` for `game_id`; a finished game stays finished.
+    fn record(&mut self, game_id: &str, phase: GamePhase, now: Instant) {
+        let ttl = self.ttl;
+        self.games
+            .retain(|_, (seen_at, _)| now.saturating_duration_since(*seen_at) < ttl);
+        let entry = self
+            .games
+            .entry(game_id.to_string())
+            .or_insert((now, phase));
+        entry.0 = now;
+        if entry.1 != GamePhase::Finished {
+            entry.1 = phase;
+        }
+    }
+}
+
+/// The games the consumer has seen and not yet seen finish, kept current
+/// from the snapshots broadcast steps carry.
+#[derive(Default)]
+struct ActiveGames {
+    games: std::sync::Mutex<HashMap<String, ActiveGame>>,
+}
+
+impl ActiveGames {
+    fn record(&self, event: &WatcherBroadcastEvent) {
+        let mut games = self.games.lock().expect("active games lock poisoned");
+        let finished = event.ws_event_type == "GAME_FINISHED"
+            || event
+                .snapshot
+                .as_ref()
+                .is_some_and(|snapshot| snapshot.status == GameStatus::Finished);
+        if finished {
+            games.remove(&event.game_id);
+            return;
+        }
+        if let Some(snapshot) = event.snapshot.as_ref() {
+            games.insert(
+                event.game_id.clone(),
+                ActiveGame {
+                    game_id: event.game_id.clone(),
+                    status: snapshot.status,
+                    turn_no: snapshot.turn_no,
+                    alive_players: snapshot.state.players.iter().filter(|p| p.alive).count(),
+                },
+            );
+        }
+    }
+
+    /// Active games ordered by id.
+    fn roster(&self) -> Vec<ActiveGame> {
+        let games = self.games.lock().expect("active games lock poisoned");
+        let mut roster: Vec<ActiveGame> = games.values().cloned().collect();
+        roster.sort_by(|a, b| a.game_id.cmp(&b.game_id));
+        roster
+    }
+}
+
+/// How many watch connections each game has open. Every connection holds a
+/// `ViewerGuard`, so the count comes back down however the socket ends,
+/// including when its task is dropped mid-await.
+#[derive(Default)]
+struct ViewerCounts {
+    counts: std::sync::Mutex<HashMap<String, usize>>,
+}
+
+impl ViewerCounts {
+    fn join(self: &Arc<Self>, game_id: &str) -> ViewerGuard {
+        *self
+            .counts
+            .lock()
+            .expect("viewer counts lock poisoned")
+            .entry(game_id.to_string())
+            .or_insert(0) += 1;
+        ViewerGuard {
+            counts: Arc::clone(self),
+            game_id: game_id.to_string(),
+        }
+    }
+
+    fn count(&self, game_id: &str) -> usize {
+        self.counts
+            .lock()
+            .expect("viewer counts lock poisoned")
+            .get(game_id)
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+/// One open watch connection; dropping it leaves the game's count.
+struct ViewerGuard {
+    counts: Arc<ViewerCounts>,
+    game_id: String,
+}
+
+impl Drop for ViewerGuard {
+    fn drop(&mut self) {
+        let mut counts = self
+            .counts
+            .counts
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(count) = counts.get_mut(&self.game_id) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                counts.remove(&self.game_id);
+            }
+        }
+    }
+}
+
+/// A step off the output topics, with the `step_seq`s the consumer never saw
+/// just before it, if any.
+struct ConsumedStep {
+    step: StepEvent,
+    gap: Option<SeqGap>,
+}
+
+/// `step_seq`s missing from a game's output, inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SeqGap {
+    missing_from: u64,
+    missing_to: u64,
+}
+
+/// The highest `step_seq` consumed per game, to notice skipped steps after a
+/// consumer restart or a lost message.
+#[derive(Default)]
+struct StepSeqTracker {
+    last_seen: HashMap<String, u64>,
+}
+
+impl StepSeqTracker {
+    /// Records `step` and returns the steps skipped before it. The first
+    /// step of a game only starts tracking. A step at or below the last one
+    /// seen, a redelivery or late arrival, never moves the tracker back.
+    fn observe(&mut self, step: &StepEvent) -> Option<SeqGap> {
+        // Bot markers repeat the current step_seq and are not steps.
+        if is_bot_marker_step(step) {
+            return None;
+        }
+        let last = self
+            .last_seen
+            .entry(step.game_id.clone())
+            .or_insert(step.step_seq);
+        let gap = (step.step_seq > *last + 1).then(|| SeqGap {
+            missing_from: *last + 1,
+            missing_to: step.step_seq - 1,
+        });
+        *last = (*last).max(step.step_seq);
+        gap
+    }
+
+    fn forget(&mut self, game_id: &str) {
+        self.last_seen.remove(game_id);
+    }
+}
+
+#[async_trait]
+trait SnapshotSource: Send + Sync {
+    async fn fetch_snapshot(&self, game_id: &str) -> Result<SnapshotResponse, ApiError>;
+}
+
+struct ManagerSnapshotSource {
+    client: reqwest::Client,
+    manager_base_url: String,
+}
+
+type SnapshotSlot = Arc<Mutex<Option<(Instant, SnapshotResponse)>>>;
+
+/// How long a game's cache slot survives without being asked for.
+const SNAPSHOT_SLOT_IDLE: Duration = Duration::from_secs(300);
+
+struct SnapshotCache {
+    ttl: Duration,
+    idle_after: Duration,
+    /// Each game's slot and when it was last asked for.
+    slots: std::sync::Mutex<HashMap<String, (Instant, SnapshotSlot)>>,
+}
+
+impl SnapshotCache {
+    fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            idle_after: SNAPSHOT_SLOT_IDLE,
+            slots: std::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the game's slot, dropping slots of other games nobody asked
+    /// for within `idle_after` and nobody is fetching into.
+    fn slot(&self, game_id: &str) -> SnapshotSlot {
+        let now = Instant::now();
+        let mut slots = self.slots.lock().expect("snapshot cache lock poisoned");
+        slots.retain(|_, (used_at, slot)| {
+            now.saturating_duration_since(*used_at) < self.idle_after || Arc::strong_count(slot) > 1
+        });
+        let (used_at, slot) = slots
+            .entry(game_id.to_string())
+            .or_insert_with(|| (now, SnapshotSlot::default()));
+        *used_at = now;
+        slot.clone()
+    }
+
+    /// Returns the cached snapshot while it is younger than the TTL, otherwise
+    /// fetches upstream. The per-game slot lock is held across the fetch so
+    /// concurrent callers wait for the in-flight request instead of issuing
+    /// their own. `force` skips the freshness check and replaces the entry.
+    /// A finished game's snapshot no longer changes, so once cached it is
+    /// served for as long as the slot lives, forced or not.
+    async fn get_or_fetch(
+        &self,
+        source: &dyn SnapshotSource,
+        game_id: &str,
+        force: bool,
+    ) -> Result<SnapshotResponse, ApiError> {
+        let slot = self.slot(game_id);
+        let mut entry = slot.lock().await;
+
+        if let Some((fetched_at, snapshot)) = entry.as_ref()
+            && (snapshot.status == GameStatus::Finished
+                || (!force && fetched_at.elapsed() < self.ttl))
+        {
+            return Ok(snapshot.clone());
+        }
+
+        match source.fetch_snapshot(game_id).await {
+            Ok(snapshot) => {
+                *entry = Some((Instant::now(), snapshot.clone()));
+                Ok(snapshot)
+            }
+            Err(error) => {
+                *entry = None;
+                Err(error)
+            }
+        }
+    }
 }
 
 #[tokio::main]
@@ -66,12 +375,37 @@ async fn main() -> anyhow::Result<()> {
         .init();
 
     let (watch_events_tx, _) = broadcast::channel(512);
+    let (raw_steps_tx, _) = broadcast::channel(512);
+    let client = reqwest::Client::new();
     let state = AppState {
-        client: reqwest::Client::new(),
-        manager_base_url: std::env::var("GAME_MANAGER_BASE_URL")
+        client: client.clone(),
+        snapshot_source: Arc::new(ManagerSnapshotSource {
+            client,
+            manager_base_url: std::env::var("GAME_MANAGER_BASE_URL")
+                .ok()
+                .unwrap_or_else(|| "http://game-manager-service:8081".to_string()),
+        }),
+        snapshot_cache: Arc::new(SnapshotCache::new(Duration::from_millis(
+            std::env::var("WATCHER_SNAPSHOT_CACHE_TTL_MS")
+                .ok()
+                .and_then(|value| value.parse::<u64>().ok())
+                .unwrap_or(500),
+        ))),
+        game_service_base_url: std::env::var("GAME_SERVICE_BASE_URL")
             .ok()
-            .unwrap_or_else(|| "http://game-manager-service:8081".to_string()),
+            .unwrap_or_else(|| "http://game-service:8084".to_string()),
         watch_events_tx,
+        raw_steps_tx,
+        heartbeat_period: Duration::from_millis(
+            std::env::var("WATCHER_HEARTBEAT_MS")
+                .ok()
+                .and_then(|value| value.parse::<u64>().ok())
+                .filter(|value| *value > 0)
+                .unwrap_or(20_000),
+        ),
+        game_phases: Arc::new(std::sync::Mutex::new(GamePhases::new(GAME_PHASE_TTL))),
+        active_games: Arc::new(ActiveGames::default()),
+        viewers: Arc::default(),
     };
 
     let app = build_router(state.clone());
@@ -95,15 +429,21 @@ async fn main() -> anyhow::Result<()> {
     let bind_addr = parse_bind_addr("WATCHER_SERVICE_BIND", "0.0.0.0:8083")?;
     info!(%bind_addr, "game-watcher-service listening");
     let listener = tokio::net::TcpListener::bind(bind_addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(cowboy_common::shutdown_signal())
+        .await?;
+    info!("game-watcher-service shut down");
     Ok(())
 }
 
 fn build_router(state: AppState) -> Router {
     Router::new()
         .route("/health", get(health))
+        .route("/v2/stream", get(aggregate_stream_handler))
         .route("/v2/games/{game_id}/snapshot", get(snapshot_handler))
         .route("/v2/games/{game_id}/stream", get(stream_handler))
+        .route("/v2/games/{game_id}/events", get(events_handler))
+        .route("/v2/games/{game_id}/raw-stream", get(raw_stream_handler))
         .with_state(state)
         .layer(CorsLayer::permissive())
         .layer(TraceLayer::new_for_http())
@@ -147,6 +487,44 @@ async fn snapshot_handler(
 #[derive(Debug, Deserialize)]
 struct StreamQuery {
     from_turn_no: Option<u64>,
+    /// Comma-separated event types to receive, e.g. `shoot,game_finished`.
+    events: Option<String>,
+    /// After the first full SNAPSHOT, send SNAPSHOT_DELTA frames instead.
+    delta: Option<bool>,
+}
+
+/// Event types a watcher subscribed to via `?events=`; `None` means all.
+#[derive(Debug, Clone, Default)]
+struct EventFilter(Option<HashSet<String>>);
+
+impl EventFilter {
+    fn parse(raw: Option<&str>) -> Self {
+        let types: HashSet<String> = raw
+            .unwrap_or_default()
+            .split(',')
+            .map(|value| value.trim().to_ascii_uppercase())
+            .filter(|value| !value.is_empty())
+            .collect();
+        if types.is_empty() {
+            Self(None)
+        } else {
+            Self(Some(types))
+        }
+    }
+
+    /// CONNECTED and heartbeat PINGs always pass so the connection stays usable.
+    /// SNAPSHOT_DELTA frames are snapshots too, so asking for SNAPSHOT covers
+    /// them under `?delta=true`.
+    fn allows(&self, event_type: &str) -> bool {
+        match &self.0 {
+            None => true,
+            Some(types) => {
+                matches!(event_type, "CONNECTED" | "PING")
+                    || types.contains(event_type)
+                    || (event_type == "SNAPSHOT_DELTA" && types.contains("SNAPSHOT"))
+            }
+        }
+    }
 }
 
 async fn stream_handler(
@@ -155,8 +533,16 @@ async fn stream_handler(
     Path(game_id): Path<String>,
     Query(query): Query<StreamQuery>,
 ) -> impl IntoResponse {
+    let event_filter = EventFilter::parse(query.events.as_deref());
     ws.on_upgrade(move |socket| {
-        handle_socket(socket, state, game_id, query.from_turn_no.unwrap_or(0))
+        handle_socket(
+            socket,
+            state,
+            game_id,
+            query.from_turn_no.unwrap_or(0),
+            event_filter,
+            query.delta.unwrap_or(false),
+        )
     })
 }
 
@@ -165,15 +551,11 @@ async fn handle_socket(
     state: AppState,
     game_id: String,
     from_turn_no: u64,
+    event_filter: EventFilter,
+    delta: bool,
 ) {
-    let connected = serde_json::json!({
-        "event_type": "CONNECTED",
-        "game_id": game_id,
-        "from_turn_no": from_turn_no,
-        "connected_at": Utc::now(),
-        "message": "watch stream connected"
-    })
-    .to_string();
+    let _viewer = state.viewers.join(&game_id);
+    let connected = connected_event_payload(&game_id, from_turn_no);
 
     if send_ws_event(&mut socket, &game_id, "CONNECTED", connected, None)
         .await
@@ -183,57 +565,126 @@ async fn handle_socket(
     }
 
     let mut watch_events_rx = state.watch_events_tx.subscribe();
+
+    if from_turn_no > 0
+        && let Ok(snapshot) = fetch_snapshot(&state, &game_id).await
+        && needs_replay(from_turn_no, snapshot.turn_no)
+        && replay_missed_steps(
+            &mut socket,
+            &state,
+            &game_id,
+            from_turn_no,
+            snapshot.turn_no,
+            &event_filter,
+        )
+        .await
+        .is_err()
+    {
+        return;
+    }
+
     let mut last_sent_turn_no = from_turn_no;
     let mut last_status: Option<GameStatus> = None;
     let mut sent_initial = false;
+    // The last full or rebuilt snapshot this watcher holds, for deltas.
+    let mut delta_base: Option<SnapshotResponse> = None;
+    let mut last_viewers: Option<usize> = None;
 
     let mut ticker = interval(Duration::from_millis(800));
     ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut heartbeat = heartbeat_interval(state.heartbeat_period);
 
     loop {
         tokio::select! {
+            ping = next_heartbeat(&mut heartbeat) => {
+                if send_ws_event(&mut socket, &game_id, "PING", ping, None)
+                    .await
+                    .is_err()
+                {
+                    break;
+                }
+            }
+            incoming = socket.recv() => {
+                match incoming {
+                    Some(Ok(axum::extract::ws::Message::Ping(data))) => {
+                        if socket
+                            .send(axum::extract::ws::Message::Pong(data))
+                            .await
+                            .is_err()
+                        {
+                            break;
+                        }
+                    }
+                    Some(Ok(axum::extract::ws::Message::Close(_))) | None => break,
+                    Some(Ok(_)) => {}
+                    Some(Err(error)) => {
+                        warn!(game_id = %game_id, ?error, "watcher socket receive error");
+                        break;
+                    }
+                }
+            }
             _ = ticker.tick() => {
+                let viewers = state.viewers.count(&game_id);
+                if last_viewers != Some(viewers) {
+                    last_viewers = Some(viewers);
+                    if event_filter.allows("VIEWERS")
+                        && send_ws_event(
+                            &mut socket,
+                            &game_id,
+                            "VIEWERS",
+                            viewers_event_payload(&game_id, viewers),
+                            None,
+                        )
+                        .await
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
                 match fetch_snapshot(&state, &game_id).await {
                     Ok(snapshot) => {
-                        let status_changed = match last_status {
-                            Some(previous) => previous != snapshot.status,
-                            None => true,
-                        };
-                        let turn_advanced = snapshot.turn_no > last_sent_turn_no;
-                        let should_send = !sent_initial || turn_advanced || status_changed;
-
-                        if should_send {
-                            let event_type = if status_changed && last_status.is_some() {
-                                if snapshot.status == GameStatus::Running {
-                                    "GAME_STARTED"
-                                } else if snapshot.status == GameStatus::Finished {
-                                    "GAME_FINISHED"
-                                } else {
-                                    "SNAPSHOT"
-                                }
-                            } else {
-                                "SNAPSHOT"
+                        if let Some(event_type) = snapshot_push_event_type(
+                            last_status,
+                            last_sent_turn_no,
+                            sent_initial,
+                            &snapshot,
+                        ) {
+                            let (event_type, event) = match delta_base.as_ref() {
+                                Some(base) if delta && event_type == "SNAPSHOT" => (
+                                    "SNAPSHOT_DELTA",
+                                    Some(diff_snapshot(base, &snapshot))
+                                        .filter(|diff| !diff.is_empty())
+                                        .map(|diff| delta_event_payload(&game_id, &diff)),
+                                ),
+                                _ => (
+                                    event_type,
+                                    Some(snapshot_event_payload(
+                                        event_type,
+                                        &game_id,
+                                        &snapshot,
+                                        viewers,
+                                    )),
+                                ),
                             };
 
-                            let event = serde_json::json!({
-                                "event_type": event_type,
-                                "game_id": game_id,
-                                "snapshot": &snapshot,
-                                "emitted_at": Utc::now()
-                            })
-                            .to_string();
-
-                            if send_ws_event(
-                                &mut socket,
-                                &game_id,
-                                event_type,
-                                event,
-                                Some(&snapshot),
-                            )
-                                .await
-                                .is_err()
+                            if let Some(event) = event
+                                && event_filter.allows(event_type)
                             {
-                                break;
+                                if send_ws_event(
+                                    &mut socket,
+                                    &game_id,
+                                    event_type,
+                                    event,
+                                    Some(&snapshot),
+                                )
+                                    .await
+                                    .is_err()
+                                {
+                                    break;
+                                }
+                                if delta {
+                                    delta_base = Some(snapshot.clone());
+                                }
                             }
 
                             sent_initial = true;
@@ -243,17 +694,12 @@ async fn handle_socket(
                         last_status = Some(snapshot.status);
                     }
                     Err(error) => {
-                        let payload = serde_json::json!({
-                            "event_type": "ERROR",
-                            "game_id": game_id,
-                            "error": error.message,
-                            "at": Utc::now()
-                        })
-                        .to_string();
+                        let payload = error_event_payload(&game_id, &error.message);
 
-                        if send_ws_event(&mut socket, &game_id, "ERROR", payload, None)
-                            .await
-                            .is_err()
+                        if event_filter.allows("ERROR")
+                            && send_ws_event(&mut socket, &game_id, "ERROR", payload, None)
+                                .await
+                                .is_err()
                         {
                             break;
                         }
@@ -273,31 +719,649 @@ async fn handle_socket(
                             sent_initial = true;
                         }
 
-                        if send_ws_event(
-                            &mut socket,
-                            &game_id,
-                            &ev.ws_event_type,
-                            ev.ws_payload,
-                            ev.snapshot.as_ref(),
-                        )
+                        if event_filter.allows(&ev.ws_event_type) {
+                            if send_ws_event(
+                                &mut socket,
+                                &game_id,
+                                &ev.ws_event_type,
+                                ev.ws_payload,
+                                ev.snapshot.as_ref(),
+                            )
+                                .await
+                                .is_err()
+                            {
+                                break;
+                            }
+                            if delta && let Some(snapshot) = ev.snapshot {
+                                delta_base = Some(snapshot);
+                            }
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!(game_id = %game_id, skipped, "watcher stream lagged broadcast events");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+async fn aggregate_stream_handler(
+    State(state): State<AppState>,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| handle_aggregate_socket(socket, state))
+}
+
+/// Streams the cross-game events of every game on one connection: a ROSTER
+/// of active games first, then game starts, finishes and eliminations, each
+/// tagged with its game_id. Nothing here polls snapshots.
+async fn handle_aggregate_socket(mut socket: axum::extract::ws::WebSocket, state: AppState) {
+    let mut watch_events_rx = state.watch_events_tx.subscribe();
+    let roster = roster_event_payload(&state.active_games.roster());
+    if send_ws_event(&mut socket, "*", "ROSTER", roster, None)
+        .await
+        .is_err()
+    {
+        return;
+    }
+    let mut heartbeat = heartbeat_interval(state.heartbeat_period);
+
+    loop {
+        tokio::select! {
+            ping = next_heartbeat(&mut heartbeat) => {
+                if send_ws_event(&mut socket, "*", "PING", ping, None)
+                    .await
+                    .is_err()
+                {
+                    break;
+                }
+            }
+            incoming = socket.recv() => {
+                match incoming {
+                    Some(Ok(axum::extract::ws::Message::Ping(data))) => {
+                        if socket
+                            .send(axum::extract::ws::Message::Pong(data))
                             .await
                             .is_err()
                         {
                             break;
                         }
                     }
-                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
-                        warn!(game_id = %game_id, skipped, "watcher stream lagged broadcast events");
+                    Some(Ok(axum::extract::ws::Message::Close(_))) | None => break,
+                    Some(Ok(_)) => {}
+                    Some(Err(error)) => {
+                        warn!(?error, "aggregate stream socket receive error");
+                        break;
                     }
-                    Err(broadcast::error::RecvError::Closed) => {
+                }
+            }
+            frame = next_aggregate_frame(&mut watch_events_rx) => {
+                let Some((game_id, event_type, payload)) = frame else {
+                    break;
+                };
+                if send_ws_event(&mut socket, &game_id, &event_type, payload, None)
+                    .await
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Waits for the next broadcast event that matters across games and returns
+/// its game_id, event type and aggregate payload. `None` once the broadcast
+/// channel closes.
+async fn next_aggregate_frame(
+    watch_events_rx: &mut broadcast::Receiver<WatcherBroadcastEvent>,
+) -> Option<(String, String, String)> {
+    loop {
+        match watch_events_rx.recv().await {
+            Ok(event) => {
+                if let Some((event_type, payload)) = aggregate_event_payload(&event) {
+                    return Some((event.game_id, event_type, payload));
+                }
+            }
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                warn!(skipped, "aggregate stream lagged broadcast events");
+            }
+            Err(broadcast::error::RecvError::Closed) => return None,
+        }
+    }
+}
+
+/// The slim aggregate frame for a game start, a game end, or any step that
+/// eliminated someone; other events stay on the per-game streams.
+fn aggregate_event_payload(event: &WatcherBroadcastEvent) -> Option<(String, String)> {
+    let event_type = match event.ws_event_type.as_str() {
+        "GAME_STARTED" | "GAME_FINISHED" => event.ws_event_type.clone(),
+        _ if !event.eliminations.is_empty() => "ELIMINATION".to_string(),
+        _ => return None,
+    };
+    let mut payload = serde_json::json!({
+        "event_type": event_type,
+        "game_id": event.game_id,
+        "emitted_at": Utc::now(),
+    });
+    let obj = payload.as_object_mut().unwrap();
+    if let Some(snapshot) = event.snapshot.as_ref() {
+        obj.insert("turn_no".into(), serde_json::json!(snapshot.turn_no));
+        obj.insert("status".into(), serde_json::json!(snapshot.status));
+    }
+    if !event.eliminations.is_empty() {
+        obj.insert("eliminations".into(), serde_json::json!(event.eliminations));
+    }
+    Some((event_type, payload.to_string()))
+}
+
+fn roster_event_payload(roster: &[ActiveGame]) -> String {
+    serde_json::json!({
+        "event_type": "ROSTER",
+        "games": roster,
+        "emitted_at": Utc::now(),
+    })
+    .to_string()
+}
+
+async fn raw_stream_handler(
+    State(state): State<AppState>,
+    ws: WebSocketUpgrade,
+    Path(game_id): Path<String>,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| handle_raw_socket(socket, state, game_id))
+}
+
+/// Streams every step of one game exactly as it was consumed from Kafka, one
+/// JSON `StepEvent` per text frame. Keep-alives use protocol-level pings so
+/// the stream carries nothing but steps.
+async fn handle_raw_socket(
+    mut socket: axum::extract::ws::WebSocket,
+    state: AppState,
+    game_id: String,
+) {
+    let mut raw_steps_rx = state.raw_steps_tx.subscribe();
+    let mut heartbeat = heartbeat_interval(state.heartbeat_period);
+
+    loop {
+        tokio::select! {
+            _ = heartbeat.tick() => {
+                if socket
+                    .send(axum::extract::ws::Message::Ping(Vec::new().into()))
+                    .await
+                    .is_err()
+                {
+                    break;
+                }
+            }
+            incoming = socket.recv() => {
+                match incoming {
+                    Some(Ok(axum::extract::ws::Message::Ping(data))) => {
+                        if socket
+                            .send(axum::extract::ws::Message::Pong(data))
+                            .await
+                            .is_err()
+                        {
+                            break;
+                        }
+                    }
+                    Some(Ok(axum::extract::ws::Message::Close(_))) | None => break,
+                    Some(Ok(_)) => {}
+                    Some(Err(error)) => {
+                        warn!(game_id = %game_id, ?error, "raw stream socket receive error");
                         break;
                     }
                 }
             }
+            step = raw_steps_rx.recv() => {
+                match step {
+                    Ok(step) if step.game_id == game_id => {
+                        if send_ws_event(&mut socket, &game_id, "RAW_STEP", raw_step_frame(&step), None)
+                            .await
+                            .is_err()
+                        {
+                            break;
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!(game_id = %game_id, skipped, "raw stream lagged broadcast steps");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        }
+    }
+}
+
+fn raw_step_frame(step: &StepEvent) -> String {
+    serde_json::to_string(step).unwrap_or_else(|error| {
+        serde_json::json!({"event_type": "ERROR", "message": format!("failed to encode step: {error}")})
+            .to_string()
+    })
+}
+
+/// Hands a consumed step to raw-stream subscribers before any classification.
+fn fan_out_raw_step(raw_steps_tx: &broadcast::Sender<Arc<StepEvent>>, step: &StepEvent) {
+    if raw_steps_tx.receiver_count() > 0 && raw_steps_tx.send(Arc::new(step.clone())).is_err() {
+        warn!(game_id = %step.game_id, "failed to fan out raw step to subscribers");
+    }
+}
+
+/// Decide whether a polled snapshot should be pushed to a watcher, and as
+/// which event type.
+fn snapshot_push_event_type(
+    last_status: Option<GameStatus>,
+    last_sent_turn_no: u64,
+    sent_initial: bool,
+    snapshot: &SnapshotResponse,
+) -> Option<&'static str> {
+    let status_changed = match last_status {
+        Some(previous) => previous != snapshot.status,
+        None => true,
+    };
+    let turn_advanced = snapshot.turn_no > last_sent_turn_no;
+    if sent_initial && !turn_advanced && !status_changed {
+        return None;
+    }
+
+    if status_changed && let Some(previous) = last_status {
+        // Resuming a paused game is not a new start, and pausing is not an end.
+        if snapshot.status == GameStatus::Running && previous == GameStatus::Created {
+            return Some("GAME_STARTED");
+        } else if snapshot.status == GameStatus::Finished {
+            return Some("GAME_FINISHED");
+        }
+    }
+    Some("SNAPSHOT")
+}
+
+fn connected_event_payload(game_id: &str, from_turn_no: u64) -> String {
+    serde_json::json!({
+        "event_type": "CONNECTED",
+        "game_id": game_id,
+        "from_turn_no": from_turn_no,
+        "connected_at": Utc::now(),
+        "message": "watch stream connected"
+    })
+    .to_string()
+}
+
+fn snapshot_event_payload(
+    event_type: &str,
+    game_id: &str,
+    snapshot: &SnapshotResponse,
+    viewers: usize,
+) -> String {
+    serde_json::json!({
+        "event_type": event_type,
+        "game_id": game_id,
+        "snapshot": snapshot,
+        "viewers": viewers,
+        "emitted_at": Utc::now()
+    })
+    .to_string()
+}
+
+fn viewers_event_payload(game_id: &str, viewers: usize) -> String {
+    serde_json::json!({
+        "event_type": "VIEWERS",
+        "game_id": game_id,
+        "viewers": viewers,
+        "emitted_at": Utc::now()
+    })
+    .to_string()
+}
+
+fn delta_event_payload(game_id: &str, delta: &SnapshotDelta) -> String {
+    serde_json::json!({
+        "event_type": "SNAPSHOT_DELTA",
+        "game_id": game_id,
+        "delta": delta,
+        "emitted_at": Utc::now()
+    })
+    .to_string()
+}
+
+/// One map cell whose value changed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct CellChange {
+    row: usize,
+    col: usize,
+    value: i32,
+}
+
+/// The fields of a snapshot that changed since the last one a watcher got.
+/// Applying it to that snapshot rebuilds the new one, except for
+/// `turn_seconds_remaining`, which clients count down locally.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+struct SnapshotDelta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<GameStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    turn_no: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    round_no: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    current_player_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_step_seq: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    turn_started_at: Option<DateTime<Utc>>,
+    /// Players whose state changed, in full.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    players: Vec<PlayerState>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stats: Vec<PlayerStats>,
+    /// Changed cells when the board keeps its size.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    cells: Vec<CellChange>,
+    /// The whole map, only when the board changed size.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    map: Option<MapData>,
+}
+
+impl SnapshotDelta {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+fn changed<T: PartialEq + Clone>(prev: &T, next: &T) -> Option<T> {
+    (prev != next).then(|| next.clone())
+}
+
+fn diff_snapshot(prev: &SnapshotResponse, next: &SnapshotResponse) -> SnapshotDelta {
+    let players = next
+        .state
+        .players
+        .iter()
+        .filter(|player| !prev.state.players.contains(player))
+        .cloned()
+        .collect();
+    let stats = next
+        .state
+        .stats
+        .iter()
+        .filter(|entry| !prev.state.stats.contains(entry))
+        .cloned()
+        .collect();
+
+    let (prev_map, next_map) = (&prev.state.map, &next.state.map);
+    let (cells, map) = if (prev_map.rows, prev_map.cols) == (next_map.rows, next_map.cols) {
+        let mut cells = Vec::new();
+        for (row, (old_row, new_row)) in prev_map.cells.iter().zip(&next_map.cells).enumerate() {
+            for (col, (old, new)) in old_row.iter().zip(new_row).enumerate() {
+                if old != new {
+                    cells.push(CellChange {
+                        row,
+                        col,
+                        value: *new,
+                    });
+                }
+            }
+        }
+        (cells, None)
+    } else {
+        (Vec::new(), Some(next_map.clone()))
+    };
+
+    SnapshotDelta {
+        status: changed(&prev.status, &next.status),
+        turn_no: changed(&prev.turn_no, &next.turn_no),
+        round_no: changed(&prev.round_no, &next.round_no),
+        current_player_id: changed(&prev.current_player_id, &next.current_player_id),
+        last_step_seq: changed(&prev.last_step_seq, &next.last_step_seq),
+        turn_started_at: changed(&prev.turn_started_at, &next.turn_started_at).flatten(),
+        players,
+        stats,
+        cells,
+        map,
+    }
+}
+
+fn error_event_payload(game_id: &str, message: &str) -> String {
+    serde_json::json!({
+        "event_type": "ERROR",
+        "game_id": game_id,
+        "error": message,
+        "at": Utc::now()
+    })
+    .to_string()
+}
+
+/// Heartbeat ticker whose first tick fires one full period after connect.
+fn heartbeat_interval(period: Duration) -> Interval {
+    let mut heartbeat = interval_at(Instant::now() + period, period);
+    heartbeat.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    heartbeat
+}
+
+/// Wait for the next heartbeat tick and build the PING payload to send.
+async fn next_heartbeat(heartbeat: &mut Interval) -> String {
+    heartbeat.tick().await;
+    serde_json::json!({
+        "event_type": "PING",
+        "ts": Utc::now()
+    })
+    .to_string()
+}
+
+/// A reconnecting client needs replay when it last saw an earlier turn than the
+/// game is on now.
+fn needs_replay(from_turn_no: u64, current_turn_no: u64) -> bool {
+    from_turn_no > 0 && from_turn_no < current_turn_no
+}
+
+/// Steps that moved the game past `from_turn_no` up to `current_turn_no`, in order.
+fn steps_to_replay(
+    mut steps: Vec<StepEvent>,
+    from_turn_no: u64,
+    current_turn_no: u64,
+) -> Vec<StepEvent> {
+    steps.retain(|step| step.turn_no > from_turn_no && step.turn_no <= current_turn_no);
+    steps.sort_by_key(|step| step.step_seq);
+    steps
+}
+
+/// Best-effort replay of missed step events; sends `REPLAY_UNAVAILABLE` when
+/// history can't be fetched. Only returns `Err` when the socket is gone.
+async fn replay_missed_steps(
+    socket: &mut axum::extract::ws::WebSocket,
+    state: &AppState,
+    game_id: &str,
+    from_turn_no: u64,
+    current_turn_no: u64,
+    event_filter: &EventFilter,
+) -> Result<(), ()> {
+    let steps = match fetch_step_history(state, game_id).await {
+        Ok(steps) => steps,
+        Err(error) => {
+            let payload = serde_json::json!({
+                "event_type": "REPLAY_UNAVAILABLE",
+                "game_id": game_id,
+                "from_turn_no": from_turn_no,
+                "reason": error.message,
+                "at": Utc::now()
+            })
+            .to_string();
+            if !event_filter.allows("REPLAY_UNAVAILABLE") {
+                return Ok(());
+            }
+            return send_ws_event(socket, game_id, "REPLAY_UNAVAILABLE", payload, None).await;
+        }
+    };
+
+    let replay = steps_to_replay(steps, from_turn_no, current_turn_no);
+    info!(
+        game_id = %game_id,
+        from_turn_no,
+        current_turn_no,
+        replay_count = replay.len(),
+        "replaying missed steps to reconnecting watcher"
+    );
+    for step in &replay {
+        let ws_event_type = step_ws_event_type(step);
+        if !event_filter.allows(ws_event_type) {
+            continue;
+        }
+        let payload = build_step_ws_payload(step, &None, ws_event_type);
+        send_ws_event(socket, game_id, ws_event_type, payload, None).await?;
+    }
+    Ok(())
+}
+
+/// Server-Sent Events alternative to the WebSocket stream, for proxies that
+/// break WebSockets. Emits the same event bodies as `handle_socket`.
+async fn events_handler(
+    State(state): State<AppState>,
+    Path(game_id): Path<String>,
+    Query(query): Query<StreamQuery>,
+) -> Response {
+    let (frame_tx, frame_rx) = mpsc::channel::<Result<String, Infallible>>(64);
+    tokio::spawn(run_sse_stream(
+        state,
+        game_id,
+        query.from_turn_no.unwrap_or(0),
+        EventFilter::parse(query.events.as_deref()),
+        frame_tx,
+    ));
+
+    (
+        [
+            (header::CONTENT_TYPE, "text/event-stream"),
+            (header::CACHE_CONTROL, "no-cache"),
+        ],
+        Body::from_stream(ReceiverStream::new(frame_rx)),
+    )
+        .into_response()
+}
+
+async fn run_sse_stream(
+    state: AppState,
+    game_id: String,
+    from_turn_no: u64,
+    event_filter: EventFilter,
+    frame_tx: mpsc::Sender<Result<String, Infallible>>,
+) {
+    let _viewer = state.viewers.join(&game_id);
+    let connected = connected_event_payload(&game_id, from_turn_no);
+    if send_sse_frame(&frame_tx, &game_id, "CONNECTED", connected)
+        .await
+        .is_err()
+    {
+        return;
+    }
+
+    let mut watch_events_rx = state.watch_events_tx.subscribe();
+    let mut last_sent_turn_no = from_turn_no;
+    let mut last_status: Option<GameStatus> = None;
+    let mut sent_initial = false;
+
+    let mut ticker = interval(Duration::from_millis(800));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        let (event_type, payload) = tokio::select! {
+            _ = frame_tx.closed() => return,
+            _ = ticker.tick() => {
+                match fetch_snapshot(&state, &game_id).await {
+                    Ok(snapshot) => {
+                        let event_type = snapshot_push_event_type(
+                            last_status,
+                            last_sent_turn_no,
+                            sent_initial,
+                            &snapshot,
+                        );
+                        last_sent_turn_no = last_sent_turn_no.max(snapshot.turn_no);
+                        last_status = Some(snapshot.status);
+                        match event_type {
+                            Some(event_type) => {
+                                sent_initial = true;
+                                (
+                                    event_type.to_string(),
+                                    snapshot_event_payload(
+                                        event_type,
+                                        &game_id,
+                                        &snapshot,
+                                        state.viewers.count(&game_id),
+                                    ),
+                                )
+                            }
+                            None => continue,
+                        }
+                    }
+                    Err(error) => (
+                        "ERROR".to_string(),
+                        error_event_payload(&game_id, &error.message),
+                    ),
+                }
+            }
+            event = watch_events_rx.recv() => {
+                match event {
+                    Ok(ev) if ev.game_id == game_id => {
+                        if let Some(snapshot) = ev.snapshot.as_ref() {
+                            last_sent_turn_no = last_sent_turn_no.max(snapshot.turn_no);
+                            last_status = Some(snapshot.status);
+                            sent_initial = true;
+                        }
+                        (ev.ws_event_type, ev.ws_payload)
+                    }
+                    Ok(_) => continue,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!(game_id = %game_id, skipped, "watcher SSE stream lagged broadcast events");
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return,
+                }
+            }
+        };
+
+        if event_filter.allows(&event_type)
+            && send_sse_frame(&frame_tx, &game_id, &event_type, payload)
+                .await
+                .is_err()
+        {
+            return;
+        }
+        if event_type == "GAME_FINISHED" {
+            return;
         }
     }
 }
 
+async fn send_sse_frame(
+    frame_tx: &mpsc::Sender<Result<String, Infallible>>,
+    game_id: &str,
+    event_type: &str,
+    payload: String,
+) -> Result<(), ()> {
+    log_ws_push(event_type, game_id, &payload, None);
+    frame_tx
+        .send(Ok(format_sse_frame(event_type, &payload)))
+        .await
+        .map_err(|_| {
+            info!(game_id = game_id, "SSE watcher disconnected");
+        })
+}
+
+/// Format one SSE frame: an `event:` line with the type and the JSON body on
+/// `data:` lines.
+fn format_sse_frame(event_type: &str, payload: &str) -> String {
+    let mut frame = format!("event: {event_type}\n");
+    for line in payload.lines() {
+        frame.push_str("data: ");
+        frame.push_str(line);
+        frame.push('\n');
+    }
+    frame.push('\n');
+    frame
+}
+
 fn to_json_log<T: Serialize>(value: &T) -> String {
     serde_json::to_string(value).unwrap_or_else(|error| format!("json_encode_error:{error}"))
 }
@@ -366,9 +1430,30 @@ async fn run_output_consumer(state: AppState) {
     let group_id = std::env::var("WATCHER_OUTPUT_CONSUMER_GROUP_ID")
         .ok()
         .unwrap_or_else(|| "game-watcher-output-v1".to_string());
+    let offset_reset = parse_offset_reset(std::env::var("WATCHER_OUTPUT_OFFSET_RESET").ok());
+    let dead_letters = if cowboy_common::env_flag("KAFKA_DLQ_ENABLED") {
+        match ClientConfig::new()
+            .set("bootstrap.servers", &bootstrap_servers)
+            .set("message.timeout.ms", "5000")
+            .create::<FutureProducer>()
+        {
+            Ok(producer) => {
+                Some(Arc::new(KafkaDeadLetterPublisher { producer }) as Arc<dyn DeadLetterPublisher>)
+            }
+            Err(error) => {
+                warn!(
+                    ?error,
+                    "watcher dead-letter producer unavailable; invalid steps will be dropped"
+                );
+                None
+            }
+        }
+    } else {
+        None
+    };
 
     let topic_pattern = format!("^{}\\..*\\.v1$", output_topic_prefix.replace('.', "\\."));
-    let (step_tx, mut step_rx) = mpsc::channel::<StepEvent>(128);
+    let (step_tx, mut step_rx) = mpsc::channel::<ConsumedStep>(128);
     let reader_bootstrap_servers = bootstrap_servers.clone();
     let reader_group_id = group_id.clone();
     let reader_topic_pattern = topic_pattern.clone();
@@ -377,6 +1462,8 @@ async fn run_output_consumer(state: AppState) {
             reader_bootstrap_servers,
             reader_topic_pattern,
             reader_group_id,
+            offset_reset,
+            dead_letters,
             step_tx,
         )
         .await
@@ -385,35 +1472,179 @@ async fn run_output_consumer(state: AppState) {
         }
     });
 
-    while let Some(step) = step_rx.recv().await {
-        let snapshot = match fetch_snapshot(&state, &step.game_id).await {
-            Ok(snapshot) => Some(snapshot),
-            Err(error) => {
-                warn!(
-                    game_id = %step.game_id,
-                    message = %error.message,
-                    "output kafka event had no fresh snapshot"
-                );
-                None
-            }
-        };
+    while let Some(consumed) = step_rx.recv().await {
+        fan_out_consumed_step(&state, consumed).await;
+    }
+}
 
-        let ws_event_type = step_ws_event_type(&step);
-        let ws_payload = build_step_ws_payload(&step, &snapshot, ws_event_type);
+/// Broadcasts one consumed step to the raw, per-game and aggregate streams.
+async fn fan_out_consumed_step(state: &AppState, ConsumedStep { step, gap }: ConsumedStep) {
+    if game_phase(state, &step.game_id) == Some(GamePhase::Finished) {
+        return;
+    }
+    if let Some(gap) = gap {
+        broadcast_watch_event(
+            &state.watch_events_tx,
+            gap_broadcast_event(&step.game_id, gap),
+        );
+    }
+    fan_out_raw_step(&state.raw_steps_tx, &step);
+
+    // Bot markers change nothing, so there is no snapshot to refresh.
+    let event = if is_bot_marker_step(&step) {
+        bot_marker_broadcast_event(&step)
+    } else {
+        match step_broadcast_event(state, step).await {
+            Some(event) => event,
+            None => return,
+        }
+    };
+    state.active_games.record(&event);
+    broadcast_watch_event(&state.watch_events_tx, event);
+}
 
-        if state.watch_events_tx.receiver_count() > 0
-            && let Err(error) = state.watch_events_tx.send(WatcherBroadcastEvent {
-                game_id: step.game_id,
-                ws_event_type: ws_event_type.to_string(),
-                ws_payload,
-                snapshot,
-            })
-        {
+/// `earliest` replays every retained step on startup; anything else,
+/// including unset, starts from new steps only.
+fn parse_offset_reset(value: Option<String>) -> &'static str {
+    match value.as_deref().map(str::trim) {
+        Some(value) if value.eq_ignore_ascii_case("earliest") => "earliest",
+        _ => "latest",
+    }
+}
+
+fn game_phase(state: &AppState, game_id: &str) -> Option<GamePhase> {
+    state
+        .game_phases
+        .lock()
+        .expect("game phases lock poisoned")
+        .phase(game_id)
+}
+
+fn record_game_phase(state: &AppState, game_id: &str, phase: GamePhase) {
+    state
+        .game_phases
+        .lock()
+        .expect("game phases lock poisoned")
+        .record(game_id, phase, Instant::now());
+}
+
+/// A step of a game that finished before this process saw it running, as
+/// seen when the consumer replays old history. Steps of a game seen live
+/// still go out when the consumer lags behind its end, and GAME_FINISHED
+/// always does.
+fn is_stale_finished_step(
+    step: &StepEvent,
+    snapshot: &SnapshotResponse,
+    seen_phase: Option<GamePhase>,
+) -> bool {
+    snapshot.status == GameStatus::Finished
+        && seen_phase.is_none()
+        && step.event_type != StepEventType::GameFinished
+}
+
+/// Returns `None` for replayed steps of a finished game, which viewers have
+/// no use for.
+async fn step_broadcast_event(state: &AppState, step: StepEvent) -> Option<WatcherBroadcastEvent> {
+    // A new step means any cached snapshot is stale; refresh it for every viewer.
+    let snapshot = match refresh_snapshot(state, &step.game_id).await {
+        Ok(snapshot) => Some(snapshot),
+        Err(error) => {
             warn!(
-                ?error,
-                "failed to fan out step event to websocket subscribers"
+                game_id = %step.game_id,
+                message = %error.message,
+                "output kafka event had no fresh snapshot"
             );
+            None
+        }
+    };
+
+    if let Some(snapshot) = snapshot.as_ref() {
+        if is_stale_finished_step(&step, snapshot, game_phase(state, &step.game_id)) {
+            return None;
         }
+        if snapshot.status != GameStatus::Finished {
+            record_game_phase(state, &step.game_id, GamePhase::Live);
+        }
+    }
+    if step.event_type == StepEventType::GameFinished {
+        record_game_phase(state, &step.game_id, GamePhase::Finished);
+    }
+
+    let ws_event_type = step_ws_event_type(&step);
+    let ws_payload = build_step_ws_payload(&step, &snapshot, ws_event_type);
+    Some(WatcherBroadcastEvent {
+        game_id: step.game_id,
+        ws_event_type: ws_event_type.to_string(),
+        ws_payload,
+        snapshot,
+        eliminations: step.eliminations,
+    })
+}
+
+/// A `GAP_DETECTED` frame telling viewers to refetch the full snapshot,
+/// since deltas and step animations after a gap can't be trusted.
+fn gap_broadcast_event(game_id: &str, gap: SeqGap) -> WatcherBroadcastEvent {
+    let ws_payload = serde_json::json!({
+        "event_type": "GAP_DETECTED",
+        "game_id": game_id,
+        "missing_from": gap.missing_from,
+        "missing_to": gap.missing_to,
+        "at": Utc::now(),
+    });
+    WatcherBroadcastEvent {
+        game_id: game_id.to_string(),
+        ws_event_type: "GAP_DETECTED".to_string(),
+        ws_payload: ws_payload.to_string(),
+        snapshot: None,
+        eliminations: Vec::new(),
+    }
+}
+
+fn broadcast_watch_event(
+    watch_events_tx: &broadcast::Sender<WatcherBroadcastEvent>,
+    event: WatcherBroadcastEvent,
+) {
+    if watch_events_tx.receiver_count() > 0
+        && let Err(error) = watch_events_tx.send(event)
+    {
+        warn!(
+            ?error,
+            "failed to fan out step event to websocket subscribers"
+        );
+    }
+}
+
+/// Whether `step` is a bot's thinking or reasoning marker rather than a
+/// real step.
+fn is_bot_marker_step(step: &StepEvent) -> bool {
+    matches!(
+        step.event_type,
+        StepEventType::BotThinking | StepEventType::BotReasoning
+    )
+}
+
+/// A `THINKING` or `BOT_REASONING` frame naming the bot whose turn it is,
+/// with the reasoning summary when there is one.
+fn bot_marker_broadcast_event(step: &StepEvent) -> WatcherBroadcastEvent {
+    let ws_event_type = step_ws_event_type(step);
+    let mut ws_payload = serde_json::json!({
+        "event_type": ws_event_type,
+        "game_id": step.game_id,
+        "player_id": step.player_id,
+        "turn_no": step.turn_no,
+        "round_no": step.round_no,
+        "emitted_at": Utc::now(),
+    });
+    if let Some(reasoning) = step.reasoning.as_ref() {
+        let obj = ws_payload.as_object_mut().unwrap();
+        obj.insert("reasoning".into(), serde_json::json!(reasoning));
+    }
+    WatcherBroadcastEvent {
+        game_id: step.game_id.clone(),
+        ws_event_type: ws_event_type.to_string(),
+        ws_payload: ws_payload.to_string(),
+        snapshot: None,
+        eliminations: Vec::new(),
     }
 }
 
@@ -421,13 +1652,15 @@ async fn consume_output_steps(
     bootstrap_servers: String,
     topic_pattern: String,
     group_id: String,
-    step_tx: mpsc::Sender<StepEvent>,
+    offset_reset: &'static str,
+    dead_letters: Option<Arc<dyn DeadLetterPublisher>>,
+    step_tx: mpsc::Sender<ConsumedStep>,
 ) -> anyhow::Result<()> {
     let consumer: StreamConsumer = ClientConfig::new()
         .set("bootstrap.servers", &bootstrap_servers)
         .set("group.id", &group_id)
         .set("enable.auto.commit", "false")
-        .set("auto.offset.reset", "earliest")
+        .set("auto.offset.reset", offset_reset)
         .set("topic.metadata.refresh.interval.ms", "1000")
         .set("topic.metadata.refresh.fast.interval.ms", "250")
         .create()
@@ -439,15 +1672,22 @@ async fn consume_output_steps(
         bootstrap_servers = %bootstrap_servers,
         topic_pattern = %topic_pattern,
         group_id = %group_id,
+        offset_reset,
         "watcher output kafka consumer started"
     );
 
+    let mut backoff = Backoff::default();
+    let mut seq_tracker = StepSeqTracker::default();
+
     loop {
         let message = match consumer.recv().await {
-            Ok(message) => message,
+            Ok(message) => {
+                backoff.reset();
+                message
+            }
             Err(error) => {
                 warn!(?error, "watcher output consumer recv error");
-                tokio::time::sleep(Duration::from_millis(300)).await;
+                tokio::time::sleep(backoff.next_delay()).await;
                 continue;
             }
         };
@@ -462,18 +1702,35 @@ async fn consume_output_steps(
             }
         };
 
-        let step = match serde_json::from_slice::<StepEvent>(payload) {
-            Ok(step) => step,
-            Err(error) => {
-                warn!(?error, "failed to parse output kafka step payload");
-                if let Err(commit_err) = consumer.commit_message(&message, CommitMode::Async) {
-                    warn!(?commit_err, "watcher failed to commit invalid payload");
-                }
-                continue;
+        let Some(step) = decode_step_or_dead_letter(
+            dead_letters.as_deref(),
+            message.topic(),
+            message.partition(),
+            message.offset(),
+            payload,
+        )
+        .await
+        else {
+            if let Err(commit_err) = consumer.commit_message(&message, CommitMode::Async) {
+                warn!(?commit_err, "watcher failed to commit invalid payload");
             }
+            continue;
         };
 
-        if step_tx.send(step).await.is_err() {
+        let gap = seq_tracker.observe(&step);
+        if let Some(gap) = gap {
+            warn!(
+                game_id = %step.game_id,
+                missing_from = gap.missing_from,
+                missing_to = gap.missing_to,
+                "watcher output consumer skipped steps"
+            );
+        }
+        if step.event_type == StepEventType::GameFinished {
+            seq_tracker.forget(&step.game_id);
+        }
+
+        if step_tx.send(ConsumedStep { step, gap }).await.is_err() {
             return Ok(());
         }
 
@@ -483,20 +1740,63 @@ async fn consume_output_steps(
     }
 }
 
+/// Decodes a consumed step. One that does not parse is republished to the
+/// dead-letter topic (when enabled) before the caller commits past it.
+async fn decode_step_or_dead_letter(
+    dead_letters: Option<&dyn DeadLetterPublisher>,
+    source_topic: &str,
+    partition: i32,
+    offset: i64,
+    payload: &[u8],
+) -> Option<StepEvent> {
+    let error = match serde_json::from_slice::<StepEvent>(payload) {
+        Ok(step) => return Some(step),
+        Err(error) => error,
+    };
+    warn!(
+        ?error,
+        topic = source_topic,
+        offset,
+        "failed to parse output kafka step payload"
+    );
+
+    if let Some(dead_letters) = dead_letters {
+        let letter = DeadLetter {
+            source_topic: source_topic.to_string(),
+            partition,
+            offset,
+            error: error.to_string(),
+            payload: payload.to_vec(),
+        };
+        if let Err(error) = dead_letters.publish_dead_letter(&letter).await {
+            warn!(
+                ?error,
+                topic = source_topic,
+                offset,
+                "watcher failed to dead-letter invalid step"
+            );
+        }
+    }
+    None
+}
+
 fn step_ws_event_type(step: &StepEvent) -> &'static str {
     match step.event_type {
         StepEventType::GameStarted => "GAME_STARTED",
         StepEventType::GameFinished => "GAME_FINISHED",
         StepEventType::TimeoutApplied => "TIMEOUT",
+        StepEventType::BotThinking => "THINKING",
+        StepEventType::BotReasoning => "BOT_REASONING",
         StepEventType::StepApplied => {
             if let Some(cmd) = step.command.as_ref() {
                 match cmd.command_type {
-                    CommandType::Move => "MOVE",
-                    CommandType::Shoot => "SHOOT",
-                    CommandType::Shield => "SHIELD",
-                    CommandType::Speak => "SPEAK",
-                    CommandType::Timeout => "TIMEOUT",
-                    CommandType::GameStarted => "GAME_STARTED",
+                    PlayerCommandType::Move => "MOVE",
+                    PlayerCommandType::Shoot => "SHOOT",
+                    PlayerCommandType::Shield => "SHIELD",
+                    PlayerCommandType::Speak => "SPEAK",
+                    PlayerCommandType::Pass => "PASS",
+                    PlayerCommandType::Charge => "CHARGE",
+                    PlayerCommandType::Timeout => "TIMEOUT",
                 }
             } else {
                 "STEP_APPLIED"
@@ -535,12 +1835,29 @@ fn build_step_ws_payload(
         obj.insert("command_id".into(), serde_json::json!(cmd.command_id));
     }
 
+    if !step.eliminations.is_empty() {
+        let obj = payload.as_object_mut().unwrap();
+        obj.insert("eliminations".into(), serde_json::json!(step.eliminations));
+    }
+
+    if let Some(path) = step.laser_path.as_ref() {
+        let obj = payload.as_object_mut().unwrap();
+        obj.insert("laser_path".into(), serde_json::json!(path));
+    }
+
+    if let Some(reason) = step.finish_reason {
+        let obj = payload.as_object_mut().unwrap();
+        obj.insert("finish_reason".into(), serde_json::json!(reason));
+    }
+
     payload.to_string()
 }
 
 #[derive(Debug)]
 struct ApiError {
     status: StatusCode,
+    /// Stable machine-readable error code, e.g. `NOT_FOUND`.
+    code: &'static str,
     message: String,
 }
 
@@ -548,6 +1865,7 @@ impl ApiError {
     fn bad_gateway(message: impl Into<String>) -> Self {
         Self {
             status: StatusCode::BAD_GATEWAY,
+            code: "BAD_GATEWAY",
             message: message.into(),
         }
     }
@@ -555,44 +1873,131 @@ impl ApiError {
     fn not_found(message: impl Into<String>) -> Self {
         Self {
             status: StatusCode::NOT_FOUND,
+            code: "NOT_FOUND",
             message: message.into(),
         }
     }
 }
 
 async fn fetch_snapshot(state: &AppState, game_id: &str) -> Result<SnapshotResponse, ApiError> {
-    let url = format!("{}/v2/games/{}", state.manager_base_url, game_id);
+    state
+        .snapshot_cache
+        .get_or_fetch(state.snapshot_source.as_ref(), game_id, false)
+        .await
+}
+
+async fn refresh_snapshot(state: &AppState, game_id: &str) -> Result<SnapshotResponse, ApiError> {
+    state
+        .snapshot_cache
+        .get_or_fetch(state.snapshot_source.as_ref(), game_id, true)
+        .await
+}
+
+#[async_trait]
+impl SnapshotSource for ManagerSnapshotSource {
+    async fn fetch_snapshot(&self, game_id: &str) -> Result<SnapshotResponse, ApiError> {
+        let url = format!("{}/v2/games/{}", self.manager_base_url, game_id);
+
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| ApiError::bad_gateway(format!("manager request failed: {e}")))?;
+
+        let status = response.status();
+
+        if status == StatusCode::NOT_FOUND {
+            return Err(ApiError::not_found(format!("game {} not found", game_id)));
+        }
+
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_else(|_| "".to_string());
+            return Err(ApiError::bad_gateway(format!(
+                "manager returned {}: {}",
+                status, body
+            )));
+        }
+
+        let game = response
+            .json::<GameInstanceResponse>()
+            .await
+            .map_err(|e| ApiError::bad_gateway(format!("invalid manager response: {e}")))?;
+
+        Ok(to_snapshot(game))
+    }
+}
+
+/// Page size for step history reads; game-service caps `limit` at 1000.
+const STEP_HISTORY_PAGE_SIZE: usize = 1000;
+
+/// Reads the game's whole step history, a page at a time, until a short page
+/// shows there is nothing further.
+async fn fetch_step_history(state: &AppState, game_id: &str) -> Result<Vec<StepEvent>, ApiError> {
+    let mut steps = Vec::new();
+    let mut from_seq = 0;
+    loop {
+        let page = fetch_step_page(state, game_id, from_seq).await?;
+        let page_len = page.len();
+        steps.extend(page);
+        match steps.last() {
+            Some(last) if page_len == STEP_HISTORY_PAGE_SIZE => from_seq = last.step_seq + 1,
+            _ => return Ok(steps),
+        }
+    }
+}
+
+async fn fetch_step_page(
+    state: &AppState,
+    game_id: &str,
+    from_seq: u64,
+) -> Result<Vec<StepEvent>, ApiError> {
+    let url = format!(
+        "{}/internal/v2/games/{}/steps?from_seq={}&limit={}",
+        state.game_service_base_url, game_id, from_seq, STEP_HISTORY_PAGE_SIZE
+    );
 
     let response = state
         .client
         .get(url)
         .send()
         .await
-        .map_err(|e| ApiError::bad_gateway(format!("manager request failed: {e}")))?;
+        .map_err(|e| ApiError::bad_gateway(format!("step history request failed: {e}")))?;
 
     let status = response.status();
-
-    if status == StatusCode::NOT_FOUND {
-        return Err(ApiError::not_found(format!("game {} not found", game_id)));
+    if status == StatusCode::NOT_IMPLEMENTED {
+        return Err(ApiError::bad_gateway(
+            "step history is not configured on game-service",
+        ));
     }
 
     if !status.is_success() {
         let body = response.text().await.unwrap_or_else(|_| "".to_string());
         return Err(ApiError::bad_gateway(format!(
-            "manager returned {}: {}",
+            "step history returned {}: {}",
             status, body
         )));
     }
 
-    let game = response
-        .json::<GameInstanceResponse>()
+    response
+        .json::<Vec<StepEvent>>()
         .await
-        .map_err(|e| ApiError::bad_gateway(format!("invalid manager response: {e}")))?;
-
-    Ok(to_snapshot(game))
+        .map_err(|e| ApiError::bad_gateway(format!("invalid step history response: {e}")))
 }
 
 fn to_snapshot(game: GameInstanceResponse) -> SnapshotResponse {
+    // Recompute for running games so the countdown is fresh as of this
+    // response; otherwise trust the manager (a paused clock is frozen).
+    let turn_seconds_remaining = if game.status == GameStatus::Running {
+        turn_seconds_remaining(
+            game.turn_started_at,
+            game.turn_timeout_seconds
+                .saturating_add(game.turn_grace_seconds),
+            Utc::now(),
+        )
+    } else {
+        game.turn_seconds_remaining
+    };
     SnapshotResponse {
         game_id: game.game_id,
         status: game.status,
@@ -603,15 +2008,18 @@ fn to_snapshot(game: GameInstanceResponse) -> SnapshotResponse {
         // V2 turn-only cursor assumption.
         last_step_seq: game.turn_no,
         turn_started_at: game.turn_started_at,
+        turn_seconds_remaining,
     }
 }
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
-        warn!(status = %self.status, message = %self.message, "request failed");
+        warn!(status = %self.status, code = self.code, message = %self.message, "request failed");
         (
             self.status,
-            Json(serde_json::json!({"error": self.message})),
+            Json(serde_json::json!({
+                "error": {"code": self.code, "message": self.message}
+            })),
         )
             .into_response()
     }
@@ -621,11 +2029,11 @@ impl IntoResponse for ApiError {
 mod tests {
     use super::*;
     use cowboy_common::{
-        CommandEnvelope, CommandSource, GameStateSnapshot, MapSource, ResultStatus, default_map,
-        initial_players,
+        CommandEnvelope, CommandSource, Direction, GameStateSnapshot, LaserArm, LaserCell,
+        LaserPath, LaserStop, MapSource, ResultStatus, default_map, initial_players,
     };
 
-    fn make_step(event_type: StepEventType, command_type: Option<CommandType>) -> StepEvent {
+    fn make_step(event_type: StepEventType, command_type: Option<PlayerCommandType>) -> StepEvent {
         let command = command_type.map(|kind| CommandEnvelope {
             command_id: "cmd-1".to_string(),
             source: CommandSource::Timer,
@@ -636,6 +2044,7 @@ mod tests {
             speak_text: None,
             turn_no: 4,
             sent_at: Utc::now(),
+            player_token: None,
         });
 
         StepEvent {
@@ -649,11 +2058,40 @@ mod tests {
             state_after: GameStateSnapshot {
                 map: default_map(),
                 players: initial_players(11, 11, 10, 4),
+                stats: Vec::new(),
             },
             created_at: Utc::now(),
+            eliminations: Vec::new(),
+            arena_shrink: None,
+            laser_path: None,
+            player_id: None,
+            reject_reason: None,
+            reasoning: None,
+            finish_reason: None,
         }
     }
 
+    #[test]
+    fn shoot_payload_includes_laser_path_when_present() {
+        let mut step = make_step(StepEventType::StepApplied, Some(PlayerCommandType::Shoot));
+        let payload: serde_json::Value =
+            serde_json::from_str(&build_step_ws_payload(&step, &None, "SHOOT")).unwrap();
+        assert!(payload.get("laser_path").is_none());
+
+        step.laser_path = Some(LaserPath {
+            entry: LaserCell { row: 1, col: 2 },
+            arms: vec![LaserArm {
+                direction: Direction::Left,
+                cells: vec![LaserCell { row: 1, col: 1 }],
+                stopped_by: LaserStop::Player,
+            }],
+        });
+        let payload: serde_json::Value =
+            serde_json::from_str(&build_step_ws_payload(&step, &None, "SHOOT")).unwrap();
+        assert_eq!(payload["laser_path"]["entry"]["col"], 2);
+        assert_eq!(payload["laser_path"]["arms"][0]["stopped_by"], "PLAYER");
+    }
+
     #[test]
     fn ws_event_type_game_started() {
         let step = make_step(StepEventType::GameStarted, None);
@@ -668,31 +2106,34 @@ mod tests {
 
     #[test]
     fn ws_event_type_timeout_applied() {
-        let step = make_step(StepEventType::TimeoutApplied, Some(CommandType::Timeout));
+        let step = make_step(
+            StepEventType::TimeoutApplied,
+            Some(PlayerCommandType::Timeout),
+        );
         assert_eq!(step_ws_event_type(&step), "TIMEOUT");
     }
 
     #[test]
     fn ws_event_type_move() {
-        let step = make_step(StepEventType::StepApplied, Some(CommandType::Move));
+        let step = make_step(StepEventType::StepApplied, Some(PlayerCommandType::Move));
         assert_eq!(step_ws_event_type(&step), "MOVE");
     }
 
     #[test]
     fn ws_event_type_shoot() {
-        let step = make_step(StepEventType::StepApplied, Some(CommandType::Shoot));
+        let step = make_step(StepEventType::StepApplied, Some(PlayerCommandType::Shoot));
         assert_eq!(step_ws_event_type(&step), "SHOOT");
     }
 
     #[test]
     fn ws_event_type_shield() {
-        let step = make_step(StepEventType::StepApplied, Some(CommandType::Shield));
+        let step = make_step(StepEventType::StepApplied, Some(PlayerCommandType::Shield));
         assert_eq!(step_ws_event_type(&step), "SHIELD");
     }
 
     #[test]
     fn ws_event_type_speak() {
-        let step = make_step(StepEventType::StepApplied, Some(CommandType::Speak));
+        let step = make_step(StepEventType::StepApplied, Some(PlayerCommandType::Speak));
         assert_eq!(step_ws_event_type(&step), "SPEAK");
     }
 
@@ -702,6 +2143,220 @@ mod tests {
         assert_eq!(step_ws_event_type(&step), "STEP_APPLIED");
     }
 
+    #[test]
+    fn thinking_marker_is_classified_apart_from_real_steps() {
+        let mut step = make_step(StepEventType::BotThinking, None);
+        step.player_id = Some("Up".to_string());
+
+        assert!(is_bot_marker_step(&step));
+        assert_eq!(step_ws_event_type(&step), "THINKING");
+        assert!(!is_bot_marker_step(&make_step(
+            StepEventType::StepApplied,
+            Some(PlayerCommandType::Move)
+        )));
+    }
+
+    #[tokio::test]
+    async fn thinking_marker_is_broadcast_with_player_and_turn() {
+        let (watch_events_tx, mut watch_events_rx) = broadcast::channel(4);
+        let mut step = make_step(StepEventType::BotThinking, None);
+        step.player_id = Some("Up".to_string());
+
+        broadcast_watch_event(&watch_events_tx, bot_marker_broadcast_event(&step));
+
+        let event = watch_events_rx.recv().await.unwrap();
+        assert_eq!(event.game_id, "game-1");
+        assert_eq!(event.ws_event_type, "THINKING");
+        assert!(event.snapshot.is_none());
+        let payload: serde_json::Value = serde_json::from_str(&event.ws_payload).unwrap();
+        assert_eq!(payload["event_type"], "THINKING");
+        assert_eq!(payload["player_id"], "Up");
+        assert_eq!(payload["turn_no"], 4);
+    }
+
+    #[tokio::test]
+    async fn heartbeat_produces_ping_within_interval() {
+        let mut heartbeat = heartbeat_interval(Duration::from_millis(30));
+        let ping = tokio::time::timeout(Duration::from_millis(500), next_heartbeat(&mut heartbeat))
+            .await
+            .expect("heartbeat should tick within the interval");
+        let payload: serde_json::Value = serde_json::from_str(&ping).unwrap();
+        assert_eq!(payload["event_type"], "PING");
+        assert!(payload["ts"].is_string());
+    }
+
+    #[tokio::test]
+    async fn heartbeat_does_not_fire_immediately() {
+        let mut heartbeat = heartbeat_interval(Duration::from_secs(60));
+        let early =
+            tokio::time::timeout(Duration::from_millis(50), next_heartbeat(&mut heartbeat)).await;
+        assert!(early.is_err());
+    }
+
+    fn parse_sse_frame(frame: &str) -> (String, serde_json::Value) {
+        let mut event_type = String::new();
+        let mut data = String::new();
+        for line in frame.lines() {
+            if let Some(value) = line.strip_prefix("event: ") {
+                event_type = value.to_string();
+            } else if let Some(value) = line.strip_prefix("data: ") {
+                data.push_str(value);
+            }
+        }
+        (event_type, serde_json::from_str(&data).unwrap())
+    }
+
+    #[test]
+    fn sse_frames_parse_back_into_event_types() {
+        let step = make_step(StepEventType::StepApplied, Some(PlayerCommandType::Speak));
+        let frames = [
+            format_sse_frame("CONNECTED", &connected_event_payload("game-1", 0)),
+            format_sse_frame("SPEAK", &build_step_ws_payload(&step, &None, "SPEAK")),
+            format_sse_frame("ERROR", &error_event_payload("game-1", "boom")),
+        ];
+
+        for (frame, expected) in frames.iter().zip(["CONNECTED", "SPEAK", "ERROR"]) {
+            assert!(frame.ends_with("\n\n"));
+            let (event_type, data) = parse_sse_frame(frame);
+            assert_eq!(event_type, expected);
+            assert_eq!(data["event_type"], expected);
+            assert_eq!(data["game_id"], "game-1");
+        }
+    }
+
+    #[test]
+    fn snapshot_push_event_type_reports_status_transitions() {
+        let mut snapshot = to_snapshot(GameInstanceResponse {
+            game_id: "game-1".to_string(),
+            status: GameStatus::Running,
+            map_source: MapSource::Default,
+            turn_timeout_seconds: 10,
+            turn_no: 3,
+            round_no: 1,
+            current_player_id: "p".to_string(),
+            created_at: Utc::now(),
+            started_at: None,
+            turn_started_at: None,
+            input_topic: None,
+            output_topic: None,
+            state: GameStateSnapshot {
+                map: default_map(),
+                players: initial_players(11, 11, 10, 2),
+                stats: Vec::new(),
+            },
+            last_step_seq: 0,
+            turn_seconds_remaining: None,
+            turn_grace_seconds: 0,
+        });
+
+        assert_eq!(
+            snapshot_push_event_type(None, 0, false, &snapshot),
+            Some("SNAPSHOT")
+        );
+        assert_eq!(
+            snapshot_push_event_type(Some(GameStatus::Running), 3, true, &snapshot),
+            None
+        );
+        // Pausing and resuming are plain snapshots, not start/finish events.
+        snapshot.status = GameStatus::Paused;
+        assert_eq!(
+            snapshot_push_event_type(Some(GameStatus::Running), 3, true, &snapshot),
+            Some("SNAPSHOT")
+        );
+        snapshot.status = GameStatus::Running;
+        assert_eq!(
+            snapshot_push_event_type(Some(GameStatus::Paused), 3, true, &snapshot),
+            Some("SNAPSHOT")
+        );
+        snapshot.status = GameStatus::Finished;
+        assert_eq!(
+            snapshot_push_event_type(Some(GameStatus::Running), 3, true, &snapshot),
+            Some("GAME_FINISHED")
+        );
+    }
+
+    #[test]
+    fn event_filter_parses_comma_separated_types() {
+        let filter = EventFilter::parse(Some(" shoot, GAME_finished,,"));
+        assert!(filter.allows("SHOOT"));
+        assert!(filter.allows("GAME_FINISHED"));
+        assert!(filter.allows("CONNECTED"));
+        assert!(!filter.allows("SNAPSHOT"));
+        assert!(!filter.allows("SPEAK"));
+
+        assert!(EventFilter::parse(None).allows("SNAPSHOT"));
+        assert!(EventFilter::parse(Some("")).allows("SNAPSHOT"));
+    }
+
+    #[test]
+    fn event_filter_drops_snapshots_when_not_requested() {
+        let filter = EventFilter::parse(Some("shoot,game_finished"));
+        let produced = [
+            "CONNECTED",
+            "SNAPSHOT",
+            "MOVE",
+            "SHOOT",
+            "SNAPSHOT",
+            "SPEAK",
+            "GAME_FINISHED",
+        ];
+        let delivered: Vec<&str> = produced
+            .into_iter()
+            .filter(|event_type| filter.allows(event_type))
+            .collect();
+        assert_eq!(delivered, vec!["CONNECTED", "SHOOT", "GAME_FINISHED"]);
+    }
+
+    #[test]
+    fn snapshot_filter_keeps_delta_frames_flowing() {
+        let produced = [
+            "CONNECTED",
+            "SNAPSHOT",
+            "MOVE",
+            "SNAPSHOT_DELTA",
+            "SNAPSHOT_DELTA",
+            "GAME_FINISHED",
+        ];
+        let delivered = |filter: EventFilter| -> Vec<&str> {
+            produced
+                .into_iter()
+                .filter(|event_type| filter.allows(event_type))
+                .collect()
+        };
+
+        assert_eq!(
+            delivered(EventFilter::parse(Some("snapshot"))),
+            vec!["CONNECTED", "SNAPSHOT", "SNAPSHOT_DELTA", "SNAPSHOT_DELTA"]
+        );
+        assert_eq!(
+            delivered(EventFilter::parse(Some("move"))),
+            vec!["CONNECTED", "MOVE"]
+        );
+    }
+
+    #[test]
+    fn needs_replay_only_when_client_is_behind() {
+        assert!(!needs_replay(0, 5));
+        assert!(needs_replay(3, 5));
+        assert!(!needs_replay(5, 5));
+        assert!(!needs_replay(7, 5));
+    }
+
+    #[test]
+    fn steps_to_replay_keeps_only_the_gap_in_order() {
+        let mut steps = Vec::new();
+        for (step_seq, turn_no) in [(14, 6), (11, 3), (13, 5), (12, 4), (10, 2)] {
+            let mut step = make_step(StepEventType::StepApplied, Some(PlayerCommandType::Speak));
+            step.step_seq = step_seq;
+            step.turn_no = turn_no;
+            steps.push(step);
+        }
+
+        let replay = steps_to_replay(steps, 3, 5);
+        let turns: Vec<u64> = replay.iter().map(|step| step.turn_no).collect();
+        assert_eq!(turns, vec![4, 5]);
+    }
+
     #[test]
     fn to_snapshot_uses_turn_number_as_cursor() {
         let now = Utc::now();
@@ -721,7 +2376,11 @@ mod tests {
             state: GameStateSnapshot {
                 map: default_map(),
                 players: initial_players(11, 11, 10, 4),
+                stats: Vec::new(),
             },
+            last_step_seq: 0,
+            turn_seconds_remaining: None,
+            turn_grace_seconds: 0,
         };
 
         let snapshot = to_snapshot(game);
@@ -730,10 +2389,592 @@ mod tests {
         assert_eq!(snapshot.status, GameStatus::Running);
     }
 
+    struct CountingSnapshotSource {
+        calls: std::sync::atomic::AtomicUsize,
+        /// Report the game as finished from the next fetch on.
+        finished: std::sync::atomic::AtomicBool,
+    }
+
+    #[async_trait]
+    impl SnapshotSource for CountingSnapshotSource {
+        async fn fetch_snapshot(&self, game_id: &str) -> Result<SnapshotResponse, ApiError> {
+            let call = self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            let status = if self.finished.load(std::sync::atomic::Ordering::SeqCst) {
+                GameStatus::Finished
+            } else {
+                GameStatus::Running
+            };
+            Ok(to_snapshot(GameInstanceResponse {
+                game_id: game_id.to_string(),
+                status,
+                map_source: MapSource::Default,
+                turn_timeout_seconds: 10,
+                turn_no: call as u64 + 1,
+                round_no: 1,
+                current_player_id: "Up".to_string(),
+                created_at: Utc::now(),
+                started_at: None,
+                turn_started_at: None,
+                input_topic: None,
+                output_topic: None,
+                state: GameStateSnapshot {
+                    map: default_map(),
+                    players: initial_players(11, 11, 10, 2),
+                    stats: Vec::new(),
+                },
+                last_step_seq: 0,
+                turn_seconds_remaining: None,
+                turn_grace_seconds: 0,
+            }))
+        }
+    }
+
+    fn counting_source() -> CountingSnapshotSource {
+        CountingSnapshotSource {
+            calls: std::sync::atomic::AtomicUsize::new(0),
+            finished: std::sync::atomic::AtomicBool::new(false),
+        }
+    }
+
+    #[tokio::test]
+    async fn snapshot_cache_shares_concurrent_fetches_within_ttl() {
+        let cache = SnapshotCache::new(Duration::from_millis(500));
+        let source = counting_source();
+
+        let (first, second) = tokio::join!(
+            cache.get_or_fetch(&source, "game-1", false),
+            cache.get_or_fetch(&source, "game-1", false),
+        );
+
+        assert_eq!(source.calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(first.unwrap().turn_no, second.unwrap().turn_no);
+    }
+
+    #[tokio::test]
+    async fn snapshot_cache_refetches_when_forced_or_expired() {
+        let cache = SnapshotCache::new(Duration::from_millis(500));
+        let source = counting_source();
+
+        cache.get_or_fetch(&source, "game-1", false).await.unwrap();
+        let forced = cache.get_or_fetch(&source, "game-1", true).await.unwrap();
+        assert_eq!(forced.turn_no, 2);
+        let cached = cache.get_or_fetch(&source, "game-1", false).await.unwrap();
+        assert_eq!(cached.turn_no, 2);
+
+        let expiring = SnapshotCache::new(Duration::ZERO);
+        expiring
+            .get_or_fetch(&source, "game-1", false)
+            .await
+            .unwrap();
+        expiring
+            .get_or_fetch(&source, "game-1", false)
+            .await
+            .unwrap();
+        assert_eq!(source.calls.load(std::sync::atomic::Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn finished_snapshots_stay_cached_even_when_forced() {
+        let cache = SnapshotCache::new(Duration::ZERO);
+        let source = counting_source();
+        source
+            .finished
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+
+        let first = cache.get_or_fetch(&source, "game-1", true).await.unwrap();
+        let second = cache.get_or_fetch(&source, "game-1", true).await.unwrap();
+        let third = cache.get_or_fetch(&source, "game-1", false).await.unwrap();
+
+        assert_eq!(source.calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(first.status, GameStatus::Finished);
+        assert_eq!(second.turn_no, first.turn_no);
+        assert_eq!(third.turn_no, first.turn_no);
+    }
+
+    #[tokio::test]
+    async fn idle_snapshot_slots_are_pruned() {
+        let cache = SnapshotCache {
+            idle_after: Duration::ZERO,
+            ..SnapshotCache::new(Duration::from_millis(500))
+        };
+        let source = counting_source();
+
+        cache.get_or_fetch(&source, "game-1", false).await.unwrap();
+        cache.get_or_fetch(&source, "game-2", false).await.unwrap();
+
+        let slots = cache.slots.lock().unwrap();
+        assert_eq!(slots.keys().collect::<Vec<_>>(), vec!["game-2"]);
+    }
+
+    #[tokio::test]
+    async fn raw_stream_receives_steps_unmodified() {
+        let (raw_steps_tx, mut raw_steps_rx) = broadcast::channel(8);
+        let mut step = make_step(StepEventType::StepApplied, Some(PlayerCommandType::Speak));
+        step.eliminations = vec![Elimination {
+            victim_player_id: "p-b".to_string(),
+            killer_player_id: "p-a".to_string(),
+            turn_no: 4,
+        }];
+
+        fan_out_raw_step(&raw_steps_tx, &step);
+        let received = raw_steps_rx.recv().await.unwrap();
+
+        let frame: serde_json::Value = serde_json::from_str(&raw_step_frame(&received)).unwrap();
+        assert_eq!(frame, serde_json::to_value(&step).unwrap());
+    }
+
     #[tokio::test]
     async fn health_reports_service_name() {
         let payload = health().await.0;
         assert_eq!(payload["ok"], true);
         assert_eq!(payload["service"], "game-watcher-service");
     }
+
+    #[tokio::test]
+    async fn api_error_body_carries_code_and_message() {
+        let response = ApiError::not_found("game g-1 not found").into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({"error": {"code": "NOT_FOUND", "message": "game g-1 not found"}})
+        );
+    }
+
+    fn delta_base_snapshot() -> SnapshotResponse {
+        SnapshotResponse {
+            game_id: "game-1".to_string(),
+            status: GameStatus::Running,
+            turn_no: 4,
+            round_no: 2,
+            current_player_id: "a".to_string(),
+            state: GameStateSnapshot {
+                map: MapData {
+                    rows: 3,
+                    cols: 3,
+                    cells: vec![vec![0, 1, 0], vec![0, 2, 0], vec![0, 0, 0]],
+                },
+                players: initial_players(3, 3, 10, 2)
+                    .into_iter()
+                    .zip(["a", "b"])
+                    .map(|(player, id)| PlayerState {
+                        player_id: id.to_string(),
+                        ..player
+                    })
+                    .collect(),
+                stats: Vec::new(),
+            },
+            last_step_seq: 4,
+            turn_started_at: None,
+            turn_seconds_remaining: Some(7),
+        }
+    }
+
+    #[test]
+    fn diff_snapshot_carries_only_the_moved_player_and_turn_fields() {
+        let prev = delta_base_snapshot();
+        let mut next = prev.clone();
+        next.state.players[0].col += 1;
+        next.turn_no = 5;
+        next.last_step_seq = 5;
+        next.current_player_id = "b".to_string();
+
+        let delta = diff_snapshot(&prev, &next);
+        assert_eq!(delta.players, vec![next.state.players[0].clone()]);
+        assert_eq!(delta.turn_no, Some(5));
+        assert_eq!(delta.current_player_id.as_deref(), Some("b"));
+        assert_eq!(delta.round_no, None);
+        assert!(delta.cells.is_empty());
+        assert!(delta.map.is_none());
+
+        let json = serde_json::to_value(&delta).unwrap();
+        assert!(json.get("map").is_none());
+        assert!(json.get("round_no").is_none());
+    }
+
+    #[test]
+    fn diff_snapshot_reports_hp_loss_and_damaged_wall() {
+        let prev = delta_base_snapshot();
+        let mut next = prev.clone();
+        next.state.players[1].hp -= 1;
+        next.state.map.cells[1][1] = 1;
+
+        let delta = diff_snapshot(&prev, &next);
+        assert_eq!(delta.players.len(), 1);
+        assert_eq!(delta.players[0].player_id, "b");
+        assert_eq!(delta.players[0].hp, 9);
+        assert_eq!(
+            delta.cells,
+            vec![CellChange {
+                row: 1,
+                col: 1,
+                value: 1
+            }]
+        );
+    }
+
+    #[test]
+    fn diff_snapshot_of_an_unchanged_tick_is_empty() {
+        let prev = delta_base_snapshot();
+        let mut next = prev.clone();
+        next.turn_seconds_remaining = Some(6);
+
+        let delta = diff_snapshot(&prev, &next);
+        assert!(delta.is_empty());
+        assert_eq!(serde_json::to_value(&delta).unwrap(), serde_json::json!({}));
+    }
+
+    #[test]
+    fn offset_reset_defaults_to_latest() {
+        assert_eq!(parse_offset_reset(None), "latest");
+        assert_eq!(parse_offset_reset(Some("latest".to_string())), "latest");
+        assert_eq!(
+            parse_offset_reset(Some(" Earliest ".to_string())),
+            "earliest"
+        );
+        assert_eq!(parse_offset_reset(Some("bogus".to_string())), "latest");
+    }
+
+    #[test]
+    fn replayed_steps_of_a_finished_game_are_skipped() {
+        let finished = SnapshotResponse {
+            status: GameStatus::Finished,
+            turn_no: 9,
+            ..delta_base_snapshot()
+        };
+        let step = make_step(StepEventType::StepApplied, Some(PlayerCommandType::Speak));
+
+        assert!(is_stale_finished_step(&step, &finished, None));
+
+        // A game seen live, its GAME_FINISHED, and a live game all go out.
+        assert!(!is_stale_finished_step(
+            &step,
+            &finished,
+            Some(GamePhase::Live)
+        ));
+        let game_finished = make_step(StepEventType::GameFinished, None);
+        assert!(!is_stale_finished_step(&game_finished, &finished, None));
+        assert!(!is_stale_finished_step(&step, &delta_base_snapshot(), None));
+    }
+
+    #[tokio::test]
+    async fn a_lagging_consumer_still_broadcasts_a_games_last_steps() {
+        let source = Arc::new(counting_source());
+        let state = AppState {
+            snapshot_source: source.clone(),
+            ..test_state()
+        };
+        let mut events_rx = state.watch_events_tx.subscribe();
+        let step = |seq, event_type, command_type| StepEvent {
+            step_seq: seq,
+            ..make_step(event_type, command_type)
+        };
+
+        fan_out_consumed_step(
+            &state,
+            ConsumedStep {
+                step: step(6, StepEventType::StepApplied, Some(PlayerCommandType::Move)),
+                gap: None,
+            },
+        )
+        .await;
+        // The game ends while steps 7, 8 and GAME_FINISHED are still queued.
+        source
+            .finished
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+        for step in [
+            step(7, StepEventType::StepApplied, Some(PlayerCommandType::Move)),
+            step(
+                8,
+                StepEventType::StepApplied,
+                Some(PlayerCommandType::Shoot),
+            ),
+            step(9, StepEventType::GameFinished, None),
+            step(
+                10,
+                StepEventType::StepApplied,
+                Some(PlayerCommandType::Speak),
+            ),
+        ] {
+            fan_out_consumed_step(&state, ConsumedStep { step, gap: None }).await;
+        }
+
+        let mut event_types = Vec::new();
+        while let Ok(event) = events_rx.try_recv() {
+            event_types.push(event.ws_event_type);
+        }
+        assert_eq!(event_types, ["MOVE", "MOVE", "SHOOT", "GAME_FINISHED"]);
+    }
+
+    #[test]
+    fn game_phases_expire_and_finished_stays_finished() {
+        let mut phases = GamePhases::new(Duration::from_secs(60));
+        let start = Instant::now();
+        phases.record("game-1", GamePhase::Live, start);
+        phases.record("game-1", GamePhase::Finished, start);
+        phases.record("game-1", GamePhase::Live, start);
+        assert_eq!(phases.phase("game-1"), Some(GamePhase::Finished));
+
+        phases.record("game-2", GamePhase::Live, start + Duration::from_secs(61));
+        assert_eq!(phases.phase("game-1"), None);
+        assert_eq!(phases.phase("game-2"), Some(GamePhase::Live));
+    }
+
+    #[derive(Default)]
+    struct RecordingDeadLetterPublisher {
+        letters: std::sync::Mutex<Vec<DeadLetter>>,
+    }
+
+    #[async_trait]
+    impl DeadLetterPublisher for RecordingDeadLetterPublisher {
+        async fn publish_dead_letter(&self, letter: &DeadLetter) -> anyhow::Result<()> {
+            self.letters.lock().unwrap().push(letter.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn malformed_step_payload_is_dead_lettered() {
+        let publisher = RecordingDeadLetterPublisher::default();
+        let step = decode_step_or_dead_letter(
+            Some(&publisher),
+            "game.output.game-1.v1",
+            0,
+            42,
+            b"{\"step_seq\": \"nope\"}",
+        )
+        .await;
+        assert!(step.is_none());
+
+        let letters = publisher.letters.lock().unwrap();
+        assert_eq!(letters.len(), 1);
+        assert_eq!(letters[0].source_topic, "game.output.game-1.v1");
+        assert_eq!(letters[0].offset, 42);
+        assert_eq!(letters[0].payload, b"{\"step_seq\": \"nope\"}".to_vec());
+    }
+
+    #[tokio::test]
+    async fn reasoning_marker_is_broadcast_with_its_summary() {
+        let (watch_events_tx, mut watch_events_rx) = broadcast::channel(4);
+        let mut step = make_step(StepEventType::BotReasoning, None);
+        step.player_id = Some("Up".to_string());
+        step.reasoning = Some("B is in my row, so I shoot.".to_string());
+
+        assert!(is_bot_marker_step(&step));
+        assert_eq!(step_ws_event_type(&step), "BOT_REASONING");
+        broadcast_watch_event(&watch_events_tx, bot_marker_broadcast_event(&step));
+
+        let event = watch_events_rx.recv().await.unwrap();
+        assert_eq!(event.ws_event_type, "BOT_REASONING");
+        assert!(event.snapshot.is_none());
+        let payload: serde_json::Value = serde_json::from_str(&event.ws_payload).unwrap();
+        assert_eq!(payload["player_id"], "Up");
+        assert_eq!(payload["reasoning"], "B is in my row, so I shoot.");
+    }
+
+    #[test]
+    fn seq_tracker_flags_a_skipped_step_and_ignores_late_ones() {
+        let mut tracker = StepSeqTracker::default();
+        let step = |seq: u64| StepEvent {
+            step_seq: seq,
+            ..make_step(StepEventType::StepApplied, None)
+        };
+
+        assert_eq!(tracker.observe(&step(1)), None);
+        assert_eq!(tracker.observe(&step(2)), None);
+        assert_eq!(
+            tracker.observe(&step(4)),
+            Some(SeqGap {
+                missing_from: 3,
+                missing_to: 3,
+            })
+        );
+        assert_eq!(tracker.observe(&step(3)), None);
+        assert_eq!(tracker.observe(&step(2)), None);
+        assert_eq!(tracker.observe(&step(5)), None);
+
+        let marker = StepEvent {
+            step_seq: 9,
+            ..make_step(StepEventType::BotThinking, None)
+        };
+        assert_eq!(tracker.observe(&marker), None);
+        assert_eq!(tracker.observe(&step(6)), None);
+
+        let other_game = StepEvent {
+            game_id: "game-2".to_string(),
+            ..step(40)
+        };
+        assert_eq!(tracker.observe(&other_game), None);
+
+        let event = gap_broadcast_event(
+            "game-1",
+            SeqGap {
+                missing_from: 3,
+                missing_to: 3,
+            },
+        );
+        assert_eq!(event.ws_event_type, "GAP_DETECTED");
+        let payload: serde_json::Value = serde_json::from_str(&event.ws_payload).unwrap();
+        assert_eq!(payload["missing_from"], 3);
+        assert_eq!(payload["missing_to"], 3);
+    }
+
+    fn test_state() -> AppState {
+        let (watch_events_tx, _) = broadcast::channel(16);
+        let (raw_steps_tx, _) = broadcast::channel(16);
+        AppState {
+            client: reqwest::Client::new(),
+            snapshot_source: Arc::new(counting_source()),
+            snapshot_cache: Arc::new(SnapshotCache::new(Duration::ZERO)),
+            game_service_base_url: "http://game-service".to_string(),
+            watch_events_tx,
+            raw_steps_tx,
+            heartbeat_period: Duration::from_secs(20),
+            game_phases: Arc::new(std::sync::Mutex::new(GamePhases::new(GAME_PHASE_TTL))),
+            active_games: Arc::new(ActiveGames::default()),
+            viewers: Arc::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn step_history_is_read_page_by_page_to_the_end() {
+        let total = 2 * STEP_HISTORY_PAGE_SIZE as u64 + 5;
+        let history = Router::new().route(
+            "/internal/v2/games/{game_id}/steps",
+            get(
+                move |Query(query): Query<HashMap<String, u64>>| async move {
+                    let from_seq = query["from_seq"].max(1);
+                    let end = (from_seq + query["limit"]).min(total + 1);
+                    let page = (from_seq..end)
+                        .map(|seq| StepEvent {
+                            step_seq: seq,
+                            ..make_step(StepEventType::StepApplied, None)
+                        })
+                        .collect::<Vec<_>>();
+                    Json(page)
+                },
+            ),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, history).await.unwrap() });
+        let state = AppState {
+            game_service_base_url: format!("http://{addr}"),
+            ..test_state()
+        };
+
+        let steps = fetch_step_history(&state, "game-1").await.unwrap();
+
+        assert_eq!(steps.len() as u64, total);
+        assert!(
+            steps
+                .iter()
+                .zip(1..)
+                .all(|(step, seq)| step.step_seq == seq)
+        );
+    }
+
+    #[tokio::test]
+    async fn game_finished_on_one_game_reaches_the_aggregate_stream() {
+        let state = test_state();
+        let mut aggregate_rx = state.watch_events_tx.subscribe();
+
+        let mut started = make_step(StepEventType::GameStarted, None);
+        started.game_id = "game-2".to_string();
+        let move_step = make_step(StepEventType::StepApplied, Some(PlayerCommandType::Move));
+        let finished = make_step(StepEventType::GameFinished, None);
+        for step in [started, move_step, finished] {
+            let event = step_broadcast_event(&state, step).await.unwrap();
+            state.active_games.record(&event);
+            broadcast_watch_event(&state.watch_events_tx, event);
+        }
+
+        // The MOVE in between is per-game detail and is skipped.
+        let (game_id, event_type, _) = next_aggregate_frame(&mut aggregate_rx).await.unwrap();
+        assert_eq!(
+            (game_id.as_str(), event_type.as_str()),
+            ("game-2", "GAME_STARTED")
+        );
+        let (game_id, event_type, payload) = next_aggregate_frame(&mut aggregate_rx).await.unwrap();
+        assert_eq!(
+            (game_id.as_str(), event_type.as_str()),
+            ("game-1", "GAME_FINISHED")
+        );
+        let payload: serde_json::Value = serde_json::from_str(&payload).unwrap();
+        assert_eq!(payload["game_id"], "game-1");
+        assert!(payload.get("snapshot").is_none());
+
+        let roster: Vec<String> = state
+            .active_games
+            .roster()
+            .into_iter()
+            .map(|game| game.game_id)
+            .collect();
+        assert_eq!(roster, vec!["game-2"]);
+    }
+
+    #[test]
+    fn eliminations_are_aggregated_and_quiet_steps_are_not() {
+        let mut step = make_step(StepEventType::StepApplied, Some(PlayerCommandType::Shoot));
+        let quiet = WatcherBroadcastEvent {
+            game_id: step.game_id.clone(),
+            ws_event_type: "SHOOT".to_string(),
+            ws_payload: String::new(),
+            snapshot: None,
+            eliminations: Vec::new(),
+        };
+        assert!(aggregate_event_payload(&quiet).is_none());
+
+        step.eliminations = vec![Elimination {
+            victim_player_id: "p-b".to_string(),
+            killer_player_id: "p-a".to_string(),
+            turn_no: 4,
+        }];
+        let lethal = WatcherBroadcastEvent {
+            eliminations: step.eliminations.clone(),
+            ..quiet
+        };
+        let (event_type, payload) = aggregate_event_payload(&lethal).unwrap();
+        assert_eq!(event_type, "ELIMINATION");
+        let payload: serde_json::Value = serde_json::from_str(&payload).unwrap();
+        assert_eq!(payload["eliminations"][0]["victim_player_id"], "p-b");
+    }
+
+    #[tokio::test]
+    async fn viewer_count_follows_connections_and_abrupt_drops() {
+        let state = test_state();
+        let first = state.viewers.join("game-1");
+        let second = state.viewers.join("game-1");
+        let _other = state.viewers.join("game-2");
+        assert_eq!(state.viewers.count("game-1"), 2);
+
+        drop(first);
+        assert_eq!(state.viewers.count("game-1"), 1);
+
+        // A connection task torn down mid-await still releases its slot.
+        let viewers = state.viewers.clone();
+        let task = tokio::spawn(async move {
+            let _viewer = viewers.join("game-1");
+            std::future::pending::<()>().await;
+        });
+        while state.viewers.count("game-1") < 2 {
+            tokio::task::yield_now().await;
+        }
+        task.abort();
+        let _ = task.await;
+        assert_eq!(state.viewers.count("game-1"), 1);
+
+        drop(second);
+        assert_eq!(state.viewers.count("game-1"), 0);
+        assert_eq!(state.viewers.count("game-2"), 1);
+
+        let payload: serde_json::Value =
+            serde_json::from_str(&viewers_event_payload("game-2", 1)).unwrap();
+        assert_eq!(payload["event_type"], "VIEWERS");
+        assert_eq!(payload["viewers"], 1);
+    }
 }
@@ -22,8 +22,8 @@ use std::{
 use anyhow::Context;
 use chrono::Utc;
 use cowboy_common::{
-    CommandEnvelope, CommandSource, CommandType, GameInstanceResponse, GameStatus, ResultStatus,
-    StepEvent, StepEventType,
+    Backoff, CommandEnvelope, CommandSource, GameInstanceResponse, GameStatus, PlayerCommandType,
+    ResultStatus, StepEvent, StepEventType,
 };
 use rdkafka::{
     Message,
@@ -34,6 +34,9 @@ use rdkafka::{
 use tokio::sync::Mutex;
 use tracing::{info, warn};
 
+/// How often a timer for a paused game checks whether the game has resumed.
+const PAUSED_RECHECK_INTERVAL: Duration = Duration::from_secs(1);
+
 #[derive(Clone)]
 struct AppState {
     producer: FutureProducer,
@@ -117,7 +120,7 @@ async fn main() -> anyhow::Result<()> {
         }
     });
 
-    tokio::signal::ctrl_c().await?;
+    cowboy_common::shutdown_signal().await;
     info!("timer-service shutting down");
     Ok(())
 }
@@ -139,12 +142,17 @@ async fn run_step_consumer(state: AppState) -> anyhow::Result<()> {
         .context("failed to subscribe timer-service output topics")?;
     info!(pattern = %pattern, "timer-service subscribed to output topics");
 
+    let mut backoff = Backoff::default();
+
     loop {
         let message = match consumer.recv().await {
-            Ok(message) => message,
+            Ok(message) => {
+                backoff.reset();
+                message
+            }
             Err(error) => {
                 warn!(?error, "timer-service kafka receive error");
-                tokio::time::sleep(Duration::from_millis(300)).await;
+                tokio::time::sleep(backoff.next_delay()).await;
                 continue;
             }
         };
@@ -207,11 +215,7 @@ async fn handle_step_event(state: &AppState, step: StepEvent) {
         return;
     }
 
-    let timeout_seconds = if game.turn_timeout_seconds == 0 {
-        state.default_timeout_seconds.max(1)
-    } else {
-        game.turn_timeout_seconds.max(1)
-    };
+    let timeout_seconds = turn_timeout_seconds(state, &game);
     let generation = {
         let mut timers = state.timers.lock().await;
         let next_generation = timers
@@ -239,14 +243,32 @@ async fn handle_step_event(state: &AppState, step: StepEvent) {
         "timer scheduled for turn"
     );
     tokio::spawn(async move {
-        tokio::time::sleep(Duration::from_secs(timeout_seconds)).await;
-        if let Err(error) = fire_timeout_if_still_valid(&runner, game_id, turn_no, generation).await
-        {
-            warn!(error = %error, "timer timeout publish failed");
+        let mut delay = Duration::from_secs(timeout_seconds);
+        loop {
+            tokio::time::sleep(delay).await;
+            match fire_timeout_if_still_valid(&runner, &game_id, turn_no, generation).await {
+                Ok(Some(wait)) => delay = wait,
+                Ok(None) => break,
+                Err(error) => {
+                    warn!(error = %error, "timer timeout publish failed");
+                    break;
+                }
+            }
         }
     });
 }
 
+/// The current turn's full budget, including any first-turn grace the
+/// manager grants while bots start up.
+fn turn_timeout_seconds(state: &AppState, game: &GameInstanceResponse) -> u64 {
+    let base = if game.turn_timeout_seconds == 0 {
+        state.default_timeout_seconds.max(1)
+    } else {
+        game.turn_timeout_seconds.max(1)
+    };
+    base.saturating_add(game.turn_grace_seconds)
+}
+
 fn should_reset_timer(step: &StepEvent) -> bool {
     if step.event_type == StepEventType::GameStarted {
         return true;
@@ -258,26 +280,54 @@ fn should_reset_timer(step: &StepEvent) -> bool {
     )
 }
 
+/// Publishes the timeout command once the turn is really over. Returns how
+/// much longer to wait when the turn is still live, which happens while the
+/// game is paused and after a resume has pushed the turn start forward.
 async fn fire_timeout_if_still_valid(
     state: &AppState,
-    game_id: String,
+    game_id: &str,
     expected_turn_no: u64,
     expected_generation: u64,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<Option<Duration>> {
     {
         let timers = state.timers.lock().await;
-        let Some(entry) = timers.get(&game_id) else {
-            return Ok(());
+        let Some(entry) = timers.get(game_id) else {
+            return Ok(None);
         };
         if entry.generation != expected_generation || entry.turn_no != expected_turn_no {
-            return Ok(());
+            return Ok(None);
         }
         let _ = entry.scheduled_at.elapsed();
     }
 
-    let game = fetch_game(state, &game_id).await?;
-    if game.status != GameStatus::Running || game.turn_no != expected_turn_no {
-        return Ok(());
+    let game = fetch_game(state, game_id).await?;
+    if game.turn_no != expected_turn_no {
+        return Ok(None);
+    }
+    if game.status == GameStatus::Paused {
+        return Ok(Some(PAUSED_RECHECK_INTERVAL));
+    }
+    if game.status != GameStatus::Running {
+        return Ok(None);
+    }
+    if let Some(turn_started_at) = game.turn_started_at {
+        // Recomputed from the fresh game, so a grace granted mid-turn (a
+        // seat rebound to a bot) still pushes the deadline out.
+        let timeout_seconds = turn_timeout_seconds(state, &game);
+        let Some(deadline) = i64::try_from(timeout_seconds)
+            .ok()
+            .and_then(chrono::Duration::try_seconds)
+            .and_then(|timeout| turn_started_at.checked_add_signed(timeout))
+        else {
+            // Too far out to represent, so the turn is still live; check
+            // back in case the game's clock settings change.
+            return Ok(Some(PAUSED_RECHECK_INTERVAL));
+        };
+        if let Ok(remaining) = (deadline - Utc::now()).to_std()
+            && !remaining.is_zero()
+        {
+            return Ok(Some(remaining));
+        }
     }
 
     let command = CommandEnvelope {
@@ -288,15 +338,16 @@ async fn fire_timeout_if_still_valid(
             Utc::now().timestamp_millis()
         ),
         source: CommandSource::Timer,
-        game_id: game_id.clone(),
+        game_id: game_id.to_string(),
         player_id: Some(game.current_player_id.clone()),
-        command_type: CommandType::Timeout,
+        command_type: PlayerCommandType::Timeout,
         direction: None,
         speak_text: None,
         turn_no: expected_turn_no,
         sent_at: Utc::now(),
+        player_token: None,
     };
-    let topic = state.input_topic_for_game(&game_id);
+    let topic = state.input_topic_for_game(game_id);
     let payload = serde_json::to_string(&command).context("failed to encode timeout command")?;
     state
         .producer
@@ -315,7 +366,7 @@ async fn fire_timeout_if_still_valid(
         topic = %topic,
         "published timeout command to input topic"
     );
-    Ok(())
+    Ok(None)
 }
 
 async fn fetch_game(state: &AppState, game_id: &str) -> anyhow::Result<GameInstanceResponse> {
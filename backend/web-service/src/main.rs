@@ -20,13 +20,14 @@ use async_trait::async_trait;
 use axum::{
     Json, Router,
     extract::{Path, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::{IntoResponse, Response},
     routing::{get, post},
 };
 use chrono::Utc;
 use cowboy_common::{
-    CommandEnvelope, CommandSource, CommandType, SubmitCommandRequest, SubmitCommandResponse,
+    CommandEnvelope, CommandSource, PLAYER_TOKEN_HEADER, PlayerCommandType, SubmitCommandRequest,
+    SubmitCommandResponse,
 };
 use lambda_http::run as lambda_run;
 use rdkafka::{
@@ -128,7 +129,10 @@ async fn main() -> anyhow::Result<()> {
     let bind_addr = parse_bind_addr("WEB_SERVICE_BIND", "0.0.0.0:8082")?;
     info!(%bind_addr, "web-service listening");
     let listener = tokio::net::TcpListener::bind(bind_addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(cowboy_common::shutdown_signal())
+        .await?;
+    info!("web-service shut down");
     Ok(())
 }
 
@@ -155,6 +159,7 @@ async fn health() -> Json<serde_json::Value> {
 async fn submit_command_handler(
     State(state): State<AppState>,
     Path(game_id): Path<String>,
+    headers: HeaderMap,
     Json(request): Json<SubmitCommandRequest>,
 ) -> Result<Json<SubmitCommandResponse>, ApiError> {
     validate_user_command(&request)?;
@@ -169,6 +174,11 @@ async fn submit_command_handler(
         speak_text: request.speak_text.clone(),
         turn_no: request.turn_no,
         sent_at: request.client_sent_at,
+        // Checked by game-manager for games that require player tokens.
+        player_token: headers
+            .get(PLAYER_TOKEN_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string),
     };
 
     state
@@ -189,18 +199,15 @@ fn validate_user_command(request: &SubmitCommandRequest) -> Result<(), ApiError>
         return Err(ApiError::bad_request("command_id is required"));
     }
 
-    if matches!(
-        request.command_type,
-        CommandType::Timeout | CommandType::GameStarted
-    ) {
+    if request.command_type == PlayerCommandType::Timeout {
         return Err(ApiError::bad_request(
-            "command_type timeout/game_started is reserved for system services",
+            "command_type timeout is reserved for system services",
         ));
     }
 
     if matches!(
         request.command_type,
-        CommandType::Move | CommandType::Shield | CommandType::Shoot
+        PlayerCommandType::Move | PlayerCommandType::Shield | PlayerCommandType::Shoot
     ) && request.direction.is_none()
     {
         return Err(ApiError::bad_request(
@@ -208,7 +215,7 @@ fn validate_user_command(request: &SubmitCommandRequest) -> Result<(), ApiError>
         ));
     }
 
-    if request.command_type == CommandType::Speak
+    if request.command_type == PlayerCommandType::Speak
         && request
             .speak_text
             .as_deref()
@@ -227,6 +234,8 @@ fn validate_user_command(request: &SubmitCommandRequest) -> Result<(), ApiError>
 #[derive(Debug)]
 struct ApiError {
     status: StatusCode,
+    /// Stable machine-readable error code, e.g. `NOT_FOUND`.
+    code: &'static str,
     message: String,
 }
 
@@ -234,6 +243,7 @@ impl ApiError {
     fn internal(message: impl Into<String>) -> Self {
         Self {
             status: StatusCode::INTERNAL_SERVER_ERROR,
+            code: "INTERNAL",
             message: message.into(),
         }
     }
@@ -241,6 +251,7 @@ impl ApiError {
     fn bad_request(message: impl Into<String>) -> Self {
         Self {
             status: StatusCode::BAD_REQUEST,
+            code: "BAD_REQUEST",
             message: message.into(),
         }
     }
@@ -248,10 +259,12 @@ impl ApiError {
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
-        warn!(status = %self.status, message = %self.message, "request failed");
+        warn!(status = %self.status, code = self.code, message = %self.message, "request failed");
         (
             self.status,
-            Json(serde_json::json!({"error": self.message})),
+            Json(serde_json::json!({
+                "error": {"code": self.code, "message": self.message}
+            })),
         )
             .into_response()
     }
@@ -282,7 +295,7 @@ mod tests {
     }
 
     fn make_request(
-        command_type: CommandType,
+        command_type: PlayerCommandType,
         direction: Option<Direction>,
     ) -> SubmitCommandRequest {
         SubmitCommandRequest {
@@ -298,21 +311,34 @@ mod tests {
 
     #[test]
     fn validate_user_command_rejects_reserved_types() {
-        let timeout_req = make_request(CommandType::Timeout, None);
-        let started_req = make_request(CommandType::GameStarted, None);
+        let timeout_req = make_request(PlayerCommandType::Timeout, None);
 
         let timeout_err = validate_user_command(&timeout_req).unwrap_err();
-        let started_err = validate_user_command(&started_req).unwrap_err();
 
         assert_eq!(timeout_err.status, StatusCode::BAD_REQUEST);
-        assert_eq!(started_err.status, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn game_started_submit_command_fails_to_deserialize() {
+        let body = serde_json::json!({
+            "command_id": "cmd-1",
+            "player_id": "Up",
+            "command_type": "game_started",
+            "direction": null,
+            "turn_no": 1,
+            "client_sent_at": Utc::now(),
+        });
+
+        let error = serde_json::from_value::<SubmitCommandRequest>(body).unwrap_err();
+
+        assert!(error.to_string().contains("game_started"), "{error}");
     }
 
     #[test]
     fn validate_user_command_requires_direction_for_non_timeout_commands() {
-        let move_req = make_request(CommandType::Move, None);
-        let shield_req = make_request(CommandType::Shield, None);
-        let shoot_req = make_request(CommandType::Shoot, None);
+        let move_req = make_request(PlayerCommandType::Move, None);
+        let shield_req = make_request(PlayerCommandType::Shield, None);
+        let shoot_req = make_request(PlayerCommandType::Shoot, None);
 
         assert!(validate_user_command(&move_req).is_err());
         assert!(validate_user_command(&shield_req).is_err());
@@ -321,19 +347,19 @@ mod tests {
 
     #[test]
     fn validate_user_command_accepts_valid_move_request() {
-        let req = make_request(CommandType::Move, Some(Direction::Left));
+        let req = make_request(PlayerCommandType::Move, Some(Direction::Left));
         assert!(validate_user_command(&req).is_ok());
     }
 
     #[test]
     fn validate_user_command_requires_speak_text_for_speak() {
-        let req = make_request(CommandType::Speak, None);
+        let req = make_request(PlayerCommandType::Speak, None);
         assert!(validate_user_command(&req).is_err());
     }
 
     #[test]
     fn validate_user_command_accepts_valid_speak_request() {
-        let mut req = make_request(CommandType::Speak, None);
+        let mut req = make_request(PlayerCommandType::Speak, None);
         req.speak_text = Some("hello cowboy".to_string());
         assert!(validate_user_command(&req).is_ok());
     }
@@ -344,11 +370,12 @@ mod tests {
         let state = AppState {
             publisher: publisher.clone(),
         };
-        let req = make_request(CommandType::Shoot, Some(Direction::Right));
+        let req = make_request(PlayerCommandType::Shoot, Some(Direction::Right));
 
         let response = submit_command_handler(
             State(state),
             Path("game-123".to_string()),
+            HeaderMap::new(),
             Json(req.clone()),
         )
         .await
@@ -362,7 +389,7 @@ mod tests {
         assert_eq!(published.len(), 1);
         let command = &published[0];
         assert_eq!(command.game_id, "game-123");
-        assert_eq!(command.command_type, CommandType::Shoot);
+        assert_eq!(command.command_type, PlayerCommandType::Shoot);
         assert_eq!(command.direction, Some(Direction::Right));
         assert_eq!(command.source, CommandSource::User);
     }
@@ -374,13 +401,56 @@ mod tests {
             fail: true,
         });
         let state = AppState { publisher };
-        let req = make_request(CommandType::Move, Some(Direction::Down));
+        let req = make_request(PlayerCommandType::Move, Some(Direction::Down));
 
-        let err = submit_command_handler(State(state), Path("game-123".to_string()), Json(req))
-            .await
-            .unwrap_err();
+        let err = submit_command_handler(
+            State(state),
+            Path("game-123".to_string()),
+            HeaderMap::new(),
+            Json(req),
+        )
+        .await
+        .unwrap_err();
 
         assert_eq!(err.status, StatusCode::INTERNAL_SERVER_ERROR);
         assert!(err.message.contains("failed to publish command"));
     }
+
+    #[tokio::test]
+    async fn submit_command_handler_forwards_player_token_header() {
+        let publisher = Arc::new(RecordingPublisher::default());
+        let state = AppState {
+            publisher: publisher.clone(),
+        };
+        let mut headers = HeaderMap::new();
+        headers.insert(PLAYER_TOKEN_HEADER, "secret-token".parse().unwrap());
+
+        let response = submit_command_handler(
+            State(state),
+            Path("game-123".to_string()),
+            headers,
+            Json(make_request(PlayerCommandType::Pass, None)),
+        )
+        .await
+        .unwrap()
+        .0;
+        assert!(response.accepted);
+
+        let published = publisher.published.lock().unwrap();
+        assert_eq!(published[0].player_token.as_deref(), Some("secret-token"));
+    }
+
+    #[tokio::test]
+    async fn api_error_body_carries_code_and_message() {
+        let response = ApiError::bad_request("command_id is required").into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({"error": {"code": "BAD_REQUEST", "message": "command_id is required"}})
+        );
+    }
 }
@@ -87,6 +87,14 @@ const PERPENDICULAR = {
   right: ["up", "down"],
 };
 
+const FINISH_REASON_LABELS = {
+  LAST_PLAYER_STANDING: "last player standing",
+  DRAW: "draw",
+  MAX_ROUNDS: "round limit reached",
+  ABORTED: "aborted",
+  FORFEIT: "won by forfeit",
+};
+
 const COLORS = {
   A: "#c94833",
   B: "#2a61b8",
@@ -181,8 +189,9 @@ let audioCtx = null;
 function displayName(id) {
   const localPlayer = getPlayerById(id);
   const name = localPlayer?.name || id;
+  const label = localPlayer?.displayName || `Player ${name}`;
   const side = SIDES[name];
-  return side ? `Player ${name} (${side})` : `Player ${name}`;
+  return side ? `${label} (${side})` : label;
 }
 
 function cloneMapFromTemplate() {
@@ -257,6 +266,8 @@ function toLocalPlayers(players, previousPlayers = []) {
         aim: previousById.get(player.player_id)?.aim || player.shield,
         spriteMode: "idle",
         alive: Boolean(player.alive),
+        color: typeof player.color === "string" && /^#[0-9a-fA-F]{6}$/.test(player.color) ? player.color : null,
+        displayName: typeof player.display_name === "string" && player.display_name ? player.display_name : null,
       },
     ];
     })
@@ -298,10 +309,11 @@ async function requestJson(url, init) {
   }
 
   if (!response.ok) {
+    const apiError = payload && typeof payload === "object" ? payload.error : null;
     const errorMessage =
-      payload && typeof payload === "object" && payload.error
-        ? payload.error
-        : `${response.status} ${response.statusText}`;
+      apiError && typeof apiError === "object"
+        ? apiError.message || apiError.code
+        : apiError || `${response.status} ${response.statusText}`;
     throw new Error(errorMessage);
   }
 
@@ -878,6 +890,10 @@ function connectWatcherStream(gameId, fromTurnNo = 0) {
       }
     }
 
+    if (payload.event_type === "GAME_FINISHED" && FINISH_REASON_LABELS[payload.finish_reason]) {
+      pushLog(`Game over: ${FINISH_REASON_LABELS[payload.finish_reason]}.`);
+    }
+
     if (payload.event_type === "GAME_FINISHED" && !payload.snapshot) {
       const winner = winnerFromPlayers(state.players);
       state.backend.status = "FINISHED";
@@ -891,6 +907,28 @@ function connectWatcherStream(gameId, fromTurnNo = 0) {
       return;
     }
 
+    if (payload.event_type === "THINKING") {
+      pushLog(`${displayName(payload.player_id || "?")} is thinking...`);
+      render();
+      return;
+    }
+
+    if (payload.event_type === "BOT_REASONING") {
+      if (payload.reasoning) {
+        pushLog(`${displayName(payload.player_id || "?")} reasons: ${payload.reasoning}`);
+        render();
+      }
+      return;
+    }
+
+    if (payload.event_type === "GAP_DETECTED") {
+      // The watcher missed steps; resync from a full snapshot.
+      refreshSnapshot(gameId)
+        .then(() => render())
+        .catch(() => {});
+      return;
+    }
+
     if (!payload.snapshot) {
       return;
     }
@@ -2086,7 +2124,8 @@ const GLOW_COLORS = {
 };
 
 function glowColor(playerName) {
-  return GLOW_COLORS[playerName] || "#ffd27a";
+  const serverColor = state.players.find((player) => player.name === playerName)?.color;
+  return serverColor || GLOW_COLORS[playerName] || "#ffd27a";
 }
 
 function drawKnightShield(x, y, direction, size, playerId) {